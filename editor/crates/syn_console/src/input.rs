@@ -0,0 +1,93 @@
+//! Console input context.
+//!
+//! While open, the console sits on top of the [`InputContextStack`] and consumes
+//! every keyboard key, so typing a command never moves the player. Mouse and
+//! gamepad input still reach the contexts below.
+
+use syn_input::{Action, InputBinding, InputContext, InputContextStack, InputSource, KeyCode};
+
+/// Name of the console input context.
+pub const CONTEXT_NAME: &str = "console";
+
+/// Priority of the console input context, above gameplay and menus.
+pub const CONTEXT_PRIORITY: i32 = 1000;
+
+/// Closes the console.
+pub const CLOSE: &str = "console.close";
+/// Runs the typed command.
+pub const SUBMIT: &str = "console.submit";
+/// Recalls the previous command.
+pub const HISTORY_PREVIOUS: &str = "console.history_previous";
+/// Recalls the next command.
+pub const HISTORY_NEXT: &str = "console.history_next";
+/// Completes the typed command.
+pub const COMPLETE: &str = "console.complete";
+
+/// Creates the console input context.
+pub fn context() -> InputContext {
+    let key = |name: &str, key: KeyCode| Action::button(name).with_binding(InputBinding::Key(key));
+
+    InputContext::new(CONTEXT_NAME, CONTEXT_PRIORITY)
+        .blocking(InputSource::AnyKey)
+        .with_action(key(CLOSE, KeyCode::Escape))
+        .with_action(key(SUBMIT, KeyCode::Enter))
+        .with_action(key(HISTORY_PREVIOUS, KeyCode::Up))
+        .with_action(key(HISTORY_NEXT, KeyCode::Down))
+        .with_action(key(COMPLETE, KeyCode::Tab))
+}
+
+/// Returns `true` if the console context is on the stack.
+pub fn is_open(stack: &InputContextStack) -> bool {
+    stack.contains(CONTEXT_NAME)
+}
+
+/// Pushes the console context. Does nothing if it is already open.
+pub fn open(stack: &mut InputContextStack) {
+    if !is_open(stack) {
+        stack.push(context());
+    }
+}
+
+/// Pops the console context. Returns `true` if it was open.
+pub fn close(stack: &mut InputContextStack) -> bool {
+    stack.remove(CONTEXT_NAME).is_some()
+}
+
+/// Opens the console if closed and closes it if open. Returns `true` if it is
+/// now open.
+pub fn toggle(stack: &mut InputContextStack) -> bool {
+    if close(stack) {
+        false
+    } else {
+        open(stack);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn_input::{InputState, Vec2};
+
+    #[test]
+    fn open_console_takes_the_keyboard_from_gameplay() {
+        let mut stack = InputContextStack::new();
+        stack.push(
+            InputContext::new("gameplay", 0)
+                .with_action(Action::axis_2d("move").with_binding(InputBinding::wasd())),
+        );
+
+        let mut input = InputState::new();
+        input.keyboard.keys_mut().press(KeyCode::W);
+        input.keyboard.keys_mut().press(KeyCode::Escape);
+
+        assert!(toggle(&mut stack));
+        stack.update(&input);
+        assert!(stack.pressed(CLOSE));
+        assert_eq!(stack.value::<Vec2>("move"), Vec2::ZERO);
+
+        assert!(!toggle(&mut stack));
+        stack.update(&input);
+        assert_eq!(stack.value::<Vec2>("move"), Vec2::Y);
+    }
+}
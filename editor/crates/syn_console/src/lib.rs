@@ -1 +1,10 @@
-//! `syn_console` - TODO: Implement
+//! `syn_console` - In-game developer console for Synarion Engine.
+//!
+//! - [`input`] - The console input context, which takes the keyboard away from
+//!   gameplay while the console is open
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+pub mod input;
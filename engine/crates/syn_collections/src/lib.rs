@@ -14,7 +14,7 @@ mod slot_map;
 mod sparse_set;
 
 pub use arena::Arena;
-pub use slot_map::SlotMap;
+pub use slot_map::{SlotLayout, SlotMap};
 pub use sparse_set::SparseSet;
 
 // Re-export Handle from syn_core for convenience
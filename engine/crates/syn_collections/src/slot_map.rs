@@ -16,6 +16,20 @@ enum Entry<T> {
     },
 }
 
+/// The allocation state of a [`SlotMap`] without its values: which slots
+/// are occupied, at which generation, and the order vacant slots are reused
+/// in.
+///
+/// A map rebuilt with [`SlotMap::from_layout`] hands out the same handles as
+/// the original from then on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SlotLayout {
+    /// The generation of each slot, and whether it holds a value.
+    pub slots: Vec<(u32, bool)>,
+    /// The vacant slots, in the order [`SlotMap::insert`] reuses them.
+    pub free: Vec<u32>,
+}
+
 /// A slot map that stores values and returns handles to them.
 ///
 /// Unlike a simple `Vec`, `SlotMap` allows O(1) removal and reuses slots
@@ -93,6 +107,94 @@ impl<T> SlotMap<T> {
         }
     }
 
+    /// Inserts a value into a new slot after the last one, without reusing
+    /// vacant slots, so its handle is known from [`slot_count`](Self::slot_count)
+    /// beforehand.
+    pub fn insert_new_slot(&mut self, value: T) -> Handle<T> {
+        // RATIONALE: SlotMap will never have more than u32::MAX entries
+        #[allow(clippy::cast_possible_truncation)]
+        let index = self.entries.len() as u32;
+        self.entries.push(Entry::Occupied {
+            value,
+            generation: 0,
+        });
+        self.len += 1;
+        Handle::new(index, 0)
+    }
+
+    /// Rebuilds a slot map with the allocation state `layout`, filling each
+    /// occupied slot with `value` called with its handle.
+    ///
+    /// Returns `None` if `free` does not list every vacant slot exactly once.
+    pub fn from_layout(layout: &SlotLayout, mut value: impl FnMut(Handle<T>) -> T) -> Option<Self> {
+        let mut entries = Vec::with_capacity(layout.slots.len());
+        let mut len = 0;
+        for (index, &(generation, occupied)) in layout.slots.iter().enumerate() {
+            entries.push(if occupied {
+                len += 1;
+                // RATIONALE: SlotMap will never have more than u32::MAX entries
+                #[allow(clippy::cast_possible_truncation)]
+                let handle = Handle::new(index as u32, generation);
+                Entry::Occupied {
+                    value: value(handle),
+                    generation,
+                }
+            } else {
+                Entry::Vacant {
+                    next_free: None,
+                    generation,
+                }
+            });
+        }
+        if layout.free.len() != entries.len() - len {
+            return None;
+        }
+
+        let mut linked = vec![false; entries.len()];
+        let mut next = None;
+        for &index in layout.free.iter().rev() {
+            let seen = linked.get_mut(index as usize)?;
+            let Entry::Vacant { next_free, .. } = &mut entries[index as usize] else {
+                return None;
+            };
+            if *seen {
+                return None;
+            }
+            *seen = true;
+            *next_free = next;
+            next = Some(index);
+        }
+
+        Some(Self {
+            entries,
+            free_head: next,
+            len,
+        })
+    }
+
+    /// Returns the allocation state, to rebuild a map that hands out the same
+    /// handles with [`from_layout`](Self::from_layout).
+    pub fn layout(&self) -> SlotLayout {
+        let slots = self
+            .entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Occupied { generation, .. } => (*generation, true),
+                Entry::Vacant { generation, .. } => (*generation, false),
+            })
+            .collect();
+        let mut free = Vec::new();
+        let mut next = self.free_head;
+        while let Some(index) = next {
+            free.push(index);
+            next = match self.entries[index as usize] {
+                Entry::Vacant { next_free, .. } => next_free,
+                Entry::Occupied { .. } => unreachable!("free list reached an occupied slot"),
+            };
+        }
+        SlotLayout { slots, free }
+    }
+
     /// Removes the value associated with the handle, returning it if valid.
     pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
         let index = handle.index() as usize;
@@ -160,6 +262,12 @@ impl<T> SlotMap<T> {
         self.len
     }
 
+    /// Returns the number of slots, occupied or vacant.
+    #[inline]
+    pub fn slot_count(&self) -> usize {
+        self.entries.len()
+    }
+
     /// Returns `true` if the slot map is empty.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -267,4 +375,46 @@ mod tests {
         assert_eq!(h4.index(), h2.index());
         assert_eq!(map.len(), 3);
     }
+
+    #[test]
+    fn insert_new_slot_skips_vacant_slots() {
+        let mut map = SlotMap::new();
+        let h1 = map.insert(1);
+        map.remove(h1);
+        assert_eq!(map.slot_count(), 1);
+
+        let h2 = map.insert_new_slot(2);
+        assert_eq!(h2.index(), 1);
+        assert_eq!(h2.generation(), 0);
+        assert_eq!(map.len(), 1);
+
+        // The vacant slot is still reused by `insert`.
+        let h3 = map.insert(3);
+        assert_eq!(h3.index(), h1.index());
+        assert_eq!(map.slot_count(), 2);
+    }
+
+    #[test]
+    fn layout_rebuilds_the_same_handles() {
+        let mut map = SlotMap::new();
+        let handles: Vec<_> = (0..4).map(|value| map.insert(value)).collect();
+        map.remove(handles[1]);
+        map.remove(handles[3]);
+
+        let layout = map.layout();
+        assert_eq!(layout.free, [3, 1]);
+        let mut rebuilt = SlotMap::from_layout(&layout, |handle| handle.index() * 10).unwrap();
+        assert_eq!(rebuilt.len(), 2);
+        assert_eq!(rebuilt.get(handles[2]), Some(&20));
+        assert!(!rebuilt.contains(handles[1]));
+        assert_eq!(rebuilt.insert(7), map.insert(7));
+        assert_eq!(rebuilt.insert(8), map.insert(8));
+        assert_eq!(rebuilt.insert(9), map.insert(9));
+
+        let broken = SlotLayout {
+            slots: vec![(0, false), (0, true)],
+            free: vec![1],
+        };
+        assert!(SlotMap::<u32>::from_layout(&broken, |_| 0).is_none());
+    }
 }
@@ -0,0 +1,113 @@
+//! Benchmarks of query iteration and structural changes.
+
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use syn_ecs::{Entity, Without, World};
+
+const ENTITIES: usize = 10_000;
+
+#[derive(Clone, Copy)]
+struct Position([f32; 3]);
+
+#[derive(Clone, Copy)]
+struct Velocity([f32; 3]);
+
+struct Health(f32);
+
+struct Frozen;
+
+/// A world whose entities are spread over four archetypes.
+fn populated_world() -> (World, Vec<Entity>) {
+    let mut world = World::new();
+    let entities = (0..ENTITIES)
+        .map(|index| {
+            let entity = world.spawn((Position([0.0; 3]), Velocity([1.0, 0.5, 0.25])));
+            if index % 2 == 0 {
+                world.insert(entity, (Health(100.0),)).unwrap();
+            }
+            if index % 3 == 0 {
+                world.insert(entity, (Frozen,)).unwrap();
+            }
+            entity
+        })
+        .collect();
+    (world, entities)
+}
+
+fn iteration(c: &mut Criterion) {
+    let (world, _) = populated_world();
+    let mut group = c.benchmark_group("iterate");
+
+    group.bench_function("read", |b| {
+        b.iter(|| {
+            let mut query = world.query::<&Position>();
+            query.iter().map(|position| position.0[0]).sum::<f32>()
+        });
+    });
+    group.bench_function("integrate", |b| {
+        b.iter(|| {
+            let mut query = world.query::<(&mut Position, &Velocity)>();
+            for (mut position, velocity) in &mut query {
+                for axis in 0..3 {
+                    position.0[axis] += velocity.0[axis] * 0.016;
+                }
+            }
+        });
+    });
+    group.bench_function("filtered_optional", |b| {
+        b.iter(|| {
+            let mut query = world.query_filtered::<(&Position, Option<&Health>), Without<Frozen>>();
+            query
+                .iter()
+                .map(|(position, health)| position.0[1] + health.map_or(0.0, |h| h.0))
+                .sum::<f32>()
+        });
+    });
+    group.finish();
+}
+
+fn structural_changes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("structural");
+
+    group.bench_function("spawn", |b| {
+        b.iter(|| {
+            let mut world = World::new();
+            for _ in 0..ENTITIES {
+                black_box(world.spawn((Position([0.0; 3]), Velocity([0.0; 3]))));
+            }
+            world
+        });
+    });
+    group.bench_function("insert_remove", |b| {
+        b.iter_batched(
+            populated_world,
+            |(mut world, entities)| {
+                for entity in &entities {
+                    world.insert(*entity, (Frozen,)).unwrap();
+                }
+                for entity in &entities {
+                    black_box(world.remove::<Velocity>(*entity));
+                }
+                world
+            },
+            BatchSize::LargeInput,
+        );
+    });
+    group.bench_function("despawn", |b| {
+        b.iter_batched(
+            populated_world,
+            |(mut world, entities)| {
+                for entity in entities {
+                    world.despawn(entity);
+                }
+                world
+            },
+            BatchSize::LargeInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, iteration, structural_changes);
+criterion_main!(benches);
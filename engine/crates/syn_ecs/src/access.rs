@@ -0,0 +1,199 @@
+//! The data a query or system borrows.
+
+use std::any::{TypeId, type_name};
+
+use crate::component::Component;
+use crate::resource::Resource;
+
+/// The components and resources a query or system reads and writes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Access {
+    components: Borrows,
+    resources: Borrows,
+    /// The first type borrowed mutably along with another borrow.
+    conflict: Option<&'static str>,
+}
+
+/// Shared and mutable borrows of one kind of data.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Borrows {
+    reads: Vec<(TypeId, &'static str)>,
+    writes: Vec<(TypeId, &'static str)>,
+}
+
+impl Borrows {
+    /// Records a shared borrow, returning `false` if the type is written.
+    fn read(&mut self, id: TypeId, name: &'static str) -> bool {
+        if !self.reads.iter().any(|(read, _)| *read == id) {
+            self.reads.push((id, name));
+        }
+        !self.writes.iter().any(|(write, _)| *write == id)
+    }
+
+    /// Records a mutable borrow, returning `false` if the type is borrowed.
+    fn write(&mut self, id: TypeId, name: &'static str) -> bool {
+        let compatible = !self.is_borrowed(id);
+        self.writes.push((id, name));
+        compatible
+    }
+
+    /// Records the borrows of `other`, returning the name of a type borrowed
+    /// by both if one of them writes it.
+    fn extend(&mut self, other: &Borrows) -> Option<&'static str> {
+        let mut conflict = None;
+        for (id, name) in &other.reads {
+            if !self.read(*id, name) {
+                conflict.get_or_insert(*name);
+            }
+        }
+        for (id, name) in &other.writes {
+            if !self.write(*id, name) {
+                conflict.get_or_insert(*name);
+            }
+        }
+        conflict
+    }
+
+    fn is_borrowed(&self, id: TypeId) -> bool {
+        self.reads
+            .iter()
+            .chain(&self.writes)
+            .any(|(borrowed, _)| *borrowed == id)
+    }
+
+    /// Returns the names of the types one of `self` and `other` writes and
+    /// the other borrows.
+    fn conflicts<'a>(&'a self, other: &'a Borrows) -> impl Iterator<Item = &'static str> + 'a {
+        let written = |by: &'a Borrows, borrower: &'a Borrows| {
+            by.writes
+                .iter()
+                .filter(|(id, _)| borrower.is_borrowed(*id))
+                .map(|(_, name)| *name)
+        };
+        written(self, other).chain(written(other, self))
+    }
+}
+
+impl Access {
+    /// Creates an access borrowing nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a shared borrow of the component `T`.
+    pub fn read<T: Component>(&mut self) {
+        if !self.components.read(TypeId::of::<T>(), type_name::<T>()) {
+            self.conflict.get_or_insert(type_name::<T>());
+        }
+    }
+
+    /// Records a mutable borrow of the component `T`.
+    pub fn write<T: Component>(&mut self) {
+        if !self.components.write(TypeId::of::<T>(), type_name::<T>()) {
+            self.conflict.get_or_insert(type_name::<T>());
+        }
+    }
+
+    /// Records a shared borrow of the resource `R`.
+    pub fn read_resource<R: Resource>(&mut self) {
+        if !self.resources.read(TypeId::of::<R>(), type_name::<R>()) {
+            self.conflict.get_or_insert(type_name::<R>());
+        }
+    }
+
+    /// Records a mutable borrow of the resource `R`.
+    pub fn write_resource<R: Resource>(&mut self) {
+        if !self.resources.write(TypeId::of::<R>(), type_name::<R>()) {
+            self.conflict.get_or_insert(type_name::<R>());
+        }
+    }
+
+    /// Records the borrows of `other` as well.
+    pub fn extend(&mut self, other: &Access) {
+        let components = self.components.extend(&other.components);
+        let resources = self.resources.extend(&other.resources);
+        if let Some(name) = components.or(resources).or(other.conflict) {
+            self.conflict.get_or_insert(name);
+        }
+    }
+
+    /// Returns the type ids of the components read.
+    pub fn reads(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.components.reads.iter().map(|(id, _)| *id)
+    }
+
+    /// Returns the type ids of the components written.
+    pub fn writes(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.components.writes.iter().map(|(id, _)| *id)
+    }
+
+    /// Returns the type ids of the resources read.
+    pub fn resource_reads(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.resources.reads.iter().map(|(id, _)| *id)
+    }
+
+    /// Returns the type ids of the resources written.
+    pub fn resource_writes(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.resources.writes.iter().map(|(id, _)| *id)
+    }
+
+    /// Returns the name of a component or resource borrowed mutably and
+    /// borrowed again, which makes the access unusable.
+    pub fn conflict(&self) -> Option<&'static str> {
+        self.conflict
+    }
+
+    /// Returns `true` if both accesses may be held at the same time: neither
+    /// writes a component or resource the other borrows.
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        self.conflicts(other).next().is_none()
+    }
+
+    /// Returns the names of the components and resources one access writes
+    /// and the other borrows.
+    pub fn conflicts<'a>(&'a self, other: &'a Access) -> impl Iterator<Item = &'static str> + 'a {
+        self.components
+            .conflicts(&other.components)
+            .chain(self.resources.conflicts(&other.resources))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Position;
+    struct Velocity;
+    struct Time;
+
+    #[test]
+    fn accesses_conflict_on_writes_of_the_same_kind() {
+        let mut movement = Access::new();
+        movement.write::<Position>();
+        movement.read::<Velocity>();
+        movement.read_resource::<Time>();
+
+        let mut render = Access::new();
+        render.read::<Position>();
+        render.read::<Time>();
+        assert!(!movement.is_compatible(&render));
+        assert_eq!(
+            movement.conflicts(&render).collect::<Vec<_>>(),
+            [type_name::<Position>()]
+        );
+
+        let mut clock = Access::new();
+        clock.write_resource::<Time>();
+        clock.write::<Velocity>();
+        assert_eq!(
+            clock.conflicts(&movement).collect::<Vec<_>>(),
+            [type_name::<Velocity>(), type_name::<Time>()]
+        );
+        // `Time` the component is not `Time` the resource.
+        assert!(clock.is_compatible(&render));
+        assert_eq!(clock.conflict(), None);
+
+        clock.read_resource::<Time>();
+        assert_eq!(clock.conflict(), Some(type_name::<Time>()));
+    }
+}
@@ -0,0 +1,243 @@
+//! Archetype tables: entities sharing the same set of components.
+
+use std::any::{TypeId, type_name};
+use std::collections::HashMap;
+
+use parking_lot::{
+    MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+
+use crate::change::{ComponentTicks, Tick};
+use crate::component::{AnyColumn, Column, Component, ComponentInfo, downcast, downcast_mut};
+use crate::entity::Entity;
+
+/// Identifies an [`Archetype`] of a [`World`](crate::World).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArchetypeId(u32);
+
+impl ArchetypeId {
+    /// The archetype of entities without components.
+    pub const EMPTY: Self = Self(0);
+
+    /// Returns the position of the archetype in
+    /// [`World::archetypes`](crate::World::archetypes).
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A table of the entities having exactly the same component types, with one
+/// column per component and one row per entity.
+pub struct Archetype {
+    id: ArchetypeId,
+    /// Sorted by type id.
+    components: Box<[ComponentInfo]>,
+    /// One column per component, locked separately so queries can borrow
+    /// different components of one table at once.
+    columns: Box<[RwLock<Box<dyn AnyColumn>>]>,
+    /// The change ticks of each column, outside the column locks so
+    /// [`Mut`](crate::Mut) can mark rows changed while filters read them.
+    ticks: Box<[Vec<ComponentTicks>]>,
+    entities: Vec<Entity>,
+    /// Archetypes reached by inserting a bundle, keyed by the bundle's type.
+    pub(crate) insert_edges: HashMap<TypeId, ArchetypeId>,
+    /// Archetypes reached by removing a component, keyed by its type.
+    pub(crate) remove_edges: HashMap<TypeId, ArchetypeId>,
+}
+
+impl Archetype {
+    /// Creates an empty table of `components`, sorted by type id.
+    pub(crate) fn new(id: ArchetypeId, components: Box<[ComponentInfo]>) -> Self {
+        let columns = components
+            .iter()
+            .map(|info| RwLock::new(info.new_column()))
+            .collect();
+        let ticks = components.iter().map(|_| Vec::new()).collect();
+        Self {
+            id,
+            components,
+            columns,
+            ticks,
+            entities: Vec::new(),
+            insert_edges: HashMap::new(),
+            remove_edges: HashMap::new(),
+        }
+    }
+
+    /// Returns the archetype's id.
+    pub fn id(&self) -> ArchetypeId {
+        self.id
+    }
+
+    /// Returns the components of the archetype, sorted by type id.
+    pub fn components(&self) -> &[ComponentInfo] {
+        &self.components
+    }
+
+    /// Returns `true` if the archetype stores the component.
+    pub fn contains(&self, component: TypeId) -> bool {
+        self.column_index(component).is_some()
+    }
+
+    /// Returns the entities of the archetype, in row order.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Returns the number of entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if the archetype has no entity.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn column_index(&self, component: TypeId) -> Option<usize> {
+        self.components
+            .binary_search_by_key(&component, ComponentInfo::type_id)
+            .ok()
+    }
+
+    /// Locks the column of `T` for reading.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the archetype has no `T`, or if the column is borrowed
+    /// mutably.
+    pub(crate) fn read<T: Component>(&self) -> MappedRwLockReadGuard<'_, Vec<T>> {
+        let column = &self.columns[self.expect_column::<T>()];
+        let guard = column
+            .try_read_recursive()
+            .unwrap_or_else(|| panic!("`{}` is already borrowed mutably", type_name::<T>()));
+        RwLockReadGuard::map(guard, |column| &downcast::<T>(column.as_ref()).values)
+    }
+
+    /// Locks the column of `T` for writing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the archetype has no `T`, or if the column is borrowed.
+    pub(crate) fn write<T: Component>(&self) -> MappedRwLockWriteGuard<'_, Vec<T>> {
+        let column = &self.columns[self.expect_column::<T>()];
+        let guard = column
+            .try_write()
+            .unwrap_or_else(|| panic!("`{}` is already borrowed", type_name::<T>()));
+        RwLockWriteGuard::map(guard, |column| {
+            &mut downcast_mut::<T>(column.as_mut()).values
+        })
+    }
+
+    fn expect_column<T: Component>(&self) -> usize {
+        self.column_index(TypeId::of::<T>())
+            .unwrap_or_else(|| panic!("archetype has no `{}`", type_name::<T>()))
+    }
+
+    /// Returns the change ticks of the column of `T`, one per row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the archetype has no `T`.
+    pub(crate) fn ticks<T: Component>(&self) -> &[ComponentTicks] {
+        &self.ticks[self.expect_column::<T>()]
+    }
+
+    /// Returns the change ticks of `component` at `row`, if the archetype has
+    /// the component.
+    pub(crate) fn row_ticks(&self, component: TypeId, row: usize) -> Option<&ComponentTicks> {
+        Some(&self.ticks[self.column_index(component)?][row])
+    }
+
+    /// Records that the `T` of `row` was written at `tick`, which either adds
+    /// it to the next row or changes an existing one. Returns `true` if it
+    /// was added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the archetype has no `T`.
+    pub(crate) fn set_ticks<T: Component>(&mut self, row: usize, tick: Tick) -> bool {
+        let ticks = &mut self.ticks[self.expect_column::<T>()];
+        if row == ticks.len() {
+            ticks.push(ComponentTicks::new(tick));
+            true
+        } else {
+            ticks[row].set_changed(tick);
+            false
+        }
+    }
+
+    /// Returns the column of `T`, if the archetype has one.
+    pub(crate) fn column_mut<T: Component>(&mut self) -> Option<&mut Column<T>> {
+        let index = self.column_index(TypeId::of::<T>())?;
+        Some(downcast_mut(self.columns[index].get_mut().as_mut()))
+    }
+
+    /// Adds `entity` as a new row, whose values the caller must push onto
+    /// every column, and returns the row.
+    pub(crate) fn push_entity(&mut self, entity: Entity) -> usize {
+        self.entities.push(entity);
+        self.entities.len() - 1
+    }
+
+    /// Removes `row`, dropping its values, and returns the entity
+    /// moved into the row, if any.
+    pub(crate) fn remove_row(&mut self, row: usize) -> Option<Entity> {
+        for (column, ticks) in self.columns.iter_mut().zip(&mut self.ticks) {
+            column.get_mut().swap_remove(row);
+            ticks.swap_remove(row);
+        }
+        self.swap_remove_entity(row)
+    }
+
+    /// Moves `row` to `target`, keeping the values and change ticks of the
+    /// components `target` has and passing the others to `removed`. Returns the entity moved into
+    /// `row` and the entity's row in `target`.
+    pub(crate) fn move_row(
+        &mut self,
+        row: usize,
+        target: &mut Archetype,
+        mut removed: impl FnMut(&mut dyn AnyColumn, usize),
+    ) -> (Option<Entity>, usize) {
+        let columns = self.columns.iter_mut().zip(&mut self.ticks);
+        for (info, (column, ticks)) in self.components.iter().zip(columns) {
+            let column = column.get_mut().as_mut();
+            let row_ticks = ticks.swap_remove(row);
+            match target.column_index(info.type_id()) {
+                Some(index) => {
+                    column.move_row(row, target.columns[index].get_mut().as_mut());
+                    target.ticks[index].push(row_ticks);
+                }
+                None => removed(column, row),
+            }
+        }
+        let new_row = target.push_entity(self.entities[row]);
+        (self.swap_remove_entity(row), new_row)
+    }
+
+    fn swap_remove_entity(&mut self, row: usize) -> Option<Entity> {
+        self.entities.swap_remove(row);
+        self.entities.get(row).copied()
+    }
+}
+
+/// Returns mutable references to two different archetypes.
+pub(crate) fn pair_mut(
+    archetypes: &mut [Archetype],
+    a: ArchetypeId,
+    b: ArchetypeId,
+) -> (&mut Archetype, &mut Archetype) {
+    assert_ne!(a, b, "an archetype cannot be borrowed twice");
+    if a < b {
+        let (low, high) = archetypes.split_at_mut(b.index());
+        (&mut low[a.index()], &mut high[0])
+    } else {
+        let (low, high) = archetypes.split_at_mut(a.index());
+        (&mut high[0], &mut low[b.index()])
+    }
+}
+
+/// Creates an archetype id for the archetype at `index`.
+pub(crate) fn archetype_id(index: usize) -> ArchetypeId {
+    ArchetypeId(u32::try_from(index).expect("too many archetypes"))
+}
@@ -0,0 +1,56 @@
+//! Groups of components inserted together.
+
+use crate::archetype::Archetype;
+use crate::change::Tick;
+use crate::component::{Component, ComponentInfo};
+
+/// A tuple of components spawned or inserted together, such as
+/// `(Position, Velocity)` or `(Name,)`.
+///
+/// A bundle may not contain the same component twice.
+pub trait Bundle: Send + Sync + 'static {
+    /// Appends the bundle's components to `out`.
+    fn components(out: &mut Vec<ComponentInfo>);
+
+    /// Stores the components at `row` of `archetype`, which has them all,
+    /// marking them added or changed at `tick`.
+    #[doc(hidden)]
+    fn write(self, archetype: &mut Archetype, row: usize, tick: Tick);
+}
+
+macro_rules! impl_bundle {
+    ($($name:ident),*) => {
+        impl<$($name: Component),*> Bundle for ($($name,)*) {
+            fn components(_out: &mut Vec<ComponentInfo>) {
+                $(_out.push(ComponentInfo::of::<$name>());)*
+            }
+
+            // RATIONALE: The components reuse the type parameter names.
+            #[allow(non_snake_case, unused_variables)]
+            fn write(self, archetype: &mut Archetype, row: usize, tick: Tick) {
+                let ($($name,)*) = self;
+                $(
+                    archetype
+                        .column_mut::<$name>()
+                        .expect("bundle archetype has every component")
+                        .set(row, $name);
+                    archetype.set_ticks::<$name>(row, tick);
+                )*
+            }
+        }
+    };
+}
+
+impl_bundle!();
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
+impl_bundle!(A, B, C, D, E, F, G, H, I);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J, K);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J, K, L);
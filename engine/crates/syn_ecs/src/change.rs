@@ -0,0 +1,104 @@
+//! Change detection: ticks recording when components were added and changed.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A point in a world's history, advanced by
+/// [`World::increment_change_tick`](crate::World::increment_change_tick).
+///
+/// Components remember the tick they were added and last changed at, so
+/// [`Added`](crate::Added) and [`Changed`](crate::Changed) can select those
+/// modified after a given tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tick(u64);
+
+impl Tick {
+    /// Creates a tick from its raw value.
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value of the tick.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+}
+
+/// When a component of one entity was added and last changed.
+///
+/// The changed tick is atomic: [`Mut`] updates it through a shared world,
+/// while the added tick only changes with structural changes, which need an
+/// exclusive one.
+pub(crate) struct ComponentTicks {
+    added: Tick,
+    changed: AtomicU64,
+}
+
+impl ComponentTicks {
+    pub(crate) fn new(tick: Tick) -> Self {
+        Self {
+            added: tick,
+            changed: AtomicU64::new(tick.0),
+        }
+    }
+
+    pub(crate) fn added(&self) -> Tick {
+        self.added
+    }
+
+    pub(crate) fn changed(&self) -> Tick {
+        Tick(self.changed.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn set_changed(&self, tick: Tick) {
+        self.changed.store(tick.0, Ordering::Relaxed);
+    }
+}
+
+/// A mutable borrow of a component fetched by a query, which marks the
+/// component changed when it is dereferenced mutably.
+///
+/// Reading through a `Mut` leaves the component unchanged, so systems
+/// updating only some entities do not wake up every [`Changed`](crate::Changed)
+/// reader.
+pub struct Mut<'q, T> {
+    value: &'q mut T,
+    ticks: &'q ComponentTicks,
+    tick: Tick,
+}
+
+impl<'q, T> Mut<'q, T> {
+    pub(crate) fn new(value: &'q mut T, ticks: &'q ComponentTicks, tick: Tick) -> Self {
+        Self { value, ticks, tick }
+    }
+
+    /// Returns the tick the component was added at.
+    pub fn added_tick(&self) -> Tick {
+        self.ticks.added()
+    }
+
+    /// Returns the tick the component last changed at.
+    pub fn changed_tick(&self) -> Tick {
+        self.ticks.changed()
+    }
+
+    /// Returns the component mutably without marking it changed.
+    pub fn bypass_change_detection(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.ticks.set_changed(self.tick);
+        self.value
+    }
+}
@@ -0,0 +1,154 @@
+//! Commands: structural changes recorded while the world is shared, and
+//! applied once it is exclusive again.
+
+use std::fmt;
+
+use crate::bundle::Bundle;
+use crate::component::Component;
+use crate::entity::Entity;
+use crate::world::World;
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Changes to a [`World`] waiting to be applied, in recording order.
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Vec<Command>,
+}
+
+impl CommandQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a change.
+    pub fn push(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.commands.push(Box::new(command));
+    }
+
+    /// Returns the number of changes recorded.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns `true` if no change is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Applies the changes in recording order, leaving the queue empty.
+    pub fn apply(&mut self, world: &mut World) {
+        for command in self.commands.drain(..) {
+            command(world);
+        }
+    }
+}
+
+impl fmt::Debug for CommandQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandQueue")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Records structural changes to a shared [`World`], created by
+/// [`World::commands`].
+///
+/// Systems only get a shared world, so they cannot spawn, despawn, insert or
+/// remove while queries borrow its tables. They record these changes instead;
+/// [`World::apply_commands`] applies them at a sync point, such as the end of
+/// a [`Schedule`](crate::Schedule) run.
+///
+/// Each `Commands` records into its own queue, so systems running on
+/// different threads never contend; the queue is handed to the world when
+/// the `Commands` is dropped. Queues are applied in the order they were
+/// handed over. Changes to entities despawned in the meantime do nothing.
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{Entity, World};
+///
+/// struct Health(i32);
+/// struct Corpse;
+///
+/// let mut world = World::new();
+/// let alive = world.spawn((Health(10),));
+/// let dead = world.spawn((Health(0),));
+///
+/// let mut commands = world.commands();
+/// for (entity, health) in &mut world.query::<(Entity, &Health)>() {
+///     if health.0 <= 0 {
+///         commands.despawn(entity);
+///         commands.spawn((Corpse,));
+///     }
+/// }
+/// drop(commands);
+///
+/// world.apply_commands();
+/// assert!(world.contains(alive));
+/// assert!(!world.contains(dead));
+/// assert_eq!(world.query::<&Corpse>().len(), 1);
+/// ```
+pub struct Commands<'w> {
+    world: &'w World,
+    queue: CommandQueue,
+}
+
+impl<'w> Commands<'w> {
+    pub(crate) fn new(world: &'w World) -> Self {
+        Self {
+            world,
+            queue: CommandQueue::new(),
+        }
+    }
+
+    /// Spawns an entity with the components of `bundle`, returning its id
+    /// right away.
+    ///
+    /// The id is reserved with [`World::reserve_entity`], so other commands
+    /// can refer to the entity before it exists.
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let entity = self.world.reserve_entity();
+        self.insert(entity, bundle);
+        entity
+    }
+
+    /// Despawns an entity.
+    pub fn despawn(&mut self, entity: Entity) {
+        self.queue.push(move |world| {
+            world.despawn(entity);
+        });
+    }
+
+    /// Adds the components of `bundle` to an entity, replacing those it
+    /// already has.
+    pub fn insert<B: Bundle>(&mut self, entity: Entity, bundle: B) {
+        self.queue.push(move |world| {
+            // The entity may have been despawned by an earlier command.
+            world.insert(entity, bundle).ok();
+        });
+    }
+
+    /// Removes the component `T` from an entity.
+    pub fn remove<T: Component>(&mut self, entity: Entity) {
+        self.queue.push(move |world| {
+            world.remove::<T>(entity);
+        });
+    }
+
+    /// Records any other change.
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.queue.push(command);
+    }
+}
+
+impl Drop for Commands<'_> {
+    fn drop(&mut self) {
+        if !self.queue.is_empty() {
+            self.world.submit_commands(std::mem::take(&mut self.queue));
+        }
+    }
+}
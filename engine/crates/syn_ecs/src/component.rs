@@ -0,0 +1,126 @@
+//! Components and their column storage.
+
+use std::any::{Any, TypeId};
+use std::fmt;
+
+/// Data attached to entities.
+///
+/// Every `Send + Sync + 'static` type is a component, so types from other
+/// crates need no wrapper.
+pub trait Component: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Component for T {}
+
+/// Describes a component type stored in a [`World`](crate::World).
+#[derive(Clone, Copy)]
+pub struct ComponentInfo {
+    type_id: TypeId,
+    name: &'static str,
+    new_column: fn() -> Box<dyn AnyColumn>,
+}
+
+impl ComponentInfo {
+    /// Returns the description of `T`.
+    pub fn of<T: Component>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+            new_column: || Box::new(Column::<T> { values: Vec::new() }),
+        }
+    }
+
+    /// Returns the component's type id.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the component's type name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Creates an empty column of the component.
+    pub(crate) fn new_column(&self) -> Box<dyn AnyColumn> {
+        (self.new_column)()
+    }
+}
+
+impl fmt::Debug for ComponentInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ComponentInfo").field(&self.name).finish()
+    }
+}
+
+impl PartialEq for ComponentInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+    }
+}
+
+impl Eq for ComponentInfo {}
+
+/// The values of one component in an archetype, one per row.
+pub(crate) struct Column<T> {
+    pub(crate) values: Vec<T>,
+}
+
+impl<T> Column<T> {
+    /// Stores `value` at `row`, which is either an existing row or the next
+    /// one.
+    pub(crate) fn set(&mut self, row: usize, value: T) {
+        if row == self.values.len() {
+            self.values.push(value);
+        } else {
+            self.values[row] = value;
+        }
+    }
+}
+
+/// A type-erased [`Column`].
+pub(crate) trait AnyColumn: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Removes and drops the value at `row`, moving the last value into it.
+    fn swap_remove(&mut self, row: usize);
+
+    /// Removes the value at `row` like [`swap_remove`](Self::swap_remove),
+    /// and pushes it onto `target`, a column of the same component.
+    fn move_row(&mut self, row: usize, target: &mut dyn AnyColumn);
+}
+
+impl<T: Component> AnyColumn for Column<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn swap_remove(&mut self, row: usize) {
+        self.values.swap_remove(row);
+    }
+
+    fn move_row(&mut self, row: usize, target: &mut dyn AnyColumn) {
+        let value = self.values.swap_remove(row);
+        downcast_mut::<T>(target).values.push(value);
+    }
+}
+
+/// Returns the typed column behind `column`.
+pub(crate) fn downcast<T: Component>(column: &dyn AnyColumn) -> &Column<T> {
+    column
+        .as_any()
+        .downcast_ref()
+        .expect("column holds the component of its type id")
+}
+
+/// Returns the typed column behind `column`.
+pub(crate) fn downcast_mut<T: Component>(column: &mut dyn AnyColumn) -> &mut Column<T> {
+    column
+        .as_any_mut()
+        .downcast_mut()
+        .expect("column holds the component of its type id")
+}
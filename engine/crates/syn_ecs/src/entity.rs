@@ -0,0 +1,101 @@
+//! Entity identifiers.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use syn_core::Handle;
+
+use crate::archetype::ArchetypeId;
+
+/// Identifies an entity of a [`World`](crate::World).
+///
+/// Like a [`Handle`], an entity is an index and a generation: despawning an
+/// entity bumps the generation of its slot, so stale ids are detected when the
+/// slot is reused.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Entity(Handle<Entity>);
+
+impl Entity {
+    /// Creates an entity id from raw parts.
+    pub const fn new(index: u32, generation: u32) -> Self {
+        Self(Handle::new(index, generation))
+    }
+
+    /// Returns the index of the entity's slot.
+    pub const fn index(self) -> u32 {
+        self.0.index()
+    }
+
+    /// Returns the generation of the entity's slot.
+    pub const fn generation(self) -> u32 {
+        self.0.generation()
+    }
+
+    /// Returns the entity packed in 64 bits, the generation above the index,
+    /// to store it.
+    pub const fn to_bits(self) -> u64 {
+        ((self.generation() as u64) << 32) | self.index() as u64
+    }
+
+    /// Unpacks an entity packed by [`to_bits`](Self::to_bits).
+    pub const fn from_bits(bits: u64) -> Self {
+        // RATIONALE: the halves of the bits are the index and the generation
+        #[allow(clippy::cast_possible_truncation)]
+        let (index, generation) = (bits as u32, (bits >> 32) as u32);
+        Self::new(index, generation)
+    }
+
+    /// Returns the entity as a handle.
+    pub const fn handle(self) -> Handle<Entity> {
+        self.0
+    }
+
+    /// Returns the handle of the entity's location.
+    pub(crate) const fn location_handle(self) -> Handle<EntityLocation> {
+        Handle::new(self.index(), self.generation())
+    }
+}
+
+impl From<Handle<EntityLocation>> for Entity {
+    fn from(handle: Handle<EntityLocation>) -> Self {
+        Self::new(handle.index(), handle.generation())
+    }
+}
+
+impl Hash for Entity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl PartialOrd for Entity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.index(), self.generation()).cmp(&(other.index(), other.generation()))
+    }
+}
+
+impl fmt::Debug for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index(), self.generation())
+    }
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Where an entity's components are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EntityLocation {
+    pub(crate) archetype: ArchetypeId,
+    pub(crate) row: usize,
+}
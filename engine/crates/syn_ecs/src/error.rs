@@ -0,0 +1,68 @@
+//! ECS error types.
+
+use thiserror::Error;
+
+use crate::entity::Entity;
+
+/// Errors returned by [`World`](crate::World) and
+/// [`Schedule`](crate::Schedule) operations.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum EcsError {
+    /// The entity was despawned, or belongs to another world.
+    #[error("entity {0} does not exist")]
+    NoSuchEntity(Entity),
+
+    /// Two systems of a schedule have the same name.
+    #[error("schedule has two systems named `{0}`")]
+    DuplicateSystem(String),
+
+    /// An ordering constraint names neither a system nor a set.
+    #[error("`{0}` is neither a system nor a set of the schedule")]
+    UnknownLabel(String),
+
+    /// Ordering constraints require systems to run before themselves.
+    #[error("ordering cycle between systems {}", .0.join(", "))]
+    OrderingCycle(Vec<String>),
+
+    /// A system borrows a component or resource mutably and again.
+    #[error("system `{system}` borrows `{component}` mutably and again")]
+    InvalidAccess {
+        /// The system's name.
+        system: String,
+        /// The component or resource borrowed twice.
+        component: &'static str,
+    },
+
+    /// A relationship is not listed by its target, or a target lists an
+    /// entity not linked to it.
+    #[error("`{relationship}` linking {entity} to {target} is not mirrored on both sides")]
+    BrokenRelationship {
+        /// The relationship's type name.
+        relationship: &'static str,
+        /// The source of the link.
+        entity: Entity,
+        /// The target of the link.
+        target: Entity,
+    },
+
+    /// An acyclic relationship, such as a hierarchy, loops.
+    #[error("`{relationship}` loops back to {entity}")]
+    RelationshipCycle {
+        /// The relationship's type name.
+        relationship: &'static str,
+        /// An entity of the loop.
+        entity: Entity,
+    },
+
+    /// A snapshot was taken with other component types than it is restored
+    /// with, or a diff is applied to another snapshot than it was taken from.
+    #[error("snapshot does not match the schema or snapshot it is applied to")]
+    SnapshotMismatch,
+
+    /// Snapshot or diff data does not decode.
+    #[error("snapshot data is corrupt")]
+    CorruptSnapshot,
+}
+
+/// Result type for ECS operations.
+pub type EcsResult<T> = Result<T, EcsError>;
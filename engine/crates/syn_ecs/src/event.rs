@@ -0,0 +1,220 @@
+//! Events: typed messages between systems, kept for two updates.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A message sent between systems through [`Events`], such as a collision
+/// or a quest trigger.
+///
+/// Every `Send + Sync + 'static` type is an event.
+pub trait Event: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Event for T {}
+
+/// The events of type `T`, double-buffered so every reader sees each event
+/// whatever the order systems run in.
+///
+/// [`update`](Self::update) drops the events sent before the previous
+/// update, so an event stays readable from when it is sent until the end of
+/// the next frame when updates happen once per frame. Readers track their
+/// position with an [`EventCursor`].
+///
+/// Stored as a world resource, registered with
+/// [`World::add_event`](crate::World::add_event) and updated by
+/// [`World::update_events`](crate::World::update_events).
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{EventCursor, Events};
+///
+/// struct Damage(u32);
+///
+/// let mut events = Events::new();
+/// let mut cursor = EventCursor::new();
+///
+/// events.send(Damage(5));
+/// events.send(Damage(7));
+/// let total: u32 = cursor.read(&events).map(|damage| damage.0).sum();
+/// assert_eq!(total, 12);
+///
+/// // Events are only read once by each cursor.
+/// assert_eq!(cursor.read(&events).count(), 0);
+///
+/// // And dropped on the second update after being sent.
+/// events.update();
+/// assert_eq!(events.len(), 2);
+/// events.update();
+/// assert!(events.is_empty());
+/// ```
+pub struct Events<T> {
+    /// The events sent before the last update.
+    previous: Vec<T>,
+    /// The events sent since the last update.
+    current: Vec<T>,
+    /// The id of the first event of `previous`; ids count every event sent.
+    start: usize,
+}
+
+impl<T: Event> Events<T> {
+    /// Creates an empty event buffer.
+    pub fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            start: 0,
+        }
+    }
+
+    /// Sends an event.
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+    }
+
+    /// Sends several events.
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        self.current.extend(events);
+    }
+
+    /// Drops the events sent before the previous update.
+    pub fn update(&mut self) {
+        self.start += self.previous.len();
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+    }
+
+    /// Returns the number of events kept.
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    /// Returns `true` if no event is kept.
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty() && self.current.is_empty()
+    }
+
+    /// Iterates over the events kept, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.previous.iter().chain(&self.current)
+    }
+
+    /// Drops every event.
+    pub fn clear(&mut self) {
+        self.start += self.len();
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// Returns the id the next event will have.
+    fn end(&self) -> usize {
+        self.start + self.len()
+    }
+}
+
+impl<T: Event> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Events<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Events")
+            .field("previous", &self.previous.len())
+            .field("current", &self.current.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The position of one reader in an [`Events`] buffer.
+///
+/// Each reader, typically a system, owns its cursor, so every reader sees
+/// every event once. Events dropped before a reader got to them are skipped.
+pub struct EventCursor<T> {
+    /// The id of the next event to read.
+    next: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Event> EventCursor<T> {
+    /// Creates a cursor reading every event kept.
+    pub fn new() -> Self {
+        Self {
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Reads the events sent since the last read, oldest first.
+    pub fn read<'e>(&mut self, events: &'e Events<T>) -> impl Iterator<Item = &'e T> + use<'e, T> {
+        let skip = self.next.saturating_sub(events.start);
+        self.next = events.end();
+        events.iter().skip(skip)
+    }
+
+    /// Returns the number of events not read yet.
+    pub fn unread(&self, events: &Events<T>) -> usize {
+        events.end() - self.next.max(events.start)
+    }
+
+    /// Marks every event as read.
+    pub fn clear(&mut self, events: &Events<T>) {
+        self.next = events.end();
+    }
+}
+
+impl<T: Event> Default for EventCursor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for EventCursor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            next: self.next,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for EventCursor<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventCursor")
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_read_independently_until_events_expire() {
+        let mut events = Events::new();
+        let mut early = EventCursor::new();
+        let mut late = EventCursor::new();
+
+        events.send_batch([1, 2]);
+        assert_eq!(early.read(&events).copied().collect::<Vec<_>>(), [1, 2]);
+
+        events.update();
+        events.send(3);
+        assert_eq!(early.unread(&events), 1);
+        assert_eq!(late.unread(&events), 3);
+        assert_eq!(early.read(&events).copied().collect::<Vec<_>>(), [3]);
+
+        // 1 and 2 expire before `late` reads them.
+        events.update();
+        events.update();
+        events.send(4);
+        assert_eq!(late.read(&events).copied().collect::<Vec<_>>(), [4]);
+        assert_eq!(early.read(&events).copied().collect::<Vec<_>>(), [4]);
+
+        events.send(5);
+        events.clear();
+        assert_eq!(early.unread(&events), 0);
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+//! Component hooks: callbacks run when components are added, inserted or
+//! removed.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::entity::Entity;
+use crate::world::World;
+
+/// A callback receiving the world and the entity whose component changed.
+pub(crate) type Hook = Arc<dyn Fn(&mut World, Entity) + Send + Sync>;
+
+/// The structural changes hooks run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HookKind {
+    /// A component is added to an entity that did not have it.
+    Add,
+    /// A component is added or replaced.
+    Insert,
+    /// A component is about to be replaced.
+    Replace,
+    /// A component is removed, or its entity despawned.
+    Remove,
+}
+
+#[derive(Default)]
+struct ComponentHooks {
+    add: Vec<Hook>,
+    insert: Vec<Hook>,
+    replace: Vec<Hook>,
+    remove: Vec<Hook>,
+}
+
+impl ComponentHooks {
+    fn of(&self, kind: HookKind) -> &[Hook] {
+        match kind {
+            HookKind::Add => &self.add,
+            HookKind::Insert => &self.insert,
+            HookKind::Replace => &self.replace,
+            HookKind::Remove => &self.remove,
+        }
+    }
+}
+
+/// The hooks of a world, by component.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    components: HashMap<TypeId, ComponentHooks>,
+}
+
+impl Hooks {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, component: TypeId, kind: HookKind, hook: Hook) {
+        let hooks = self.components.entry(component).or_default();
+        match kind {
+            HookKind::Add => hooks.add.push(hook),
+            HookKind::Insert => hooks.insert.push(hook),
+            HookKind::Replace => hooks.replace.push(hook),
+            HookKind::Remove => hooks.remove.push(hook),
+        }
+    }
+
+    /// Returns the hooks of `kind` of `components`, in registration order
+    /// for each component.
+    pub(crate) fn collect(&self, kind: HookKind, components: &[TypeId]) -> Vec<Hook> {
+        components
+            .iter()
+            .filter_map(|component| self.components.get(component))
+            .flat_map(|hooks| hooks.of(kind).iter().cloned())
+            .collect()
+    }
+}
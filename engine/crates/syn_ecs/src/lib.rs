@@ -1 +1,62 @@
-//! `syn_ecs` - TODO: Implement
+//! `syn_ecs` - Entity Component System for Synarion Engine.
+//!
+//! - [`World`] - Entities and their components, stored in archetype tables
+//! - [`Entity`] - Generational entity ids
+//! - [`Bundle`] - Tuples of components spawned or inserted together
+//! - [`Query`] - Typed iteration such as `Query<(&A, &mut B), (With<C>, Without<D>)>`
+//! - [`Added`] / [`Changed`] - Filters on per-component change [`Tick`]s, with
+//!   removal tracking and component hooks on the [`World`]
+//! - [`Archetype`] - A table of entities sharing the same component types
+//! - [`Resource`] - Values stored once per world
+//! - [`Commands`] - Structural changes recorded through a shared world and
+//!   applied at sync points
+//! - [`Relationship`] / [`RelationshipTarget`] - Links between entities
+//!   mirrored on their targets, such as [`ChildOf`] and [`Children`]
+//! - [`Events`] / [`EventCursor`] - Double-buffered typed events, read by
+//!   each reader through its own cursor
+//! - [`Snapshot`] / [`SnapshotDiff`] - Components of a [`SnapshotSchema`]
+//!   copied as bytes, diffed, checksummed and restored with stable entity ids
+//! - [`System`] / [`FnSystem`] - Logic declaring the data it reads and writes
+//! - [`Schedule`] - Systems run in parallel on a task pool, with ordering
+//!   constraints, sets and run conditions
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+mod access;
+mod archetype;
+mod bundle;
+mod change;
+mod command;
+mod component;
+mod entity;
+mod error;
+mod event;
+mod hook;
+mod query;
+mod relation;
+mod resource;
+mod schedule;
+mod snapshot;
+mod system;
+mod world;
+
+pub use access::Access;
+pub use archetype::{Archetype, ArchetypeId};
+pub use bundle::Bundle;
+pub use change::{Mut, Tick};
+pub use command::{CommandQueue, Commands};
+pub use component::{Component, ComponentInfo};
+pub use entity::Entity;
+pub use error::{EcsError, EcsResult};
+pub use event::{Event, EventCursor, Events};
+pub use query::{
+    Added, ArchetypeFilter, Changed, Query, QueryData, QueryFilter, QueryIter, With, Without,
+};
+pub use relation::{ChildOf, Children, Relationship, RelationshipTarget};
+pub use resource::Resource;
+pub use schedule::{Ambiguity, Schedule, SetConfig, SystemConfig};
+pub use snapshot::{Snapshot, SnapshotComponent, SnapshotDiff, SnapshotSchema};
+pub use system::{FnSystem, System};
+pub use world::{Ref, RefMut, World};
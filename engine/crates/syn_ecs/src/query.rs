@@ -0,0 +1,759 @@
+//! Typed queries over the entities of a world.
+
+use std::any::TypeId;
+use std::marker::PhantomData;
+use std::slice;
+
+use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard};
+
+use crate::access::Access;
+use crate::archetype::{Archetype, ArchetypeId};
+use crate::change::{ComponentTicks, Mut, Tick};
+use crate::component::Component;
+use crate::entity::Entity;
+use crate::world::World;
+
+/// Data a [`Query`] fetches for each entity: `&T`, `&mut T`, `Option<&T>`,
+/// `Option<&mut T>`, [`Entity`], or a tuple of these.
+///
+/// `&mut T` fetches a [`Mut<T>`](Mut), which marks the component changed when
+/// it is written.
+pub trait QueryData {
+    /// The value produced for one entity.
+    type Item<'q>;
+    /// The columns borrowed from one archetype.
+    #[doc(hidden)]
+    type Fetch<'w>;
+    /// Iterates over the rows of one archetype.
+    #[doc(hidden)]
+    type Iter<'q>: Iterator<Item = Self::Item<'q>>;
+
+    /// Records the components the data borrows.
+    fn access(access: &mut Access);
+
+    /// Returns `true` if entities of `archetype` have the data.
+    fn matches(archetype: &Archetype) -> bool;
+
+    /// Borrows the columns of `archetype`, marking components written through
+    /// the fetch changed at `tick`.
+    #[doc(hidden)]
+    fn fetch(archetype: &Archetype, tick: Tick) -> Self::Fetch<'_>;
+
+    /// Iterates over the `len` rows of a fetch.
+    #[doc(hidden)]
+    fn iter<'q>(fetch: &'q mut Self::Fetch<'_>, len: usize) -> Self::Iter<'q>;
+
+    /// Returns the item of one row of a fetch.
+    #[doc(hidden)]
+    fn get<'q>(fetch: &'q mut Self::Fetch<'_>, row: usize) -> Self::Item<'q>;
+}
+
+impl QueryData for Entity {
+    type Item<'q> = Entity;
+    type Fetch<'w> = &'w [Entity];
+    type Iter<'q> = std::iter::Copied<slice::Iter<'q, Entity>>;
+
+    fn access(_access: &mut Access) {}
+
+    fn matches(_archetype: &Archetype) -> bool {
+        true
+    }
+
+    fn fetch(archetype: &Archetype, _tick: Tick) -> Self::Fetch<'_> {
+        archetype.entities()
+    }
+
+    fn iter<'q>(fetch: &'q mut Self::Fetch<'_>, _len: usize) -> Self::Iter<'q> {
+        fetch.iter().copied()
+    }
+
+    fn get<'q>(fetch: &'q mut Self::Fetch<'_>, row: usize) -> Self::Item<'q> {
+        fetch[row]
+    }
+}
+
+impl<T: Component> QueryData for &T {
+    type Item<'q> = &'q T;
+    type Fetch<'w> = MappedRwLockReadGuard<'w, Vec<T>>;
+    type Iter<'q> = slice::Iter<'q, T>;
+
+    fn access(access: &mut Access) {
+        access.read::<T>();
+    }
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.contains(TypeId::of::<T>())
+    }
+
+    fn fetch(archetype: &Archetype, _tick: Tick) -> Self::Fetch<'_> {
+        archetype.read()
+    }
+
+    fn iter<'q>(fetch: &'q mut Self::Fetch<'_>, _len: usize) -> Self::Iter<'q> {
+        fetch.iter()
+    }
+
+    fn get<'q>(fetch: &'q mut Self::Fetch<'_>, row: usize) -> Self::Item<'q> {
+        &fetch[row]
+    }
+}
+
+impl<T: Component> QueryData for &mut T {
+    type Item<'q> = Mut<'q, T>;
+    type Fetch<'w> = MutFetch<'w, T>;
+    type Iter<'q> = MutIter<'q, T>;
+
+    fn access(access: &mut Access) {
+        access.write::<T>();
+    }
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.contains(TypeId::of::<T>())
+    }
+
+    fn fetch(archetype: &Archetype, tick: Tick) -> Self::Fetch<'_> {
+        MutFetch {
+            values: archetype.write(),
+            ticks: archetype.ticks::<T>(),
+            tick,
+        }
+    }
+
+    fn iter<'q>(fetch: &'q mut Self::Fetch<'_>, _len: usize) -> Self::Iter<'q> {
+        MutIter {
+            values: fetch.values.iter_mut(),
+            ticks: fetch.ticks.iter(),
+            tick: fetch.tick,
+        }
+    }
+
+    fn get<'q>(fetch: &'q mut Self::Fetch<'_>, row: usize) -> Self::Item<'q> {
+        Mut::new(&mut fetch.values[row], &fetch.ticks[row], fetch.tick)
+    }
+}
+
+/// A column borrowed mutably, with the change ticks of its rows.
+#[doc(hidden)]
+pub struct MutFetch<'w, T> {
+    values: MappedRwLockWriteGuard<'w, Vec<T>>,
+    ticks: &'w [ComponentTicks],
+    tick: Tick,
+}
+
+/// Iterates over the rows of a column borrowed mutably.
+#[doc(hidden)]
+pub struct MutIter<'q, T> {
+    values: slice::IterMut<'q, T>,
+    ticks: slice::Iter<'q, ComponentTicks>,
+    tick: Tick,
+}
+
+impl<'q, T> Iterator for MutIter<'q, T> {
+    type Item = Mut<'q, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(Mut::new(self.values.next()?, self.ticks.next()?, self.tick))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl<D: QueryData> QueryData for Option<D> {
+    type Item<'q> = Option<D::Item<'q>>;
+    type Fetch<'w> = Option<D::Fetch<'w>>;
+    type Iter<'q> = OptionIter<D::Iter<'q>>;
+
+    fn access(access: &mut Access) {
+        D::access(access);
+    }
+
+    fn matches(_archetype: &Archetype) -> bool {
+        true
+    }
+
+    fn fetch(archetype: &Archetype, tick: Tick) -> Self::Fetch<'_> {
+        D::matches(archetype).then(|| D::fetch(archetype, tick))
+    }
+
+    fn iter<'q>(fetch: &'q mut Self::Fetch<'_>, len: usize) -> Self::Iter<'q> {
+        OptionIter {
+            inner: fetch.as_mut().map(|fetch| D::iter(fetch, len)),
+            remaining: len,
+        }
+    }
+
+    fn get<'q>(fetch: &'q mut Self::Fetch<'_>, row: usize) -> Self::Item<'q> {
+        fetch.as_mut().map(|fetch| D::get(fetch, row))
+    }
+}
+
+/// Iterates over optional data, yielding `None` for every row of archetypes
+/// without it.
+#[doc(hidden)]
+pub struct OptionIter<I> {
+    inner: Option<I>,
+    remaining: usize,
+}
+
+impl<I: Iterator> Iterator for OptionIter<I> {
+    type Item = Option<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        Some(self.inner.as_mut().and_then(Iterator::next))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// Iterates over several columns in lockstep.
+#[doc(hidden)]
+pub struct TupleIter<T>(T);
+
+macro_rules! impl_query_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: QueryData),*> QueryData for ($($name,)*) {
+            type Item<'q> = ($($name::Item<'q>,)*);
+            type Fetch<'w> = ($($name::Fetch<'w>,)*);
+            type Iter<'q> = TupleIter<($($name::Iter<'q>,)*)>;
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn matches(archetype: &Archetype) -> bool {
+                $($name::matches(archetype))&&*
+            }
+
+            fn fetch(archetype: &Archetype, tick: Tick) -> Self::Fetch<'_> {
+                ($($name::fetch(archetype, tick),)*)
+            }
+
+            // RATIONALE: The fetches reuse the type parameter names.
+            #[allow(non_snake_case)]
+            fn iter<'q>(fetch: &'q mut Self::Fetch<'_>, len: usize) -> Self::Iter<'q> {
+                let ($($name,)*) = fetch;
+                TupleIter(($($name::iter($name, len),)*))
+            }
+
+            // RATIONALE: The fetches reuse the type parameter names.
+            #[allow(non_snake_case)]
+            fn get<'q>(fetch: &'q mut Self::Fetch<'_>, row: usize) -> Self::Item<'q> {
+                let ($($name,)*) = fetch;
+                ($($name::get($name, row),)*)
+            }
+        }
+
+        // RATIONALE: The iterators reuse the type parameter names.
+        #[allow(non_snake_case)]
+        impl<$($name: Iterator),*> Iterator for TupleIter<($($name,)*)> {
+            type Item = ($($name::Item,)*);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let ($($name,)*) = &mut self.0;
+                Some(($($name.next()?,)*))
+            }
+        }
+    };
+}
+
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+impl_query_tuple!(A, B, C, D, E, F, G);
+impl_query_tuple!(A, B, C, D, E, F, G, H);
+
+/// Restricts the entities a [`Query`] visits without fetching data:
+/// [`With`], [`Without`], [`Added`], [`Changed`], or a tuple of these that
+/// must all match.
+pub trait QueryFilter {
+    /// The change ticks borrowed from one archetype.
+    #[doc(hidden)]
+    type Fetch<'w>;
+
+    /// `true` if the filter keeps every entity of the archetypes it matches.
+    #[doc(hidden)]
+    const ARCHETYPAL: bool;
+
+    /// Returns `true` if entities of `archetype` may pass the filter.
+    fn matches(archetype: &Archetype) -> bool;
+
+    /// Borrows the change ticks of `archetype`.
+    #[doc(hidden)]
+    fn fetch(archetype: &Archetype) -> Self::Fetch<'_>;
+
+    /// Returns `true` if the entity at `row` of a fetch passes the filter,
+    /// comparing change ticks against `since`.
+    #[doc(hidden)]
+    fn keep(fetch: &Self::Fetch<'_>, row: usize, since: Tick) -> bool;
+}
+
+/// A [`QueryFilter`] keeping or rejecting whole archetypes, so queries using
+/// it know their length without visiting entities.
+pub trait ArchetypeFilter: QueryFilter {}
+
+/// Keeps entities having the component `T`.
+pub struct With<T>(PhantomData<fn() -> T>);
+
+impl<T: Component> QueryFilter for With<T> {
+    type Fetch<'w> = ();
+    const ARCHETYPAL: bool = true;
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.contains(TypeId::of::<T>())
+    }
+
+    fn fetch(_archetype: &Archetype) -> Self::Fetch<'_> {}
+
+    fn keep(_fetch: &Self::Fetch<'_>, _row: usize, _since: Tick) -> bool {
+        true
+    }
+}
+
+impl<T: Component> ArchetypeFilter for With<T> {}
+
+/// Keeps entities without the component `T`.
+pub struct Without<T>(PhantomData<fn() -> T>);
+
+impl<T: Component> QueryFilter for Without<T> {
+    type Fetch<'w> = ();
+    const ARCHETYPAL: bool = true;
+
+    fn matches(archetype: &Archetype) -> bool {
+        !archetype.contains(TypeId::of::<T>())
+    }
+
+    fn fetch(_archetype: &Archetype) -> Self::Fetch<'_> {}
+
+    fn keep(_fetch: &Self::Fetch<'_>, _row: usize, _since: Tick) -> bool {
+        true
+    }
+}
+
+impl<T: Component> ArchetypeFilter for Without<T> {}
+
+/// The change ticks of one column, fetched by [`Added`] and [`Changed`].
+#[doc(hidden)]
+pub struct TickColumn<'w>(&'w [ComponentTicks]);
+
+/// Keeps entities whose `T` was added after the query's
+/// [`since`](Query::since) tick.
+///
+/// The filter reads change ticks without borrowing `T`: systems using it
+/// should still declare a read of `T`, so the schedule orders them against
+/// the systems writing it.
+pub struct Added<T>(PhantomData<fn() -> T>);
+
+impl<T: Component> QueryFilter for Added<T> {
+    type Fetch<'w> = TickColumn<'w>;
+    const ARCHETYPAL: bool = false;
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.contains(TypeId::of::<T>())
+    }
+
+    fn fetch(archetype: &Archetype) -> Self::Fetch<'_> {
+        TickColumn(archetype.ticks::<T>())
+    }
+
+    fn keep(fetch: &Self::Fetch<'_>, row: usize, since: Tick) -> bool {
+        fetch.0[row].added() > since
+    }
+}
+
+/// Keeps entities whose `T` was added or written after the query's
+/// [`since`](Query::since) tick.
+///
+/// Components are written by inserting them, through [`World::get_mut`], or
+/// by dereferencing a [`Mut`] mutably. Like [`Added`], the filter does not
+/// borrow `T`.
+pub struct Changed<T>(PhantomData<fn() -> T>);
+
+impl<T: Component> QueryFilter for Changed<T> {
+    type Fetch<'w> = TickColumn<'w>;
+    const ARCHETYPAL: bool = false;
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.contains(TypeId::of::<T>())
+    }
+
+    fn fetch(archetype: &Archetype) -> Self::Fetch<'_> {
+        TickColumn(archetype.ticks::<T>())
+    }
+
+    fn keep(fetch: &Self::Fetch<'_>, row: usize, since: Tick) -> bool {
+        fetch.0[row].changed() > since
+    }
+}
+
+macro_rules! impl_filter_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: QueryFilter),*> QueryFilter for ($($name,)*) {
+            type Fetch<'w> = ($($name::Fetch<'w>,)*);
+            const ARCHETYPAL: bool = true $(&& $name::ARCHETYPAL)*;
+
+            fn matches(_archetype: &Archetype) -> bool {
+                true $(&& $name::matches(_archetype))*
+            }
+
+            // RATIONALE: The empty filter fetches the unit tuple.
+            #[allow(clippy::unused_unit)]
+            fn fetch(_archetype: &Archetype) -> Self::Fetch<'_> {
+                ($($name::fetch(_archetype),)*)
+            }
+
+            // RATIONALE: The fetches reuse the type parameter names.
+            #[allow(non_snake_case)]
+            fn keep(fetch: &Self::Fetch<'_>, _row: usize, _since: Tick) -> bool {
+                let ($($name,)*) = fetch;
+                true $(&& $name::keep($name, _row, _since))*
+            }
+        }
+
+        impl<$($name: ArchetypeFilter),*> ArchetypeFilter for ($($name,)*) {}
+    };
+}
+
+impl_filter_tuple!();
+impl_filter_tuple!(A);
+impl_filter_tuple!(A, B);
+impl_filter_tuple!(A, B, C);
+impl_filter_tuple!(A, B, C, D);
+impl_filter_tuple!(A, B, C, D, E);
+impl_filter_tuple!(A, B, C, D, E, F);
+impl_filter_tuple!(A, B, C, D, E, F, G);
+impl_filter_tuple!(A, B, C, D, E, F, G, H);
+
+/// The columns and change ticks a query borrowed from one archetype.
+struct Matched<'w, D: QueryData, F: QueryFilter> {
+    archetype: ArchetypeId,
+    len: usize,
+    fetch: D::Fetch<'w>,
+    filter: F::Fetch<'w>,
+}
+
+/// The entities of a [`World`] matching `D` and the filter `F`, created by
+/// [`World::query`] and [`World::query_filtered`].
+///
+/// The query borrows the columns it fetches until it is dropped: `&T` shares
+/// them with other queries, `&mut T` borrows them exclusively. Borrowing a
+/// column already borrowed mutably panics, like a `RefCell`.
+///
+/// [`Added`] and [`Changed`] filters compare change ticks against
+/// [`World::last_change_tick`], unless [`since`](Self::since) gives another
+/// tick.
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{Entity, With, Without, World};
+///
+/// struct Position(f32);
+/// struct Velocity(f32);
+/// struct Frozen;
+/// struct Player;
+///
+/// let mut world = World::new();
+/// world.spawn((Position(0.0), Velocity(1.0), Player));
+/// world.spawn((Position(0.0), Velocity(2.0)));
+/// world.spawn((Position(0.0), Velocity(3.0), Frozen));
+///
+/// let mut moving = world.query_filtered::<(&mut Position, &Velocity), Without<Frozen>>();
+/// for (mut position, velocity) in &mut moving {
+///     position.0 += velocity.0;
+/// }
+/// drop(moving);
+///
+/// let mut players = world.query_filtered::<(Entity, &Position, Option<&Frozen>), With<Player>>();
+/// let (_, position, frozen) = players.iter().next().unwrap();
+/// assert_eq!(position.0, 1.0);
+/// assert!(frozen.is_none());
+/// ```
+pub struct Query<'w, D: QueryData, F: QueryFilter = ()> {
+    world: &'w World,
+    matched: Vec<Matched<'w, D, F>>,
+    since: Tick,
+}
+
+impl<'w, D: QueryData, F: QueryFilter> Query<'w, D, F> {
+    /// Borrows the columns of every non-empty archetype matching the query.
+    pub(crate) fn new(world: &'w World) -> Self {
+        let mut access = Access::new();
+        D::access(&mut access);
+        if let Some(component) = access.conflict() {
+            panic!("query borrows `{component}` mutably and again");
+        }
+        let tick = world.change_tick();
+        let matched = world
+            .archetypes()
+            .iter()
+            .filter(|archetype| {
+                !archetype.is_empty() && D::matches(archetype) && F::matches(archetype)
+            })
+            .map(|archetype| Matched {
+                archetype: archetype.id(),
+                len: archetype.len(),
+                fetch: D::fetch(archetype, tick),
+                filter: F::fetch(archetype),
+            })
+            .collect();
+        Self {
+            world,
+            matched,
+            since: world.last_change_tick(),
+        }
+    }
+
+    /// Makes [`Added`] and [`Changed`] filters keep components added or
+    /// changed after `tick` rather than after
+    /// [`World::last_change_tick`].
+    #[must_use]
+    pub fn since(mut self, tick: Tick) -> Self {
+        self.since = tick;
+        self
+    }
+
+    /// Iterates over the matching entities, archetype by archetype.
+    pub fn iter(&mut self) -> QueryIter<'_, 'w, D, F> {
+        let remaining = self.matched.iter().map(|matched| matched.len).sum();
+        QueryIter {
+            matched: self.matched.iter_mut(),
+            current: None,
+            row: 0,
+            remaining,
+            since: self.since,
+        }
+    }
+
+    /// Returns the data of `entity`, or `None` if it does not match.
+    pub fn get(&mut self, entity: Entity) -> Option<D::Item<'_>> {
+        let location = self.world.location(entity)?;
+        let matched = self
+            .matched
+            .iter_mut()
+            .find(|matched| matched.archetype == location.archetype)?;
+        F::keep(&matched.filter, location.row, self.since)
+            .then(|| D::get(&mut matched.fetch, location.row))
+    }
+
+    /// Returns the number of matching entities.
+    ///
+    /// Queries with [`Added`] or [`Changed`] filters count them by visiting
+    /// the entities of every matching archetype.
+    pub fn len(&self) -> usize {
+        self.matched
+            .iter()
+            .map(|matched| {
+                if F::ARCHETYPAL {
+                    matched.len
+                } else {
+                    (0..matched.len)
+                        .filter(|row| F::keep(&matched.filter, *row, self.since))
+                        .count()
+                }
+            })
+            .sum()
+    }
+
+    /// Returns `true` if no entity matches.
+    pub fn is_empty(&self) -> bool {
+        if F::ARCHETYPAL {
+            return self.matched.is_empty();
+        }
+        !self
+            .matched
+            .iter()
+            .any(|matched| (0..matched.len).any(|row| F::keep(&matched.filter, row, self.since)))
+    }
+}
+
+// RATIONALE: `Query::iter` takes `&mut self` for every query, as items may be
+// mutable, so there is no separate `iter_mut`.
+#[allow(clippy::into_iter_without_iter)]
+impl<'q, 'w, D: QueryData, F: QueryFilter> IntoIterator for &'q mut Query<'w, D, F> {
+    type Item = D::Item<'q>;
+    type IntoIter = QueryIter<'q, 'w, D, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the entities of a [`Query`].
+pub struct QueryIter<'q, 'w, D: QueryData, F: QueryFilter = ()> {
+    matched: slice::IterMut<'q, Matched<'w, D, F>>,
+    current: Option<(D::Iter<'q>, &'q F::Fetch<'w>)>,
+    /// The row of the next item of `current`.
+    row: usize,
+    /// The rows left to visit, kept or not.
+    remaining: usize,
+    since: Tick,
+}
+
+impl<'q, D: QueryData, F: QueryFilter> Iterator for QueryIter<'q, '_, D, F> {
+    type Item = D::Item<'q>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = self
+                .current
+                .as_mut()
+                .and_then(|(items, filter)| Some((items.next()?, *filter)));
+            if let Some((item, filter)) = next {
+                let row = self.row;
+                self.row += 1;
+                self.remaining -= 1;
+                if F::keep(filter, row, self.since) {
+                    return Some(item);
+                }
+                continue;
+            }
+            let Matched {
+                len, fetch, filter, ..
+            } = self.matched.next()?;
+            self.current = Some((D::iter(fetch, *len), &*filter));
+            self.row = 0;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let lower = if F::ARCHETYPAL { self.remaining } else { 0 };
+        (lower, Some(self.remaining))
+    }
+}
+
+impl<D: QueryData, F: ArchetypeFilter> ExactSizeIterator for QueryIter<'_, '_, D, F> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Position(i32);
+    struct Velocity(i32);
+    struct Frozen;
+
+    fn world() -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        let entities = vec![
+            world.spawn((Position(0), Velocity(1))),
+            world.spawn((Position(0), Velocity(2), Frozen)),
+            world.spawn((Position(0),)),
+            world.spawn((Velocity(4),)),
+        ];
+        (world, entities)
+    }
+
+    #[test]
+    fn queries_match_filters_and_optional_components() {
+        let (world, entities) = world();
+
+        let mut moving = world.query_filtered::<(&mut Position, &Velocity), Without<Frozen>>();
+        assert_eq!(moving.len(), 1);
+        for (mut position, velocity) in &mut moving {
+            position.0 += velocity.0;
+        }
+        drop(moving);
+
+        let mut positions = world.query::<(Entity, &Position, Option<&Velocity>)>();
+        let mut seen: Vec<_> = positions
+            .iter()
+            .map(|(entity, position, velocity)| (entity, position.0, velocity.map(|v| v.0)))
+            .collect();
+        seen.sort_by_key(|(entity, _, _)| *entity);
+        assert_eq!(
+            seen,
+            [
+                (entities[0], 1, Some(1)),
+                (entities[1], 0, Some(2)),
+                (entities[2], 0, None),
+            ]
+        );
+        assert!(positions.get(entities[3]).is_none());
+        assert_eq!(positions.get(entities[2]).unwrap().1.0, 0);
+        drop(positions);
+
+        let mut frozen =
+            world.query_filtered::<Option<&mut Velocity>, (With<Frozen>, With<Position>)>();
+        let mut velocity = frozen.get(entities[1]).unwrap().unwrap();
+        velocity.0 = 0;
+        assert!(frozen.get(entities[0]).is_none());
+        drop(frozen);
+        assert_eq!(world.get::<Velocity>(entities[1]).unwrap().0, 0);
+        assert_eq!(world.query::<&Velocity>().iter().len(), 3);
+    }
+
+    #[test]
+    fn added_and_changed_filters_compare_ticks() {
+        let (mut world, entities) = world();
+        assert_eq!(world.query_filtered::<Entity, Added<Position>>().len(), 3);
+        world.clear_trackers();
+        assert!(
+            world
+                .query_filtered::<Entity, Changed<Position>>()
+                .is_empty()
+        );
+
+        let mut positions = world.query::<(Entity, &mut Position)>();
+        for (entity, mut position) in &mut positions {
+            if entity == entities[0] {
+                position.0 = 5;
+            } else {
+                assert_eq!(position.0, 0);
+            }
+        }
+        drop(positions);
+        world.insert(entities[3], (Position(1),)).unwrap();
+
+        let mut changed = world.query_filtered::<Entity, Changed<Position>>();
+        assert_eq!(changed.iter().size_hint(), (0, Some(4)));
+        let mut seen: Vec<Entity> = changed.iter().collect();
+        seen.sort();
+        assert_eq!(seen, [entities[0], entities[3]]);
+        assert_eq!(changed.len(), 2);
+        drop(changed);
+
+        let mut added = world.query_filtered::<Entity, (Added<Position>, With<Velocity>)>();
+        assert_eq!(added.iter().collect::<Vec<_>>(), [entities[3]]);
+        assert!(added.get(entities[0]).is_none());
+        drop(added);
+
+        // A reader keeping its own tick sees the changes made after it.
+        let read = world.increment_change_tick();
+        world.get_mut::<Position>(entities[2]).unwrap().0 = 7;
+        let mut since = world
+            .query_filtered::<Entity, Changed<Position>>()
+            .since(read);
+        assert_eq!(since.iter().collect::<Vec<_>>(), [entities[2]]);
+    }
+
+    #[test]
+    fn shared_borrows_coexist() {
+        let (world, _) = world();
+        let mut first = world.query::<&Position>();
+        let mut second = world.query::<(&Position, &Velocity)>();
+        assert_eq!(first.iter().count(), 3);
+        assert_eq!(second.iter().count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn mutable_borrows_are_exclusive() {
+        let (world, _) = world();
+        let _positions = world.query::<&mut Position>();
+        let _ = world.query::<&Position>();
+    }
+
+    #[test]
+    #[should_panic(expected = "mutably and again")]
+    fn queries_cannot_alias_a_mutable_borrow() {
+        let (world, _) = world();
+        let _ = world.query::<(&mut Position, Option<&Position>)>();
+    }
+}
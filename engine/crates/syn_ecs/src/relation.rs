@@ -0,0 +1,422 @@
+//! Relationships: components linking entities, mirrored on their targets.
+
+use std::any::{TypeId, type_name};
+use std::collections::HashSet;
+use std::ops::Deref;
+
+use crate::component::Component;
+use crate::entity::Entity;
+use crate::error::{EcsError, EcsResult};
+use crate::query::With;
+use crate::world::World;
+
+/// A component linking its entity, the source, to a target entity, such as
+/// [`ChildOf`].
+///
+/// Once registered with [`World::add_relationship`], the world lists the
+/// sources of each target in its [`Target`](Self::Target) component, and
+/// keeps both sides consistent as relationships are inserted, replaced and
+/// removed, and as entities are despawned.
+pub trait Relationship: Component {
+    /// The component listing the sources of a target.
+    type Target: RelationshipTarget<Relationship = Self>;
+
+    /// Whether following targets must never lead back to an entity, as in
+    /// hierarchies.
+    ///
+    /// Links closing a loop are rejected, and
+    /// [`World::check_relationship`] reports loops.
+    const ACYCLIC: bool = false;
+
+    /// Returns the entity the source is linked to.
+    fn target(&self) -> Entity;
+}
+
+/// The component listing the sources of a [`Relationship`] targeting its
+/// entity, such as [`Children`].
+///
+/// The world inserts it when the first source links to an entity, and
+/// removes it when the last source unlinks.
+pub trait RelationshipTarget: Component + Default {
+    /// The component linking sources to this target.
+    type Relationship: Relationship<Target = Self>;
+
+    /// Whether removing the component, for instance by despawning its
+    /// entity, despawns the sources rather than just unlinking them.
+    const LINKED_DESPAWN: bool = false;
+
+    /// Returns the sources, in the order they were linked.
+    fn sources(&self) -> &[Entity];
+
+    /// Returns the sources mutably, for the world to maintain.
+    ///
+    /// Changing them directly breaks the relationship; insert or remove the
+    /// [`Relationship`](Self::Relationship) component instead.
+    fn sources_mut(&mut self) -> &mut Vec<Entity>;
+}
+
+/// Makes its entity a child of another, listed in the parent's [`Children`].
+///
+/// Hierarchies are acyclic, and despawning a parent despawns its children.
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{ChildOf, Children, World};
+///
+/// let mut world = World::new();
+/// world.add_relationship::<ChildOf>();
+///
+/// let ship = world.spawn(());
+/// let turret = world.spawn((ChildOf(ship),));
+/// let barrel = world.spawn((ChildOf(turret),));
+/// assert_eq!(world.sources::<ChildOf>(ship), [turret]);
+/// assert_eq!(world.descendants::<ChildOf>(ship), [turret, barrel]);
+/// assert_eq!(world.ancestors::<ChildOf>(barrel), [turret, ship]);
+///
+/// world.despawn(ship);
+/// assert!(!world.contains(barrel));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChildOf(pub Entity);
+
+impl Relationship for ChildOf {
+    type Target = Children;
+
+    const ACYCLIC: bool = true;
+
+    fn target(&self) -> Entity {
+        self.0
+    }
+}
+
+/// The children of an entity, in the order they were attached, maintained
+/// from their [`ChildOf`] components.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Children(Vec<Entity>);
+
+impl RelationshipTarget for Children {
+    type Relationship = ChildOf;
+
+    const LINKED_DESPAWN: bool = true;
+
+    fn sources(&self) -> &[Entity] {
+        &self.0
+    }
+
+    fn sources_mut(&mut self) -> &mut Vec<Entity> {
+        &mut self.0
+    }
+}
+
+impl Deref for Children {
+    type Target = [Entity];
+
+    fn deref(&self) -> &[Entity] {
+        &self.0
+    }
+}
+
+impl World {
+    /// Registers the relationship `R`, maintaining the
+    /// [`Target`](Relationship::Target) components of the entities it links
+    /// to from then on.
+    ///
+    /// Register relationships before inserting them: links inserted earlier
+    /// are not mirrored on their targets. Registering twice does nothing.
+    pub fn add_relationship<R: Relationship>(&mut self) {
+        if !self.relationships.insert(TypeId::of::<R>()) {
+            return;
+        }
+        self.on_insert::<R>(link::<R>);
+        self.on_replace::<R>(unlink::<R>);
+        self.on_remove::<R>(unlink::<R>);
+        self.on_remove::<R::Target>(release::<R>);
+    }
+
+    /// Returns the target `source` is linked to by `R`, or `None` if it has
+    /// no `R`.
+    pub fn target<R: Relationship>(&self, source: Entity) -> Option<Entity> {
+        self.get::<R>(source)
+            .map(|relationship| relationship.target())
+    }
+
+    /// Returns the sources linked to `target` by `R`, in the order they were
+    /// linked.
+    pub fn sources<R: Relationship>(&self, target: Entity) -> Vec<Entity> {
+        self.get::<R::Target>(target)
+            .map(|sources| sources.sources().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Returns the targets reached by following `R` from `source`, nearest
+    /// first, stopping before any entity is repeated.
+    pub fn ancestors<R: Relationship>(&self, source: Entity) -> Vec<Entity> {
+        let mut ancestors = Vec::new();
+        let mut visited = HashSet::from([source]);
+        let mut current = source;
+        while let Some(target) = self.target::<R>(current) {
+            if !visited.insert(target) {
+                break;
+            }
+            ancestors.push(target);
+            current = target;
+        }
+        ancestors
+    }
+
+    /// Returns the sources reached by following `R` backwards from `target`,
+    /// depth first with each source before its own sources, each once.
+    pub fn descendants<R: Relationship>(&self, target: Entity) -> Vec<Entity> {
+        let mut descendants = Vec::new();
+        let mut visited = HashSet::from([target]);
+        let mut stack = self.sources::<R>(target);
+        stack.reverse();
+        while let Some(source) = stack.pop() {
+            if !visited.insert(source) {
+                continue;
+            }
+            descendants.push(source);
+            stack.extend(self.sources::<R>(source).into_iter().rev());
+        }
+        descendants
+    }
+
+    /// Checks that every `R` is listed by its target, that targets list only
+    /// their sources, and that acyclic relationships do not loop.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EcsError::BrokenRelationship`] for the first link missing
+    /// on either side, and [`EcsError::RelationshipCycle`] if an acyclic
+    /// relationship loops.
+    pub fn check_relationship<R: Relationship>(&self) -> EcsResult<()> {
+        let broken = |entity, target| EcsError::BrokenRelationship {
+            relationship: type_name::<R>(),
+            entity,
+            target,
+        };
+        for (entity, relationship) in &mut self.query::<(Entity, &R)>() {
+            let target = relationship.target();
+            let listed = self
+                .get::<R::Target>(target)
+                .is_some_and(|sources| sources.sources().contains(&entity));
+            if !listed {
+                return Err(broken(entity, target));
+            }
+        }
+        for (target, sources) in &mut self.query::<(Entity, &R::Target)>() {
+            for source in sources.sources() {
+                if self.target::<R>(*source) != Some(target) {
+                    return Err(broken(*source, target));
+                }
+            }
+        }
+        if R::ACYCLIC {
+            for entity in &mut self.query_filtered::<Entity, With<R>>() {
+                if self.closes_loop::<R>(entity, entity) {
+                    return Err(EcsError::RelationshipCycle {
+                        relationship: type_name::<R>(),
+                        entity,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if following `R` from `target` reaches `source`.
+    fn closes_loop<R: Relationship>(&self, source: Entity, target: Entity) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = target;
+        loop {
+            let Some(next) = self.target::<R>(current) else {
+                return false;
+            };
+            if next == source {
+                return true;
+            }
+            if !visited.insert(next) {
+                return false;
+            }
+            current = next;
+        }
+    }
+}
+
+/// Lists a source on its new target, rejecting links to itself, to missing
+/// entities and, for acyclic relationships, closing a loop.
+fn link<R: Relationship>(world: &mut World, source: Entity) {
+    let Some(target) = world.target::<R>(source) else {
+        return;
+    };
+    let loops = target == source || (R::ACYCLIC && world.closes_loop::<R>(source, target));
+    if loops || !world.contains(target) {
+        log::warn!(
+            "removing `{}` linking {source} to {target}: the target {}",
+            type_name::<R>(),
+            if loops {
+                "closes a loop"
+            } else {
+                "does not exist"
+            },
+        );
+        world.remove::<R>(source);
+        return;
+    }
+    if let Some(sources) = world.get_mut::<R::Target>(target) {
+        sources.sources_mut().push(source);
+    } else {
+        let mut sources = R::Target::default();
+        sources.sources_mut().push(source);
+        world.insert(target, (sources,)).expect("the target exists");
+    }
+}
+
+/// Removes a source from the list of its current target, removing the list
+/// once empty.
+fn unlink<R: Relationship>(world: &mut World, source: Entity) {
+    let Some(target) = world.target::<R>(source) else {
+        return;
+    };
+    let Some(sources) = world.get_mut::<R::Target>(target) else {
+        return;
+    };
+    let sources = sources.sources_mut();
+    let Some(index) = sources.iter().position(|entity| *entity == source) else {
+        return;
+    };
+    sources.remove(index);
+    if sources.is_empty() {
+        world.remove::<R::Target>(target);
+    }
+}
+
+/// Despawns or unlinks the sources of a target losing its list.
+fn release<R: Relationship>(world: &mut World, target: Entity) {
+    let Some(sources) = world.get_mut::<R::Target>(target) else {
+        return;
+    };
+    for source in std::mem::take(sources.sources_mut()) {
+        if world.target::<R>(source) != Some(target) {
+            continue;
+        }
+        if R::Target::LINKED_DESPAWN {
+            world.despawn(source);
+        } else {
+            world.remove::<R>(source);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An item held in an inventory, unlinked rather than despawned with it.
+    struct HeldBy(Entity);
+
+    #[derive(Default)]
+    struct Holds(Vec<Entity>);
+
+    impl Relationship for HeldBy {
+        type Target = Holds;
+
+        fn target(&self) -> Entity {
+            self.0
+        }
+    }
+
+    impl RelationshipTarget for Holds {
+        type Relationship = HeldBy;
+
+        fn sources(&self) -> &[Entity] {
+            &self.0
+        }
+
+        fn sources_mut(&mut self) -> &mut Vec<Entity> {
+            &mut self.0
+        }
+    }
+
+    #[test]
+    fn targets_list_their_sources_through_structural_changes() {
+        let mut world = World::new();
+        world.add_relationship::<ChildOf>();
+        let first = world.spawn(());
+        let second = world.spawn(());
+        let a = world.spawn((ChildOf(first),));
+        let b = world.spawn((ChildOf(first),));
+        assert_eq!(world.sources::<ChildOf>(first), [a, b]);
+
+        // Reparenting unlinks from the previous parent.
+        world.insert(a, (ChildOf(second),)).unwrap();
+        assert_eq!(world.sources::<ChildOf>(first), [b]);
+        assert_eq!(world.sources::<ChildOf>(second), [a]);
+
+        // The last child leaving removes `Children`.
+        world.remove::<ChildOf>(b);
+        assert!(!world.has::<Children>(first));
+        world.despawn(a);
+        assert!(!world.has::<Children>(second));
+
+        // Self-links, loops and missing targets are rejected.
+        world.insert(first, (ChildOf(first),)).unwrap();
+        world.insert(second, (ChildOf(first),)).unwrap();
+        world.insert(first, (ChildOf(second),)).unwrap();
+        world.insert(b, (ChildOf(a),)).unwrap();
+        assert_eq!(world.target::<ChildOf>(first), None);
+        assert_eq!(world.target::<ChildOf>(b), None);
+        assert_eq!(world.ancestors::<ChildOf>(second), [first]);
+        assert_eq!(world.check_relationship::<ChildOf>(), Ok(()));
+    }
+
+    #[test]
+    fn removing_targets_despawns_or_unlinks_sources() {
+        let mut world = World::new();
+        world.add_relationship::<ChildOf>();
+        world.add_relationship::<HeldBy>();
+        let player = world.spawn(());
+        let bag = world.spawn((ChildOf(player),));
+        let sword = world.spawn((HeldBy(bag),));
+        let strap = world.spawn((ChildOf(bag),));
+
+        world.commands().despawn(player);
+        world.apply_commands();
+        assert!(!world.contains(bag));
+        assert!(!world.contains(strap));
+        assert!(world.contains(sword));
+        assert!(!world.has::<HeldBy>(sword));
+        assert_eq!(world.len(), 1);
+        assert_eq!(world.check_relationship::<HeldBy>(), Ok(()));
+    }
+
+    #[test]
+    fn checks_report_links_made_before_registration() {
+        let mut world = World::new();
+        let parent = world.spawn(());
+        let child = world.spawn((ChildOf(parent),));
+        let a = world.spawn(());
+        let b = world.spawn((ChildOf(a),));
+        world.insert(a, (ChildOf(b),)).unwrap();
+        assert_eq!(
+            world.check_relationship::<ChildOf>(),
+            Err(EcsError::BrokenRelationship {
+                relationship: type_name::<ChildOf>(),
+                entity: child,
+                target: parent,
+            })
+        );
+        assert_eq!(world.descendants::<ChildOf>(a), []);
+
+        world.despawn(child);
+        world.insert(a, (Children(vec![b]),)).unwrap();
+        world.insert(b, (Children(vec![a]),)).unwrap();
+        assert!(matches!(
+            world.check_relationship::<ChildOf>(),
+            Err(EcsError::RelationshipCycle { .. })
+        ));
+        assert_eq!(world.descendants::<ChildOf>(a), [b]);
+        assert_eq!(world.ancestors::<ChildOf>(a), [b]);
+    }
+}
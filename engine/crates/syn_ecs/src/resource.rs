@@ -0,0 +1,88 @@
+//! Resources: singletons stored in the world next to the entities.
+
+use std::any::{Any, TypeId, type_name};
+use std::collections::HashMap;
+
+use parking_lot::{
+    MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+
+/// Data stored once per [`World`](crate::World) rather than per entity, such
+/// as the frame time or an asset registry.
+///
+/// Every `Send + Sync + 'static` type is a resource.
+pub trait Resource: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Resource for T {}
+
+/// The resources of a world, each locked separately so systems can borrow
+/// different resources at once.
+#[derive(Default)]
+pub(crate) struct Resources {
+    values: HashMap<TypeId, RwLock<Box<dyn Any + Send + Sync>>>,
+}
+
+impl Resources {
+    pub(crate) fn insert<R: Resource>(&mut self, value: R) -> Option<R> {
+        self.values
+            .insert(TypeId::of::<R>(), RwLock::new(Box::new(value)))
+            .map(|previous| downcast(previous.into_inner()))
+    }
+
+    pub(crate) fn remove<R: Resource>(&mut self) -> Option<R> {
+        self.values
+            .remove(&TypeId::of::<R>())
+            .map(|value| downcast(value.into_inner()))
+    }
+
+    pub(crate) fn contains<R: Resource>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<R>())
+    }
+
+    /// Locks `R` for reading.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `R` is borrowed mutably.
+    pub(crate) fn read<R: Resource>(&self) -> Option<MappedRwLockReadGuard<'_, R>> {
+        let value = self.values.get(&TypeId::of::<R>())?;
+        let guard = value
+            .try_read_recursive()
+            .unwrap_or_else(|| panic!("`{}` is already borrowed mutably", type_name::<R>()));
+        Some(RwLockReadGuard::map(guard, |value| {
+            value
+                .downcast_ref()
+                .expect("resources are keyed by type id")
+        }))
+    }
+
+    /// Locks `R` for writing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `R` is borrowed.
+    pub(crate) fn write<R: Resource>(&self) -> Option<MappedRwLockWriteGuard<'_, R>> {
+        let value = self.values.get(&TypeId::of::<R>())?;
+        let guard = value
+            .try_write()
+            .unwrap_or_else(|| panic!("`{}` is already borrowed", type_name::<R>()));
+        Some(RwLockWriteGuard::map(guard, |value| {
+            value
+                .downcast_mut()
+                .expect("resources are keyed by type id")
+        }))
+    }
+
+    pub(crate) fn get_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.values
+            .get_mut(&TypeId::of::<R>())?
+            .get_mut()
+            .downcast_mut()
+    }
+}
+
+fn downcast<R: Resource>(value: Box<dyn Any + Send + Sync>) -> R {
+    *value
+        .downcast()
+        .unwrap_or_else(|_| unreachable!("resources are keyed by type id"))
+}
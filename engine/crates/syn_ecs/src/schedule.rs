@@ -0,0 +1,911 @@
+//! Schedules: systems run in parallel wherever their accesses allow.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use syn_tasks::{Scope, TaskPool};
+
+use crate::access::Access;
+use crate::error::{EcsError, EcsResult};
+use crate::system::System;
+use crate::world::World;
+
+type Condition = Box<dyn FnMut(&World) -> bool + Send>;
+
+/// Indices of systems or sets, per system.
+type Indices = Vec<Vec<usize>>;
+
+/// Ordering constraints and run conditions of a system or a set.
+#[derive(Default)]
+struct Constraints {
+    before: Vec<String>,
+    after: Vec<String>,
+    conditions: Vec<Condition>,
+}
+
+struct SystemNode {
+    system: Box<dyn System>,
+    sets: Vec<String>,
+    constraints: Constraints,
+}
+
+struct SetNode {
+    name: String,
+    constraints: Constraints,
+}
+
+/// The systems of a schedule, ordered and checked for conflicts.
+struct Plan {
+    /// The systems that may only start once each system finished.
+    successors: Indices,
+    /// The number of systems each system waits for.
+    predecessors: Vec<usize>,
+    /// Whether two systems may not run at the same time.
+    conflicts: Vec<Vec<bool>>,
+    /// The configured sets of each system, indexing [`Schedule::sets`].
+    sets: Indices,
+    ambiguities: Vec<Ambiguity>,
+}
+
+/// Two systems that conflict but are not ordered, so they run in either
+/// order from one run to the next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    /// The system added first.
+    pub first: String,
+    /// The system added second.
+    pub second: String,
+    /// The components and resources one system writes and the other borrows.
+    pub conflicts: Vec<&'static str>,
+}
+
+impl fmt::Display for Ambiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "systems `{}` and `{}` both borrow {} without an ordering constraint",
+            self.first,
+            self.second,
+            self.conflicts.join(", ")
+        )
+    }
+}
+
+/// Systems run over a [`World`], in parallel wherever their accesses allow.
+///
+/// Each [`System`] declares the components and resources it reads and
+/// writes. Two systems conflict when one writes data the other borrows; the
+/// schedule never runs conflicting systems at the same time, and runs the
+/// others on a [`TaskPool`] as soon as their ordering constraints allow.
+///
+/// Systems are ordered with [`before`](SystemConfig::before) and
+/// [`after`](SystemConfig::after), naming a system or a set. Sets group
+/// systems under a name, so ordering constraints and run conditions of the
+/// set apply to all its members. Run conditions are evaluated on the calling
+/// thread when [`run`](Self::run) starts; skipped systems still order the
+/// systems around them.
+///
+/// Conflicting systems without an ordering constraint between them run in
+/// either order, which makes results depend on timing.
+/// [`ambiguities`](Self::ambiguities) lists them, and debug builds log them
+/// as warnings when the schedule is built.
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{FnSystem, Schedule, World};
+/// use syn_tasks::TaskPool;
+///
+/// struct Position(i32);
+/// struct Velocity(i32);
+/// struct Paused(bool);
+///
+/// let mut schedule = Schedule::new();
+/// schedule
+///     .add_system(
+///         FnSystem::new("movement", |world: &World| {
+///             for (mut position, velocity) in &mut world.query::<(&mut Position, &Velocity)>() {
+///                 position.0 += velocity.0;
+///             }
+///         })
+///         .query::<(&mut Position, &Velocity)>(),
+///     )
+///     .in_set("simulation");
+/// schedule
+///     .add_system(
+///         FnSystem::new("friction", |world: &World| {
+///             for mut velocity in &mut world.query::<&mut Velocity>() {
+///                 velocity.0 /= 2;
+///             }
+///         })
+///         .writes::<Velocity>(),
+///     )
+///     .after("movement")
+///     .in_set("simulation");
+/// schedule
+///     .configure_set("simulation")
+///     .run_if(|world: &World| !world.resource::<Paused>().unwrap().0);
+///
+/// let mut world = World::new();
+/// world.insert_resource(Paused(false));
+/// let entity = world.spawn((Position(0), Velocity(8)));
+///
+/// let pool = TaskPool::new(2);
+/// schedule.run(&mut world, &pool);
+/// schedule.run(&mut world, &pool);
+/// world.get_resource_mut::<Paused>().unwrap().0 = true;
+/// schedule.run(&mut world, &pool);
+///
+/// assert_eq!(world.get::<Position>(entity).unwrap().0, 12);
+/// assert!(schedule.ambiguities().is_empty());
+/// ```
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<SystemNode>,
+    sets: Vec<SetNode>,
+    plan: Option<Plan>,
+}
+
+impl Schedule {
+    /// Creates a schedule without systems.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a system, returning its configuration.
+    pub fn add_system(&mut self, system: impl System + 'static) -> SystemConfig<'_> {
+        self.plan = None;
+        let index = self.systems.len();
+        self.systems.push(SystemNode {
+            system: Box::new(system),
+            sets: Vec::new(),
+            constraints: Constraints::default(),
+        });
+        SystemConfig {
+            node: &mut self.systems[index],
+        }
+    }
+
+    /// Returns the configuration of the set `name`, creating the set if
+    /// needed.
+    pub fn configure_set(&mut self, name: impl Into<String>) -> SetConfig<'_> {
+        self.plan = None;
+        let name = name.into();
+        let index = if let Some(index) = self.sets.iter().position(|set| set.name == name) {
+            index
+        } else {
+            self.sets.push(SetNode {
+                name,
+                constraints: Constraints::default(),
+            });
+            self.sets.len() - 1
+        };
+        SetConfig {
+            constraints: &mut self.sets[index].constraints,
+        }
+    }
+
+    /// Returns the number of systems.
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    /// Returns `true` if the schedule has no system.
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Resolves ordering constraints and computes which systems conflict.
+    ///
+    /// [`run`](Self::run) builds the schedule when systems or sets changed;
+    /// building beforehand reports errors instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EcsError::DuplicateSystem`] if two systems have the same
+    /// name, [`EcsError::UnknownLabel`] if an ordering constraint names
+    /// neither a system nor a set, [`EcsError::OrderingCycle`] if constraints
+    /// contradict each other, and [`EcsError::InvalidAccess`] if a system
+    /// borrows data mutably and again.
+    pub fn build(&mut self) -> EcsResult<()> {
+        if self.plan.is_some() {
+            return Ok(());
+        }
+        let plan = self.plan()?;
+        #[cfg(debug_assertions)]
+        for ambiguity in &plan.ambiguities {
+            log::warn!("{ambiguity}");
+        }
+        self.plan = Some(plan);
+        Ok(())
+    }
+
+    /// Returns the conflicting systems without an ordering constraint between
+    /// them, empty until the schedule is built.
+    pub fn ambiguities(&self) -> &[Ambiguity] {
+        self.plan
+            .as_ref()
+            .map_or(&[], |plan| plan.ambiguities.as_slice())
+    }
+
+    /// Runs every system whose run conditions hold once, as jobs on the
+    /// workers of `pool`, then applies the [`Commands`](crate::Commands) they
+    /// recorded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule cannot be [built](Self::build). Resumes the
+    /// panic of a system once the systems already running finished; systems
+    /// not started yet are skipped.
+    pub fn run(&mut self, world: &mut World, pool: &TaskPool) {
+        if let Err(error) = self.build() {
+            panic!("invalid schedule: {error}");
+        }
+        let Self {
+            systems,
+            sets,
+            plan,
+        } = self;
+        let plan = plan.as_ref().expect("the schedule was just built");
+        let shared = &*world;
+
+        let sets_enabled: Vec<bool> = sets
+            .iter_mut()
+            .map(|set| check(&mut set.constraints, shared))
+            .collect();
+        let enabled: Vec<bool> = systems
+            .iter_mut()
+            .zip(&plan.sets)
+            .map(|(node, sets)| {
+                sets.iter().all(|set| sets_enabled[*set]) && check(&mut node.constraints, shared)
+            })
+            .collect();
+        let systems = systems
+            .iter_mut()
+            .map(|node| {
+                let system: &mut dyn System = node.system.as_mut();
+                Some(system)
+            })
+            .collect();
+        pool.scope(|scope| {
+            Executor::new(plan, &enabled, systems).run(scope, shared);
+        });
+        world.apply_commands();
+    }
+
+    /// Orders the systems and computes which conflict.
+    fn plan(&self) -> EcsResult<Plan> {
+        let count = self.systems.len();
+        let (successors, sets) = self.edges()?;
+
+        let mut predecessors = vec![0; count];
+        for to in successors.iter().flatten() {
+            predecessors[*to] += 1;
+        }
+        let order = self.topological_order(&successors, &predecessors)?;
+
+        // Systems reachable from each system through ordering constraints.
+        let mut reachable = vec![vec![false; count]; count];
+        for from in order.iter().rev() {
+            let mut reached = vec![false; count];
+            for to in &successors[*from] {
+                reached[*to] = true;
+                for (reached, transitive) in reached.iter_mut().zip(&reachable[*to]) {
+                    *reached |= transitive;
+                }
+            }
+            reachable[*from] = reached;
+        }
+
+        let mut accesses = Vec::with_capacity(count);
+        for node in &self.systems {
+            let mut access = Access::new();
+            node.system.access(&mut access);
+            if let Some(component) = access.conflict() {
+                return Err(EcsError::InvalidAccess {
+                    system: node.system.name().to_owned(),
+                    component,
+                });
+            }
+            accesses.push(access);
+        }
+
+        let mut conflicts = vec![vec![false; count]; count];
+        let mut ambiguities = Vec::new();
+        for first in 0..count {
+            for second in first + 1..count {
+                let mut shared = Vec::new();
+                for name in accesses[first].conflicts(&accesses[second]) {
+                    if !shared.contains(&name) {
+                        shared.push(name);
+                    }
+                }
+                if shared.is_empty() {
+                    continue;
+                }
+                conflicts[first][second] = true;
+                conflicts[second][first] = true;
+                if !reachable[first][second] && !reachable[second][first] {
+                    ambiguities.push(Ambiguity {
+                        first: self.systems[first].system.name().to_owned(),
+                        second: self.systems[second].system.name().to_owned(),
+                        conflicts: shared,
+                    });
+                }
+            }
+        }
+
+        Ok(Plan {
+            successors,
+            predecessors,
+            conflicts,
+            sets,
+            ambiguities,
+        })
+    }
+
+    /// Resolves ordering constraints into the systems that wait for each
+    /// system, and returns them with the configured sets of each system.
+    fn edges(&self) -> EcsResult<(Indices, Indices)> {
+        let mut names = HashSet::new();
+        let mut labels: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, node) in self.systems.iter().enumerate() {
+            let name = node.system.name();
+            if !names.insert(name) {
+                return Err(EcsError::DuplicateSystem(name.to_owned()));
+            }
+            for label in std::iter::once(name).chain(node.sets.iter().map(String::as_str)) {
+                let members = labels.entry(label).or_default();
+                if members.last() != Some(&index) {
+                    members.push(index);
+                }
+            }
+        }
+        for set in &self.sets {
+            labels.entry(&set.name).or_default();
+        }
+
+        let mut successors = vec![Vec::new(); self.systems.len()];
+        let mut set_indices = Vec::with_capacity(self.systems.len());
+        for (index, node) in self.systems.iter().enumerate() {
+            let indices: Vec<usize> = node
+                .sets
+                .iter()
+                .filter_map(|name| self.sets.iter().position(|set| set.name == *name))
+                .collect();
+            let constraints = std::iter::once(&node.constraints)
+                .chain(indices.iter().map(|set| &self.sets[*set].constraints));
+            for constraints in constraints {
+                for (label, before) in constraints
+                    .before
+                    .iter()
+                    .map(|label| (label, true))
+                    .chain(constraints.after.iter().map(|label| (label, false)))
+                {
+                    let others = labels
+                        .get(label.as_str())
+                        .ok_or_else(|| EcsError::UnknownLabel(label.clone()))?;
+                    for other in others {
+                        let (from, to) = if before {
+                            (index, *other)
+                        } else {
+                            (*other, index)
+                        };
+                        if !successors[from].contains(&to) {
+                            successors[from].push(to);
+                        }
+                    }
+                }
+            }
+            set_indices.push(indices);
+        }
+        Ok((successors, set_indices))
+    }
+
+    /// Returns the systems in an order satisfying every constraint.
+    fn topological_order(
+        &self,
+        successors: &[Vec<usize>],
+        predecessors: &[usize],
+    ) -> EcsResult<Vec<usize>> {
+        let mut waiting = predecessors.to_vec();
+        let mut order: Vec<usize> = (0..waiting.len()).filter(|i| waiting[*i] == 0).collect();
+        let mut next = 0;
+        while let Some(&from) = order.get(next) {
+            next += 1;
+            for to in &successors[from] {
+                waiting[*to] -= 1;
+                if waiting[*to] == 0 {
+                    order.push(*to);
+                }
+            }
+        }
+        if order.len() == waiting.len() {
+            return Ok(order);
+        }
+
+        // Systems after a cycle wait too: keep those leading back into one.
+        let mut cyclic: Vec<bool> = waiting.iter().map(|count| *count > 0).collect();
+        while let Some(sink) =
+            (0..cyclic.len()).find(|i| cyclic[*i] && !successors[*i].iter().any(|to| cyclic[*to]))
+        {
+            cyclic[sink] = false;
+        }
+        let names = (0..cyclic.len())
+            .filter(|i| cyclic[*i])
+            .map(|i| self.systems[i].system.name().to_owned())
+            .collect();
+        Err(EcsError::OrderingCycle(names))
+    }
+}
+
+/// Evaluates run conditions, stopping at the first that fails.
+fn check(constraints: &mut Constraints, world: &World) -> bool {
+    constraints
+        .conditions
+        .iter_mut()
+        .all(|condition| condition(world))
+}
+
+/// Configures a system added by [`Schedule::add_system`].
+pub struct SystemConfig<'s> {
+    node: &'s mut SystemNode,
+}
+
+impl SystemConfig<'_> {
+    /// Runs the system before the system or the members of the set `label`.
+    pub fn before(&mut self, label: impl Into<String>) -> &mut Self {
+        self.node.constraints.before.push(label.into());
+        self
+    }
+
+    /// Runs the system after the system or the members of the set `label`.
+    pub fn after(&mut self, label: impl Into<String>) -> &mut Self {
+        self.node.constraints.after.push(label.into());
+        self
+    }
+
+    /// Adds the system to the set `name`.
+    pub fn in_set(&mut self, name: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        if !self.node.sets.contains(&name) {
+            self.node.sets.push(name);
+        }
+        self
+    }
+
+    /// Runs the system only when `condition` holds.
+    pub fn run_if(&mut self, condition: impl FnMut(&World) -> bool + Send + 'static) -> &mut Self {
+        self.node.constraints.conditions.push(Box::new(condition));
+        self
+    }
+}
+
+/// Configures a set, created by [`Schedule::configure_set`].
+pub struct SetConfig<'s> {
+    constraints: &'s mut Constraints,
+}
+
+impl SetConfig<'_> {
+    /// Runs the members of the set before the system or the members of the
+    /// set `label`.
+    pub fn before(&mut self, label: impl Into<String>) -> &mut Self {
+        self.constraints.before.push(label.into());
+        self
+    }
+
+    /// Runs the members of the set after the system or the members of the
+    /// set `label`.
+    pub fn after(&mut self, label: impl Into<String>) -> &mut Self {
+        self.constraints.after.push(label.into());
+        self
+    }
+
+    /// Runs the members of the set only when `condition` holds.
+    pub fn run_if(&mut self, condition: impl FnMut(&World) -> bool + Send + 'static) -> &mut Self {
+        self.constraints.conditions.push(Box::new(condition));
+        self
+    }
+}
+
+/// Starts systems as their predecessors finish, never alongside a system they
+/// conflict with.
+struct Executor<'p, 'env> {
+    plan: &'p Plan,
+    enabled: &'p [bool],
+    systems: Vec<Option<&'env mut dyn System>>,
+    waiting: Vec<usize>,
+    ready: Vec<usize>,
+    running: Vec<usize>,
+    remaining: usize,
+}
+
+impl<'p, 'env> Executor<'p, 'env> {
+    fn new(
+        plan: &'p Plan,
+        enabled: &'p [bool],
+        systems: Vec<Option<&'env mut dyn System>>,
+    ) -> Self {
+        let waiting = plan.predecessors.clone();
+        let ready = (0..waiting.len()).filter(|i| waiting[*i] == 0).collect();
+        Self {
+            plan,
+            enabled,
+            remaining: systems.len(),
+            systems,
+            waiting,
+            ready,
+            running: Vec::new(),
+        }
+    }
+
+    fn run(mut self, scope: &Scope<'env>, world: &'env World) {
+        let (sender, finished) = mpsc::channel();
+        let mut panicked = false;
+        while self.remaining > 0 {
+            if !panicked {
+                self.start_ready(scope, world, &sender);
+            }
+            if self.running.is_empty() {
+                break;
+            }
+            let (system, completed) = finished
+                .recv()
+                .expect("running systems report when they finish");
+            self.running.retain(|running| *running != system);
+            if completed {
+                self.finish(system);
+            } else {
+                panicked = true;
+            }
+        }
+    }
+
+    /// Starts the ready systems that conflict with no running system, and
+    /// skips those whose run conditions failed.
+    fn start_ready(
+        &mut self,
+        scope: &Scope<'env>,
+        world: &'env World,
+        sender: &Sender<(usize, bool)>,
+    ) {
+        let mut index = 0;
+        while let Some(&system) = self.ready.get(index) {
+            if !self.enabled[system] {
+                self.ready.remove(index);
+                self.finish(system);
+                continue;
+            }
+            let conflicts = &self.plan.conflicts[system];
+            if self.running.iter().any(|running| conflicts[*running]) {
+                index += 1;
+                continue;
+            }
+            self.ready.remove(index);
+            self.running.push(system);
+            let run = self.systems[system].take().expect("systems run once");
+            let done = Done {
+                system,
+                sender: sender.clone(),
+            };
+            scope.spawn(move |_| {
+                run.run(world);
+                drop(done);
+            });
+        }
+    }
+
+    /// Marks a system finished, readying the systems waiting only for it.
+    fn finish(&mut self, system: usize) {
+        self.remaining -= 1;
+        for successor in &self.plan.successors[system] {
+            self.waiting[*successor] -= 1;
+            if self.waiting[*successor] == 0 {
+                self.ready.push(*successor);
+            }
+        }
+    }
+}
+
+/// Reports a system finished when dropped, including by a panic.
+struct Done {
+    system: usize,
+    sender: Sender<(usize, bool)>,
+}
+
+impl Drop for Done {
+    fn drop(&mut self) {
+        // The executor waits for every running system before returning, so
+        // the receiver is alive.
+        self.sender.send((self.system, !thread::panicking())).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::Entity;
+    use crate::event::{EventCursor, Events};
+    use crate::system::FnSystem;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    struct Position(i32);
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    fn logging(name: &'static str) -> FnSystem<impl FnMut(&World) + Send> {
+        FnSystem::new(name, move |world: &World| {
+            world.resource_mut::<Log>().unwrap().0.push(name);
+        })
+        .writes_resource::<Log>()
+    }
+
+    fn run_logged(schedule: &mut Schedule) -> Vec<&'static str> {
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        schedule.run(&mut world, &TaskPool::new(2));
+        world.remove_resource::<Log>().unwrap().0
+    }
+
+    #[test]
+    fn compatible_systems_run_in_parallel() {
+        static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+        static OVERLAPPED: AtomicBool = AtomicBool::new(false);
+        let reader = |name| {
+            FnSystem::new(name, |world: &World| {
+                assert_eq!(world.query::<&Position>().len(), 1);
+                ACTIVE.fetch_add(1, Ordering::SeqCst);
+                let start = Instant::now();
+                while start.elapsed() < Duration::from_secs(5) {
+                    if ACTIVE.load(Ordering::SeqCst) == 2 {
+                        OVERLAPPED.store(true, Ordering::SeqCst);
+                        break;
+                    }
+                    thread::yield_now();
+                }
+            })
+            .reads::<Position>()
+        };
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(reader("first"));
+        schedule.add_system(reader("second"));
+        let mut world = World::new();
+        world.spawn((Position(0),));
+        schedule.run(&mut world, &TaskPool::new(2));
+        assert!(OVERLAPPED.load(Ordering::SeqCst));
+        assert!(schedule.ambiguities().is_empty());
+    }
+
+    #[test]
+    fn systems_run_on_the_pool_workers() {
+        #[derive(Default)]
+        struct Threads(Vec<String>);
+
+        let mut schedule = Schedule::new();
+        for name in ["input", "physics", "render"] {
+            schedule.add_system(
+                FnSystem::new(name, |world: &World| {
+                    let name = thread::current().name().unwrap_or_default().to_owned();
+                    world.resource_mut::<Threads>().unwrap().0.push(name);
+                })
+                .writes_resource::<Threads>(),
+            );
+        }
+        let mut world = World::new();
+        world.insert_resource(Threads::default());
+        schedule.run(&mut world, &TaskPool::new(2));
+
+        let threads = world.remove_resource::<Threads>().unwrap().0;
+        assert_eq!(threads.len(), 3);
+        assert!(threads.iter().all(|name| name.starts_with("syn-task-")));
+    }
+
+    #[test]
+    fn conflicting_systems_never_overlap_and_are_reported() {
+        static RUNNING: AtomicBool = AtomicBool::new(false);
+        let writer = |name| {
+            FnSystem::new(name, |world: &World| {
+                assert!(!RUNNING.swap(true, Ordering::SeqCst));
+                for mut position in &mut world.query::<&mut Position>() {
+                    position.0 += 1;
+                }
+                thread::sleep(Duration::from_millis(1));
+                RUNNING.store(false, Ordering::SeqCst);
+            })
+            .writes::<Position>()
+        };
+
+        let mut schedule = Schedule::new();
+        for name in ["a", "b", "c", "d"] {
+            schedule.add_system(writer(name));
+        }
+        schedule.add_system(writer("e")).after("d");
+        schedule.build().unwrap();
+        let position = std::any::type_name::<Position>();
+        assert_eq!(schedule.ambiguities().len(), 9);
+        assert_eq!(
+            schedule.ambiguities()[0],
+            Ambiguity {
+                first: "a".to_owned(),
+                second: "b".to_owned(),
+                conflicts: vec![position],
+            }
+        );
+        assert_eq!(
+            schedule.ambiguities()[0].to_string(),
+            format!("systems `a` and `b` both borrow {position} without an ordering constraint")
+        );
+
+        let mut world = World::new();
+        let entity = world.spawn((Position(0),));
+        let pool = TaskPool::new(3);
+        for _ in 0..10 {
+            schedule.run(&mut world, &pool);
+        }
+        assert_eq!(world.get::<Position>(entity).unwrap().0, 50);
+    }
+
+    #[test]
+    fn ordering_constraints_apply_to_systems_and_sets() {
+        let mut schedule = Schedule::new();
+        schedule.add_system(logging("render")).after("physics");
+        schedule.add_system(logging("collide")).in_set("physics");
+        schedule
+            .add_system(logging("integrate"))
+            .in_set("physics")
+            .before("collide");
+        schedule.add_system(logging("input")).before("integrate");
+        schedule.configure_set("physics").after("input");
+        assert_eq!(
+            run_logged(&mut schedule),
+            ["input", "integrate", "collide", "render"]
+        );
+        assert!(schedule.ambiguities().is_empty());
+    }
+
+    #[test]
+    fn run_conditions_skip_systems_and_sets() {
+        struct Paused(bool);
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(logging("input"));
+        schedule
+            .add_system(logging("debug"))
+            .after("input")
+            .run_if(|_: &World| false);
+        schedule
+            .add_system(logging("physics"))
+            .in_set("simulation")
+            .after("debug");
+        schedule.add_system(logging("render")).after("simulation");
+        schedule
+            .configure_set("simulation")
+            .run_if(|world: &World| !world.resource::<Paused>().unwrap().0);
+
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        world.insert_resource(Paused(false));
+        let pool = TaskPool::new(2);
+        schedule.run(&mut world, &pool);
+        world.get_resource_mut::<Paused>().unwrap().0 = true;
+        schedule.run(&mut world, &pool);
+        assert_eq!(
+            world.resource::<Log>().unwrap().0,
+            ["input", "physics", "render", "input", "render"]
+        );
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        let mut schedule = Schedule::new();
+        schedule.add_system(logging("a"));
+        schedule.add_system(logging("a"));
+        assert_eq!(
+            schedule.build(),
+            Err(EcsError::DuplicateSystem("a".to_owned()))
+        );
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(logging("a")).after("missing");
+        assert_eq!(
+            schedule.build(),
+            Err(EcsError::UnknownLabel("missing".to_owned()))
+        );
+        // Configuring a set makes it a valid label, even without members.
+        schedule.configure_set("missing");
+        assert_eq!(schedule.build(), Ok(()));
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(logging("a")).before("b");
+        schedule.add_system(logging("b")).in_set("late");
+        schedule.add_system(logging("c")).after("b");
+        schedule.configure_set("late").before("a");
+        assert_eq!(
+            schedule.build(),
+            Err(EcsError::OrderingCycle(vec![
+                "a".to_owned(),
+                "b".to_owned()
+            ]))
+        );
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(
+            FnSystem::new("alias", |_: &World| {}).query::<(&mut Position, &Position)>(),
+        );
+        assert_eq!(
+            schedule.build(),
+            Err(EcsError::InvalidAccess {
+                system: "alias".to_owned(),
+                component: std::any::type_name::<Position>(),
+            })
+        );
+    }
+
+    #[test]
+    fn panicking_systems_stop_the_run() {
+        let mut schedule = Schedule::new();
+        schedule.add_system(logging("before"));
+        schedule
+            .add_system(FnSystem::new("fails", |_: &World| panic!("system failed")))
+            .after("before");
+        schedule.add_system(logging("after")).after("fails");
+
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        let pool = TaskPool::new(2);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| schedule.run(&mut world, &pool)));
+        assert!(result.is_err());
+        assert_eq!(world.resource::<Log>().unwrap().0, ["before"]);
+    }
+
+    #[test]
+    fn systems_defer_spawns_and_exchange_events() {
+        struct Hit(Entity);
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(
+            FnSystem::new("collide", |world: &World| {
+                let mut events = world.resource_mut::<Events<Hit>>().unwrap();
+                for entity in &mut world.query::<Entity>() {
+                    events.send(Hit(entity));
+                }
+            })
+            .query::<Entity>()
+            .writes_resource::<Events<Hit>>(),
+        );
+        let mut cursor = EventCursor::new();
+        schedule
+            .add_system(
+                FnSystem::new("damage", move |world: &World| {
+                    let events = world.resource::<Events<Hit>>().unwrap();
+                    let mut commands = world.commands();
+                    for hit in cursor.read(&events) {
+                        commands.despawn(hit.0);
+                        commands.spawn((Position(0),));
+                    }
+                })
+                .reads_resource::<Events<Hit>>(),
+            )
+            .after("collide");
+
+        let mut world = World::new();
+        world.add_event::<Hit>();
+        let first = world.spawn((Position(0),));
+        let pool = TaskPool::new(2);
+        schedule.run(&mut world, &pool);
+        assert!(!world.contains(first));
+        assert_eq!(world.len(), 1);
+
+        // The cursor does not read the first frame's hit again.
+        world.update_events();
+        schedule.run(&mut world, &pool);
+        assert_eq!(world.len(), 1);
+        assert_eq!(world.resource::<Events<Hit>>().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,912 @@
+//! Snapshots: components of chosen types copied out of a world as bytes,
+//! compared with each other, and restored with the same entity ids.
+
+use std::any::{Any, type_name};
+use std::cmp::Ordering;
+use std::fmt;
+
+use syn_collections::SlotLayout;
+
+use crate::component::Component;
+use crate::entity::Entity;
+use crate::error::{EcsError, EcsResult};
+use crate::relation::{ChildOf, Children, RelationshipTarget};
+use crate::world::World;
+
+/// A component stored in [`Snapshot`]s as bytes.
+///
+/// Encodings should not depend on pointers or hash map order, so that equal
+/// components encode to equal bytes across machines and
+/// [`Snapshot::checksum`] detects desyncs.
+pub trait SnapshotComponent: Component + Sized {
+    /// Appends the component's bytes to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decodes a component from the bytes [`encode`](Self::encode) wrote, or
+    /// returns `None` if they are invalid.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+impl SnapshotComponent for ChildOf {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.0.to_bits().to_le_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        Some(Self(Entity::from_bits(u64::from_le_bytes(
+            bytes.try_into().ok()?,
+        ))))
+    }
+}
+
+impl SnapshotComponent for Children {
+    fn encode(&self, out: &mut Vec<u8>) {
+        for child in self.sources() {
+            out.extend_from_slice(&child.to_bits().to_le_bytes());
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let chunks = bytes.chunks_exact(8);
+        if !chunks.remainder().is_empty() {
+            return None;
+        }
+        let mut children = Self::default();
+        children.sources_mut().extend(chunks.map(|chunk| {
+            Entity::from_bits(u64::from_le_bytes(
+                chunk.try_into().expect("chunks are 8 bytes"),
+            ))
+        }));
+        Some(children)
+    }
+}
+
+type Decoded = Vec<(Entity, Box<dyn Any + Send>)>;
+
+/// How to capture and restore one component type.
+#[derive(Clone, Copy)]
+struct SchemaComponent {
+    name: &'static str,
+    capture: fn(&World) -> Column,
+    decode: fn(&[u8]) -> Option<Box<dyn Any + Send>>,
+    restore: fn(&mut World, Decoded),
+}
+
+/// The component types a [`Snapshot`] captures.
+///
+/// Components of other types are left alone: restoring a snapshot keeps the
+/// ones entities already have.
+#[derive(Clone, Default)]
+pub struct SnapshotSchema {
+    components: Vec<SchemaComponent>,
+}
+
+impl SnapshotSchema {
+    /// Creates a schema capturing no component.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Captures the `T` components too.
+    #[must_use]
+    pub fn with<T: SnapshotComponent>(mut self) -> Self {
+        let name = type_name::<T>();
+        if self
+            .components
+            .iter()
+            .all(|component| component.name != name)
+        {
+            self.components.push(SchemaComponent {
+                name,
+                capture: capture::<T>,
+                decode: decode::<T>,
+                restore: restore::<T>,
+            });
+        }
+        self
+    }
+
+    /// Returns an error unless `columns` hold the components of this schema.
+    fn check(&self, columns: &[Column]) -> EcsResult<()> {
+        let matches = self.components.len() == columns.len()
+            && self
+                .components
+                .iter()
+                .zip(columns)
+                .all(|(component, column)| component.name == column.name);
+        if matches {
+            Ok(())
+        } else {
+            Err(EcsError::SnapshotMismatch)
+        }
+    }
+}
+
+impl fmt::Debug for SnapshotSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.components.iter().map(|component| component.name))
+            .finish()
+    }
+}
+
+fn capture<T: SnapshotComponent>(world: &World) -> Column {
+    let mut query = world.query::<(Entity, &T)>();
+    let mut rows: Vec<_> = query.iter().collect();
+    rows.sort_unstable_by_key(|(entity, _)| *entity);
+    let mut column = Column::new(type_name::<T>().to_owned());
+    for (entity, component) in rows {
+        component.encode(&mut column.bytes);
+        column.entities.push(entity);
+        column.ends.push(column.bytes.len());
+    }
+    column
+}
+
+fn decode<T: SnapshotComponent>(bytes: &[u8]) -> Option<Box<dyn Any + Send>> {
+    T::decode(bytes).map(|component| Box::new(component) as Box<dyn Any + Send>)
+}
+
+fn restore<T: SnapshotComponent>(world: &mut World, components: Decoded) {
+    let stale: Vec<Entity> = world
+        .query::<(Entity, &T)>()
+        .iter()
+        .map(|(entity, _)| entity)
+        .filter(|entity| {
+            components
+                .binary_search_by_key(entity, |(entity, _)| *entity)
+                .is_err()
+        })
+        .collect();
+    for entity in stale {
+        world.remove::<T>(entity);
+    }
+    for (entity, component) in components {
+        let component = *component
+            .downcast::<T>()
+            .expect("components are decoded by their schema entry");
+        world
+            .insert(entity, (component,))
+            .expect("snapshot entities are restored first");
+    }
+}
+
+/// The encoded components of one type, sorted by entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Column {
+    name: String,
+    entities: Vec<Entity>,
+    /// The end of each entity's bytes in `bytes`.
+    ends: Vec<usize>,
+    bytes: Vec<u8>,
+}
+
+impl Column {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            entities: Vec::new(),
+            ends: Vec::new(),
+            bytes: Vec::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    fn row(&self, row: usize) -> &[u8] {
+        let start = if row == 0 { 0 } else { self.ends[row - 1] };
+        &self.bytes[start..self.ends[row]]
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (Entity, &[u8])> {
+        self.entities
+            .iter()
+            .enumerate()
+            .map(|(row, entity)| (*entity, self.row(row)))
+    }
+
+    fn push(&mut self, entity: Entity, bytes: &[u8]) {
+        self.entities.push(entity);
+        self.bytes.extend_from_slice(bytes);
+        self.ends.push(self.bytes.len());
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        put_bytes(out, self.name.as_bytes());
+        put_len(out, self.len());
+        for (entity, bytes) in self.rows() {
+            put_u64(out, entity.to_bits());
+            put_bytes(out, bytes);
+        }
+    }
+
+    fn read(reader: &mut Reader<'_>) -> EcsResult<Self> {
+        let mut column = Self::new(reader.string()?);
+        for _ in 0..reader.len()? {
+            let entity = reader.entity()?;
+            if column.entities.last().is_some_and(|last| *last >= entity) {
+                return Err(EcsError::CorruptSnapshot);
+            }
+            column.push(entity, reader.bytes()?);
+        }
+        Ok(column)
+    }
+}
+
+/// Returns how the next rows of two columns merged by entity compare, a
+/// missing row coming after every other.
+fn merge_order(left: Option<&Entity>, right: Option<&Entity>) -> Ordering {
+    match (left, right) {
+        (Some(left), Some(right)) => left.cmp(right),
+        (Some(_), None) => Ordering::Less,
+        (None, _) => Ordering::Greater,
+    }
+}
+
+/// Returns `true` if both lists hold components of the same types.
+fn same_components(left: &[Column], right: &[Column]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .all(|(left, right)| left.name == right.name)
+}
+
+/// The entity ids and the components of the types of a [`SnapshotSchema`]
+/// of a world at one point in time, encoded as bytes.
+///
+/// Snapshots are taken by [`World::snapshot`] and put back by
+/// [`World::restore`], which keeps entity ids stable: the entities of the
+/// snapshot exist again with the same ids, and later spawns reuse ids as
+/// they did after the snapshot was taken. Snapshots compare with
+/// [`diff`](Self::diff), and save to bytes with [`to_bytes`](Self::to_bytes).
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{SnapshotComponent, SnapshotSchema, World};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Health(u32);
+///
+/// impl SnapshotComponent for Health {
+///     fn encode(&self, out: &mut Vec<u8>) {
+///         out.extend_from_slice(&self.0.to_le_bytes());
+///     }
+///
+///     fn decode(bytes: &[u8]) -> Option<Self> {
+///         Some(Self(u32::from_le_bytes(bytes.try_into().ok()?)))
+///     }
+/// }
+///
+/// let schema = SnapshotSchema::new().with::<Health>();
+/// let mut world = World::new();
+/// let player = world.spawn((Health(100),));
+/// let snapshot = world.snapshot(&schema);
+///
+/// world.get_mut::<Health>(player).unwrap().0 = 40;
+/// let later = world.spawn((Health(10),));
+/// assert_ne!(world.checksum(&schema), snapshot.checksum());
+///
+/// world.restore(&schema, &snapshot).unwrap();
+/// assert_eq!(*world.get::<Health>(player).unwrap(), Health(100));
+/// assert!(!world.contains(later));
+/// assert_eq!(world.checksum(&schema), snapshot.checksum());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    layout: SlotLayout,
+    columns: Vec<Column>,
+}
+
+const SNAPSHOT_MAGIC: [u8; 4] = *b"SNAP";
+const DIFF_MAGIC: [u8; 4] = *b"SDIF";
+
+impl Snapshot {
+    /// Returns the number of entities.
+    pub fn len(&self) -> usize {
+        self.layout
+            .slots
+            .iter()
+            .filter(|(_, occupied)| *occupied)
+            .count()
+    }
+
+    /// Returns `true` if the snapshot has no entity.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if `entity` existed when the snapshot was taken.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.layout.slots.get(entity.index() as usize) == Some(&(entity.generation(), true))
+    }
+
+    /// Returns a hash of the snapshot, equal for equal snapshots on every
+    /// platform, to detect desyncs between peers.
+    pub fn checksum(&self) -> u64 {
+        // FNV-1a, which unlike the standard hasher is stable across releases.
+        self.to_bytes()
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// Returns the changes from this snapshot to `newer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EcsError::SnapshotMismatch`] if the snapshots hold
+    /// components of different types.
+    pub fn diff(&self, newer: &Snapshot) -> EcsResult<SnapshotDiff> {
+        if !same_components(&self.columns, &newer.columns) {
+            return Err(EcsError::SnapshotMismatch);
+        }
+        let (old, new) = (&self.layout, &newer.layout);
+        let slots = new
+            .slots
+            .iter()
+            .enumerate()
+            .filter(|(index, slot)| old.slots.get(*index) != Some(slot))
+            .map(|(index, &(generation, occupied))| {
+                // RATIONALE: entity slots are indexed by u32, like `SlotMap` handles
+                #[allow(clippy::cast_possible_truncation)]
+                let index = index as u32;
+                (index, generation, occupied)
+            })
+            .collect();
+        Ok(SnapshotDiff {
+            base: self.checksum(),
+            slot_count: (new.slots.len() != old.slots.len()).then_some(new.slots.len()),
+            slots,
+            free: (new.free != old.free).then(|| new.free.clone()),
+            columns: self
+                .columns
+                .iter()
+                .zip(&newer.columns)
+                .map(|(old, new)| ColumnDiff::new(old, new))
+                .collect(),
+        })
+    }
+
+    /// Applies `diff` to this snapshot, returning the snapshot it was taken
+    /// to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EcsError::SnapshotMismatch`] if `diff` was not taken from
+    /// this snapshot.
+    pub fn patch(&self, diff: &SnapshotDiff) -> EcsResult<Snapshot> {
+        if diff.base != self.checksum() {
+            return Err(EcsError::SnapshotMismatch);
+        }
+        let mut slots = self.layout.slots.clone();
+        if let Some(count) = diff.slot_count {
+            slots.resize(count, (0, false));
+        }
+        for &(index, generation, occupied) in &diff.slots {
+            *slots
+                .get_mut(index as usize)
+                .ok_or(EcsError::CorruptSnapshot)? = (generation, occupied);
+        }
+        let free = diff
+            .free
+            .clone()
+            .unwrap_or_else(|| self.layout.free.clone());
+        Ok(Self {
+            layout: SlotLayout { slots, free },
+            columns: self
+                .columns
+                .iter()
+                .zip(&diff.columns)
+                .map(|(column, diff)| diff.apply(column))
+                .collect(),
+        })
+    }
+
+    /// Encodes the snapshot, to save it or send it over the network.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = SNAPSHOT_MAGIC.to_vec();
+        put_len(&mut out, self.layout.slots.len());
+        for &(generation, occupied) in &self.layout.slots {
+            put_u32(&mut out, generation);
+            out.push(u8::from(occupied));
+        }
+        put_len(&mut out, self.layout.free.len());
+        for &index in &self.layout.free {
+            put_u32(&mut out, index);
+        }
+        put_len(&mut out, self.columns.len());
+        for column in &self.columns {
+            column.write(&mut out);
+        }
+        out
+    }
+
+    /// Decodes a snapshot encoded by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EcsError::CorruptSnapshot`] if `bytes` are not a snapshot.
+    pub fn from_bytes(bytes: &[u8]) -> EcsResult<Self> {
+        let mut reader = Reader::new(bytes, SNAPSHOT_MAGIC)?;
+        let mut layout = SlotLayout::default();
+        for _ in 0..reader.len()? {
+            layout.slots.push((reader.u32()?, reader.bool()?));
+        }
+        for _ in 0..reader.len()? {
+            layout.free.push(reader.u32()?);
+        }
+        let mut columns = Vec::new();
+        for _ in 0..reader.len()? {
+            columns.push(Column::read(&mut reader)?);
+        }
+        reader.finish()?;
+        Ok(Self { layout, columns })
+    }
+}
+
+/// The changes of one component type between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ColumnDiff {
+    /// The entities that lost the component, sorted.
+    removed: Vec<Entity>,
+    /// The components added or changed.
+    set: Column,
+}
+
+impl ColumnDiff {
+    fn new(old: &Column, new: &Column) -> Self {
+        let mut diff = Self {
+            removed: Vec::new(),
+            set: Column::new(new.name.clone()),
+        };
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            match merge_order(old.entities.get(i), new.entities.get(j)) {
+                Ordering::Less => {
+                    diff.removed.push(old.entities[i]);
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    diff.set.push(new.entities[j], new.row(j));
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    if old.row(i) != new.row(j) {
+                        diff.set.push(new.entities[j], new.row(j));
+                    }
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        diff
+    }
+
+    fn apply(&self, old: &Column) -> Column {
+        let mut column = Column::new(old.name.clone());
+        let mut removed = self.removed.iter().peekable();
+        let set = &self.set;
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < set.len() {
+            match merge_order(old.entities.get(i), set.entities.get(j)) {
+                Ordering::Less => {
+                    let entity = old.entities[i];
+                    while removed.next_if(|removed| **removed < entity).is_some() {}
+                    if removed.next_if_eq(&&entity).is_none() {
+                        column.push(entity, old.row(i));
+                    }
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    column.push(set.entities[j], set.row(j));
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    column.push(set.entities[j], set.row(j));
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        column
+    }
+
+    fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.set.len() == 0
+    }
+}
+
+/// The changes from one [`Snapshot`] to a newer one, returned by
+/// [`Snapshot::diff`]: only the entity slots and the components that
+/// differ, to send over the network or keep as a compact history.
+///
+/// A diff only applies to the snapshot it was taken from, which
+/// [`Snapshot::patch`] checks with the snapshot's checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// The checksum of the snapshot the diff was taken from.
+    base: u64,
+    /// The number of entity slots, if it changed.
+    slot_count: Option<usize>,
+    /// The index, generation and occupancy of the slots that changed.
+    slots: Vec<(u32, u32, bool)>,
+    /// The order vacant slots are reused in, if it changed.
+    free: Option<Vec<u32>>,
+    columns: Vec<ColumnDiff>,
+}
+
+impl SnapshotDiff {
+    /// Returns `true` if the snapshots are equal.
+    pub fn is_empty(&self) -> bool {
+        self.slot_count.is_none()
+            && self.slots.is_empty()
+            && self.free.is_none()
+            && self.columns.iter().all(ColumnDiff::is_empty)
+    }
+
+    /// Encodes the diff.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = DIFF_MAGIC.to_vec();
+        put_u64(&mut out, self.base);
+        out.push(u8::from(self.slot_count.is_some()));
+        if let Some(count) = self.slot_count {
+            put_len(&mut out, count);
+        }
+        put_len(&mut out, self.slots.len());
+        for &(index, generation, occupied) in &self.slots {
+            put_u32(&mut out, index);
+            put_u32(&mut out, generation);
+            out.push(u8::from(occupied));
+        }
+        out.push(u8::from(self.free.is_some()));
+        if let Some(free) = &self.free {
+            put_len(&mut out, free.len());
+            for &index in free {
+                put_u32(&mut out, index);
+            }
+        }
+        put_len(&mut out, self.columns.len());
+        for column in &self.columns {
+            put_len(&mut out, column.removed.len());
+            for entity in &column.removed {
+                put_u64(&mut out, entity.to_bits());
+            }
+            column.set.write(&mut out);
+        }
+        out
+    }
+
+    /// Decodes a diff encoded by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EcsError::CorruptSnapshot`] if `bytes` are not a diff.
+    pub fn from_bytes(bytes: &[u8]) -> EcsResult<Self> {
+        let mut reader = Reader::new(bytes, DIFF_MAGIC)?;
+        let base = reader.u64()?;
+        let slot_count = if reader.bool()? {
+            Some(reader.len()?)
+        } else {
+            None
+        };
+        let mut slots = Vec::new();
+        for _ in 0..reader.len()? {
+            slots.push((reader.u32()?, reader.u32()?, reader.bool()?));
+        }
+        let free = if reader.bool()? {
+            let mut free = Vec::new();
+            for _ in 0..reader.len()? {
+                free.push(reader.u32()?);
+            }
+            Some(free)
+        } else {
+            None
+        };
+        let mut columns = Vec::new();
+        for _ in 0..reader.len()? {
+            let mut removed = Vec::new();
+            for _ in 0..reader.len()? {
+                removed.push(reader.entity()?);
+            }
+            if !removed.is_sorted() {
+                return Err(EcsError::CorruptSnapshot);
+            }
+            columns.push(ColumnDiff {
+                removed,
+                set: Column::read(&mut reader)?,
+            });
+        }
+        reader.finish()?;
+        Ok(Self {
+            base,
+            slot_count,
+            slots,
+            free,
+            columns,
+        })
+    }
+}
+
+impl World {
+    /// Captures the entity ids and the components of the types of `schema`.
+    ///
+    /// Entities reserved through a shared world but not yet created are left
+    /// out.
+    pub fn snapshot(&self, schema: &SnapshotSchema) -> Snapshot {
+        Snapshot {
+            layout: self.entity_layout(),
+            columns: schema
+                .components
+                .iter()
+                .map(|component| (component.capture)(self))
+                .collect(),
+        }
+    }
+
+    /// Puts the world back to `snapshot`, taken with `schema`.
+    ///
+    /// Entities created since are despawned, and those despawned since are
+    /// created again with the same ids; later spawns reuse ids as they did
+    /// after the snapshot was taken. The components of the types of
+    /// `schema` become those of the snapshot, marked changed; components of
+    /// other types are kept. Commands recorded and entity ids reserved before
+    /// the restore are discarded, so they cannot alter the restored state.
+    ///
+    /// No hook runs, so both sides of a relationship, such as [`ChildOf`]
+    /// and [`Children`], are captured together.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EcsError::SnapshotMismatch`] if the snapshot was taken with
+    /// another schema, and [`EcsError::CorruptSnapshot`] if its components do
+    /// not decode. The world is left unchanged, pending commands included.
+    pub fn restore(&mut self, schema: &SnapshotSchema, snapshot: &Snapshot) -> EcsResult<()> {
+        schema.check(&snapshot.columns)?;
+        let mut decoded = Vec::with_capacity(snapshot.columns.len());
+        for (component, column) in schema.components.iter().zip(&snapshot.columns) {
+            let mut components: Decoded = Vec::with_capacity(column.len());
+            for (entity, bytes) in column.rows() {
+                if !snapshot.contains(entity) {
+                    return Err(EcsError::CorruptSnapshot);
+                }
+                let value = (component.decode)(bytes).ok_or(EcsError::CorruptSnapshot)?;
+                components.push((entity, value));
+            }
+            decoded.push(components);
+        }
+
+        self.without_hooks(|world| {
+            if !world.restore_entities(&snapshot.layout) {
+                return Err(EcsError::CorruptSnapshot);
+            }
+            for (component, components) in schema.components.iter().zip(decoded) {
+                (component.restore)(world, components);
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the [`Snapshot::checksum`] of the world's snapshot of
+    /// `schema`, to compare worlds expected to be in sync.
+    pub fn checksum(&self, schema: &SnapshotSchema) -> u64 {
+        self.snapshot(schema).checksum()
+    }
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_len(out: &mut Vec<u8>, len: usize) {
+    put_u64(out, len as u64);
+}
+
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    put_len(out, bytes.len());
+    out.extend_from_slice(bytes);
+}
+
+/// Reads the values written by the `put_*` functions.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Starts reading `bytes`, which must begin with `magic`.
+    fn new(bytes: &'a [u8], magic: [u8; 4]) -> EcsResult<Self> {
+        let mut reader = Self { bytes };
+        if reader.take(magic.len())? == magic {
+            Ok(reader)
+        } else {
+            Err(EcsError::CorruptSnapshot)
+        }
+    }
+
+    fn take(&mut self, len: usize) -> EcsResult<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(EcsError::CorruptSnapshot);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> EcsResult<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+
+    fn bool(&mut self) -> EcsResult<bool> {
+        match self.array::<1>()? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(EcsError::CorruptSnapshot),
+        }
+    }
+
+    fn u32(&mut self) -> EcsResult<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> EcsResult<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn len(&mut self) -> EcsResult<usize> {
+        usize::try_from(self.u64()?).map_err(|_| EcsError::CorruptSnapshot)
+    }
+
+    fn entity(&mut self) -> EcsResult<Entity> {
+        self.u64().map(Entity::from_bits)
+    }
+
+    fn bytes(&mut self) -> EcsResult<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> EcsResult<String> {
+        let bytes = self.bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| EcsError::CorruptSnapshot)
+    }
+
+    /// Checks that every byte was read.
+    fn finish(self) -> EcsResult<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(EcsError::CorruptSnapshot)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(i32);
+
+    impl SnapshotComponent for Position {
+        fn encode(&self, out: &mut Vec<u8>) {
+            out.extend_from_slice(&self.0.to_le_bytes());
+        }
+
+        fn decode(bytes: &[u8]) -> Option<Self> {
+            Some(Self(i32::from_le_bytes(bytes.try_into().ok()?)))
+        }
+    }
+
+    /// Not captured by snapshots.
+    #[derive(Debug, PartialEq)]
+    struct Label(&'static str);
+
+    fn schema() -> SnapshotSchema {
+        SnapshotSchema::new()
+            .with::<Position>()
+            .with::<ChildOf>()
+            .with::<Children>()
+    }
+
+    #[test]
+    fn restoring_keeps_entity_ids_and_other_components() {
+        let schema = schema();
+        let mut world = World::new();
+        world.add_relationship::<ChildOf>();
+        let ship = world.spawn((Position(1), Label("ship")));
+        let turret = world.spawn((Position(2), ChildOf(ship)));
+        let gone = world.spawn((Position(3),));
+        world.despawn(gone);
+        let snapshot = world.snapshot(&schema);
+        assert_eq!(snapshot.len(), 2);
+        assert!(snapshot.contains(turret) && !snapshot.contains(gone));
+
+        let spawned = world.spawn((Position(4),));
+        world.despawn(turret);
+        world.insert(ship, (Position(10), Label("moved"))).unwrap();
+        assert_ne!(world.checksum(&schema), snapshot.checksum());
+
+        world.restore(&schema, &snapshot).unwrap();
+        assert!(!world.contains(spawned));
+        assert_eq!(*world.get::<Position>(ship).unwrap(), Position(1));
+        assert_eq!(*world.get::<Position>(turret).unwrap(), Position(2));
+        assert_eq!(*world.get::<Label>(ship).unwrap(), Label("moved"));
+        assert_eq!(world.sources::<ChildOf>(ship), [turret]);
+        assert_eq!(world.checksum(&schema), snapshot.checksum());
+
+        // Ids are allocated as they were after the snapshot, and hooks run
+        // again.
+        assert_eq!(world.spawn(()), spawned);
+        world.despawn(ship);
+        assert!(!world.contains(turret));
+
+        let other = SnapshotSchema::new().with::<Position>();
+        assert_eq!(
+            world.restore(&other, &snapshot),
+            Err(EcsError::SnapshotMismatch)
+        );
+    }
+
+    #[test]
+    fn restoring_discards_pending_commands() {
+        let schema = schema();
+        let mut server = World::new();
+        let a = server.spawn((Position(1),));
+        let b = server.spawn((Position(2),));
+        let snapshot = server.snapshot(&schema);
+
+        let mut client = World::new();
+        assert_eq!(client.spawn(()), a);
+        {
+            let mut commands = client.commands();
+            assert_eq!(commands.spawn((Label("predicted"),)), b);
+            commands.insert(a, (Label("predicted"),));
+        }
+        client.restore(&schema, &snapshot).unwrap();
+        client.apply_commands();
+
+        assert!(!client.has::<Label>(a) && !client.has::<Label>(b));
+        assert_eq!(*client.get::<Position>(b).unwrap(), Position(2));
+        assert_eq!(client.checksum(&schema), snapshot.checksum());
+        assert_eq!(client.spawn(()), server.spawn(()));
+    }
+
+    #[test]
+    fn diffs_patch_older_snapshots_into_newer_ones() {
+        let schema = schema();
+        let mut world = World::new();
+        let a = world.spawn((Position(1),));
+        let b = world.spawn((Position(2),));
+        let older = world.snapshot(&schema);
+        assert!(older.diff(&older).unwrap().is_empty());
+
+        world.get_mut::<Position>(a).unwrap().0 = 5;
+        world.despawn(b);
+        world.spawn((Position(7),));
+        world.spawn(());
+        let newer = world.snapshot(&schema);
+
+        let diff = older.diff(&newer).unwrap();
+        assert!(!diff.is_empty());
+        let bytes = diff.to_bytes();
+        assert_eq!(SnapshotDiff::from_bytes(&bytes), Ok(diff.clone()));
+        assert_eq!(older.patch(&diff), Ok(newer.clone()));
+        assert_eq!(newer.patch(&diff), Err(EcsError::SnapshotMismatch));
+
+        let saved = newer.to_bytes();
+        assert_eq!(Snapshot::from_bytes(&saved), Ok(newer.clone()));
+        assert_eq!(Snapshot::from_bytes(&bytes), Err(EcsError::CorruptSnapshot));
+        assert_eq!(
+            Snapshot::from_bytes(&saved[..saved.len() - 1]),
+            Err(EcsError::CorruptSnapshot)
+        );
+    }
+}
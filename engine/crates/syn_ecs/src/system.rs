@@ -0,0 +1,119 @@
+//! Systems: the logic run over a world by a schedule.
+
+use crate::access::Access;
+use crate::component::Component;
+use crate::query::QueryData;
+use crate::resource::Resource;
+use crate::world::World;
+
+/// Logic run over a [`World`] by a [`Schedule`](crate::Schedule).
+///
+/// A system declares the components and resources it reads and writes, so the
+/// schedule can run systems whose accesses are compatible at the same time.
+/// Systems only get a shared world: queries and
+/// [`World::resource_mut`] lock what they borrow, and a borrow the system did
+/// not declare may panic when a conflicting system runs alongside it.
+pub trait System: Send {
+    /// Returns the name ordering constraints refer to the system by, unique
+    /// within a schedule.
+    fn name(&self) -> &str;
+
+    /// Records the components and resources the system borrows.
+    fn access(&self, access: &mut Access);
+
+    /// Runs the system once.
+    fn run(&mut self, world: &World);
+}
+
+/// A [`System`] running a closure, with its access declared by builder
+/// methods.
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{FnSystem, System, World};
+///
+/// struct Position(f32);
+/// struct Velocity(f32);
+/// struct DeltaTime(f32);
+///
+/// let mut movement = FnSystem::new("movement", |world: &World| {
+///     let dt = world.resource::<DeltaTime>().unwrap().0;
+///     for (mut position, velocity) in &mut world.query::<(&mut Position, &Velocity)>() {
+///         position.0 += velocity.0 * dt;
+///     }
+/// })
+/// .query::<(&mut Position, &Velocity)>()
+/// .reads_resource::<DeltaTime>();
+///
+/// let mut world = World::new();
+/// world.insert_resource(DeltaTime(0.5));
+/// let entity = world.spawn((Position(0.0), Velocity(4.0)));
+/// movement.run(&world);
+/// assert_eq!(world.get::<Position>(entity).unwrap().0, 2.0);
+/// ```
+pub struct FnSystem<F> {
+    name: String,
+    access: Access,
+    run: F,
+}
+
+impl<F: FnMut(&World) + Send> FnSystem<F> {
+    /// Creates a system borrowing nothing.
+    pub fn new(name: impl Into<String>, run: F) -> Self {
+        Self {
+            name: name.into(),
+            access: Access::new(),
+            run,
+        }
+    }
+
+    /// Declares a shared borrow of the component `T`.
+    #[must_use]
+    pub fn reads<T: Component>(mut self) -> Self {
+        self.access.read::<T>();
+        self
+    }
+
+    /// Declares a mutable borrow of the component `T`.
+    #[must_use]
+    pub fn writes<T: Component>(mut self) -> Self {
+        self.access.write::<T>();
+        self
+    }
+
+    /// Declares the borrows of a query fetching `D`.
+    #[must_use]
+    pub fn query<D: QueryData>(mut self) -> Self {
+        D::access(&mut self.access);
+        self
+    }
+
+    /// Declares a shared borrow of the resource `R`.
+    #[must_use]
+    pub fn reads_resource<R: Resource>(mut self) -> Self {
+        self.access.read_resource::<R>();
+        self
+    }
+
+    /// Declares a mutable borrow of the resource `R`.
+    #[must_use]
+    pub fn writes_resource<R: Resource>(mut self) -> Self {
+        self.access.write_resource::<R>();
+        self
+    }
+}
+
+impl<F: FnMut(&World) + Send> System for FnSystem<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn access(&self, access: &mut Access) {
+        access.extend(&self.access);
+    }
+
+    fn run(&mut self, world: &World) {
+        (self.run)(world);
+    }
+}
@@ -0,0 +1,990 @@
+//! The world: entities and their components.
+
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard, Mutex};
+use syn_collections::{SlotLayout, SlotMap};
+
+use crate::archetype::{Archetype, ArchetypeId, archetype_id, pair_mut};
+use crate::bundle::Bundle;
+use crate::change::Tick;
+use crate::command::{CommandQueue, Commands};
+use crate::component::{AnyColumn, Component, ComponentInfo, downcast_mut};
+use crate::entity::{Entity, EntityLocation};
+use crate::error::{EcsError, EcsResult};
+use crate::event::{Event, Events};
+use crate::hook::{HookKind, Hooks};
+use crate::query::{Query, QueryData, QueryFilter};
+use crate::resource::{Resource, Resources};
+
+/// Entities and their components, stored in archetype tables.
+///
+/// Entities with the same set of component types share an [`Archetype`],
+/// whose components are stored in one contiguous column per type, so queries
+/// iterate over densely packed data. Inserting or removing a component moves
+/// the entity to the archetype of its new set.
+///
+/// The world also stores [`Resource`]s, values of which there is one per
+/// world rather than one per entity.
+///
+/// Components remember the [`Tick`] they were added and last changed at, for
+/// [`Added`](crate::Added) and [`Changed`](crate::Changed) query filters; the
+/// world records the entities that lost each component since
+/// [`clear_trackers`](Self::clear_trackers) was last called. Hooks registered
+/// with [`on_add`](Self::on_add), [`on_insert`](Self::on_insert) and
+/// [`on_remove`](Self::on_remove) run synchronously during structural
+/// changes.
+///
+/// Structural changes need an exclusive world; through a shared one, they
+/// are recorded with [`commands`](Self::commands) and applied by
+/// [`apply_commands`](Self::apply_commands).
+///
+/// Relationships such as [`ChildOf`](crate::ChildOf) link entities to each
+/// other once registered with [`add_relationship`](Self::add_relationship).
+///
+/// [`snapshot`](Self::snapshot) copies the components of chosen types, to be
+/// put back with the same entity ids by [`restore`](Self::restore).
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{With, World};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Health(u32);
+/// struct Poisoned;
+///
+/// let mut world = World::new();
+/// let player = world.spawn((Health(100),));
+/// world.insert(player, (Poisoned,)).unwrap();
+///
+/// for mut health in &mut world.query_filtered::<&mut Health, With<Poisoned>>() {
+///     health.0 -= 10;
+/// }
+///
+/// assert_eq!(*world.get::<Health>(player).unwrap(), Health(90));
+/// assert!(world.remove::<Poisoned>(player).is_some());
+/// assert!(world.despawn(player));
+/// assert!(!world.contains(player));
+/// ```
+pub struct World {
+    locations: SlotMap<EntityLocation>,
+    archetypes: Vec<Archetype>,
+    archetype_ids: HashMap<Box<[TypeId]>, ArchetypeId>,
+    resources: Resources,
+    change_tick: AtomicU64,
+    last_change_tick: Tick,
+    /// The entities that lost each component since the trackers were cleared.
+    removed: HashMap<TypeId, Vec<Entity>>,
+    hooks: Hooks,
+    /// Entities reserved since the last flush, created after the last slot.
+    reserved: AtomicU32,
+    /// Command queues waiting to be applied, in submission order.
+    pending: Mutex<Vec<CommandQueue>>,
+    /// The update function of each registered [`Events`] resource.
+    event_updaters: HashMap<TypeId, fn(&mut World)>,
+    /// The relationships registered with [`World::add_relationship`].
+    pub(crate) relationships: HashSet<TypeId>,
+}
+
+impl World {
+    /// Creates a world without entities.
+    pub fn new() -> Self {
+        let mut archetype_ids = HashMap::new();
+        archetype_ids.insert(Box::default(), ArchetypeId::EMPTY);
+        Self {
+            locations: SlotMap::new(),
+            archetypes: vec![Archetype::new(ArchetypeId::EMPTY, Box::default())],
+            archetype_ids,
+            resources: Resources::default(),
+            change_tick: AtomicU64::new(1),
+            last_change_tick: Tick::new(0),
+            removed: HashMap::new(),
+            hooks: Hooks::default(),
+            reserved: AtomicU32::new(0),
+            pending: Mutex::new(Vec::new()),
+            event_updaters: HashMap::new(),
+            relationships: HashSet::new(),
+        }
+    }
+
+    /// Returns the number of entities.
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Returns `true` if the world has no entity.
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+
+    /// Returns `true` if `entity` exists.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.locations.contains(entity.location_handle())
+    }
+
+    /// Returns the archetypes, indexed by [`ArchetypeId::index`].
+    pub fn archetypes(&self) -> &[Archetype] {
+        &self.archetypes
+    }
+
+    /// Creates an entity with the components of `bundle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bundle contains a component twice.
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        self.flush();
+        let tick = self.change_tick();
+        let archetype = self.bundle_archetype::<B>(ArchetypeId::EMPTY);
+        let table = &mut self.archetypes[archetype.index()];
+        let row = table.len();
+        let entity = Entity::from(self.locations.insert(EntityLocation { archetype, row }));
+        table.push_entity(entity);
+        bundle.write(table, row, tick);
+        if !self.hooks.is_empty() {
+            let components = bundle_components::<B>();
+            self.run_hooks(HookKind::Add, entity, &components);
+            self.run_hooks(HookKind::Insert, entity, &components);
+        }
+        entity
+    }
+
+    /// Destroys an entity and its components, returning `false` if it did not
+    /// exist.
+    ///
+    /// [`on_remove`](Self::on_remove) hooks of the entity's components run
+    /// first, while the components can still be read.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        let Some(location) = self.location(entity) else {
+            return false;
+        };
+        if !self.hooks.is_empty() {
+            let components: Vec<TypeId> = self.archetypes[location.archetype.index()]
+                .components()
+                .iter()
+                .map(ComponentInfo::type_id)
+                .collect();
+            self.run_hooks(HookKind::Remove, entity, &components);
+        }
+        // Hooks may have moved or despawned the entity.
+        let Some(location) = self.locations.remove(entity.location_handle()) else {
+            return true;
+        };
+        let table = &mut self.archetypes[location.archetype.index()];
+        for info in table.components() {
+            self.removed.entry(info.type_id()).or_default().push(entity);
+        }
+        if let Some(moved) = table.remove_row(location.row) {
+            self.set_row(moved, location.row);
+        }
+        true
+    }
+
+    /// Adds the components of `bundle` to an entity, replacing those it
+    /// already has.
+    ///
+    /// Components the entity did not have are marked added, and all of them
+    /// changed. [`on_replace`](Self::on_replace) hooks of the replaced
+    /// components run first; [`on_add`](Self::on_add) hooks of the added
+    /// components run after the insertion, followed by
+    /// [`on_insert`](Self::on_insert) hooks of every component of the bundle.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EcsError::NoSuchEntity`] if the entity does not exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bundle contains a component twice.
+    pub fn insert<B: Bundle>(&mut self, entity: Entity, bundle: B) -> EcsResult<()> {
+        self.flush();
+        let mut location = self
+            .location(entity)
+            .ok_or(EcsError::NoSuchEntity(entity))?;
+        let components = (!self.hooks.is_empty()).then(bundle_components::<B>);
+        if let Some(components) = &components {
+            let source = &self.archetypes[location.archetype.index()];
+            let replaced: Vec<TypeId> = components
+                .iter()
+                .copied()
+                .filter(|component| source.contains(*component))
+                .collect();
+            self.run_hooks(HookKind::Replace, entity, &replaced);
+            // Hooks may have moved or despawned the entity.
+            location = self
+                .location(entity)
+                .ok_or(EcsError::NoSuchEntity(entity))?;
+        }
+        let hooked = components.map(|components| {
+            let source = &self.archetypes[location.archetype.index()];
+            let added: Vec<TypeId> = components
+                .iter()
+                .copied()
+                .filter(|component| !source.contains(*component))
+                .collect();
+            (components, added)
+        });
+        let tick = self.change_tick();
+        let target = self.bundle_archetype::<B>(location.archetype);
+        let row = if target == location.archetype {
+            location.row
+        } else {
+            self.move_entity(entity, location, target, |_, _| {
+                unreachable!("inserting keeps every component")
+            })
+        };
+        bundle.write(&mut self.archetypes[target.index()], row, tick);
+        if let Some((components, added)) = hooked {
+            self.run_hooks(HookKind::Add, entity, &added);
+            self.run_hooks(HookKind::Insert, entity, &components);
+        }
+        Ok(())
+    }
+
+    /// Removes a component from an entity and returns it, or `None` if the
+    /// entity does not exist or has no `T`.
+    ///
+    /// [`on_remove`](Self::on_remove) hooks of `T` run first, while the
+    /// component can still be read.
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        let component = TypeId::of::<T>();
+        if !self.has::<T>(entity) {
+            return None;
+        }
+        self.run_hooks(HookKind::Remove, entity, &[component]);
+        // Hooks may have moved the entity, or removed the component.
+        let location = self.location(entity)?;
+        let source = &self.archetypes[location.archetype.index()];
+        if !source.contains(component) {
+            return None;
+        }
+        let target = if let Some(target) = source.remove_edges.get(&component) {
+            *target
+        } else {
+            let components = source
+                .components()
+                .iter()
+                .filter(|info| info.type_id() != component)
+                .copied()
+                .collect();
+            let target = self.archetype_with(components);
+            self.archetypes[location.archetype.index()]
+                .remove_edges
+                .insert(component, target);
+            target
+        };
+
+        let mut value = None;
+        self.move_entity(entity, location, target, |column, row| {
+            value = Some(downcast_mut::<T>(column).values.swap_remove(row));
+        });
+        self.removed.entry(component).or_default().push(entity);
+        value
+    }
+
+    /// Returns `true` if the entity exists and has a `T`.
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.location(entity).is_some_and(|location| {
+            self.archetypes[location.archetype.index()].contains(TypeId::of::<T>())
+        })
+    }
+
+    /// Reserves an entity id through a shared world.
+    ///
+    /// The entity is created without components by the next structural
+    /// change, such as [`insert`](Self::insert) or
+    /// [`apply_commands`](Self::apply_commands); until then,
+    /// [`contains`](Self::contains) returns `false` for it.
+    pub fn reserve_entity(&self) -> Entity {
+        let reserved = self.reserved.fetch_add(1, Ordering::Relaxed);
+        // RATIONALE: entity slots are indexed by u32, like `SlotMap` handles
+        #[allow(clippy::cast_possible_truncation)]
+        let index = self.locations.slot_count() as u32 + reserved;
+        Entity::new(index, 0)
+    }
+
+    /// Creates the entities reserved since the last flush.
+    fn flush(&mut self) {
+        let reserved = std::mem::take(self.reserved.get_mut());
+        let table = &mut self.archetypes[ArchetypeId::EMPTY.index()];
+        for _ in 0..reserved {
+            let row = table.len();
+            let handle = self.locations.insert_new_slot(EntityLocation {
+                archetype: ArchetypeId::EMPTY,
+                row,
+            });
+            table.push_entity(Entity::from(handle));
+        }
+    }
+
+    /// Returns a [`Commands`] recording structural changes, applied by
+    /// [`apply_commands`](Self::apply_commands) once the world is exclusive
+    /// again.
+    pub fn commands(&self) -> Commands<'_> {
+        Commands::new(self)
+    }
+
+    /// Applies the commands recorded since the last call, including those
+    /// the commands themselves record, in the order their [`Commands`] were
+    /// dropped.
+    pub fn apply_commands(&mut self) {
+        self.flush();
+        loop {
+            let queues = std::mem::take(self.pending.get_mut());
+            if queues.is_empty() {
+                break;
+            }
+            for mut queue in queues {
+                queue.apply(self);
+            }
+        }
+        self.flush();
+    }
+
+    pub(crate) fn submit_commands(&self, queue: CommandQueue) {
+        self.pending.lock().push(queue);
+    }
+
+    /// Registers the event type `T`, inserting an empty [`Events<T>`]
+    /// resource that [`update_events`](Self::update_events) updates.
+    pub fn add_event<T: Event>(&mut self) {
+        if !self.contains_resource::<Events<T>>() {
+            self.insert_resource(Events::<T>::new());
+        }
+        self.event_updaters
+            .insert(TypeId::of::<T>(), |world: &mut World| {
+                if let Some(events) = world.get_resource_mut::<Events<T>>() {
+                    events.update();
+                }
+            });
+    }
+
+    /// Updates the [`Events`] of every type registered with
+    /// [`add_event`](Self::add_event), dropping the events sent before the
+    /// previous update. Call it once per frame.
+    pub fn update_events(&mut self) {
+        let updaters: Vec<fn(&mut World)> = self.event_updaters.values().copied().collect();
+        for update in updaters {
+            update(self);
+        }
+    }
+
+    /// Returns the `T` of an entity, or `None` if the entity does not exist or
+    /// has no `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a query borrows the `T` column of the entity's archetype
+    /// mutably.
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<Ref<'_, T>> {
+        let location = self.location(entity)?;
+        let table = &self.archetypes[location.archetype.index()];
+        if !table.contains(TypeId::of::<T>()) {
+            return None;
+        }
+        let column = table.read::<T>();
+        let guard = MappedRwLockReadGuard::map(column, |values| &values[location.row]);
+        Some(Ref { guard })
+    }
+
+    /// Returns the `T` of an entity mutably, or `None` if the entity does not
+    /// exist or has no `T`.
+    ///
+    /// The component is marked changed, whether it is written or not.
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        let tick = self.change_tick();
+        let location = self.location(entity)?;
+        let table = &mut self.archetypes[location.archetype.index()];
+        table
+            .row_ticks(TypeId::of::<T>(), location.row)?
+            .set_changed(tick);
+        let column = table.column_mut::<T>()?;
+        Some(&mut column.values[location.row])
+    }
+
+    /// Returns the entities matching `D`, borrowing the components they fetch
+    /// until the query is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `D` borrows a component mutably twice, or if a component it
+    /// fetches is borrowed mutably by another query, or borrowed at all when
+    /// `D` borrows it mutably.
+    pub fn query<D: QueryData>(&self) -> Query<'_, D> {
+        Query::new(self)
+    }
+
+    /// Returns the entities matching `D` and the filter `F`, like
+    /// [`query`](Self::query).
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`query`](Self::query).
+    pub fn query_filtered<D: QueryData, F: QueryFilter>(&self) -> Query<'_, D, F> {
+        Query::new(self)
+    }
+
+    /// Returns the tick components written now are marked with.
+    pub fn change_tick(&self) -> Tick {
+        Tick::new(self.change_tick.load(Ordering::Relaxed))
+    }
+
+    /// Returns the tick [`Added`](crate::Added) and
+    /// [`Changed`](crate::Changed) filters compare against by default: the
+    /// last tick before [`clear_trackers`](Self::clear_trackers) was called.
+    pub fn last_change_tick(&self) -> Tick {
+        self.last_change_tick
+    }
+
+    /// Advances the change tick, and returns the tick before.
+    ///
+    /// Components written from now on are marked with a later tick, so a
+    /// reader that keeps the returned tick and passes it to
+    /// [`Query::since`](crate::Query::since) next time sees exactly the
+    /// changes made in between.
+    pub fn increment_change_tick(&self) -> Tick {
+        Tick::new(self.change_tick.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Starts a new change detection period, usually once per frame: queries
+    /// only see components added or changed from now on by default, and the
+    /// removals recorded so far are forgotten.
+    pub fn clear_trackers(&mut self) {
+        self.last_change_tick = self.increment_change_tick();
+        self.removed.clear();
+    }
+
+    /// Returns the entities that lost their `T`, by removal or despawn, since
+    /// [`clear_trackers`](Self::clear_trackers) was last called.
+    pub fn removed<T: Component>(&self) -> &[Entity] {
+        self.removed
+            .get(&TypeId::of::<T>())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Registers a hook run when a `T` is added to an entity that did not
+    /// have one, after the insertion.
+    pub fn on_add<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) {
+        self.hooks
+            .push(TypeId::of::<T>(), HookKind::Add, Arc::new(hook));
+    }
+
+    /// Registers a hook run when a `T` is added to an entity or replaced,
+    /// after the insertion.
+    pub fn on_insert<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) {
+        self.hooks
+            .push(TypeId::of::<T>(), HookKind::Insert, Arc::new(hook));
+    }
+
+    /// Registers a hook run when a `T` is about to be replaced by
+    /// [`insert`](Self::insert), while the previous value can still be read.
+    pub fn on_replace<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) {
+        self.hooks
+            .push(TypeId::of::<T>(), HookKind::Replace, Arc::new(hook));
+    }
+
+    /// Registers a hook run when a `T` is removed from an entity or its
+    /// entity despawned, before the removal.
+    pub fn on_remove<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) {
+        self.hooks
+            .push(TypeId::of::<T>(), HookKind::Remove, Arc::new(hook));
+    }
+
+    /// Runs the hooks of `kind` of `components` for `entity`, stopping if a
+    /// hook despawns it.
+    fn run_hooks(&mut self, kind: HookKind, entity: Entity, components: &[TypeId]) {
+        if self.hooks.is_empty() {
+            return;
+        }
+        for hook in self.hooks.collect(kind, components) {
+            if !self.contains(entity) {
+                return;
+            }
+            hook(self, entity);
+        }
+    }
+
+    /// Stores a resource, returning the previous value of its type.
+    pub fn insert_resource<R: Resource>(&mut self, value: R) -> Option<R> {
+        self.resources.insert(value)
+    }
+
+    /// Removes a resource and returns it, or `None` if the world has no `R`.
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        self.resources.remove()
+    }
+
+    /// Returns `true` if the world has an `R`.
+    pub fn contains_resource<R: Resource>(&self) -> bool {
+        self.resources.contains::<R>()
+    }
+
+    /// Returns the resource `R`, or `None` if the world has none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `R` is borrowed mutably.
+    pub fn resource<R: Resource>(&self) -> Option<Ref<'_, R>> {
+        self.resources.read().map(|guard| Ref { guard })
+    }
+
+    /// Returns the resource `R` mutably, or `None` if the world has none.
+    ///
+    /// Unlike [`get_resource_mut`](Self::get_resource_mut), this only needs a
+    /// shared world, so systems running in parallel can each borrow a
+    /// different resource.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `R` is borrowed.
+    pub fn resource_mut<R: Resource>(&self) -> Option<RefMut<'_, R>> {
+        self.resources.write().map(|guard| RefMut { guard })
+    }
+
+    /// Returns the resource `R` mutably, or `None` if the world has none.
+    pub fn get_resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut()
+    }
+
+    /// Returns the allocation state of entity ids.
+    pub(crate) fn entity_layout(&self) -> SlotLayout {
+        self.locations.layout()
+    }
+
+    /// Makes the entities allocated in `layout` exist and no others,
+    /// despawning extra entities and creating missing ones without
+    /// components, and allocates later entities as `layout` would.
+    ///
+    /// Pending commands and reserved entity ids are discarded: they belong
+    /// to the state being replaced, and reserved ids may name restored
+    /// entities.
+    ///
+    /// Returns `false`, leaving the world unchanged, if `layout` is
+    /// inconsistent.
+    pub(crate) fn restore_entities(&mut self, layout: &SlotLayout) -> bool {
+        const UNPLACED: usize = usize::MAX;
+        let Some(mut locations) = SlotMap::from_layout(layout, |_| EntityLocation {
+            archetype: ArchetypeId::EMPTY,
+            row: UNPLACED,
+        }) else {
+            return false;
+        };
+        self.pending.get_mut().clear();
+        *self.reserved.get_mut() = 0;
+        let stale: Vec<Entity> = self
+            .archetypes
+            .iter()
+            .flat_map(Archetype::entities)
+            .copied()
+            .filter(|entity| !locations.contains(entity.location_handle()))
+            .collect();
+        for entity in stale {
+            self.despawn(entity);
+        }
+
+        for archetype in &self.archetypes {
+            for (row, entity) in archetype.entities().iter().enumerate() {
+                *locations
+                    .get_mut(entity.location_handle())
+                    .expect("stale entities were despawned") = EntityLocation {
+                    archetype: archetype.id(),
+                    row,
+                };
+            }
+        }
+        let table = &mut self.archetypes[ArchetypeId::EMPTY.index()];
+        for (index, &(generation, _)) in layout.slots.iter().enumerate() {
+            // RATIONALE: entity slots are indexed by u32, like `SlotMap` handles
+            #[allow(clippy::cast_possible_truncation)]
+            let entity = Entity::new(index as u32, generation);
+            if let Some(location) = locations
+                .get_mut(entity.location_handle())
+                .filter(|location| location.row == UNPLACED)
+            {
+                location.row = table.push_entity(entity);
+            }
+        }
+        self.locations = locations;
+        true
+    }
+
+    /// Runs `f` without running hooks.
+    pub(crate) fn without_hooks<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let hooks = std::mem::take(&mut self.hooks);
+        let result = f(self);
+        self.hooks = hooks;
+        result
+    }
+
+    /// Returns where an entity is stored.
+    pub(crate) fn location(&self, entity: Entity) -> Option<EntityLocation> {
+        self.locations.get(entity.location_handle()).copied()
+    }
+
+    fn set_row(&mut self, entity: Entity, row: usize) {
+        self.locations
+            .get_mut(entity.location_handle())
+            .expect("entities of archetypes are alive")
+            .row = row;
+    }
+
+    /// Moves an entity to the `target` archetype, passing the components
+    /// `target` lacks to `removed`, and returns its new row.
+    fn move_entity(
+        &mut self,
+        entity: Entity,
+        location: EntityLocation,
+        target: ArchetypeId,
+        removed: impl FnMut(&mut dyn AnyColumn, usize),
+    ) -> usize {
+        let (source, table) = pair_mut(&mut self.archetypes, location.archetype, target);
+        let (moved, row) = source.move_row(location.row, table, removed);
+        if let Some(moved) = moved {
+            self.set_row(moved, location.row);
+        }
+        *self
+            .locations
+            .get_mut(entity.location_handle())
+            .expect("moved entities are alive") = EntityLocation {
+            archetype: target,
+            row,
+        };
+        row
+    }
+
+    /// Returns the archetype of the entities of `source` after inserting a
+    /// `B`.
+    fn bundle_archetype<B: Bundle>(&mut self, source: ArchetypeId) -> ArchetypeId {
+        let bundle_type = TypeId::of::<B>();
+        if let Some(target) = self.archetypes[source.index()]
+            .insert_edges
+            .get(&bundle_type)
+        {
+            return *target;
+        }
+
+        let mut bundle = Vec::new();
+        B::components(&mut bundle);
+        bundle.sort_by_key(ComponentInfo::type_id);
+        if let Some(pair) = bundle.windows(2).find(|pair| pair[0] == pair[1]) {
+            panic!("bundle contains `{}` twice", pair[0].name());
+        }
+
+        let existing = self.archetypes[source.index()].components();
+        let mut components = existing.to_vec();
+        components.extend(bundle.into_iter().filter(|info| !existing.contains(info)));
+        let target = self.archetype_with(components);
+        self.archetypes[source.index()]
+            .insert_edges
+            .insert(bundle_type, target);
+        target
+    }
+
+    /// Returns the archetype of exactly `components`, creating it if needed.
+    fn archetype_with(&mut self, mut components: Vec<ComponentInfo>) -> ArchetypeId {
+        components.sort_by_key(ComponentInfo::type_id);
+        let key: Box<[TypeId]> = components.iter().map(ComponentInfo::type_id).collect();
+        if let Some(id) = self.archetype_ids.get(&key) {
+            return *id;
+        }
+        let id = archetype_id(self.archetypes.len());
+        self.archetypes
+            .push(Archetype::new(id, components.into_boxed_slice()));
+        self.archetype_ids.insert(key, id);
+        id
+    }
+}
+
+/// Returns the type ids of the components of `B`.
+fn bundle_components<B: Bundle>() -> Vec<TypeId> {
+    let mut components = Vec::new();
+    B::components(&mut components);
+    components.iter().map(ComponentInfo::type_id).collect()
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A shared borrow of a component or resource, returned by [`World::get`] and
+/// [`World::resource`].
+pub struct Ref<'w, T> {
+    guard: MappedRwLockReadGuard<'w, T>,
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+/// A mutable borrow of a resource, returned by [`World::resource_mut`].
+pub struct RefMut<'w, T> {
+    guard: MappedRwLockWriteGuard<'w, T>,
+}
+
+impl<T> Deref for RefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for RefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{Added, Changed};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Velocity(i32);
+
+    #[test]
+    fn despawned_entities_are_stale_after_slot_reuse() {
+        let mut world = World::new();
+        let first = world.spawn((Position(1),));
+        let second = world.spawn((Position(2),));
+        assert!(world.despawn(first));
+        assert!(!world.despawn(first));
+
+        let third = world.spawn((Position(3),));
+        assert_eq!(third.index(), first.index());
+        assert_ne!(third.generation(), first.generation());
+        assert!(!world.contains(first));
+        assert!(world.get::<Position>(first).is_none());
+        assert_eq!(
+            world.insert(first, (Velocity(0),)),
+            Err(EcsError::NoSuchEntity(first))
+        );
+        assert_eq!(*world.get::<Position>(second).unwrap(), Position(2));
+        assert_eq!(*world.get::<Position>(third).unwrap(), Position(3));
+        assert_eq!(world.len(), 2);
+    }
+
+    #[test]
+    fn insert_and_remove_move_entities_between_archetypes() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..4)
+            .map(|index| world.spawn((Position(index),)))
+            .collect();
+
+        world.insert(entities[0], (Velocity(10),)).unwrap();
+        world
+            .insert(entities[2], (Velocity(12), Position(-2)))
+            .unwrap();
+        assert_eq!(world.archetypes().len(), 3);
+        for (index, entity) in (0..).zip(&entities) {
+            let expected = if index == 2 { -2 } else { index };
+            assert_eq!(world.get::<Position>(*entity).unwrap().0, expected);
+        }
+        assert_eq!(world.get::<Velocity>(entities[2]).unwrap().0, 12);
+        assert!(!world.has::<Velocity>(entities[1]));
+
+        // Replacing a component in place keeps the archetype.
+        world.insert(entities[0], (Velocity(20),)).unwrap();
+        *world.get_mut::<Position>(entities[0]).unwrap() = Position(100);
+        assert_eq!(world.get::<Velocity>(entities[0]).unwrap().0, 20);
+
+        assert_eq!(world.remove::<Velocity>(entities[0]), Some(Velocity(20)));
+        assert_eq!(world.remove::<Velocity>(entities[0]), None);
+        assert_eq!(world.remove::<Position>(entities[1]), Some(Position(1)));
+        assert_eq!(world.archetypes().len(), 3);
+        assert_eq!(world.get::<Position>(entities[0]).unwrap().0, 100);
+        assert_eq!(world.get::<Velocity>(entities[2]).unwrap().0, 12);
+        assert!(world.contains(entities[1]));
+        assert_eq!(
+            world.archetypes()[ArchetypeId::EMPTY.index()].entities(),
+            [entities[1]]
+        );
+    }
+
+    #[test]
+    fn components_are_dropped_with_their_entity() {
+        let shared = Arc::new(());
+        let mut world = World::new();
+        let entity = world.spawn((Arc::clone(&shared), Position(0)));
+        world.spawn((Arc::clone(&shared),));
+        assert_eq!(Arc::strong_count(&shared), 3);
+
+        world.insert(entity, (Velocity(0),)).unwrap();
+        assert_eq!(Arc::strong_count(&shared), 3);
+        world.despawn(entity);
+        assert_eq!(Arc::strong_count(&shared), 2);
+        drop(world);
+        assert_eq!(Arc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn hooks_run_synchronously_on_structural_changes() {
+        #[derive(Default)]
+        struct Events(Vec<(&'static str, i32)>);
+
+        fn record(name: &'static str) -> impl Fn(&mut World, Entity) + Send + Sync {
+            move |world, entity| {
+                let position = world
+                    .get::<Position>(entity)
+                    .map_or(-1, |position| position.0);
+                world
+                    .get_resource_mut::<Events>()
+                    .unwrap()
+                    .0
+                    .push((name, position));
+            }
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Events::default());
+        world.on_add::<Position>(record("add"));
+        world.on_insert::<Position>(record("insert"));
+        world.on_replace::<Position>(record("replace"));
+        world.on_remove::<Position>(record("remove"));
+        // Hooks may change the world themselves.
+        world.on_add::<Velocity>(|world, entity| {
+            world.remove::<Position>(entity);
+        });
+
+        let entity = world.spawn((Position(1),));
+        world.insert(entity, (Position(2),)).unwrap();
+        world.insert(entity, (Velocity(0),)).unwrap();
+        world.insert(entity, (Position(3),)).unwrap();
+        assert!(world.despawn(entity));
+        assert_eq!(
+            world.resource::<Events>().unwrap().0,
+            [
+                ("add", 1),
+                ("insert", 1),
+                ("replace", 1),
+                ("insert", 2),
+                ("remove", 2),
+                ("add", 3),
+                ("insert", 3),
+                ("remove", 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn removals_are_tracked_until_cleared() {
+        let mut world = World::new();
+        let first = world.spawn((Position(0), Velocity(0)));
+        let second = world.spawn((Position(1),));
+        world.remove::<Velocity>(first);
+        world.despawn(second);
+        assert_eq!(world.removed::<Velocity>(), [first]);
+        assert_eq!(world.removed::<Position>(), [second]);
+
+        world.clear_trackers();
+        assert!(world.removed::<Position>().is_empty());
+        // Change ticks move with the entity between archetypes.
+        world.insert(first, (Velocity(1),)).unwrap();
+        assert!(
+            world
+                .query_filtered::<Entity, Changed<Position>>()
+                .is_empty()
+        );
+        assert_eq!(
+            world
+                .query_filtered::<Entity, Added<Velocity>>()
+                .iter()
+                .collect::<Vec<_>>(),
+            [first]
+        );
+    }
+
+    #[test]
+    fn commands_apply_in_order_at_sync_points() {
+        let mut world = World::new();
+        let doomed = world.spawn((Position(0),));
+        let freed = world.spawn((Position(1),));
+        world.despawn(freed);
+
+        let mut commands = world.commands();
+        let spawned = commands.spawn((Position(2),));
+        commands.insert(spawned, (Velocity(3),));
+        commands.despawn(doomed);
+        // Ignored: the entity is despawned by the time it applies.
+        commands.insert(doomed, (Velocity(4),));
+        commands.add(|world| {
+            world.commands().remove::<Position>(world.reserve_entity());
+        });
+        let other = world.commands().spawn(());
+        assert!(!world.contains(spawned));
+        drop(commands);
+
+        world.apply_commands();
+        assert!(!world.contains(doomed));
+        assert_eq!(world.get::<Velocity>(spawned).unwrap().0, 3);
+        assert!(world.contains(other));
+        // Reserved ids never reuse freed slots.
+        assert_ne!(spawned.index(), freed.index());
+        assert_eq!(world.len(), 3);
+    }
+
+    #[test]
+    fn resources_are_borrowed_separately() {
+        struct Time(i32);
+        struct Gravity(i32);
+
+        let mut world = World::new();
+        assert!(world.resource::<Time>().is_none());
+        assert!(world.insert_resource(Time(1)).is_none());
+        assert_eq!(world.insert_resource(Time(2)).map(|time| time.0), Some(1));
+        world.insert_resource(Gravity(-10));
+
+        let time = world.resource::<Time>().unwrap();
+        let mut gravity = world.resource_mut::<Gravity>().unwrap();
+        gravity.0 *= time.0;
+        drop((time, gravity));
+        assert_eq!(world.resource::<Gravity>().unwrap().0, -20);
+
+        world.get_resource_mut::<Time>().unwrap().0 = 3;
+        assert_eq!(world.remove_resource::<Time>().map(|time| time.0), Some(3));
+        assert!(!world.contains_resource::<Time>());
+        assert!(world.contains_resource::<Gravity>());
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn mutable_resource_borrows_are_exclusive() {
+        let mut world = World::new();
+        world.insert_resource(0_u32);
+        let _value = world.resource_mut::<u32>();
+        let _ = world.resource::<u32>();
+    }
+
+    #[test]
+    #[should_panic(expected = "twice")]
+    fn bundles_cannot_repeat_a_component() {
+        World::new().spawn((Position(0), Position(1)));
+    }
+}
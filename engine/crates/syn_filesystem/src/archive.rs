@@ -0,0 +1,579 @@
+//! Packed archives.
+//!
+//! `syn_packager` bundles game content into a single archive file that can be
+//! mounted into the [`Vfs`](crate::Vfs) with a [`PackArchive`].
+//!
+//! # File format
+//!
+//! All integers are little-endian.
+//!
+//! | Field        | Encoding                                          |
+//! |--------------|---------------------------------------------------|
+//! | magic        | `b"SYNPAK"`                                       |
+//! | version      | `u16`, currently [`PackArchive::VERSION`]         |
+//! | entry count  | `u32`                                             |
+//! | index offset | `u64`                                             |
+//! | data         | stored file contents, back to back                |
+//! | index        | per entry: path length `u16`, UTF-8 path, offset `u64`, stored length `u64`, length `u64`, compression `u8`, CRC-32 `u32` |
+//!
+//! The CRC-32 covers the uncompressed contents.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::{VfsError, VfsResult};
+use crate::memory::list_children;
+use crate::path::VfsPath;
+use crate::source::{EncodedFile, EntryKind, Metadata, MountSource, clamp_range};
+
+/// Size of the fixed archive header.
+const HEADER_LEN: usize = 20;
+
+/// Largest ratio between the decompressed and compressed sizes of an LZ4
+/// block, so a corrupted index cannot make decompression allocate gigabytes.
+const MAX_LZ4_RATIO: u64 = 255;
+
+/// How a file is stored in an archive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// Stored as is.
+    #[default]
+    None,
+    /// LZ4 block compression, fast to decompress.
+    Lz4,
+}
+
+impl Compression {
+    pub(crate) fn tag(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Lz4 => 1,
+        }
+    }
+
+    pub(crate) fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::None),
+            1 => Some(Self::Lz4),
+            _ => None,
+        }
+    }
+}
+
+/// Location and encoding of one file in an archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackEntry {
+    /// Offset of the stored contents from the start of the archive.
+    pub offset: u64,
+    /// Size of the stored, possibly compressed, contents.
+    pub stored_len: u64,
+    /// Size of the uncompressed contents.
+    pub len: u64,
+    /// How the contents are stored.
+    pub compression: Compression,
+    /// CRC-32 of the uncompressed contents.
+    pub checksum: u32,
+}
+
+#[derive(Debug, Clone)]
+enum Backing {
+    File(PathBuf),
+    Memory(Arc<[u8]>),
+}
+
+/// A read-only archive produced by `syn_packager`.
+///
+/// File-backed archives keep only their index in memory and open the file for
+/// each read, so they can be shared between threads.
+///
+/// # Example
+///
+/// ```
+/// use syn_filesystem::{Compression, PackArchive, PackWriter, Vfs};
+///
+/// let mut writer = PackWriter::new();
+/// writer.add("textures/rock.ktx", b"KTX".to_vec(), Compression::Lz4).unwrap();
+/// let mut bytes = Vec::new();
+/// writer.write_to(&mut bytes).unwrap();
+///
+/// let vfs = Vfs::new();
+/// vfs.mount("vfs://", 0, PackArchive::from_bytes(bytes).unwrap()).unwrap();
+///
+/// assert_eq!(vfs.read("vfs://textures/rock.ktx").unwrap(), b"KTX");
+/// ```
+#[derive(Debug, Clone)]
+pub struct PackArchive {
+    backing: Backing,
+    entries: BTreeMap<VfsPath, PackEntry>,
+}
+
+impl PackArchive {
+    /// File magic identifying an archive.
+    pub const MAGIC: [u8; 6] = *b"SYNPAK";
+    /// Current file format version.
+    pub const VERSION: u16 = 1;
+
+    /// Opens an archive file and reads its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not an archive, was
+    /// written by a newer version or has a malformed index.
+    pub fn open(path: impl AsRef<Path>) -> VfsResult<Self> {
+        let path = path.as_ref();
+        let io_error = |error| VfsError::io(&path.display(), error);
+
+        let mut file = File::open(path).map_err(io_error)?;
+        let mut header = [0; HEADER_LEN];
+        file.read_exact(&mut header).map_err(io_error)?;
+        let (count, index_offset) = parse_header(&header)?;
+
+        file.seek(SeekFrom::Start(index_offset)).map_err(io_error)?;
+        let mut index = Vec::new();
+        file.read_to_end(&mut index).map_err(io_error)?;
+
+        Ok(Self {
+            backing: Backing::File(path.to_owned()),
+            entries: parse_index(&index, count, index_offset)?,
+        })
+    }
+
+    /// Reads an archive held in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not an archive, was written by a newer
+    /// version or has a malformed index.
+    pub fn from_bytes(bytes: impl Into<Arc<[u8]>>) -> VfsResult<Self> {
+        let bytes = bytes.into();
+        let header = bytes
+            .get(..HEADER_LEN)
+            .ok_or(VfsError::InvalidArchive("truncated header"))?;
+        let (count, index_offset) = parse_header(header)?;
+        let index = usize::try_from(index_offset)
+            .ok()
+            .and_then(|offset| bytes.get(offset..))
+            .ok_or(VfsError::InvalidArchive("index out of bounds"))?;
+        let entries = parse_index(index, count, index_offset)?;
+
+        Ok(Self {
+            backing: Backing::Memory(bytes),
+            entries,
+        })
+    }
+
+    /// Returns the number of files.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the archive has no files.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the paths of every file, in order.
+    pub fn paths(&self) -> impl Iterator<Item = &VfsPath> {
+        self.entries.keys()
+    }
+
+    /// Returns where and how a file is stored.
+    pub fn entry(&self, path: &VfsPath) -> Option<&PackEntry> {
+        self.entries.get(path)
+    }
+
+    /// Reads the stored, possibly compressed, contents of an entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be read.
+    pub fn read_stored(&self, entry: &PackEntry) -> VfsResult<Vec<u8>> {
+        self.read_stored_range(entry, 0, entry.stored_len)
+    }
+
+    /// Reads part of the stored contents of an entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be read or the range lies outside
+    /// the entry.
+    pub fn read_stored_range(&self, entry: &PackEntry, start: u64, len: u64) -> VfsResult<Vec<u8>> {
+        let out_of_bounds = VfsError::InvalidArchive("range outside entry");
+        if start
+            .checked_add(len)
+            .is_none_or(|end| end > entry.stored_len)
+        {
+            return Err(out_of_bounds);
+        }
+        let offset = entry.offset + start;
+        let len = usize::try_from(len).map_err(|_| out_of_bounds)?;
+
+        match &self.backing {
+            Backing::File(path) => {
+                let io_error = |error| VfsError::io(&path.display(), error);
+                let mut file = File::open(path).map_err(io_error)?;
+                file.seek(SeekFrom::Start(offset)).map_err(io_error)?;
+                let mut stored = vec![0; len];
+                file.read_exact(&mut stored).map_err(io_error)?;
+                Ok(stored)
+            }
+            Backing::Memory(bytes) => usize::try_from(offset)
+                .ok()
+                .and_then(|offset| bytes.get(offset..offset + len))
+                .map(<[u8]>::to_vec)
+                .ok_or(VfsError::InvalidArchive("entry out of bounds")),
+        }
+    }
+}
+
+/// Decompresses the stored contents of an entry and verifies its checksum.
+///
+/// # Errors
+///
+/// Returns [`VfsError::Corrupted`] if the contents cannot be decompressed, are
+/// larger than LZ4 can expand the stored bytes to, or do not match the
+/// checksum.
+pub fn decode_entry(path: &VfsPath, entry: &PackEntry, stored: Vec<u8>) -> VfsResult<Vec<u8>> {
+    let corrupted = || VfsError::Corrupted(path.to_string());
+    let contents = match entry.compression {
+        Compression::None => stored,
+        Compression::Lz4 => {
+            if entry.len > (stored.len() as u64).saturating_mul(MAX_LZ4_RATIO) {
+                return Err(corrupted());
+            }
+            let len = usize::try_from(entry.len).map_err(|_| corrupted())?;
+            lz4_flex::block::decompress(&stored, len).map_err(|_| corrupted())?
+        }
+    };
+    if contents.len() as u64 != entry.len || crc32fast::hash(&contents) != entry.checksum {
+        return Err(corrupted());
+    }
+    Ok(contents)
+}
+
+impl MountSource for PackArchive {
+    fn metadata(&self, path: &VfsPath) -> VfsResult<Option<Metadata>> {
+        if let Some(entry) = self.entries.get(path) {
+            return Ok(Some(Metadata::file(entry.len, None)));
+        }
+        let is_dir = path.is_root() || self.entries.keys().any(|file| file.starts_with(path));
+        Ok(is_dir.then(|| Metadata::directory(None)))
+    }
+
+    fn read(&self, path: &VfsPath) -> VfsResult<Vec<u8>> {
+        let entry = self
+            .entries
+            .get(path)
+            .ok_or_else(|| VfsError::NotFound(path.to_string()))?;
+        decode_entry(path, entry, self.read_stored(entry)?)
+    }
+
+    fn list(&self, path: &VfsPath) -> VfsResult<Option<Vec<(String, EntryKind)>>> {
+        Ok(list_children(self.entries.keys(), path))
+    }
+
+    fn read_encoded(&self, path: &VfsPath, range: Option<Range<u64>>) -> VfsResult<EncodedFile> {
+        let entry = self
+            .entries
+            .get(path)
+            .ok_or_else(|| VfsError::NotFound(path.to_string()))?;
+        match range {
+            // Stored ranges are read directly, without the whole-file checksum.
+            Some(range) if entry.compression == Compression::None => {
+                let (start, end) = clamp_range(&range, entry.len);
+                let stored = self.read_stored_range(entry, start as u64, (end - start) as u64)?;
+                Ok(EncodedFile::plain(stored))
+            }
+            range => Ok(EncodedFile::archive(
+                path,
+                *entry,
+                self.read_stored(entry)?,
+                range,
+            )),
+        }
+    }
+}
+
+fn parse_header(header: &[u8]) -> VfsResult<(u32, u64)> {
+    if header[..6] != PackArchive::MAGIC {
+        return Err(VfsError::InvalidArchive("missing magic"));
+    }
+    let version = u16::from_le_bytes([header[6], header[7]]);
+    if version == 0 || version > PackArchive::VERSION {
+        return Err(VfsError::UnsupportedVersion(version));
+    }
+    let count = u32::from_le_bytes(header[8..12].try_into().expect("4 bytes"));
+    let index_offset = u64::from_le_bytes(header[12..20].try_into().expect("8 bytes"));
+    Ok((count, index_offset))
+}
+
+fn parse_index(index: &[u8], count: u32, data_end: u64) -> VfsResult<BTreeMap<VfsPath, PackEntry>> {
+    let malformed = || VfsError::InvalidArchive("malformed index");
+    let mut index = IndexReader(index);
+
+    let mut entries = BTreeMap::new();
+    for _ in 0..count {
+        let path_len = usize::from(u16::from_le_bytes(index.array()?));
+        let path = std::str::from_utf8(index.take(path_len)?).map_err(|_| malformed())?;
+        let path = VfsPath::new(path).map_err(|_| malformed())?;
+        let offset = u64::from_le_bytes(index.array()?);
+        let stored_len = u64::from_le_bytes(index.array()?);
+        let len = u64::from_le_bytes(index.array()?);
+        let [tag] = index.array()?;
+        let compression = Compression::from_tag(tag).ok_or_else(malformed)?;
+        let checksum = u32::from_le_bytes(index.array()?);
+
+        if offset
+            .checked_add(stored_len)
+            .is_none_or(|end| end > data_end)
+        {
+            return Err(VfsError::InvalidArchive("entry out of bounds"));
+        }
+        entries.insert(
+            path,
+            PackEntry {
+                offset,
+                stored_len,
+                len,
+                compression,
+                checksum,
+            },
+        );
+    }
+    Ok(entries)
+}
+
+/// Reads the fields of an archive index.
+struct IndexReader<'a>(&'a [u8]);
+
+impl<'a> IndexReader<'a> {
+    fn take(&mut self, len: usize) -> VfsResult<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(VfsError::InvalidArchive("truncated index"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> VfsResult<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+}
+
+/// Builds a [`PackArchive`].
+#[derive(Debug, Clone, Default)]
+pub struct PackWriter {
+    files: BTreeMap<VfsPath, (Vec<u8>, Compression)>,
+}
+
+impl PackWriter {
+    /// Creates an empty archive.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a file.
+    ///
+    /// Files that do not shrink when compressed are stored as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] if the path is invalid, the root or
+    /// longer than 65535 bytes.
+    pub fn add(
+        &mut self,
+        path: &str,
+        contents: Vec<u8>,
+        compression: Compression,
+    ) -> VfsResult<()> {
+        let path = VfsPath::new(path)?;
+        if path.is_root() || u16::try_from(path.as_str().len()).is_err() {
+            return Err(VfsError::InvalidPath {
+                path: path.to_string(),
+                reason: "not a valid archive entry name",
+            });
+        }
+        self.files.insert(path, (contents, compression));
+        Ok(())
+    }
+
+    /// Returns the number of files.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns `true` if the archive has no files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Encodes the archive into a writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_to(&self, mut writer: impl Write) -> VfsResult<()> {
+        let io_error = |error| VfsError::io(&"archive", error);
+        let count = u32::try_from(self.files.len())
+            .map_err(|_| VfsError::InvalidArchive("too many entries"))?;
+
+        let mut offset = HEADER_LEN as u64;
+        let mut index = Vec::new();
+        let mut stored_files = Vec::with_capacity(self.files.len());
+        for (path, (contents, compression)) in &self.files {
+            let (stored, compression) = match compression {
+                Compression::Lz4 => {
+                    let compressed = lz4_flex::block::compress(contents);
+                    if compressed.len() < contents.len() {
+                        (compressed, Compression::Lz4)
+                    } else {
+                        (contents.clone(), Compression::None)
+                    }
+                }
+                Compression::None => (contents.clone(), Compression::None),
+            };
+
+            let path_len = u16::try_from(path.as_str().len())
+                .map_err(|_| VfsError::InvalidArchive("entry name too long"))?;
+            index.extend_from_slice(&path_len.to_le_bytes());
+            index.extend_from_slice(path.as_str().as_bytes());
+            index.extend_from_slice(&offset.to_le_bytes());
+            index.extend_from_slice(&(stored.len() as u64).to_le_bytes());
+            index.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+            index.push(compression.tag());
+            index.extend_from_slice(&crc32fast::hash(contents).to_le_bytes());
+
+            offset += stored.len() as u64;
+            stored_files.push(stored);
+        }
+
+        writer.write_all(&PackArchive::MAGIC).map_err(io_error)?;
+        writer
+            .write_all(&PackArchive::VERSION.to_le_bytes())
+            .map_err(io_error)?;
+        writer.write_all(&count.to_le_bytes()).map_err(io_error)?;
+        writer.write_all(&offset.to_le_bytes()).map_err(io_error)?;
+        for stored in &stored_files {
+            writer.write_all(stored).map_err(io_error)?;
+        }
+        writer.write_all(&index).map_err(io_error)?;
+        Ok(())
+    }
+
+    /// Saves the archive to a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written.
+    pub fn save(&self, path: impl AsRef<Path>) -> VfsResult<()> {
+        let path = path.as_ref();
+        let io_error = |error| VfsError::io(&path.display(), error);
+        let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+        self.write_to(&mut writer)?;
+        writer.flush().map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PackWriter {
+        let mut writer = PackWriter::new();
+        writer
+            .add("textures/rock.ktx", vec![7; 4096], Compression::Lz4)
+            .unwrap();
+        writer
+            .add("textures/ui/icon.png", b"png".to_vec(), Compression::Lz4)
+            .unwrap();
+        writer
+            .add("scripts/main.lua", b"print()".to_vec(), Compression::None)
+            .unwrap();
+        writer
+    }
+
+    fn path(text: &str) -> VfsPath {
+        VfsPath::new(text).unwrap()
+    }
+
+    #[test]
+    fn archives_round_trip_through_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("base.synpak");
+        sample().save(&file).unwrap();
+
+        let archive = PackArchive::open(&file).unwrap();
+        assert_eq!(archive.len(), 3);
+
+        let rock = archive.entry(&path("textures/rock.ktx")).unwrap();
+        assert_eq!(rock.compression, Compression::Lz4);
+        assert!(rock.stored_len < rock.len);
+        assert_eq!(
+            archive
+                .entry(&path("textures/ui/icon.png"))
+                .unwrap()
+                .compression,
+            Compression::None
+        );
+
+        assert_eq!(
+            archive.read(&path("textures/rock.ktx")).unwrap(),
+            vec![7; 4096]
+        );
+        assert_eq!(archive.read(&path("scripts/main.lua")).unwrap(), b"print()");
+        assert_eq!(
+            archive.list(&path("textures")).unwrap().unwrap(),
+            [
+                ("rock.ktx".to_owned(), EntryKind::File),
+                ("ui".to_owned(), EntryKind::Directory)
+            ]
+        );
+        assert!(
+            archive
+                .metadata(&path("textures/ui"))
+                .unwrap()
+                .unwrap()
+                .is_dir()
+        );
+        assert_eq!(archive.metadata(&path("missing")).unwrap(), None);
+    }
+
+    #[test]
+    fn corruption_is_detected() {
+        let mut bytes = Vec::new();
+        sample().write_to(&mut bytes).unwrap();
+
+        let archive = PackArchive::from_bytes(bytes.clone()).unwrap();
+        let main = *archive.entry(&path("scripts/main.lua")).unwrap();
+        bytes[usize::try_from(main.offset).unwrap()] ^= 0xFF;
+        let archive = PackArchive::from_bytes(bytes.clone()).unwrap();
+        assert!(matches!(
+            archive.read(&path("scripts/main.lua")),
+            Err(VfsError::Corrupted(_))
+        ));
+
+        let rock = path("textures/rock.ktx");
+        let mut entry = *archive.entry(&rock).unwrap();
+        let stored = archive.read_stored(&entry).unwrap();
+        entry.len = u64::MAX / 2;
+        assert!(matches!(
+            decode_entry(&rock, &entry, stored),
+            Err(VfsError::Corrupted(_))
+        ));
+
+        bytes[0] = b'X';
+        assert!(matches!(
+            PackArchive::from_bytes(bytes.clone()),
+            Err(VfsError::InvalidArchive(_))
+        ));
+        assert!(matches!(
+            PackArchive::from_bytes(bytes[..10].to_vec()),
+            Err(VfsError::InvalidArchive(_))
+        ));
+    }
+}
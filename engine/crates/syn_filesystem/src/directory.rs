@@ -0,0 +1,112 @@
+//! Operating system directories.
+
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::error::{VfsError, VfsResult};
+use crate::path::VfsPath;
+use crate::source::{EncodedFile, EntryKind, Metadata, MountSource};
+
+/// A directory of the operating system file system.
+///
+/// Entries whose names are not valid VFS path components, such as names that
+/// are not UTF-8 or contain a backslash, are not listed.
+#[derive(Debug, Clone)]
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+impl DirectorySource {
+    /// Creates a source serving the files under `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns the directory served.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the operating system path of a relative VFS path.
+    pub fn os_path(&self, path: &VfsPath) -> PathBuf {
+        let mut os_path = self.root.clone();
+        os_path.extend(path.components());
+        os_path
+    }
+}
+
+impl MountSource for DirectorySource {
+    fn metadata(&self, path: &VfsPath) -> VfsResult<Option<Metadata>> {
+        let os_path = self.os_path(path);
+        match fs::metadata(&os_path) {
+            Ok(metadata) => {
+                let modified = metadata.modified().ok();
+                Ok(Some(if metadata.is_dir() {
+                    Metadata::directory(modified)
+                } else {
+                    Metadata::file(metadata.len(), modified)
+                }))
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(VfsError::io(&os_path.display(), error)),
+        }
+    }
+
+    fn read(&self, path: &VfsPath) -> VfsResult<Vec<u8>> {
+        let os_path = self.os_path(path);
+        fs::read(&os_path).map_err(|error| VfsError::io(&os_path.display(), error))
+    }
+
+    fn list(&self, path: &VfsPath) -> VfsResult<Option<Vec<(String, EntryKind)>>> {
+        let os_path = self.os_path(path);
+        let entries = match fs::read_dir(&os_path) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(VfsError::io(&os_path.display(), error)),
+        };
+
+        let mut listed = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|error| VfsError::io(&os_path.display(), error))?;
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if VfsPath::new(&name).map_or(true, |valid| valid.as_str() != name) {
+                continue;
+            }
+            let is_dir = entry
+                .file_type()
+                .map_err(|error| VfsError::io(&entry.path().display(), error))?
+                .is_dir();
+            let kind = if is_dir {
+                EntryKind::Directory
+            } else {
+                EntryKind::File
+            };
+            listed.push((name, kind));
+        }
+        Ok(Some(listed))
+    }
+
+    fn read_encoded(&self, path: &VfsPath, range: Option<Range<u64>>) -> VfsResult<EncodedFile> {
+        let Some(range) = range else {
+            return self.read(path).map(EncodedFile::plain);
+        };
+        let os_path = self.os_path(path);
+        let io_error = |error| VfsError::io(&os_path.display(), error);
+
+        let mut file = File::open(&os_path).map_err(io_error)?;
+        file.seek(SeekFrom::Start(range.start)).map_err(io_error)?;
+        let mut contents = Vec::new();
+        file.take(range.end.saturating_sub(range.start))
+            .read_to_end(&mut contents)
+            .map_err(io_error)?;
+        Ok(EncodedFile::plain(contents))
+    }
+
+    fn watch_root(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+}
@@ -0,0 +1,109 @@
+//! File system error types.
+
+use std::fmt;
+use std::io;
+
+use thiserror::Error;
+
+/// Errors raised by the virtual file system.
+#[derive(Error, Debug)]
+pub enum VfsError {
+    /// The path is malformed or tries to escape the VFS root.
+    #[error("Invalid path {path:?}: {reason}")]
+    InvalidPath {
+        /// The rejected path.
+        path: String,
+        /// Why it was rejected.
+        reason: &'static str,
+    },
+
+    /// No mounted source has the file or directory.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// The path names a directory where a file was expected.
+    #[error("Is a directory: {0}")]
+    IsADirectory(String),
+
+    /// The path names a file where a directory was expected.
+    #[error("Not a directory: {0}")]
+    NotADirectory(String),
+
+    /// The file is not valid UTF-8.
+    #[error("Not valid UTF-8: {0}")]
+    InvalidUtf8(String),
+
+    /// The operating system failed to access a file.
+    #[error("I/O error on {path}: {source}")]
+    Io {
+        /// The VFS or OS path being accessed.
+        path: String,
+        /// The underlying error.
+        #[source]
+        source: io::Error,
+    },
+
+    /// The data does not start with the archive magic or has a malformed index.
+    #[error("Invalid archive: {0}")]
+    InvalidArchive(&'static str),
+
+    /// The archive was written by a newer, unsupported format version.
+    #[error("Unsupported archive version: {0}")]
+    UnsupportedVersion(u16),
+
+    /// The request was cancelled before it completed.
+    #[error("Request cancelled")]
+    Cancelled,
+
+    /// The operating system refused to watch a directory.
+    #[error("Cannot watch {path}: {reason}")]
+    Watch {
+        /// The directory that could not be watched.
+        path: String,
+        /// Why it could not be watched.
+        reason: String,
+    },
+
+    /// The data is not a save file or has a malformed header.
+    #[error("Invalid save: {0}")]
+    InvalidSave(&'static str),
+
+    /// The save was written by a newer version of the game.
+    #[error("Save schema version {version} is newer than {current}")]
+    UnsupportedSchema {
+        /// Schema version of the save.
+        version: u32,
+        /// Newest schema version the game knows.
+        current: u32,
+    },
+
+    /// No migration upgrades saves from this schema version.
+    #[error("No migration from save schema version {0}")]
+    MissingMigration(u32),
+
+    /// A migration failed to upgrade a save.
+    #[error("Migration from save schema version {version} failed: {reason}")]
+    Migration {
+        /// Schema version the migration upgrades from.
+        version: u32,
+        /// Why it failed.
+        reason: String,
+    },
+
+    /// File contents do not match their checksum.
+    #[error("Corrupted file: {0}")]
+    Corrupted(String),
+}
+
+impl VfsError {
+    /// Wraps an I/O error with the path that caused it.
+    pub(crate) fn io(path: &impl fmt::Display, source: io::Error) -> Self {
+        Self::Io {
+            path: path.to_string(),
+            source,
+        }
+    }
+}
+
+/// Result type for file system operations.
+pub type VfsResult<T> = std::result::Result<T, VfsError>;
@@ -0,0 +1,730 @@
+//! Asynchronous prioritised file I/O.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::future::Future;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use parking_lot::{Condvar, Mutex};
+use syn_tasks::TaskPool;
+
+use crate::error::{VfsError, VfsResult};
+use crate::path::VfsPath;
+use crate::vfs::Vfs;
+
+/// Window over which [`IoStats::bytes_per_second`] is measured.
+const BANDWIDTH_WINDOW: Duration = Duration::from_secs(1);
+
+/// How urgently a read is needed. Higher priorities are served first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IoPriority {
+    /// Prefetching and other speculative reads.
+    Low,
+    /// Regular streaming.
+    #[default]
+    Normal,
+    /// Content visible soon, such as terrain near the camera.
+    High,
+    /// Content the game is waiting for right now.
+    Critical,
+}
+
+impl IoPriority {
+    /// Every priority, from the lowest to the highest.
+    pub const ALL: [IoPriority; 4] = [Self::Low, Self::Normal, Self::High, Self::Critical];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Cancels the reads it was given to.
+///
+/// Clones share the same flag, so one token can cancel a group of requests.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every request using this token.
+    ///
+    /// Requests still queued complete with [`VfsError::Cancelled`] without
+    /// being read; requests being read or decoded complete with it as soon as
+    /// that step ends.
+    pub fn cancel(&self) {
+        self.0.store(true, AtomicOrdering::Release);
+    }
+
+    /// Returns `true` once [`cancel`](Self::cancel) was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(AtomicOrdering::Acquire)
+    }
+}
+
+/// A read submitted to [`AsyncIo`].
+#[derive(Debug, Clone)]
+pub struct ReadRequest {
+    path: String,
+    range: Option<Range<u64>>,
+    priority: IoPriority,
+    token: CancellationToken,
+}
+
+impl ReadRequest {
+    /// Creates a request reading a whole file at [`IoPriority::Normal`].
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            range: None,
+            priority: IoPriority::Normal,
+            token: CancellationToken::new(),
+        }
+    }
+
+    /// Reads only the bytes of `range`, clamped to the file length.
+    #[must_use]
+    pub fn with_range(mut self, range: Range<u64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Sets the priority.
+    #[must_use]
+    pub fn with_priority(mut self, priority: IoPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Uses a shared cancellation token.
+    #[must_use]
+    pub fn with_token(mut self, token: CancellationToken) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Returns the cancellation token.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+type Callback = Box<dyn FnOnce(VfsResult<Vec<u8>>) + Send>;
+
+#[derive(Default)]
+struct CompletionState {
+    result: Option<VfsResult<Vec<u8>>>,
+    finished: bool,
+    waker: Option<Waker>,
+    callback: Option<Callback>,
+}
+
+#[derive(Default)]
+struct Completion {
+    state: Mutex<CompletionState>,
+    done: Condvar,
+}
+
+impl Completion {
+    fn complete(&self, result: VfsResult<Vec<u8>>) {
+        let mut state = self.state.lock();
+        state.finished = true;
+        if let Some(callback) = state.callback.take() {
+            drop(state);
+            // A panicking callback must not take down the thread serving
+            // every other read.
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| callback(result))) {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("non-string payload");
+                log::error!("read callback panicked: {message}");
+            }
+            return;
+        }
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.done.notify_all();
+    }
+}
+
+/// A pending read, awaitable as a [`Future`].
+///
+/// The result can also be polled with [`try_take`](Self::try_take) from a game
+/// loop, or waited for with [`wait`](Self::wait). Dropping the handle does not
+/// cancel the read; call [`cancel`](Self::cancel) for that.
+pub struct IoHandle {
+    completion: Arc<Completion>,
+    token: CancellationToken,
+}
+
+impl IoHandle {
+    /// Cancels the read.
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Returns `true` once the read has completed.
+    pub fn is_finished(&self) -> bool {
+        self.completion.state.lock().finished
+    }
+
+    /// Returns the result if the read has completed and the result was not
+    /// taken yet.
+    pub fn try_take(&mut self) -> Option<VfsResult<Vec<u8>>> {
+        self.completion.state.lock().result.take()
+    }
+
+    /// Blocks the calling thread until the read completes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the read, or [`VfsError::Cancelled`] if the result
+    /// was already taken.
+    pub fn wait(self) -> VfsResult<Vec<u8>> {
+        let mut state = self.completion.state.lock();
+        while !state.finished {
+            self.completion.done.wait(&mut state);
+        }
+        state.result.take().unwrap_or(Err(VfsError::Cancelled))
+    }
+}
+
+impl Future for IoHandle {
+    type Output = VfsResult<Vec<u8>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.completion.state.lock();
+        if state.finished {
+            return Poll::Ready(state.result.take().unwrap_or(Err(VfsError::Cancelled)));
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Configuration of [`AsyncIo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsyncIoConfig {
+    /// Number of threads issuing blocking reads.
+    pub io_threads: usize,
+}
+
+impl Default for AsyncIoConfig {
+    fn default() -> Self {
+        Self { io_threads: 2 }
+    }
+}
+
+/// Counters describing the work done by [`AsyncIo`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IoStats {
+    /// Requests waiting for an I/O thread.
+    pub pending: usize,
+    /// Requests that completed successfully.
+    pub completed: u64,
+    /// Requests that failed.
+    pub failed: u64,
+    /// Requests that were cancelled.
+    pub cancelled: u64,
+    /// Bytes read from storage, before decompression.
+    pub bytes_read: u64,
+    /// Bytes read from storage per [`IoPriority`], indexed from
+    /// [`IoPriority::Low`].
+    pub bytes_read_by_priority: [u64; 4],
+    /// Bytes read from storage during the last second.
+    pub bytes_per_second: u64,
+}
+
+struct Pending {
+    priority: IoPriority,
+    sequence: u64,
+    path: String,
+    range: Option<Range<u64>>,
+    token: CancellationToken,
+    completion: Arc<Completion>,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    /// Higher priority first, then first submitted first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+#[derive(Default)]
+struct Queue {
+    requests: BinaryHeap<Pending>,
+    next_sequence: u64,
+    shutdown: bool,
+}
+
+#[derive(Default)]
+struct Accounting {
+    stats: IoStats,
+    recent: VecDeque<(Instant, u64)>,
+}
+
+struct Shared {
+    vfs: Arc<Vfs>,
+    tasks: Arc<TaskPool>,
+    queue: Mutex<Queue>,
+    available: Condvar,
+    accounting: Mutex<Accounting>,
+}
+
+/// Reads files from a [`Vfs`] without blocking the calling thread.
+///
+/// Dedicated I/O threads serve requests by priority, then in submission order.
+/// Compressed archive entries are decompressed and verified on a
+/// [`TaskPool`], so the I/O threads go straight on to the next read. Results
+/// are delivered through an awaitable [`IoHandle`] or a callback.
+///
+/// Dropping the service cancels the requests still queued.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use syn_filesystem::{AsyncIo, AsyncIoConfig, IoPriority, MemorySource, ReadRequest, Vfs};
+/// use syn_tasks::TaskPool;
+///
+/// let files = MemorySource::new();
+/// files.insert("terrain/tile_0_0.bin", vec![1, 2, 3, 4]).unwrap();
+/// let vfs = Arc::new(Vfs::new());
+/// vfs.mount("vfs://", 0, files).unwrap();
+///
+/// let io = AsyncIo::new(vfs, Arc::new(TaskPool::new(2)), AsyncIoConfig::default());
+/// let handle = io.read(
+///     ReadRequest::new("vfs://terrain/tile_0_0.bin")
+///         .with_range(1..3)
+///         .with_priority(IoPriority::High),
+/// );
+///
+/// assert_eq!(handle.wait().unwrap(), [2, 3]);
+/// assert_eq!(io.stats().bytes_read, 2);
+/// ```
+pub struct AsyncIo {
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl AsyncIo {
+    /// Starts the I/O threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot spawn a thread.
+    pub fn new(vfs: Arc<Vfs>, tasks: Arc<TaskPool>, config: AsyncIoConfig) -> Self {
+        let shared = Arc::new(Shared {
+            vfs,
+            tasks,
+            queue: Mutex::new(Queue::default()),
+            available: Condvar::new(),
+            accounting: Mutex::new(Accounting::default()),
+        });
+        let threads = (0..config.io_threads.max(1))
+            .map(|index| {
+                let shared = Arc::clone(&shared);
+                thread::Builder::new()
+                    .name(format!("syn-io-{index}"))
+                    .spawn(move || io_thread(&shared))
+                    .expect("failed to spawn an I/O thread")
+            })
+            .collect();
+        Self { shared, threads }
+    }
+
+    /// Returns the file system read from.
+    pub fn vfs(&self) -> &Arc<Vfs> {
+        &self.shared.vfs
+    }
+
+    /// Submits a read and returns a handle to its result.
+    pub fn read(&self, request: ReadRequest) -> IoHandle {
+        let completion = Arc::new(Completion::default());
+        let token = request.token.clone();
+        self.submit(request, Arc::clone(&completion));
+        IoHandle { completion, token }
+    }
+
+    /// Submits a read whose result is passed to `callback`.
+    ///
+    /// The callback runs on the I/O or worker thread that completes the read;
+    /// a panic inside it is caught and logged. Returns the request's cancellation token.
+    pub fn read_with(
+        &self,
+        request: ReadRequest,
+        callback: impl FnOnce(VfsResult<Vec<u8>>) + Send + 'static,
+    ) -> CancellationToken {
+        let completion = Arc::new(Completion::default());
+        completion.state.lock().callback = Some(Box::new(callback));
+        let token = request.token.clone();
+        self.submit(request, completion);
+        token
+    }
+
+    /// Returns the work done so far.
+    pub fn stats(&self) -> IoStats {
+        self.stats_at(Instant::now())
+    }
+
+    /// Returns the work done so far, measuring the bandwidth over the window
+    /// ending at `now`.
+    fn stats_at(&self, now: Instant) -> IoStats {
+        let pending = self.shared.queue.lock().requests.len();
+        let mut accounting = self.shared.accounting.lock();
+        accounting.prune(now);
+        let bytes_per_second = accounting.recent.iter().map(|(_, bytes)| bytes).sum();
+        IoStats {
+            pending,
+            bytes_per_second,
+            ..accounting.stats.clone()
+        }
+    }
+
+    fn submit(&self, request: ReadRequest, completion: Arc<Completion>) {
+        let mut queue = self.shared.queue.lock();
+        let sequence = queue.next_sequence;
+        queue.next_sequence += 1;
+        queue.requests.push(Pending {
+            priority: request.priority,
+            sequence,
+            path: request.path,
+            range: request.range,
+            token: request.token,
+            completion,
+        });
+        drop(queue);
+        self.shared.available.notify_one();
+    }
+}
+
+impl Drop for AsyncIo {
+    fn drop(&mut self) {
+        let abandoned = {
+            let mut queue = self.shared.queue.lock();
+            queue.shutdown = true;
+            std::mem::take(&mut queue.requests)
+        };
+        self.shared.available.notify_all();
+        for request in abandoned {
+            finish(&self.shared, &request.completion, Err(VfsError::Cancelled));
+        }
+        for thread in self.threads.drain(..) {
+            // Callbacks run under `catch_unwind`, so I/O threads only end on
+            // shutdown.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Accounting {
+    fn prune(&mut self, now: Instant) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|(time, _)| now.duration_since(*time) > BANDWIDTH_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+    }
+}
+
+fn io_thread(shared: &Arc<Shared>) {
+    loop {
+        let request = {
+            let mut queue = shared.queue.lock();
+            loop {
+                if queue.shutdown {
+                    return;
+                }
+                if let Some(request) = queue.requests.pop() {
+                    break request;
+                }
+                shared.available.wait(&mut queue);
+            }
+        };
+        serve(shared, request);
+    }
+}
+
+fn serve(shared: &Arc<Shared>, request: Pending) {
+    if request.token.is_cancelled() {
+        finish(shared, &request.completion, Err(VfsError::Cancelled));
+        return;
+    }
+
+    let file = VfsPath::new(&request.path)
+        .and_then(|path| shared.vfs.read_encoded(&path, request.range.clone()));
+    let file = match file {
+        Ok(file) => file,
+        Err(error) => {
+            finish(shared, &request.completion, Err(error));
+            return;
+        }
+    };
+
+    {
+        let mut accounting = shared.accounting.lock();
+        let bytes = file.stored_len() as u64;
+        let now = Instant::now();
+        accounting.stats.bytes_read += bytes;
+        accounting.stats.bytes_read_by_priority[request.priority.index()] += bytes;
+        accounting.recent.push_back((now, bytes));
+        accounting.prune(now);
+    }
+
+    if request.token.is_cancelled() {
+        finish(shared, &request.completion, Err(VfsError::Cancelled));
+    } else if file.is_encoded() {
+        let worker_shared = Arc::clone(shared);
+        shared.tasks.spawn(move || {
+            let result = if request.token.is_cancelled() {
+                Err(VfsError::Cancelled)
+            } else {
+                file.decode()
+            };
+            finish(&worker_shared, &request.completion, result);
+        });
+    } else {
+        finish(shared, &request.completion, file.decode());
+    }
+}
+
+fn finish(shared: &Shared, completion: &Completion, result: VfsResult<Vec<u8>>) {
+    {
+        let stats = &mut shared.accounting.lock().stats;
+        match &result {
+            Ok(_) => stats.completed += 1,
+            Err(VfsError::Cancelled) => stats.cancelled += 1,
+            Err(_) => stats.failed += 1,
+        }
+    }
+    completion.complete(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::{Compression, PackArchive, PackWriter};
+    use crate::memory::MemorySource;
+    use crate::source::{EntryKind, Metadata, MountSource};
+    use std::sync::mpsc;
+    use std::task::Wake;
+
+    /// A source whose `gate` file blocks until released.
+    struct GatedSource {
+        files: MemorySource,
+        release: Mutex<Option<mpsc::Receiver<()>>>,
+    }
+
+    impl MountSource for GatedSource {
+        fn metadata(&self, path: &VfsPath) -> VfsResult<Option<Metadata>> {
+            self.files.metadata(path)
+        }
+
+        fn read(&self, path: &VfsPath) -> VfsResult<Vec<u8>> {
+            let release = match path.as_str() {
+                "gate" => self.release.lock().take(),
+                _ => None,
+            };
+            if let Some(release) = release {
+                release.recv().unwrap();
+            }
+            self.files.read(path)
+        }
+
+        fn list(&self, path: &VfsPath) -> VfsResult<Option<Vec<(String, EntryKind)>>> {
+            self.files.list(path)
+        }
+    }
+
+    fn gated_io() -> (AsyncIo, mpsc::Sender<()>) {
+        let files = MemorySource::new();
+        for name in ["gate", "low", "normal", "critical", "cancelled"] {
+            files.insert(name, name.as_bytes().to_vec()).unwrap();
+        }
+        let (release, gate) = mpsc::channel();
+        let vfs = Vfs::new();
+        vfs.mount(
+            "",
+            0,
+            GatedSource {
+                files,
+                release: Mutex::new(Some(gate)),
+            },
+        )
+        .unwrap();
+        let config = AsyncIoConfig { io_threads: 1 };
+        let io = AsyncIo::new(Arc::new(vfs), Arc::new(TaskPool::new(1)), config);
+        (io, release)
+    }
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn requests_are_served_by_priority_and_can_be_cancelled() {
+        let (io, release) = gated_io();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let record = |name: &'static str| {
+            let order = Arc::clone(&order);
+            move |result: VfsResult<Vec<u8>>| order.lock().push((name, result.is_ok()))
+        };
+
+        let gate = io.read(ReadRequest::new("gate"));
+        // Wait for the single I/O thread to block on the gate.
+        while io.stats().pending > 0 {
+            thread::yield_now();
+        }
+        io.read_with(
+            ReadRequest::new("low").with_priority(IoPriority::Low),
+            record("low"),
+        );
+        io.read_with(ReadRequest::new("normal"), record("normal"));
+        let token = io.read_with(ReadRequest::new("cancelled"), record("cancelled"));
+        io.read_with(
+            ReadRequest::new("critical").with_priority(IoPriority::Critical),
+            record("critical"),
+        );
+        token.cancel();
+        assert_eq!(io.stats().pending, 4);
+
+        release.send(()).unwrap();
+        assert_eq!(gate.wait().unwrap(), b"gate");
+        while order.lock().len() < 4 {
+            thread::yield_now();
+        }
+        assert_eq!(io.stats().cancelled, 1);
+
+        assert_eq!(
+            *order.lock(),
+            [
+                ("critical", true),
+                ("normal", true),
+                ("cancelled", false),
+                ("low", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn archive_entries_decode_on_workers_and_support_ranges() {
+        let mut writer = PackWriter::new();
+        let contents: Vec<u8> = (0..=255).cycle().take(64 * 1024).collect();
+        writer
+            .add("packed.bin", contents.clone(), Compression::Lz4)
+            .unwrap();
+        writer
+            .add("stored.bin", contents.clone(), Compression::None)
+            .unwrap();
+        let mut bytes = Vec::new();
+        writer.write_to(&mut bytes).unwrap();
+        let archive = PackArchive::from_bytes(bytes).unwrap();
+        let packed_len = archive
+            .entry(&VfsPath::new("packed.bin").unwrap())
+            .unwrap()
+            .stored_len;
+
+        let vfs = Vfs::new();
+        vfs.mount("", 0, archive).unwrap();
+        let io = AsyncIo::new(
+            Arc::new(vfs),
+            Arc::new(TaskPool::new(2)),
+            AsyncIoConfig::default(),
+        );
+        let start = Instant::now();
+
+        let whole = block_on(io.read(ReadRequest::new("packed.bin"))).unwrap();
+        assert_eq!(whole, contents);
+        let packed_range = io
+            .read(ReadRequest::new("packed.bin").with_range(1000..1010))
+            .wait()
+            .unwrap();
+        assert_eq!(packed_range, contents[1000..1010]);
+        let stored_range = io
+            .read(ReadRequest::new("stored.bin").with_range(65530..70000))
+            .wait()
+            .unwrap();
+        assert_eq!(stored_range, contents[65530..]);
+        assert!(matches!(
+            io.read(ReadRequest::new("missing.bin")).wait(),
+            Err(VfsError::NotFound(_))
+        ));
+
+        let stats = io.stats_at(start);
+        assert_eq!(stats.completed, 3);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.bytes_read, packed_len * 2 + 6);
+        assert_eq!(
+            stats.bytes_read_by_priority[IoPriority::Normal.index()],
+            stats.bytes_read
+        );
+        assert_eq!(stats.bytes_per_second, stats.bytes_read);
+        let later = io.stats_at(Instant::now() + BANDWIDTH_WINDOW * 2);
+        assert_eq!(later.bytes_per_second, 0);
+        assert_eq!(later.bytes_read, stats.bytes_read);
+    }
+
+    #[test]
+    fn panicking_callbacks_do_not_stop_the_io_thread() {
+        let files = MemorySource::new();
+        files.insert("a.txt", b"a".to_vec()).unwrap();
+        let vfs = Vfs::new();
+        vfs.mount("", 0, files).unwrap();
+        let config = AsyncIoConfig { io_threads: 1 };
+        let io = AsyncIo::new(Arc::new(vfs), Arc::new(TaskPool::new(1)), config);
+
+        io.read_with(ReadRequest::new("a.txt"), |_| panic!("callback failed"));
+        assert_eq!(io.read(ReadRequest::new("a.txt")).wait().unwrap(), b"a");
+        assert_eq!(io.stats().completed, 2);
+    }
+}
@@ -1 +1,40 @@
-//! `syn_filesystem` - TODO: Implement
+//! `syn_filesystem` - Virtual file system and asynchronous file I/O for Synarion Engine.
+//!
+//! Game code reads `vfs://` paths; the data comes from mounted sources:
+//! - [`Vfs`] - Mount table with priorities, so mods and patches shadow base content
+//! - [`VfsPath`] - Normalized `vfs://` paths that reject path traversal
+//! - [`MountSource`] - Trait implemented by mountable sources
+//! - [`DirectorySource`] - Operating system directories
+//! - [`MemorySource`] - Files held in memory
+//! - [`PackArchive`] / [`PackWriter`] - Packed archives produced by `syn_packager`
+//! - [`AsyncIo`] - Prioritised, cancellable background reads
+//! - [`SaveStore`] - Versioned save slots with atomic writes, backups and migrations
+//! - [`FileWatcher`] - Debounced change events of mounted directories, for hot reload
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+mod archive;
+mod directory;
+mod error;
+mod io;
+mod memory;
+mod path;
+mod save;
+mod source;
+mod vfs;
+mod watcher;
+
+pub use archive::{Compression, PackArchive, PackEntry, PackWriter, decode_entry};
+pub use directory::DirectorySource;
+pub use error::{VfsError, VfsResult};
+pub use io::{
+    AsyncIo, AsyncIoConfig, CancellationToken, IoHandle, IoPriority, IoStats, ReadRequest,
+};
+pub use memory::MemorySource;
+pub use path::VfsPath;
+pub use save::{MigrationRegistry, SaveGame, SaveInfo, SaveMetadata, SaveStore};
+pub use source::{DirEntry, EncodedFile, EntryKind, Metadata, MountSource};
+pub use vfs::{MountId, MountInfo, Vfs};
+pub use watcher::{FileWatcher, WatchConfig, WatchEvent};
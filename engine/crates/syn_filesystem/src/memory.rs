@@ -0,0 +1,123 @@
+//! In-memory file sources.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::error::{VfsError, VfsResult};
+use crate::path::VfsPath;
+use crate::source::{EntryKind, Metadata, MountSource};
+
+/// Files held in memory, such as generated content or test fixtures.
+///
+/// Clones share the same files, so a source can be edited after being mounted.
+///
+/// # Example
+///
+/// ```
+/// use syn_filesystem::{MemorySource, Vfs};
+///
+/// let files = MemorySource::new();
+/// let vfs = Vfs::new();
+/// vfs.mount("vfs://generated", 0, files.clone()).unwrap();
+///
+/// files.insert("noise.bin", vec![1, 2, 3]).unwrap();
+/// assert_eq!(vfs.read("vfs://generated/noise.bin").unwrap(), [1, 2, 3]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    files: Arc<RwLock<BTreeMap<VfsPath, Arc<[u8]>>>>,
+}
+
+impl MemorySource {
+    /// Creates an empty source.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a file. Parent directories exist implicitly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] if the path is invalid or the root.
+    pub fn insert(&self, path: &str, contents: impl Into<Arc<[u8]>>) -> VfsResult<()> {
+        let path = VfsPath::new(path)?;
+        if path.is_root() {
+            return Err(VfsError::InvalidPath {
+                path: path.to_string(),
+                reason: "the root is a directory",
+            });
+        }
+        self.files.write().insert(path, contents.into());
+        Ok(())
+    }
+
+    /// Removes a file, returning `true` if it existed.
+    pub fn remove(&self, path: &str) -> bool {
+        VfsPath::new(path).is_ok_and(|path| self.files.write().remove(&path).is_some())
+    }
+
+    /// Returns the number of files.
+    pub fn len(&self) -> usize {
+        self.files.read().len()
+    }
+
+    /// Returns `true` if the source has no files.
+    pub fn is_empty(&self) -> bool {
+        self.files.read().is_empty()
+    }
+}
+
+impl MountSource for MemorySource {
+    fn metadata(&self, path: &VfsPath) -> VfsResult<Option<Metadata>> {
+        let files = self.files.read();
+        if let Some(contents) = files.get(path) {
+            return Ok(Some(Metadata::file(contents.len() as u64, None)));
+        }
+        let is_dir = path.is_root() || files.keys().any(|file| file.starts_with(path));
+        Ok(is_dir.then(|| Metadata::directory(None)))
+    }
+
+    fn read(&self, path: &VfsPath) -> VfsResult<Vec<u8>> {
+        self.files
+            .read()
+            .get(path)
+            .map(|contents| contents.to_vec())
+            .ok_or_else(|| VfsError::NotFound(path.to_string()))
+    }
+
+    fn list(&self, path: &VfsPath) -> VfsResult<Option<Vec<(String, EntryKind)>>> {
+        Ok(list_children(self.files.read().keys(), path))
+    }
+}
+
+/// Lists the direct children of `dir` among a set of file paths, inferring
+/// directories from deeper paths. Returns `None` if no file lies inside `dir`.
+pub(crate) fn list_children<'a>(
+    files: impl Iterator<Item = &'a VfsPath>,
+    dir: &VfsPath,
+) -> Option<Vec<(String, EntryKind)>> {
+    let mut children: BTreeMap<&str, EntryKind> = BTreeMap::new();
+    let mut found = dir.is_root();
+    for file in files {
+        let Some(rest) = file.relative_to(dir) else {
+            continue;
+        };
+        if rest.is_empty() {
+            // `dir` is a file.
+            return None;
+        }
+        found = true;
+        match rest.split_once('/') {
+            Some((name, _)) => children.insert(name, EntryKind::Directory),
+            None => children.insert(rest, EntryKind::File),
+        };
+    }
+    found.then(|| {
+        children
+            .into_iter()
+            .map(|(name, kind)| (name.to_owned(), kind))
+            .collect()
+    })
+}
@@ -0,0 +1,232 @@
+//! Virtual file system paths.
+
+use std::fmt;
+
+use crate::error::{VfsError, VfsResult};
+
+/// A normalized path inside the virtual file system.
+///
+/// Paths use `/` separators and are relative to the VFS root, with an optional
+/// `vfs://` scheme. Empty and `.` components are dropped. Anything that could
+/// escape a mounted directory — `..`, backslashes, drive letters or NUL bytes —
+/// is rejected.
+///
+/// # Example
+///
+/// ```
+/// use syn_filesystem::VfsPath;
+///
+/// let path = VfsPath::new("vfs://textures//./rock.ktx").unwrap();
+///
+/// assert_eq!(path.as_str(), "textures/rock.ktx");
+/// assert_eq!(path.to_string(), "vfs://textures/rock.ktx");
+/// assert_eq!(path.extension(), Some("ktx"));
+/// assert!(VfsPath::new("vfs://textures/../../etc/passwd").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VfsPath(String);
+
+impl VfsPath {
+    /// Scheme prefix accepted, and printed, before a path.
+    pub const SCHEME: &'static str = "vfs://";
+
+    /// Parses and normalizes a path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] if the path has another scheme, a `..`
+    /// component, a backslash, a colon or a NUL byte.
+    pub fn new(path: &str) -> VfsResult<Self> {
+        let invalid = |reason| VfsError::InvalidPath {
+            path: path.to_owned(),
+            reason,
+        };
+
+        let rest = path.strip_prefix(Self::SCHEME).unwrap_or(path);
+        if rest.contains("://") {
+            return Err(invalid("unsupported scheme"));
+        }
+        if rest.contains('\\') {
+            return Err(invalid("backslash separator"));
+        }
+        if rest.contains(':') {
+            return Err(invalid("drive or stream separator"));
+        }
+        if rest.contains('\0') {
+            return Err(invalid("NUL byte"));
+        }
+
+        let mut normalized = String::with_capacity(rest.len());
+        for component in rest.split('/') {
+            match component {
+                "" | "." => {}
+                ".." => return Err(invalid("parent directory component")),
+                component => {
+                    if !normalized.is_empty() {
+                        normalized.push('/');
+                    }
+                    normalized.push_str(component);
+                }
+            }
+        }
+
+        Ok(Self(normalized))
+    }
+
+    /// Returns the root of the file system.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Returns the path without scheme, such as `textures/rock.ktx`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` for the root of the file system.
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Appends a relative path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] if `path` is invalid.
+    pub fn join(&self, path: &str) -> VfsResult<Self> {
+        let path = Self::new(path)?;
+        Ok(match (self.is_root(), path.is_root()) {
+            (true, _) => path,
+            (false, true) => self.clone(),
+            (false, false) => Self(format!("{}/{}", self.0, path.0)),
+        })
+    }
+
+    /// Returns the parent directory, or `None` for the root.
+    pub fn parent(&self) -> Option<Self> {
+        if self.is_root() {
+            return None;
+        }
+        Some(Self(
+            self.0
+                .rsplit_once('/')
+                .map_or_else(String::new, |(parent, _)| parent.to_owned()),
+        ))
+    }
+
+    /// Returns the last component, or `None` for the root.
+    pub fn file_name(&self) -> Option<&str> {
+        if self.is_root() {
+            return None;
+        }
+        Some(
+            self.0
+                .rsplit_once('/')
+                .map_or(self.0.as_str(), |(_, name)| name),
+        )
+    }
+
+    /// Returns the extension of the last component, without the dot.
+    pub fn extension(&self) -> Option<&str> {
+        let (stem, extension) = self.file_name()?.rsplit_once('.')?;
+        (!stem.is_empty()).then_some(extension)
+    }
+
+    /// Returns the components of the path.
+    pub fn components(&self) -> impl Iterator<Item = &str> {
+        self.0.split('/').filter(|component| !component.is_empty())
+    }
+
+    /// Returns the rest of the path if it lies inside `base`.
+    ///
+    /// A path lies inside itself; the rest is then the root.
+    pub fn strip_prefix(&self, base: &VfsPath) -> Option<VfsPath> {
+        self.relative_to(base).map(|rest| Self(rest.to_owned()))
+    }
+
+    /// Returns the rest of the path as a string if it lies inside `base`.
+    pub(crate) fn relative_to(&self, base: &VfsPath) -> Option<&str> {
+        if base.is_root() {
+            return Some(&self.0);
+        }
+        match self.0.strip_prefix(&base.0)? {
+            "" => Some(""),
+            rest => rest.strip_prefix('/'),
+        }
+    }
+
+    /// Returns `true` if the path lies inside `base`, or is `base`.
+    pub fn starts_with(&self, base: &VfsPath) -> bool {
+        self.strip_prefix(base).is_some()
+    }
+}
+
+impl fmt::Display for VfsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", Self::SCHEME, self.0)
+    }
+}
+
+impl AsRef<str> for VfsPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for VfsPath {
+    type Error = VfsError;
+
+    fn try_from(path: &str) -> VfsResult<Self> {
+        Self::new(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(text: &str) -> VfsPath {
+        VfsPath::new(text).unwrap()
+    }
+
+    #[test]
+    fn paths_are_normalized() {
+        assert_eq!(path("vfs://a/b").as_str(), "a/b");
+        assert_eq!(path("/a//./b/").as_str(), "a/b");
+        assert!(path("vfs://").is_root());
+        assert_eq!(path("a").join("b/c.txt").unwrap().as_str(), "a/b/c.txt");
+        assert_eq!(path("a/b").parent(), Some(path("a")));
+        assert_eq!(path("a").parent(), Some(VfsPath::root()));
+        assert_eq!(path(".hidden").extension(), None);
+    }
+
+    #[test]
+    fn traversal_and_foreign_paths_are_rejected() {
+        for bad in [
+            "../secret",
+            "a/../../b",
+            "a\\..\\b",
+            "C:/Windows",
+            "file:///etc/passwd",
+            "a\0b",
+        ] {
+            assert!(
+                matches!(VfsPath::new(bad), Err(VfsError::InvalidPath { .. })),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn prefixes_match_whole_components() {
+        let textures = path("textures");
+
+        assert_eq!(
+            path("textures/rock.ktx").strip_prefix(&textures),
+            Some(path("rock.ktx"))
+        );
+        assert_eq!(textures.strip_prefix(&textures), Some(VfsPath::root()));
+        assert_eq!(path("textures2/rock.ktx").strip_prefix(&textures), None);
+        assert!(textures.starts_with(&VfsPath::root()));
+    }
+}
@@ -0,0 +1,757 @@
+//! Save-game storage.
+//!
+//! A [`SaveStore`] keeps one file per named slot in an operating system
+//! directory, usually under the player's profile. Saves are written to a
+//! temporary file and renamed into place, so a crash never leaves a half
+//! written save, and the previous saves of a slot are kept as numbered backups.
+//!
+//! # File format
+//!
+//! All integers are little-endian.
+//!
+//! | Field           | Encoding                                         |
+//! |-----------------|--------------------------------------------------|
+//! | magic           | `b"SYNSAV"`                                      |
+//! | version         | `u16`, currently [`SaveStore::FORMAT_VERSION`]   |
+//! | schema version  | `u32`, the game's save data version              |
+//! | compression     | `u8`: 0 stored, 1 LZ4                            |
+//! | play time       | `u64` milliseconds                               |
+//! | saved at        | `u64` milliseconds since the Unix epoch          |
+//! | description     | length `u16`, UTF-8 text                         |
+//! | thumbnail       | length `u32`, image bytes                        |
+//! | data length     | `u64`, uncompressed                              |
+//! | data            | stored length `u64`, stored bytes                |
+//! | checksum        | `u32`, CRC-32 of every preceding byte            |
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::archive::Compression;
+use crate::error::{VfsError, VfsResult};
+
+/// Extension of save files.
+const EXTENSION: &str = "sav";
+
+/// Longest slot name.
+const MAX_SLOT_LEN: usize = 64;
+
+/// Describes a save to the player, for instance in a load menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveMetadata {
+    /// Time played when the game was saved.
+    pub play_time: Duration,
+    /// When the game was saved.
+    pub saved_at: SystemTime,
+    /// Text shown with the save, such as the current location.
+    pub description: String,
+    /// Encoded thumbnail image, or empty.
+    pub thumbnail: Vec<u8>,
+}
+
+impl SaveMetadata {
+    /// Creates metadata for a save made now, without description or thumbnail.
+    pub fn new(play_time: Duration) -> Self {
+        Self {
+            play_time,
+            saved_at: SystemTime::now(),
+            description: String::new(),
+            thumbnail: Vec::new(),
+        }
+    }
+
+    /// Sets the description.
+    #[must_use]
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Sets the encoded thumbnail image.
+    #[must_use]
+    pub fn with_thumbnail(mut self, thumbnail: Vec<u8>) -> Self {
+        self.thumbnail = thumbnail;
+        self
+    }
+}
+
+/// A loaded save.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveGame {
+    /// Schema version of `data`, after migration.
+    pub schema_version: u32,
+    /// The save's metadata.
+    pub metadata: SaveMetadata,
+    /// The game's save data.
+    pub data: Vec<u8>,
+    /// `0` if the save came from the slot's file, or the number of the backup
+    /// it was recovered from.
+    pub generation: usize,
+}
+
+/// A slot listed by [`SaveStore::list`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveInfo {
+    /// The slot name.
+    pub slot: String,
+    /// Schema version the save was written with.
+    pub schema_version: u32,
+    /// The save's metadata.
+    pub metadata: SaveMetadata,
+    /// `0` for the slot's file, or the number of the backup describing the slot
+    /// because the file is missing or corrupted.
+    pub generation: usize,
+}
+
+type Migration = Box<dyn Fn(Vec<u8>) -> Result<Vec<u8>, String> + Send + Sync>;
+
+/// Migrations upgrading save data from one schema version to the next.
+///
+/// Loading a save written with an older schema runs each migration in turn
+/// until the data reaches the current version.
+#[derive(Default)]
+pub struct MigrationRegistry {
+    migrations: BTreeMap<u32, Migration>,
+}
+
+impl MigrationRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the migration upgrading data from `from_version` to
+    /// `from_version + 1`, replacing any previous one.
+    ///
+    /// The migration returns the upgraded data, or why it cannot upgrade it.
+    pub fn register(
+        &mut self,
+        from_version: u32,
+        migration: impl Fn(Vec<u8>) -> Result<Vec<u8>, String> + Send + Sync + 'static,
+    ) {
+        self.migrations.insert(from_version, Box::new(migration));
+    }
+
+    /// Returns `true` if a migration upgrades data from `version`.
+    pub fn contains(&self, version: u32) -> bool {
+        self.migrations.contains_key(&version)
+    }
+
+    /// Upgrades data from schema version `from` to `to`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::MissingMigration`] if a step has no migration and
+    /// [`VfsError::Migration`] if a migration fails.
+    pub fn migrate(&self, mut data: Vec<u8>, from: u32, to: u32) -> VfsResult<Vec<u8>> {
+        for version in from..to {
+            let migration = self
+                .migrations
+                .get(&version)
+                .ok_or(VfsError::MissingMigration(version))?;
+            data = migration(data).map_err(|reason| VfsError::Migration { version, reason })?;
+        }
+        Ok(data)
+    }
+}
+
+impl fmt::Debug for MigrationRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MigrationRegistry")
+            .field("versions", &self.migrations.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Named save slots in an operating system directory.
+///
+/// Each save records the schema version of the game's data, metadata for load
+/// menus, and a checksum; a save whose checksum does not match is never
+/// returned. Saving a slot moves its previous file to a numbered backup, and
+/// loading falls back to the newest valid backup if the slot's file is missing
+/// or corrupted. Saves written with an older schema are upgraded by the
+/// registered migrations when loaded.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use syn_filesystem::{SaveMetadata, SaveStore};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let store = SaveStore::new(dir.path(), 1).unwrap();
+///
+/// let metadata = SaveMetadata::new(Duration::from_secs(3600)).with_description("Harbour");
+/// store.save("slot-1", &metadata, b"player at the docks").unwrap();
+///
+/// let mut store = SaveStore::new(dir.path(), 2).unwrap();
+/// store.migrations_mut().register(1, |mut data| {
+///     data.extend_from_slice(b" with 0 gold");
+///     Ok(data)
+/// });
+///
+/// let save = store.load("slot-1").unwrap();
+/// assert_eq!(save.schema_version, 2);
+/// assert_eq!(save.data, b"player at the docks with 0 gold");
+/// assert_eq!(save.metadata.description, "Harbour");
+/// ```
+#[derive(Debug)]
+pub struct SaveStore {
+    dir: PathBuf,
+    schema_version: u32,
+    compression: Compression,
+    backups: usize,
+    migrations: MigrationRegistry,
+}
+
+impl SaveStore {
+    /// Magic bytes at the start of every save file.
+    pub const MAGIC: [u8; 6] = *b"SYNSAV";
+    /// Current file format version.
+    pub const FORMAT_VERSION: u16 = 1;
+
+    /// Opens the saves in `dir`, creating it if needed, for a game whose save
+    /// data is at `schema_version`.
+    ///
+    /// Saves are LZ4 compressed and keep 3 backups by default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::Io`] if the directory cannot be created.
+    pub fn new(dir: impl Into<PathBuf>, schema_version: u32) -> VfsResult<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|error| VfsError::io(&dir.display(), error))?;
+        Ok(Self {
+            dir,
+            schema_version,
+            compression: Compression::Lz4,
+            backups: 3,
+            migrations: MigrationRegistry::new(),
+        })
+    }
+
+    /// Sets how save data is compressed.
+    #[must_use]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets how many previous saves of each slot are kept.
+    #[must_use]
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    /// Returns the directory holding the saves.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the schema version new saves are written with.
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Returns the migrations.
+    pub fn migrations(&self) -> &MigrationRegistry {
+        &self.migrations
+    }
+
+    /// Returns the migrations mutably, to register them.
+    pub fn migrations_mut(&mut self) -> &mut MigrationRegistry {
+        &mut self.migrations
+    }
+
+    /// Returns the file of a slot, or of one of its backups when `generation`
+    /// is not `0`.
+    pub fn slot_path(&self, slot: &str, generation: usize) -> PathBuf {
+        match generation {
+            0 => self.dir.join(format!("{slot}.{EXTENSION}")),
+            _ => self.dir.join(format!("{slot}.{EXTENSION}.{generation}")),
+        }
+    }
+
+    /// Returns the file a save is written to before replacing the slot's.
+    fn temp_path(&self, slot: &str) -> PathBuf {
+        self.dir.join(format!("{slot}.{EXTENSION}.tmp"))
+    }
+
+    /// Flushes the renames and deletions in the save directory to disk, on
+    /// platforms that can sync a directory.
+    fn sync_dir(&self) -> VfsResult<()> {
+        #[cfg(unix)]
+        File::open(&self.dir)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|error| VfsError::io(&self.dir.display(), error))?;
+        Ok(())
+    }
+
+    /// Returns `true` if the slot has a save or a backup.
+    pub fn exists(&self, slot: &str) -> bool {
+        valid_slot(slot).is_ok()
+            && (0..=self.backups).any(|generation| self.slot_path(slot, generation).is_file())
+    }
+
+    /// Saves data to a slot, at the store's schema version.
+    ///
+    /// The save is written to a temporary file, flushed to disk, and renamed
+    /// over the slot's file once the previous save has become the first
+    /// backup; the directory is then flushed so the renames survive a power
+    /// loss. If the game stops in between, loading recovers that backup.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] if the slot name is not 1 to 64
+    /// ASCII letters, digits, `-` or `_`, and [`VfsError::Io`] if the save
+    /// cannot be written.
+    pub fn save(&self, slot: &str, metadata: &SaveMetadata, data: &[u8]) -> VfsResult<()> {
+        valid_slot(slot)?;
+        let bytes = encode(self.schema_version, metadata, data, self.compression)?;
+
+        let path = self.slot_path(slot, 0);
+        let temp = self.temp_path(slot);
+        let io_error = |path: &Path| {
+            let path = path.display().to_string();
+            move |error| VfsError::io(&path, error)
+        };
+        let mut file = File::create(&temp).map_err(io_error(&temp))?;
+        file.write_all(&bytes).map_err(io_error(&temp))?;
+        file.sync_all().map_err(io_error(&temp))?;
+        drop(file);
+
+        for generation in (0..self.backups).rev() {
+            let from = self.slot_path(slot, generation);
+            match fs::rename(&from, self.slot_path(slot, generation + 1)) {
+                Err(error) if error.kind() != ErrorKind::NotFound => {
+                    return Err(VfsError::io(&from.display(), error));
+                }
+                _ => {}
+            }
+        }
+        fs::rename(&temp, &path).map_err(io_error(&path))?;
+        self.sync_dir()
+    }
+
+    /// Loads a slot, upgrading its data to the store's schema version.
+    ///
+    /// If the slot's file is missing or corrupted, the newest valid backup is
+    /// loaded instead; [`SaveGame::generation`] tells which.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::NotFound`] if the slot has no save, the slot file's
+    /// error if neither it nor a backup is valid, and the errors of
+    /// [`MigrationRegistry::migrate`]. Returns
+    /// [`VfsError::UnsupportedSchema`] for saves written by a newer game.
+    pub fn load(&self, slot: &str) -> VfsResult<SaveGame> {
+        let (generation, decoded) = self.read_valid(slot, true)?;
+        if decoded.schema_version > self.schema_version {
+            return Err(VfsError::UnsupportedSchema {
+                version: decoded.schema_version,
+                current: self.schema_version,
+            });
+        }
+        let data =
+            self.migrations
+                .migrate(decoded.data, decoded.schema_version, self.schema_version)?;
+        Ok(SaveGame {
+            schema_version: self.schema_version,
+            metadata: decoded.metadata,
+            data,
+            generation,
+        })
+    }
+
+    /// Lists the slots with a valid save or backup, by slot name, without
+    /// decompressing their data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::Io`] if the directory cannot be listed.
+    pub fn list(&self) -> VfsResult<Vec<SaveInfo>> {
+        let io_error = |error| VfsError::io(&self.dir.display(), error);
+        let mut slots = Vec::new();
+        for entry in fs::read_dir(&self.dir).map_err(io_error)? {
+            let name = entry.map_err(io_error)?.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            let slot = name
+                .strip_suffix(EXTENSION)
+                .and_then(|rest| rest.strip_suffix('.'))
+                .or_else(|| {
+                    let (rest, generation) = name.rsplit_once('.')?;
+                    generation.parse::<usize>().ok()?;
+                    rest.strip_suffix(EXTENSION)?.strip_suffix('.')
+                });
+            if let Some(slot) = slot.filter(|slot| valid_slot(slot).is_ok()) {
+                slots.push(slot.to_owned());
+            }
+        }
+        slots.sort();
+        slots.dedup();
+
+        Ok(slots
+            .into_iter()
+            .filter_map(|slot| {
+                let (generation, decoded) = self.read_valid(&slot, false).ok()?;
+                Some(SaveInfo {
+                    slot,
+                    schema_version: decoded.schema_version,
+                    metadata: decoded.metadata,
+                    generation,
+                })
+            })
+            .collect())
+    }
+
+    /// Deletes a slot and its backups, returning `true` if anything was deleted.
+    ///
+    /// The temporary file of an interrupted save is deleted too, but does not
+    /// count as a save.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] for invalid slot names and
+    /// [`VfsError::Io`] if a file cannot be deleted.
+    pub fn delete(&self, slot: &str) -> VfsResult<bool> {
+        valid_slot(slot)?;
+        let mut deleted = false;
+        // Backups beyond the current count may remain from older settings.
+        for generation in 0.. {
+            let path = self.slot_path(slot, generation);
+            match fs::remove_file(&path) {
+                Ok(()) => deleted = true,
+                Err(error) if error.kind() == ErrorKind::NotFound => {
+                    if generation >= self.backups {
+                        break;
+                    }
+                }
+                Err(error) => return Err(VfsError::io(&path.display(), error)),
+            }
+        }
+        let temp = self.temp_path(slot);
+        match fs::remove_file(&temp) {
+            Err(error) if error.kind() != ErrorKind::NotFound => {
+                return Err(VfsError::io(&temp.display(), error));
+            }
+            _ => {}
+        }
+        self.sync_dir()?;
+        Ok(deleted)
+    }
+
+    /// Reads the newest valid generation of a slot.
+    fn read_valid(&self, slot: &str, with_data: bool) -> VfsResult<(usize, Decoded)> {
+        valid_slot(slot)?;
+        let mut first_error = None;
+        for generation in 0..=self.backups {
+            let path = self.slot_path(slot, generation);
+            let result = match fs::read(&path) {
+                Ok(bytes) => decode(&bytes, with_data).map_err(|error| match error {
+                    VfsError::Corrupted(_) => VfsError::Corrupted(path.display().to_string()),
+                    error => error,
+                }),
+                Err(error) if error.kind() == ErrorKind::NotFound => continue,
+                Err(error) => Err(VfsError::io(&path.display(), error)),
+            };
+            match result {
+                Ok(decoded) => return Ok((generation, decoded)),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        Err(first_error
+            .unwrap_or_else(|| VfsError::NotFound(self.slot_path(slot, 0).display().to_string())))
+    }
+}
+
+/// Checks that a slot name is safe to use in a file name.
+fn valid_slot(slot: &str) -> VfsResult<()> {
+    let valid = !slot.is_empty()
+        && slot.len() <= MAX_SLOT_LEN
+        && slot
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_');
+    if valid {
+        Ok(())
+    } else {
+        Err(VfsError::InvalidPath {
+            path: slot.to_owned(),
+            reason: "slot names are 1 to 64 ASCII letters, digits, '-' or '_'",
+        })
+    }
+}
+
+fn encode(
+    schema_version: u32,
+    metadata: &SaveMetadata,
+    data: &[u8],
+    compression: Compression,
+) -> VfsResult<Vec<u8>> {
+    let description_len = u16::try_from(metadata.description.len())
+        .map_err(|_| VfsError::InvalidSave("description too long"))?;
+    let thumbnail_len = u32::try_from(metadata.thumbnail.len())
+        .map_err(|_| VfsError::InvalidSave("thumbnail too large"))?;
+    let (stored, compression) = match compression {
+        Compression::Lz4 => {
+            let compressed = lz4_flex::block::compress(data);
+            if compressed.len() < data.len() {
+                (compressed, Compression::Lz4)
+            } else {
+                (data.to_vec(), Compression::None)
+            }
+        }
+        Compression::None => (data.to_vec(), Compression::None),
+    };
+    let saved_at = metadata
+        .saved_at
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let mut bytes = Vec::with_capacity(64 + metadata.thumbnail.len() + stored.len());
+    bytes.extend_from_slice(&SaveStore::MAGIC);
+    bytes.extend_from_slice(&SaveStore::FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&schema_version.to_le_bytes());
+    bytes.push(compression.tag());
+    bytes.extend_from_slice(&millis(metadata.play_time).to_le_bytes());
+    bytes.extend_from_slice(&millis(saved_at).to_le_bytes());
+    bytes.extend_from_slice(&description_len.to_le_bytes());
+    bytes.extend_from_slice(metadata.description.as_bytes());
+    bytes.extend_from_slice(&thumbnail_len.to_le_bytes());
+    bytes.extend_from_slice(&metadata.thumbnail);
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(stored.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&stored);
+    let checksum = crc32fast::hash(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    Ok(bytes)
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// A decoded save file, before migration.
+struct Decoded {
+    schema_version: u32,
+    metadata: SaveMetadata,
+    /// Empty unless the data was requested.
+    data: Vec<u8>,
+}
+
+/// Verifies and decodes a save file, decompressing the data only if
+/// `with_data` is set.
+fn decode(bytes: &[u8], with_data: bool) -> VfsResult<Decoded> {
+    let corrupted = || VfsError::Corrupted(String::new());
+    let Some((contents, checksum)) = bytes.split_last_chunk::<4>() else {
+        return Err(VfsError::InvalidSave("truncated"));
+    };
+    if !contents.starts_with(&SaveStore::MAGIC) {
+        return Err(VfsError::InvalidSave("missing magic"));
+    }
+    if crc32fast::hash(contents) != u32::from_le_bytes(*checksum) {
+        return Err(corrupted());
+    }
+
+    let mut reader = SaveReader(&contents[SaveStore::MAGIC.len()..]);
+    let version = u16::from_le_bytes(reader.array()?);
+    if version == 0 || version > SaveStore::FORMAT_VERSION {
+        return Err(VfsError::UnsupportedVersion(version));
+    }
+    let schema_version = u32::from_le_bytes(reader.array()?);
+    let [tag] = reader.array()?;
+    let compression =
+        Compression::from_tag(tag).ok_or(VfsError::InvalidSave("unknown compression"))?;
+    let play_time = Duration::from_millis(u64::from_le_bytes(reader.array()?));
+    let saved_at = UNIX_EPOCH + Duration::from_millis(u64::from_le_bytes(reader.array()?));
+    let description_len = usize::from(u16::from_le_bytes(reader.array()?));
+    let description = std::str::from_utf8(reader.take(description_len)?)
+        .map_err(|_| VfsError::InvalidSave("description is not UTF-8"))?
+        .to_owned();
+    let thumbnail_len = u32::from_le_bytes(reader.array()?);
+    let thumbnail = reader
+        .take(usize::try_from(thumbnail_len).unwrap_or(usize::MAX))?
+        .to_vec();
+    let len = u64::from_le_bytes(reader.array()?);
+    let stored_len = u64::from_le_bytes(reader.array()?);
+    let stored = reader.take(usize::try_from(stored_len).unwrap_or(usize::MAX))?;
+
+    let data = if with_data {
+        let len = usize::try_from(len).map_err(|_| corrupted())?;
+        let data = match compression {
+            Compression::None => stored.to_vec(),
+            Compression::Lz4 => {
+                lz4_flex::block::decompress(stored, len).map_err(|_| corrupted())?
+            }
+        };
+        if data.len() != len {
+            return Err(corrupted());
+        }
+        data
+    } else {
+        Vec::new()
+    };
+
+    Ok(Decoded {
+        schema_version,
+        metadata: SaveMetadata {
+            play_time,
+            saved_at,
+            description,
+            thumbnail,
+        },
+        data,
+    })
+}
+
+/// Reads the fields of a save file.
+struct SaveReader<'a>(&'a [u8]);
+
+impl<'a> SaveReader<'a> {
+    fn take(&mut self, len: usize) -> VfsResult<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(VfsError::InvalidSave("truncated"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> VfsResult<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(description: &str) -> SaveMetadata {
+        SaveMetadata {
+            play_time: Duration::from_millis(90_500),
+            saved_at: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            description: description.to_owned(),
+            thumbnail: vec![0x89, b'P', b'N', b'G'],
+        }
+    }
+
+    #[test]
+    fn saves_round_trip_and_rotate_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SaveStore::new(dir.path(), 4).unwrap().with_backups(2);
+        let data = b"inventory ".repeat(100);
+
+        for index in 0..5 {
+            let description = format!("save {index}");
+            store
+                .save("autosave", &metadata(&description), &data)
+                .unwrap();
+        }
+        store
+            .save("manual_1", &metadata("manual"), b"tiny")
+            .unwrap();
+
+        let save = store.load("autosave").unwrap();
+        assert_eq!(save.data, data);
+        assert_eq!(save.metadata, metadata("save 4"));
+        assert_eq!(save.schema_version, 4);
+        assert_eq!(save.generation, 0);
+        assert!(fs::metadata(store.slot_path("autosave", 0)).unwrap().len() < 200);
+
+        assert!(store.slot_path("autosave", 2).is_file());
+        assert!(!store.slot_path("autosave", 3).exists());
+        assert!(!dir.path().join("autosave.sav.tmp").exists());
+
+        let listed = store.list().unwrap();
+        let slots: Vec<_> = listed.iter().map(|info| info.slot.as_str()).collect();
+        assert_eq!(slots, ["autosave", "manual_1"]);
+        assert_eq!(listed[1].metadata.description, "manual");
+
+        fs::write(dir.path().join("autosave.sav.tmp"), b"interrupted").unwrap();
+        assert!(store.delete("autosave").unwrap());
+        assert!(!store.exists("autosave"));
+        assert!(!dir.path().join("autosave.sav.tmp").exists());
+        assert!(matches!(store.load("autosave"), Err(VfsError::NotFound(_))));
+        assert!(matches!(
+            store.save("../escape", &metadata(""), b""),
+            Err(VfsError::InvalidPath { .. })
+        ));
+    }
+
+    #[test]
+    fn corrupted_saves_fall_back_to_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SaveStore::new(dir.path(), 1).unwrap();
+        store.save("slot", &metadata("old"), b"old data").unwrap();
+        store.save("slot", &metadata("new"), b"new data").unwrap();
+
+        let path = store.slot_path("slot", 0);
+        let mut bytes = fs::read(&path).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xFF;
+        fs::write(&path, &bytes).unwrap();
+
+        let save = store.load("slot").unwrap();
+        assert_eq!(save.data, b"old data");
+        assert_eq!(save.generation, 1);
+        assert_eq!(store.list().unwrap()[0].generation, 1);
+
+        let strict = SaveStore::new(dir.path(), 1).unwrap().with_backups(0);
+        assert!(matches!(strict.load("slot"), Err(VfsError::Corrupted(_))));
+        fs::write(&path, b"not a save").unwrap();
+        assert!(matches!(strict.load("slot"), Err(VfsError::InvalidSave(_))));
+    }
+
+    #[test]
+    fn old_schemas_are_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        SaveStore::new(dir.path(), 1)
+            .unwrap()
+            .save("slot", &metadata(""), b"v1")
+            .unwrap();
+
+        let mut store = SaveStore::new(dir.path(), 3).unwrap();
+        assert!(matches!(
+            store.load("slot"),
+            Err(VfsError::MissingMigration(1))
+        ));
+
+        store.migrations_mut().register(1, |mut data| {
+            data.extend_from_slice(b"+v2");
+            Ok(data)
+        });
+        store.migrations_mut().register(2, |mut data| {
+            data.extend_from_slice(b"+v3");
+            Ok(data)
+        });
+        let save = store.load("slot").unwrap();
+        assert_eq!(save.data, b"v1+v2+v3");
+        assert_eq!(save.schema_version, 3);
+        assert_eq!(store.list().unwrap()[0].schema_version, 1);
+
+        store
+            .migrations_mut()
+            .register(2, |_| Err("unknown quest".to_owned()));
+        assert!(matches!(
+            store.load("slot"),
+            Err(VfsError::Migration { version: 2, .. })
+        ));
+
+        let older_game = SaveStore::new(dir.path(), 0).unwrap();
+        assert!(matches!(
+            older_game.load("slot"),
+            Err(VfsError::UnsupportedSchema {
+                version: 1,
+                current: 0
+            })
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+//! Mountable file sources.
+
+use std::ops::Range;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::archive::{PackEntry, decode_entry};
+use crate::error::VfsResult;
+use crate::path::VfsPath;
+
+/// Whether a path names a file or a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EntryKind {
+    /// A regular file.
+    File,
+    /// A directory.
+    Directory,
+}
+
+/// Information about a file or directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    /// File or directory.
+    pub kind: EntryKind,
+    /// Size of the file contents in bytes, or zero for directories.
+    pub len: u64,
+    /// Last modification time, if the source tracks it.
+    pub modified: Option<SystemTime>,
+}
+
+impl Metadata {
+    /// Creates the metadata of a file.
+    pub fn file(len: u64, modified: Option<SystemTime>) -> Self {
+        Self {
+            kind: EntryKind::File,
+            len,
+            modified,
+        }
+    }
+
+    /// Creates the metadata of a directory.
+    pub fn directory(modified: Option<SystemTime>) -> Self {
+        Self {
+            kind: EntryKind::Directory,
+            len: 0,
+            modified,
+        }
+    }
+
+    /// Returns `true` for files.
+    pub fn is_file(&self) -> bool {
+        self.kind == EntryKind::File
+    }
+
+    /// Returns `true` for directories.
+    pub fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Directory
+    }
+}
+
+/// A directory entry returned by [`Vfs::list`](crate::Vfs::list).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DirEntry {
+    /// Full VFS path of the entry.
+    pub path: VfsPath,
+    /// File or directory.
+    pub kind: EntryKind,
+}
+
+/// File contents as read from a source, possibly still compressed.
+///
+/// Reading and decoding are split so that [`AsyncIo`](crate::AsyncIo) can keep
+/// its I/O threads reading while worker threads decompress.
+#[derive(Debug, Clone)]
+pub struct EncodedFile {
+    bytes: Vec<u8>,
+    archive_entry: Option<ArchiveEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct ArchiveEntry {
+    path: VfsPath,
+    entry: PackEntry,
+    range: Option<Range<u64>>,
+}
+
+impl EncodedFile {
+    /// Wraps contents that need no decoding.
+    pub fn plain(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            archive_entry: None,
+        }
+    }
+
+    /// Wraps the stored contents of an archive entry, keeping only `range` of
+    /// the decoded contents.
+    pub(crate) fn archive(
+        path: &VfsPath,
+        entry: PackEntry,
+        stored: Vec<u8>,
+        range: Option<Range<u64>>,
+    ) -> Self {
+        Self {
+            bytes: stored,
+            archive_entry: Some(ArchiveEntry {
+                path: path.clone(),
+                entry,
+                range,
+            }),
+        }
+    }
+
+    /// Returns `true` if [`decode`](Self::decode) has work to do.
+    pub fn is_encoded(&self) -> bool {
+        self.archive_entry.is_some()
+    }
+
+    /// Returns the number of bytes read from storage.
+    pub fn stored_len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Decompresses and verifies the contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::Corrupted`](crate::VfsError::Corrupted) if the
+    /// contents cannot be decompressed or do not match their checksum.
+    pub fn decode(self) -> VfsResult<Vec<u8>> {
+        let Some(ArchiveEntry { path, entry, range }) = self.archive_entry else {
+            return Ok(self.bytes);
+        };
+        let contents = decode_entry(&path, &entry, self.bytes)?;
+        Ok(match range {
+            Some(range) => slice_range(contents, &range),
+            None => contents,
+        })
+    }
+}
+
+/// Keeps the part of `bytes` inside `range`, clamped to its length.
+pub(crate) fn slice_range(mut bytes: Vec<u8>, range: &Range<u64>) -> Vec<u8> {
+    let (start, end) = clamp_range(range, bytes.len() as u64);
+    bytes.truncate(end);
+    bytes.drain(..start);
+    bytes
+}
+
+/// Clamps a byte range to a file of `len` bytes, as `usize` bounds.
+pub(crate) fn clamp_range(range: &Range<u64>, len: u64) -> (usize, usize) {
+    let end = range.end.min(len);
+    let start = range.start.min(end);
+    (
+        usize::try_from(start).unwrap_or(usize::MAX),
+        usize::try_from(end).unwrap_or(usize::MAX),
+    )
+}
+
+/// A source of files that can be mounted into a [`Vfs`](crate::Vfs).
+///
+/// Paths are relative to the mount point and already validated. The root path
+/// names the source's top directory.
+pub trait MountSource: Send + Sync {
+    /// Returns the metadata of a path, or `None` if the source does not have it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source exists but cannot be queried.
+    fn metadata(&self, path: &VfsPath) -> VfsResult<Option<Metadata>>;
+
+    /// Reads a whole file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not exist or cannot be read.
+    fn read(&self, path: &VfsPath) -> VfsResult<Vec<u8>>;
+
+    /// Returns the names and kinds of the entries of a directory, or `None` if
+    /// the source has no such directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory exists but cannot be listed.
+    fn list(&self, path: &VfsPath) -> VfsResult<Option<Vec<(String, EntryKind)>>>;
+
+    /// Reads a file, or the bytes of `range` within it, leaving any decoding to
+    /// [`EncodedFile::decode`]. Ranges are clamped to the file length.
+    ///
+    /// The default implementation reads the whole file with
+    /// [`read`](Self::read).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not exist or cannot be read.
+    fn read_encoded(&self, path: &VfsPath, range: Option<Range<u64>>) -> VfsResult<EncodedFile> {
+        let contents = self.read(path)?;
+        Ok(EncodedFile::plain(match range {
+            Some(range) => slice_range(contents, &range),
+            None => contents,
+        }))
+    }
+
+    /// Returns the operating system directory holding the source's files, so
+    /// a [`FileWatcher`](crate::FileWatcher) can report their changes.
+    ///
+    /// The default implementation returns `None`: the source is not watched.
+    fn watch_root(&self) -> Option<&Path> {
+        None
+    }
+}
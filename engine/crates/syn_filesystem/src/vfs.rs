@@ -0,0 +1,423 @@
+//! The virtual file system.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::RwLock;
+
+use crate::error::{VfsError, VfsResult};
+use crate::path::VfsPath;
+use crate::source::{DirEntry, EncodedFile, EntryKind, Metadata, MountSource};
+
+/// Identifies a mount for [`Vfs::unmount`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MountId(u64);
+
+/// A mounted source.
+pub(crate) struct Mount {
+    pub(crate) id: MountId,
+    pub(crate) point: VfsPath,
+    pub(crate) priority: i32,
+    pub(crate) source: Box<dyn MountSource>,
+}
+
+/// Where a path was found by [`Vfs::resolve`].
+pub(crate) struct Resolved {
+    /// The mount providing the path, or `None` for the root and directories
+    /// leading to a mount point that no source has.
+    pub(crate) mount: Option<Arc<Mount>>,
+    /// The path relative to the mount.
+    pub(crate) relative: VfsPath,
+    pub(crate) metadata: Metadata,
+}
+
+/// A mount point and priority, as returned by [`Vfs::mounts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountInfo {
+    /// The mount.
+    pub id: MountId,
+    /// Where the source is mounted.
+    pub point: VfsPath,
+    /// Priority against overlapping mounts.
+    pub priority: i32,
+}
+
+/// A virtual file system assembled from mounted sources.
+///
+/// Game code reads `vfs://` paths without knowing where the data lives. Each
+/// source — an OS directory, an in-memory source or a packed archive — is
+/// mounted at a point of the tree with a priority. When mounts overlap, the
+/// highest priority one that has a path shadows the others; among equal
+/// priorities, the latest mount wins. Mods and patches are mounted above the
+/// base content to override its files, and listings merge every mount.
+///
+/// Mounting and reading take `&self`, so a `Vfs` can be shared between threads.
+///
+/// # Example
+///
+/// ```
+/// use syn_filesystem::{MemorySource, Vfs};
+///
+/// let base = MemorySource::new();
+/// base.insert("textures/rock.ktx", b"base rock".to_vec()).unwrap();
+/// base.insert("textures/tree.ktx", b"base tree".to_vec()).unwrap();
+///
+/// let patch = MemorySource::new();
+/// patch.insert("rock.ktx", b"patched rock".to_vec()).unwrap();
+///
+/// let vfs = Vfs::new();
+/// vfs.mount("vfs://", 0, base).unwrap();
+/// vfs.mount("vfs://textures", 10, patch).unwrap();
+///
+/// assert_eq!(vfs.read("vfs://textures/rock.ktx").unwrap(), b"patched rock");
+/// assert_eq!(vfs.read("vfs://textures/tree.ktx").unwrap(), b"base tree");
+/// assert_eq!(vfs.list("vfs://textures").unwrap().len(), 2);
+/// assert!(vfs.read("vfs://../secrets.txt").is_err());
+/// ```
+#[derive(Default)]
+pub struct Vfs {
+    /// Mounts by decreasing priority, the latest first among equals.
+    mounts: RwLock<Vec<Arc<Mount>>>,
+    next_id: AtomicU64,
+}
+
+impl Vfs {
+    /// Creates a file system with nothing mounted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mounts a source at `point`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] if the mount point is invalid.
+    pub fn mount(
+        &self,
+        point: &str,
+        priority: i32,
+        source: impl MountSource + 'static,
+    ) -> VfsResult<MountId> {
+        let point = VfsPath::new(point)?;
+        let id = MountId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let mount = Arc::new(Mount {
+            id,
+            point,
+            priority,
+            source: Box::new(source),
+        });
+
+        let mut mounts = self.mounts.write();
+        let index = mounts
+            .iter()
+            .position(|existing| existing.priority <= priority)
+            .unwrap_or(mounts.len());
+        mounts.insert(index, mount);
+        Ok(id)
+    }
+
+    /// Unmounts a source, returning `true` if it was mounted.
+    pub fn unmount(&self, id: MountId) -> bool {
+        let mut mounts = self.mounts.write();
+        let before = mounts.len();
+        mounts.retain(|mount| mount.id != id);
+        mounts.len() != before
+    }
+
+    /// Returns the mounts, from the highest priority to the lowest.
+    pub fn mounts(&self) -> Vec<MountInfo> {
+        self.mounts
+            .read()
+            .iter()
+            .map(|mount| MountInfo {
+                id: mount.id,
+                point: mount.point.clone(),
+                priority: mount.priority,
+            })
+            .collect()
+    }
+
+    /// Returns `true` if a file or directory exists at `path`.
+    ///
+    /// Invalid paths do not exist.
+    pub fn exists(&self, path: &str) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// Returns the metadata of a file or directory.
+    ///
+    /// Directories leading to a mount point exist even if no source has them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] for invalid paths,
+    /// [`VfsError::NotFound`] if nothing exists at `path`, or the error of the
+    /// source that failed.
+    pub fn metadata(&self, path: &str) -> VfsResult<Metadata> {
+        let path = VfsPath::new(path)?;
+        match self.resolve(&path)? {
+            Some(resolved) => Ok(resolved.metadata),
+            None => Err(VfsError::NotFound(path.to_string())),
+        }
+    }
+
+    /// Reads a whole file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] for invalid paths,
+    /// [`VfsError::NotFound`] if no source has the file,
+    /// [`VfsError::IsADirectory`] if it is a directory, or the error of the
+    /// source that failed.
+    pub fn read(&self, path: &str) -> VfsResult<Vec<u8>> {
+        let path = VfsPath::new(path)?;
+        let (mount, relative) = self.resolve_file(&path)?;
+        mount.source.read(&relative)
+    }
+
+    /// Reads the bytes of `range` within a file, clamped to the file length.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Vfs::read`].
+    pub fn read_range(&self, path: &str, range: Range<u64>) -> VfsResult<Vec<u8>> {
+        self.read_encoded(&VfsPath::new(path)?, Some(range))?
+            .decode()
+    }
+
+    /// Reads a file, or part of it, without decoding it.
+    pub(crate) fn read_encoded(
+        &self,
+        path: &VfsPath,
+        range: Option<Range<u64>>,
+    ) -> VfsResult<EncodedFile> {
+        let (mount, relative) = self.resolve_file(path)?;
+        mount.source.read_encoded(&relative, range)
+    }
+
+    /// Reads a whole UTF-8 file.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Vfs::read`], or [`VfsError::InvalidUtf8`].
+    pub fn read_to_string(&self, path: &str) -> VfsResult<String> {
+        let bytes = self.read(path)?;
+        String::from_utf8(bytes).map_err(|_| VfsError::InvalidUtf8(path.to_owned()))
+    }
+
+    /// Lists a directory, merging the entries of every mount, sorted by path.
+    ///
+    /// When mounts disagree on whether an entry is a file or a directory, the
+    /// highest priority one wins.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] for invalid paths,
+    /// [`VfsError::NotFound`] if no mount has the directory,
+    /// [`VfsError::NotADirectory`] if it is a file, or the error of the source
+    /// that failed.
+    pub fn list(&self, path: &str) -> VfsResult<Vec<DirEntry>> {
+        let dir = VfsPath::new(path)?;
+        match self.resolve(&dir)? {
+            Some(resolved) if resolved.metadata.is_file() => {
+                return Err(VfsError::NotADirectory(dir.to_string()));
+            }
+            Some(_) => {}
+            None => return Err(VfsError::NotFound(dir.to_string())),
+        }
+
+        let mut children: BTreeMap<String, EntryKind> = BTreeMap::new();
+        for mount in self.snapshot() {
+            if let Some(relative) = dir.strip_prefix(&mount.point) {
+                for (name, kind) in mount.source.list(&relative)?.unwrap_or_default() {
+                    children.entry(name).or_insert(kind);
+                }
+            } else if let Some(rest) = mount.point.relative_to(&dir) {
+                let name = rest.split('/').next().unwrap_or(rest);
+                children
+                    .entry(name.to_owned())
+                    .or_insert(EntryKind::Directory);
+            }
+        }
+
+        Ok(children
+            .into_iter()
+            .filter_map(|(name, kind)| {
+                Some(DirEntry {
+                    path: dir.join(&name).ok()?,
+                    kind,
+                })
+            })
+            .collect())
+    }
+
+    /// Returns a copy of the mount list, so sources are queried without
+    /// holding the lock.
+    pub(crate) fn snapshot(&self) -> Vec<Arc<Mount>> {
+        self.mounts.read().clone()
+    }
+
+    /// Returns the mount point and operating system directory of every mount
+    /// whose source can be watched.
+    pub(crate) fn watch_roots(&self) -> Vec<(VfsPath, PathBuf)> {
+        self.snapshot()
+            .iter()
+            .filter_map(|mount| {
+                let root = mount.source.watch_root()?;
+                Some((mount.point.clone(), root.to_path_buf()))
+            })
+            .collect()
+    }
+
+    /// Finds the mount that provides `path`, or `None` if nothing exists there.
+    pub(crate) fn resolve(&self, path: &VfsPath) -> VfsResult<Option<Resolved>> {
+        let mounts = self.snapshot();
+        for mount in &mounts {
+            let Some(relative) = path.strip_prefix(&mount.point) else {
+                continue;
+            };
+            if let Some(metadata) = mount.source.metadata(&relative)? {
+                return Ok(Some(Resolved {
+                    mount: Some(Arc::clone(mount)),
+                    relative,
+                    metadata,
+                }));
+            }
+        }
+
+        let leads_to_mount = mounts.iter().any(|mount| mount.point.starts_with(path));
+        Ok((path.is_root() || leads_to_mount).then(|| Resolved {
+            mount: None,
+            relative: path.clone(),
+            metadata: Metadata::directory(None),
+        }))
+    }
+
+    /// Finds the mount that provides the file at `path`.
+    pub(crate) fn resolve_file(&self, path: &VfsPath) -> VfsResult<(Arc<Mount>, VfsPath)> {
+        match self.resolve(path)? {
+            Some(Resolved {
+                mount: Some(mount),
+                relative,
+                metadata,
+            }) if metadata.is_file() => Ok((mount, relative)),
+            Some(_) => Err(VfsError::IsADirectory(path.to_string())),
+            None => Err(VfsError::NotFound(path.to_string())),
+        }
+    }
+}
+
+impl fmt::Debug for Vfs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vfs")
+            .field("mounts", &self.mounts())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::directory::DirectorySource;
+    use crate::memory::MemorySource;
+
+    fn memory(files: &[(&str, &str)]) -> MemorySource {
+        let source = MemorySource::new();
+        for (path, contents) in files {
+            source.insert(path, contents.as_bytes().to_vec()).unwrap();
+        }
+        source
+    }
+
+    fn names(entries: &[DirEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.path.as_str()).collect()
+    }
+
+    #[test]
+    fn priorities_shadow_and_unmount_restores() {
+        let vfs = Vfs::new();
+        vfs.mount("", 0, memory(&[("config.ron", "base")])).unwrap();
+        let patch = vfs
+            .mount("", 5, memory(&[("config.ron", "patch")]))
+            .unwrap();
+        vfs.mount("", 1, memory(&[("config.ron", "mod")])).unwrap();
+
+        assert_eq!(vfs.read_to_string("config.ron").unwrap(), "patch");
+        assert!(vfs.unmount(patch));
+        assert!(!vfs.unmount(patch));
+        assert_eq!(vfs.read_to_string("config.ron").unwrap(), "mod");
+
+        vfs.mount("", 1, memory(&[("config.ron", "newer mod")]))
+            .unwrap();
+        assert_eq!(vfs.read_to_string("config.ron").unwrap(), "newer mod");
+    }
+
+    #[test]
+    fn listings_merge_mounts_and_mount_points() {
+        let vfs = Vfs::new();
+        vfs.mount(
+            "",
+            0,
+            memory(&[("textures/rock.ktx", ""), ("readme.txt", "")]),
+        )
+        .unwrap();
+        vfs.mount("textures", 1, memory(&[("tree.ktx", "")]))
+            .unwrap();
+        vfs.mount("mods/forest/data", 0, memory(&[("a.txt", "")]))
+            .unwrap();
+
+        assert_eq!(
+            names(&vfs.list("vfs://").unwrap()),
+            ["mods", "readme.txt", "textures"]
+        );
+        assert_eq!(
+            names(&vfs.list("textures").unwrap()),
+            ["textures/rock.ktx", "textures/tree.ktx"]
+        );
+        assert_eq!(names(&vfs.list("mods").unwrap()), ["mods/forest"]);
+        assert!(vfs.metadata("mods/forest").unwrap().is_dir());
+        assert!(matches!(
+            vfs.list("readme.txt"),
+            Err(VfsError::NotADirectory(_))
+        ));
+        assert!(matches!(vfs.list("missing"), Err(VfsError::NotFound(_))));
+        assert!(matches!(
+            vfs.read("textures"),
+            Err(VfsError::IsADirectory(_))
+        ));
+    }
+
+    #[test]
+    fn directories_are_served_without_escaping_their_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("data")).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        std::fs::write(dir.path().join("data").join("level.txt"), "level").unwrap();
+
+        let vfs = Vfs::new();
+        vfs.mount(
+            "vfs://game",
+            0,
+            DirectorySource::new(dir.path().join("data")),
+        )
+        .unwrap();
+
+        assert_eq!(vfs.read_to_string("vfs://game/level.txt").unwrap(), "level");
+        assert_eq!(vfs.metadata("vfs://game/level.txt").unwrap().len, 5);
+        assert!(vfs.metadata("game/level.txt").unwrap().modified.is_some());
+        assert!(vfs.exists("game"));
+        assert!(!vfs.exists("game/../secret.txt"));
+        assert!(matches!(
+            vfs.read("vfs://game/../secret.txt"),
+            Err(VfsError::InvalidPath { .. })
+        ));
+        assert!(matches!(
+            vfs.read("vfs://game/..\\secret.txt"),
+            Err(VfsError::InvalidPath { .. })
+        ));
+    }
+}
@@ -0,0 +1,628 @@
+//! File change watching for hot reload.
+//!
+//! The operating system reports raw changes as they happen: an editor saving a
+//! file may create a temporary file, write it several times and rename it over
+//! the original. [`FileWatcher`] collects those changes and, once the watched
+//! directories have been quiet for a moment, reports their net effect per VFS
+//! path, so the save above is a single [`WatchEvent::Modified`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use notify::event::{EventKind, MetadataKind, ModifyKind, RenameMode};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+
+use crate::error::{VfsError, VfsResult};
+use crate::path::VfsPath;
+use crate::vfs::Vfs;
+
+/// A debounced change reported by [`FileWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WatchEvent {
+    /// A file or directory appeared.
+    Created(VfsPath),
+    /// A file's contents changed, including being replaced by another file.
+    Modified(VfsPath),
+    /// A file or directory disappeared.
+    Removed(VfsPath),
+    /// A file or directory was moved.
+    Renamed {
+        /// Where it was.
+        from: VfsPath,
+        /// Where it is now.
+        to: VfsPath,
+    },
+}
+
+impl WatchEvent {
+    /// Returns the path that exists after the change, or the removed path.
+    pub fn path(&self) -> &VfsPath {
+        match self {
+            Self::Created(path) | Self::Modified(path) | Self::Removed(path) => path,
+            Self::Renamed { to, .. } => to,
+        }
+    }
+}
+
+/// Timing of [`FileWatcher`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchConfig {
+    /// How long the watched directories must stay quiet before changes are
+    /// reported.
+    pub debounce: Duration,
+    /// Longest time a change waits while other changes keep arriving.
+    pub max_delay: Duration,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Watches mounted directories and reports debounced changes as VFS paths.
+///
+/// Changes are collected on a background thread and returned by
+/// [`poll`](Self::poll), typically called once per frame. Within a batch:
+///
+/// - a file created then written is [`Created`](WatchEvent::Created);
+/// - a file replaced by renaming a freshly written file over it, or removed and
+///   written again, is [`Modified`](WatchEvent::Modified);
+/// - a file created then removed is not reported;
+/// - an existing file moved is [`Renamed`](WatchEvent::Renamed).
+///
+/// A directory mounted at several points reports its changes under each of
+/// them. Files shadowed by a higher priority mount are reported too.
+///
+/// # Example
+///
+/// ```no_run
+/// use syn_filesystem::{DirectorySource, FileWatcher, Vfs, WatchConfig, WatchEvent};
+///
+/// let vfs = Vfs::new();
+/// vfs.mount("vfs://shaders", 0, DirectorySource::new("assets/shaders")).unwrap();
+///
+/// let mut watcher = FileWatcher::new(WatchConfig::default()).unwrap();
+/// watcher.watch_vfs(&vfs).unwrap();
+///
+/// let mut events = Vec::new();
+/// loop {
+///     watcher.poll(&mut events);
+///     for event in events.drain(..) {
+///         if let WatchEvent::Modified(path) = event {
+///             println!("reloading {path}");
+///         }
+///     }
+///     # break;
+/// }
+/// ```
+pub struct FileWatcher {
+    watcher: RecommendedWatcher,
+    shared: Arc<Mutex<Shared>>,
+}
+
+struct Shared {
+    /// Canonical OS directories and their mount points.
+    roots: Vec<(PathBuf, VfsPath)>,
+    debouncer: Debouncer,
+}
+
+impl FileWatcher {
+    /// Creates a watcher watching nothing yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::Watch`] if the operating system watcher cannot be
+    /// created.
+    pub fn new(config: WatchConfig) -> VfsResult<Self> {
+        let shared = Arc::new(Mutex::new(Shared {
+            roots: Vec::new(),
+            debouncer: Debouncer::new(config),
+        }));
+        let handler_shared = Arc::clone(&shared);
+        let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            // Errors carry no path to report; the next change is picked up
+            // normally.
+            let Ok(event) = result else {
+                return;
+            };
+            let now = Instant::now();
+            let mut shared = handler_shared.lock();
+            for change in translate(&event, &shared.roots) {
+                shared.debouncer.push(change, now);
+            }
+        })
+        .map_err(|error| VfsError::Watch {
+            path: String::new(),
+            reason: error.to_string(),
+        })?;
+        Ok(Self { watcher, shared })
+    }
+
+    /// Watches an operating system directory, and everything under it, as the
+    /// VFS directory `point`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VfsError::InvalidPath`] if `point` is invalid,
+    /// [`VfsError::Io`] if `root` does not exist, and [`VfsError::Watch`] if
+    /// the operating system refuses to watch it.
+    pub fn watch(&mut self, root: impl AsRef<Path>, point: &str) -> VfsResult<()> {
+        let point = VfsPath::new(point)?;
+        self.watch_root(root.as_ref(), point)?;
+        Ok(())
+    }
+
+    /// Watches the directory of every mount of `vfs` that has one, such as
+    /// [`DirectorySource`](crate::DirectorySource) mounts, and returns how many
+    /// were added.
+    ///
+    /// Mounts made afterwards are not watched until this is called again.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of [`watch`](Self::watch).
+    pub fn watch_vfs(&mut self, vfs: &Vfs) -> VfsResult<usize> {
+        let mut added = 0;
+        for (point, root) in vfs.watch_roots() {
+            if self.watch_root(&root, point)? {
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Returns the watched directories and their mount points.
+    pub fn watched(&self) -> Vec<(PathBuf, VfsPath)> {
+        self.shared.lock().roots.clone()
+    }
+
+    /// Appends the changes whose batch has settled to `events`.
+    pub fn poll(&self, events: &mut Vec<WatchEvent>) {
+        self.shared.lock().debouncer.flush(Instant::now(), events);
+    }
+
+    /// Watches `root` as `point`, returning `false` if it already was.
+    fn watch_root(&mut self, root: &Path, point: VfsPath) -> VfsResult<bool> {
+        let root = fs::canonicalize(root).map_err(|error| VfsError::io(&root.display(), error))?;
+        let (already_watched, root_watched) = {
+            let roots = &self.shared.lock().roots;
+            (
+                roots.contains(&(root.clone(), point.clone())),
+                roots.iter().any(|(watched, _)| *watched == root),
+            )
+        };
+        if already_watched {
+            return Ok(false);
+        }
+        // The event handler takes the shared lock, so it must not be held
+        // while the watcher talks to its thread.
+        if !root_watched {
+            self.watcher
+                .watch(&root, RecursiveMode::Recursive)
+                .map_err(|error| VfsError::Watch {
+                    path: root.display().to_string(),
+                    reason: error.to_string(),
+                })?;
+        }
+        let mut shared = self.shared.lock();
+        shared.roots.push((root, point));
+        Ok(true)
+    }
+}
+
+/// A raw change, already mapped to a VFS path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Change {
+    Created(VfsPath),
+    Modified(VfsPath),
+    Removed(VfsPath),
+    /// The first half of a rename, matched with a [`Change::RenamedTo`] with
+    /// the same tracker.
+    RenamedFrom(VfsPath, Option<usize>),
+    RenamedTo(VfsPath, Option<usize>),
+    /// A whole rename. Ignored if its halves were already paired.
+    Renamed {
+        from: VfsPath,
+        to: VfsPath,
+        tracker: Option<usize>,
+    },
+}
+
+/// Maps an operating system event to changes of the watched VFS paths.
+fn translate(event: &Event, roots: &[(PathBuf, VfsPath)]) -> Vec<Change> {
+    let tracker = event.tracker();
+    let mut changes = Vec::new();
+    for (root, point) in roots {
+        let map = |os_path: &PathBuf| vfs_path(os_path, root, point);
+        let mapped = || event.paths.iter().filter_map(map);
+        match event.kind {
+            EventKind::Create(_) => changes.extend(mapped().map(Change::Created)),
+            EventKind::Remove(_) => changes.extend(mapped().map(Change::Removed)),
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                changes.extend(mapped().map(|path| Change::RenamedFrom(path, tracker)));
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                changes.extend(mapped().map(|path| Change::RenamedTo(path, tracker)));
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                let [from, to] = event.paths.as_slice() else {
+                    continue;
+                };
+                changes.push(match (map(from), map(to)) {
+                    (Some(from), Some(to)) => Change::Renamed { from, to, tracker },
+                    (Some(from), None) => Change::Removed(from),
+                    (None, Some(to)) => Change::Created(to),
+                    (None, None) => continue,
+                });
+            }
+            // Some platforms only say that a path was involved in a rename.
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                for os_path in &event.paths {
+                    if let Some(path) = map(os_path) {
+                        changes.push(if os_path.exists() {
+                            Change::Created(path)
+                        } else {
+                            Change::Removed(path)
+                        });
+                    }
+                }
+            }
+            EventKind::Modify(
+                ModifyKind::Data(_)
+                | ModifyKind::Metadata(MetadataKind::WriteTime)
+                | ModifyKind::Any
+                | ModifyKind::Other,
+            ) => changes.extend(mapped().map(Change::Modified)),
+            _ => {}
+        }
+    }
+    changes
+}
+
+/// Maps an operating system path under `root` to the VFS, or `None` if it is
+/// outside `root`, is `root` itself, or has a name the VFS cannot represent.
+fn vfs_path(os_path: &Path, root: &Path, point: &VfsPath) -> Option<VfsPath> {
+    let relative = os_path.strip_prefix(root).ok()?;
+    let mut path = point.clone();
+    let mut any = false;
+    for component in relative.components() {
+        let name = component.as_os_str().to_str()?;
+        let joined = path.join(name).ok()?;
+        if joined.file_name() != Some(name) {
+            return None;
+        }
+        path = joined;
+        any = true;
+    }
+    any.then_some(path)
+}
+
+/// Returns the path `path` was moved from if the move is its net change: it
+/// exists and its origin does not exist anymore.
+fn moved_from<'a>(
+    states: &BTreeMap<VfsPath, PathState>,
+    path: &VfsPath,
+    state: &'a PathState,
+) -> Option<&'a VfsPath> {
+    let origin = state.origin.as_ref()?;
+    let still_gone = states.get(origin).is_none_or(|source| !source.exists);
+    (state.exists && origin != path && still_gone).then_some(origin)
+}
+
+/// The net effect of the changes to one path within a batch.
+#[derive(Debug, Clone)]
+struct PathState {
+    /// Whether the path existed before the batch.
+    existed: bool,
+    /// Whether the path exists now.
+    exists: bool,
+    /// Whether contents were written.
+    modified: bool,
+    /// The pre-existing path this one was moved from.
+    origin: Option<VfsPath>,
+}
+
+/// Coalesces raw changes into [`WatchEvent`]s once they settle.
+#[derive(Debug)]
+pub(crate) struct Debouncer {
+    config: WatchConfig,
+    states: BTreeMap<VfsPath, PathState>,
+    /// Rename sources waiting for their destination.
+    unpaired: Vec<(VfsPath, Option<usize>)>,
+    /// Trackers of renames already applied from their halves.
+    paired: Vec<usize>,
+    first_change: Option<Instant>,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    pub(crate) fn new(config: WatchConfig) -> Self {
+        Self {
+            config,
+            states: BTreeMap::new(),
+            unpaired: Vec::new(),
+            paired: Vec::new(),
+            first_change: None,
+            last_change: None,
+        }
+    }
+
+    /// Records a change made at `now`.
+    pub(crate) fn push(&mut self, change: Change, now: Instant) {
+        self.first_change.get_or_insert(now);
+        self.last_change = Some(now);
+        match change {
+            Change::Created(path) => self.created(path),
+            Change::Modified(path) => {
+                let state = self.state(path, true);
+                state.exists = true;
+                state.modified = true;
+            }
+            Change::Removed(path) => self.removed(path),
+            Change::RenamedFrom(path, tracker) => self.unpaired.push((path, tracker)),
+            Change::RenamedTo(to, tracker) => {
+                match self.unpaired.iter().rposition(|(_, from)| *from == tracker) {
+                    Some(index) => {
+                        let (from, _) = self.unpaired.remove(index);
+                        self.paired.extend(tracker);
+                        self.renamed(&from, to);
+                    }
+                    None => self.created(to),
+                }
+            }
+            Change::Renamed { from, to, tracker } => {
+                if tracker.is_none_or(|tracker| !self.paired.contains(&tracker)) {
+                    self.renamed(&from, to);
+                }
+            }
+        }
+    }
+
+    /// Appends the net changes to `events` if the batch has settled at `now`.
+    pub(crate) fn flush(&mut self, now: Instant, events: &mut Vec<WatchEvent>) {
+        let (Some(first), Some(last)) = (self.first_change, self.last_change) else {
+            return;
+        };
+        if now.duration_since(last) < self.config.debounce
+            && now.duration_since(first) < self.config.max_delay
+        {
+            return;
+        }
+
+        // Sources moved out of the watched directories.
+        for (path, _) in std::mem::take(&mut self.unpaired) {
+            self.removed(path);
+        }
+        self.paired.clear();
+        self.first_change = None;
+        self.last_change = None;
+        let states = std::mem::take(&mut self.states);
+
+        let consumed: BTreeSet<&VfsPath> = states
+            .iter()
+            .filter_map(|(path, state)| moved_from(&states, path, state))
+            .collect();
+
+        for (path, state) in &states {
+            if let Some(origin) = moved_from(&states, path, state) {
+                events.push(WatchEvent::Renamed {
+                    from: origin.clone(),
+                    to: path.clone(),
+                });
+                continue;
+            }
+            if consumed.contains(path) {
+                continue;
+            }
+            match (state.existed, state.exists) {
+                (false, true) => events.push(WatchEvent::Created(path.clone())),
+                (true, true) if state.modified => events.push(WatchEvent::Modified(path.clone())),
+                (true, false) => events.push(WatchEvent::Removed(path.clone())),
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the state of a path, starting from `existed` if the path has
+    /// not changed yet in this batch.
+    fn state(&mut self, path: VfsPath, existed: bool) -> &mut PathState {
+        self.states.entry(path).or_insert(PathState {
+            existed,
+            exists: existed,
+            modified: false,
+            origin: None,
+        })
+    }
+
+    fn created(&mut self, path: VfsPath) {
+        let state = self.state(path, false);
+        state.exists = true;
+        state.modified = true;
+        state.origin = None;
+    }
+
+    fn removed(&mut self, path: VfsPath) {
+        let state = self.state(path, true);
+        state.exists = false;
+        state.modified = false;
+        state.origin = None;
+    }
+
+    fn renamed(&mut self, from: &VfsPath, to: VfsPath) {
+        let source = self.state(from.clone(), true);
+        let origin = source
+            .origin
+            .take()
+            .or_else(|| source.existed.then(|| from.clone()));
+        source.exists = false;
+        source.modified = false;
+
+        // A file written in this batch and renamed over a path is a save that
+        // replaces the existing file; an older file is being moved.
+        let destination = self.state(to, origin.is_none());
+        destination.exists = true;
+        destination.modified = true;
+        destination.origin = origin;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(text: &str) -> VfsPath {
+        VfsPath::new(text).unwrap()
+    }
+
+    fn settle(changes: Vec<Change>) -> Vec<WatchEvent> {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(WatchConfig::default());
+        for change in changes {
+            debouncer.push(change, start);
+        }
+        let mut events = Vec::new();
+        debouncer.flush(start + Duration::from_millis(50), &mut events);
+        assert!(events.is_empty(), "flushed before the debounce delay");
+        debouncer.flush(start + Duration::from_millis(100), &mut events);
+        events
+    }
+
+    #[test]
+    fn save_storms_coalesce_into_one_modification() {
+        // Write a temporary file, then rename it over the original.
+        let temp_and_rename = settle(vec![
+            Change::Created(path("shader.wgsl.tmp")),
+            Change::Modified(path("shader.wgsl.tmp")),
+            Change::Modified(path("shader.wgsl.tmp")),
+            Change::RenamedFrom(path("shader.wgsl.tmp"), Some(7)),
+            Change::RenamedTo(path("shader.wgsl"), Some(7)),
+            Change::Renamed {
+                from: path("shader.wgsl.tmp"),
+                to: path("shader.wgsl"),
+                tracker: Some(7),
+            },
+        ]);
+        assert_eq!(temp_and_rename, [WatchEvent::Modified(path("shader.wgsl"))]);
+
+        // Move the original to a backup, write a new file, remove the backup.
+        let backup = settle(vec![
+            Change::Renamed {
+                from: path("script.lua"),
+                to: path("script.lua~"),
+                tracker: None,
+            },
+            Change::Created(path("script.lua")),
+            Change::Modified(path("script.lua")),
+            Change::Removed(path("script.lua~")),
+        ]);
+        assert_eq!(backup, [WatchEvent::Modified(path("script.lua"))]);
+
+        let repeated_writes = settle(vec![
+            Change::Modified(path("a.png")),
+            Change::Modified(path("a.png")),
+        ]);
+        assert_eq!(repeated_writes, [WatchEvent::Modified(path("a.png"))]);
+    }
+
+    #[test]
+    fn net_effects_are_reported_per_path() {
+        let events = settle(vec![
+            Change::Created(path("new.txt")),
+            Change::Modified(path("new.txt")),
+            Change::Created(path("scratch.txt")),
+            Change::Removed(path("scratch.txt")),
+            Change::Removed(path("old.txt")),
+            Change::RenamedFrom(path("a/moved.txt"), Some(1)),
+            Change::RenamedTo(path("b/moved.txt"), Some(1)),
+            Change::RenamedFrom(path("leaving.txt"), Some(2)),
+            Change::RenamedTo(path("arriving.txt"), Some(3)),
+        ]);
+        assert_eq!(
+            events,
+            [
+                WatchEvent::Created(path("arriving.txt")),
+                WatchEvent::Renamed {
+                    from: path("a/moved.txt"),
+                    to: path("b/moved.txt"),
+                },
+                WatchEvent::Removed(path("leaving.txt")),
+                WatchEvent::Created(path("new.txt")),
+                WatchEvent::Removed(path("old.txt")),
+            ]
+        );
+    }
+
+    #[test]
+    fn continuous_changes_are_reported_after_the_maximum_delay() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(WatchConfig::default());
+        let mut events = Vec::new();
+        for step in 0..20 {
+            let now = start + Duration::from_millis(step * 60);
+            debouncer.push(Change::Modified(path("log.txt")), now);
+            debouncer.flush(now, &mut events);
+        }
+        assert_eq!(events, [WatchEvent::Modified(path("log.txt"))]);
+    }
+
+    #[test]
+    fn operating_system_paths_map_to_mount_points() {
+        let root = Path::new("/game/assets");
+        let point = path("vfs://textures");
+        assert_eq!(
+            vfs_path(Path::new("/game/assets/rock/albedo.ktx"), root, &point),
+            Some(path("textures/rock/albedo.ktx"))
+        );
+        assert_eq!(vfs_path(root, root, &point), None);
+        assert_eq!(vfs_path(Path::new("/game/saves/1.sav"), root, &point), None);
+        assert_eq!(
+            vfs_path(Path::new("/game/assets/bad\\name"), root, &point),
+            None
+        );
+    }
+
+    #[test]
+    fn directory_changes_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("config.toml"), "volume = 1").unwrap();
+
+        let vfs = Vfs::new();
+        vfs.mount("vfs://config", 0, crate::DirectorySource::new(dir.path()))
+            .unwrap();
+        let mut watcher = FileWatcher::new(WatchConfig {
+            debounce: Duration::from_millis(200),
+            max_delay: Duration::from_secs(1),
+        })
+        .unwrap();
+        assert_eq!(watcher.watch_vfs(&vfs).unwrap(), 1);
+        assert_eq!(watcher.watch_vfs(&vfs).unwrap(), 0);
+
+        let temp = dir.path().join("config.toml.tmp");
+        fs::write(&temp, "volume = 2").unwrap();
+        fs::rename(&temp, dir.path().join("config.toml")).unwrap();
+
+        // Operating systems batch events differently: exact coalescing is left
+        // to the `Debouncer` tests.
+        let modified = WatchEvent::Modified(path("config/config.toml"));
+        let mut events = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !events.contains(&modified) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+            watcher.poll(&mut events);
+        }
+        assert!(events.contains(&modified));
+        let temp = path("config/config.toml.tmp");
+        assert!(!events.iter().any(|event| match event {
+            WatchEvent::Renamed { from, to } => *from == temp || *to == temp,
+            _ => *event.path() == temp,
+        }));
+    }
+}
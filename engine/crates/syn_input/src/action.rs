@@ -1,34 +1,121 @@
 //! Action mapping system.
 
 use hashbrown::HashMap;
-use crate::keyboard::KeyCode;
-use crate::mouse::MouseButton;
-use crate::gamepad::GamepadButton;
+use serde::{Deserialize, Serialize};
 
-/// An input binding.
-#[derive(Debug, Clone)]
-pub enum InputBinding {
-    /// Keyboard key.
-    Key(KeyCode),
-    /// Mouse button.
-    Mouse(MouseButton),
-    /// Gamepad button.
-    Gamepad(GamepadButton),
-}
+use crate::binding::{InputBinding, InputMask};
+use crate::interaction::{ActionEvent, Interaction, InteractionTracker};
+use crate::state::InputState;
+use crate::value::{ActionKind, ActionValue, FromActionValue};
 
 /// A named action.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Action {
     /// The action name.
     pub name: String,
+    /// The kind of value the action produces.
+    #[serde(default)]
+    pub kind: ActionKind,
     /// Input bindings for this action.
+    ///
+    /// When several bindings are active, the one with the largest magnitude wins.
+    #[serde(default)]
     pub bindings: Vec<InputBinding>,
+    /// Interactions raising [`ActionEvent`]s from the action's press pattern.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interactions: Vec<Interaction>,
+}
+
+impl Action {
+    /// Creates an action with no bindings.
+    pub fn new(name: impl Into<String>, kind: ActionKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            bindings: Vec::new(),
+            interactions: Vec::new(),
+        }
+    }
+
+    /// Creates a button action with no bindings.
+    pub fn button(name: impl Into<String>) -> Self {
+        Self::new(name, ActionKind::Button)
+    }
+
+    /// Creates a one-dimensional axis action with no bindings.
+    pub fn axis_1d(name: impl Into<String>) -> Self {
+        Self::new(name, ActionKind::Axis1D)
+    }
+
+    /// Creates a two-dimensional vector action with no bindings.
+    pub fn axis_2d(name: impl Into<String>) -> Self {
+        Self::new(name, ActionKind::Axis2D)
+    }
+
+    /// Adds a binding.
+    #[must_use]
+    pub fn with_binding(mut self, binding: InputBinding) -> Self {
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Adds an interaction.
+    #[must_use]
+    pub fn with_interaction(mut self, interaction: Interaction) -> Self {
+        self.interactions.push(interaction);
+        self
+    }
+
+    /// Evaluates the action against the current input state.
+    pub fn evaluate(&self, input: &InputState) -> ActionValue {
+        self.evaluate_masked(input, &InputMask::default())
+    }
+
+    fn evaluate_masked(&self, input: &InputState, mask: &InputMask) -> ActionValue {
+        let raw = self
+            .bindings
+            .iter()
+            .map(|binding| binding.evaluate_masked(input, mask))
+            .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+            .unwrap_or_default();
+        ActionValue::from_raw(self.kind, raw)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ActionState {
+    value: ActionValue,
+    was_actuated: bool,
+    trackers: Vec<InteractionTracker>,
 }
 
 /// Maps action names to their bindings.
-#[derive(Debug, Default)]
+///
+/// Serializes as its actions sorted by name; per-frame values are not saved.
+///
+/// Call [`update`](ActionMap::update) once per frame, after the
+/// [`InputState`] has received the frame's events, then read the actions.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::{Action, ActionMap, InputBinding, InputState, KeyCode, Vec2};
+///
+/// let mut map = ActionMap::new();
+/// map.register(Action::axis_2d("move").with_binding(InputBinding::wasd()));
+///
+/// let mut input = InputState::new();
+/// input.keyboard.keys_mut().press(KeyCode::D);
+/// map.update(&input);
+///
+/// assert_eq!(map.value::<Vec2>("move"), Vec2::X);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "ActionMapData", into = "ActionMapData")]
 pub struct ActionMap {
     actions: HashMap<String, Action>,
+    states: HashMap<String, ActionState>,
+    events: Vec<ActionEvent>,
 }
 
 impl ActionMap {
@@ -39,6 +126,7 @@ impl ActionMap {
 
     /// Registers an action.
     pub fn register(&mut self, action: Action) {
+        self.states.remove(&action.name);
         self.actions.insert(action.name.clone(), action);
     }
 
@@ -46,4 +134,300 @@ impl ActionMap {
     pub fn get(&self, name: &str) -> Option<&Action> {
         self.actions.get(name)
     }
+
+    /// Removes an action by name.
+    pub fn remove(&mut self, name: &str) -> Option<Action> {
+        self.states.remove(name);
+        self.actions.remove(name)
+    }
+
+    /// Returns the number of actions.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns `true` if no action is registered.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Returns the registered actions, in no particular order.
+    pub fn actions(&self) -> impl Iterator<Item = &Action> {
+        self.actions.values()
+    }
+
+    /// Evaluates every action for the current frame.
+    pub fn update(&mut self, input: &InputState) {
+        self.update_masked(input, &InputMask::default());
+    }
+
+    pub(crate) fn update_masked(&mut self, input: &InputState, mask: &InputMask) {
+        self.events.clear();
+        let mut phases = Vec::new();
+
+        for (name, action) in &self.actions {
+            let value = action.evaluate_masked(input, mask);
+            let state = self
+                .states
+                .entry_ref(name.as_str())
+                .or_insert_with(|| ActionState {
+                    value: ActionValue::zero(action.kind),
+                    was_actuated: false,
+                    trackers: Vec::new(),
+                });
+            state.was_actuated = state.value.is_actuated();
+            state.value = value;
+            state
+                .trackers
+                .resize_with(action.interactions.len(), InteractionTracker::default);
+
+            for (interaction, tracker) in action.interactions.iter().zip(&mut state.trackers) {
+                tracker.update(
+                    *interaction,
+                    value.is_actuated(),
+                    state.was_actuated,
+                    input.delta(),
+                    &mut phases,
+                );
+                self.events
+                    .extend(phases.drain(..).map(|(phase, held, charge)| ActionEvent {
+                        action: name.clone(),
+                        interaction: *interaction,
+                        phase,
+                        held,
+                        charge,
+                    }));
+            }
+        }
+    }
+
+    /// Returns the interaction events raised by the last update.
+    pub fn events(&self) -> &[ActionEvent] {
+        &self.events
+    }
+
+    /// Returns the current charge, in 0 to 1, of an action's
+    /// [`Interaction::Charge`] while it is being held.
+    pub fn charge(&self, name: &str) -> f32 {
+        let (Some(action), Some(state)) = (self.actions.get(name), self.states.get(name)) else {
+            return 0.0;
+        };
+        action
+            .interactions
+            .iter()
+            .zip(&state.trackers)
+            .map(|(interaction, tracker)| tracker.charge(*interaction))
+            .fold(0.0, f32::max)
+    }
+
+    /// Returns the value of an action this frame, converted to `T`.
+    ///
+    /// Unknown actions and actions not yet updated read as their resting value.
+    pub fn value<T: FromActionValue>(&self, name: &str) -> T {
+        T::from_action_value(self.action_value(name))
+    }
+
+    /// Returns the raw value of an action this frame.
+    pub fn action_value(&self, name: &str) -> ActionValue {
+        match self.states.get(name) {
+            Some(state) => state.value,
+            None => ActionValue::zero(
+                self.actions
+                    .get(name)
+                    .map_or_else(ActionKind::default, |a| a.kind),
+            ),
+        }
+    }
+
+    /// Returns `true` while the action is actuated.
+    pub fn pressed(&self, name: &str) -> bool {
+        self.states
+            .get(name)
+            .is_some_and(|state| state.value.is_actuated())
+    }
+
+    /// Returns `true` if the action became actuated this frame.
+    pub fn just_pressed(&self, name: &str) -> bool {
+        self.states
+            .get(name)
+            .is_some_and(|state| state.value.is_actuated() && !state.was_actuated)
+    }
+
+    /// Returns `true` if the action stopped being actuated this frame.
+    pub fn just_released(&self, name: &str) -> bool {
+        self.states
+            .get(name)
+            .is_some_and(|state| !state.value.is_actuated() && state.was_actuated)
+    }
+}
+
+/// Serialized form of an [`ActionMap`].
+#[derive(Serialize, Deserialize)]
+struct ActionMapData {
+    #[serde(default)]
+    actions: Vec<Action>,
+}
+
+impl From<ActionMapData> for ActionMap {
+    fn from(data: ActionMapData) -> Self {
+        let mut map = ActionMap::new();
+        for action in data.actions {
+            map.register(action);
+        }
+        map
+    }
+}
+
+impl From<ActionMap> for ActionMapData {
+    fn from(map: ActionMap) -> Self {
+        let mut actions: Vec<_> = map.actions.into_values().collect();
+        actions.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { actions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding::{AxisResponse, Modifier, Stick};
+    use crate::gamepad::{GamepadAxis, GamepadState};
+    use crate::keyboard::KeyCode;
+    use glam::Vec2;
+    use std::time::Duration;
+
+    fn game_map() -> ActionMap {
+        let mut map = ActionMap::new();
+        map.register(
+            Action::axis_2d("move")
+                .with_binding(InputBinding::wasd())
+                .with_binding(InputBinding::stick(Stick::Left)),
+        );
+        map.register(
+            Action::button("save")
+                .with_binding(InputBinding::chord([Modifier::Control], KeyCode::S)),
+        );
+        map.register(Action::axis_2d("look").with_binding(InputBinding::MouseMotion));
+        map.register(Action::axis_1d("zoom").with_binding(InputBinding::MouseScroll));
+        map.register(
+            Action::axis_1d("throttle").with_binding(InputBinding::GamepadAxis {
+                axis: GamepadAxis::RightTrigger,
+                response: AxisResponse::default(),
+            }),
+        );
+        map
+    }
+
+    #[test]
+    fn composite_keys_produce_normalized_vectors() {
+        let mut map = game_map();
+        let mut input = InputState::new();
+        input.keyboard.keys_mut().press(KeyCode::W);
+        input.keyboard.keys_mut().press(KeyCode::D);
+        map.update(&input);
+
+        let value = map.value::<Vec2>("move");
+        assert!((value.length() - 1.0).abs() < 1e-6);
+        assert!(value.x > 0.0 && value.y > 0.0);
+    }
+
+    #[test]
+    fn strongest_binding_wins() {
+        let mut map = game_map();
+        let mut input = InputState::new();
+        let mut pad = GamepadState::new();
+        pad.set_axis(GamepadAxis::LeftStickX, -0.5);
+        input.gamepads.push(pad);
+        map.update(&input);
+        assert!(map.value::<Vec2>("move").x < -0.3);
+
+        input.keyboard.keys_mut().press(KeyCode::D);
+        map.update(&input);
+        assert_eq!(map.value::<Vec2>("move"), Vec2::X);
+    }
+
+    #[test]
+    fn chords_require_their_modifiers() {
+        let mut map = game_map();
+        let mut input = InputState::new();
+        input.keyboard.keys_mut().press(KeyCode::S);
+        map.update(&input);
+        assert!(!map.pressed("save"));
+
+        input.begin_frame(Duration::from_millis(16));
+        input.keyboard.keys_mut().press(KeyCode::LCtrl);
+        map.update(&input);
+        assert!(map.just_pressed("save"));
+
+        map.update(&input);
+        assert!(map.pressed("save"));
+        assert!(!map.just_pressed("save"));
+
+        input.keyboard.keys_mut().release(KeyCode::S);
+        map.update(&input);
+        assert!(map.just_released("save"));
+    }
+
+    #[test]
+    fn mouse_and_trigger_axes() {
+        let mut map = game_map();
+        let mut input = InputState::new();
+        input.mouse.delta = (4.0, -2.0);
+        input.mouse.scroll_delta = -1.0;
+        let mut pad = GamepadState::new();
+        pad.set_axis(GamepadAxis::RightTrigger, 1.0);
+        input.gamepads.push(pad);
+        map.update(&input);
+
+        assert_eq!(map.value::<Vec2>("look"), Vec2::new(4.0, -2.0));
+        assert!((map.value::<f32>("zoom") + 1.0).abs() < f32::EPSILON);
+        assert!((map.value::<f32>("throttle") - 1.0).abs() < f32::EPSILON);
+        assert!(!map.value::<bool>("missing"));
+    }
+
+    #[test]
+    fn interactions_raise_phased_events() {
+        use crate::interaction::{ActionPhase, Interaction};
+
+        let mut map = ActionMap::new();
+        map.register(
+            Action::button("attack")
+                .with_binding(InputBinding::Key(KeyCode::J))
+                .with_interaction(Interaction::Tap {
+                    max_duration: Duration::from_millis(200),
+                })
+                .with_interaction(Interaction::Hold {
+                    duration: Duration::from_millis(300),
+                }),
+        );
+        let phases = |map: &ActionMap| -> Vec<_> {
+            map.events()
+                .iter()
+                .map(|e| (e.interaction, e.phase))
+                .collect()
+        };
+
+        let mut input = InputState::new();
+        input.keyboard.keys_mut().press(KeyCode::J);
+        map.update(&input);
+        assert_eq!(map.events().len(), 2);
+        assert!(map.events().iter().all(|e| e.phase == ActionPhase::Started));
+
+        for _ in 0..3 {
+            input.begin_frame(Duration::from_millis(100));
+            map.update(&input);
+        }
+        let last = phases(&map);
+        assert_eq!(last.len(), 2);
+        assert!(last.iter().any(|(interaction, phase)| {
+            matches!(interaction, Interaction::Tap { .. }) && *phase == ActionPhase::Cancelled
+        }));
+        assert!(last.iter().any(|(interaction, phase)| {
+            matches!(interaction, Interaction::Hold { .. }) && *phase == ActionPhase::Performed
+        }));
+
+        input.begin_frame(Duration::from_millis(100));
+        input.keyboard.keys_mut().release(KeyCode::J);
+        map.update(&input);
+        assert!(map.events().is_empty());
+    }
 }
@@ -0,0 +1,201 @@
+//! Gamepad backends.
+//!
+//! A [`GamepadBackend`] reports raw device input, indexed the way the driver
+//! exposes it, and drives force feedback. [`Gamepads`](crate::Gamepads) turns the
+//! raw input into normalized [`GamepadEvent`](crate::GamepadEvent)s.
+
+use hashbrown::HashMap;
+
+use crate::gamepad::GamepadId;
+
+/// Raw input reported by a [`GamepadBackend`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawGamepadEvent {
+    /// A controller was plugged in.
+    Connected {
+        /// Id of the device for as long as it stays connected.
+        id: GamepadId,
+        /// SDL GUID of the controller model, as 32 hexadecimal digits.
+        guid: String,
+        /// Name reported by the driver.
+        name: String,
+    },
+    /// A controller was unplugged.
+    Disconnected {
+        /// The device.
+        id: GamepadId,
+    },
+    /// A raw button changed state.
+    Button {
+        /// The device.
+        id: GamepadId,
+        /// Raw button index.
+        index: u8,
+        /// `true` if the button went down.
+        pressed: bool,
+    },
+    /// A raw axis moved.
+    Axis {
+        /// The device.
+        id: GamepadId,
+        /// Raw axis index.
+        index: u8,
+        /// New value, from -1 to 1.
+        value: f32,
+    },
+    /// A raw hat switch moved.
+    Hat {
+        /// The device.
+        id: GamepadId,
+        /// Raw hat index.
+        index: u8,
+        /// Pressed directions: 1 up, 2 right, 4 down, 8 left.
+        mask: u8,
+    },
+}
+
+impl RawGamepadEvent {
+    /// Returns the device the event comes from.
+    pub fn id(&self) -> GamepadId {
+        match *self {
+            Self::Connected { id, .. }
+            | Self::Disconnected { id }
+            | Self::Button { id, .. }
+            | Self::Axis { id, .. }
+            | Self::Hat { id, .. } => id,
+        }
+    }
+}
+
+/// A source of raw gamepad input, such as a platform driver.
+pub trait GamepadBackend {
+    /// Appends the raw events received since the last poll to `events`.
+    fn poll(&mut self, events: &mut Vec<RawGamepadEvent>);
+
+    /// Returns `true` if the controller has rumble motors.
+    fn supports_rumble(&self, id: GamepadId) -> bool;
+
+    /// Sets the strong (low-frequency) and weak (high-frequency) motor speeds,
+    /// from 0 to 1.
+    fn set_rumble(&mut self, id: GamepadId, strong: f32, weak: f32);
+}
+
+/// A scripted backend for tests and headless runs.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::{GamepadBackend, MockGamepadBackend, RawGamepadEvent};
+///
+/// let mut backend = MockGamepadBackend::new();
+/// let id = backend.connect("03000000de280000ff11000001000000", "Steam Virtual Gamepad");
+/// backend.press(id, 0);
+///
+/// let mut events = Vec::new();
+/// backend.poll(&mut events);
+///
+/// assert_eq!(events.len(), 2);
+/// assert_eq!(events[1], RawGamepadEvent::Button { id, index: 0, pressed: true });
+/// ```
+#[derive(Debug, Default)]
+pub struct MockGamepadBackend {
+    next_id: u32,
+    pending: Vec<RawGamepadEvent>,
+    pads: HashMap<GamepadId, MockPad>,
+}
+
+#[derive(Debug)]
+struct MockPad {
+    rumble_supported: bool,
+    rumble: (f32, f32),
+}
+
+impl MockGamepadBackend {
+    /// Creates a backend with no controller.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Plugs in a controller with rumble motors and returns its id.
+    pub fn connect(&mut self, guid: &str, name: &str) -> GamepadId {
+        let id = GamepadId(self.next_id);
+        self.next_id += 1;
+        self.pads.insert(
+            id,
+            MockPad {
+                rumble_supported: true,
+                rumble: (0.0, 0.0),
+            },
+        );
+        self.pending.push(RawGamepadEvent::Connected {
+            id,
+            guid: guid.to_owned(),
+            name: name.to_owned(),
+        });
+        id
+    }
+
+    /// Unplugs a controller.
+    pub fn disconnect(&mut self, id: GamepadId) {
+        if self.pads.remove(&id).is_some() {
+            self.pending.push(RawGamepadEvent::Disconnected { id });
+        }
+    }
+
+    /// Presses a raw button.
+    pub fn press(&mut self, id: GamepadId, index: u8) {
+        self.pending.push(RawGamepadEvent::Button {
+            id,
+            index,
+            pressed: true,
+        });
+    }
+
+    /// Releases a raw button.
+    pub fn release(&mut self, id: GamepadId, index: u8) {
+        self.pending.push(RawGamepadEvent::Button {
+            id,
+            index,
+            pressed: false,
+        });
+    }
+
+    /// Moves a raw axis.
+    pub fn move_axis(&mut self, id: GamepadId, index: u8, value: f32) {
+        self.pending
+            .push(RawGamepadEvent::Axis { id, index, value });
+    }
+
+    /// Moves a raw hat switch.
+    pub fn set_hat(&mut self, id: GamepadId, index: u8, mask: u8) {
+        self.pending.push(RawGamepadEvent::Hat { id, index, mask });
+    }
+
+    /// Sets whether a controller has rumble motors.
+    pub fn set_rumble_supported(&mut self, id: GamepadId, supported: bool) {
+        if let Some(pad) = self.pads.get_mut(&id) {
+            pad.rumble_supported = supported;
+        }
+    }
+
+    /// Returns the current strong and weak motor speeds of a controller.
+    pub fn rumble(&self, id: GamepadId) -> (f32, f32) {
+        self.pads.get(&id).map_or((0.0, 0.0), |pad| pad.rumble)
+    }
+}
+
+impl GamepadBackend for MockGamepadBackend {
+    fn poll(&mut self, events: &mut Vec<RawGamepadEvent>) {
+        events.append(&mut self.pending);
+    }
+
+    fn supports_rumble(&self, id: GamepadId) -> bool {
+        self.pads.get(&id).is_some_and(|pad| pad.rumble_supported)
+    }
+
+    fn set_rumble(&mut self, id: GamepadId, strong: f32, weak: f32) {
+        if let Some(pad) = self.pads.get_mut(&id).filter(|pad| pad.rumble_supported) {
+            pad.rumble = (strong, weak);
+        }
+    }
+}
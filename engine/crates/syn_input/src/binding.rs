@@ -0,0 +1,524 @@
+//! Input bindings and their evaluation against the input state.
+
+use glam::Vec2;
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::gamepad::{GamepadAxis, GamepadButton, GamepadState};
+use crate::keyboard::KeyCode;
+use crate::mouse::MouseButton;
+use crate::state::InputState;
+
+/// A keyboard modifier required by a [`InputBinding::Chord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Modifier {
+    /// Either Shift key.
+    Shift,
+    /// Either Control key.
+    Control,
+    /// Either Alt key.
+    Alt,
+    /// Either Super key (Windows, Command).
+    Super,
+}
+
+impl Modifier {
+    fn is_held(self, input: &InputState) -> bool {
+        let keyboard = &input.keyboard;
+        let modifiers = keyboard.modifiers();
+        match self {
+            Self::Shift => {
+                modifiers.shift
+                    || keyboard.pressed(KeyCode::LShift)
+                    || keyboard.pressed(KeyCode::RShift)
+            }
+            Self::Control => {
+                modifiers.control
+                    || keyboard.pressed(KeyCode::LCtrl)
+                    || keyboard.pressed(KeyCode::RCtrl)
+            }
+            Self::Alt => {
+                modifiers.alt || keyboard.pressed(KeyCode::LAlt) || keyboard.pressed(KeyCode::RAlt)
+            }
+            Self::Super => {
+                modifiers.super_key
+                    || keyboard.pressed(KeyCode::LSuper)
+                    || keyboard.pressed(KeyCode::RSuper)
+            }
+        }
+    }
+}
+
+/// An analog stick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Stick {
+    /// Left stick.
+    Left,
+    /// Right stick.
+    Right,
+}
+
+impl Stick {
+    /// Returns the x and y axes of the stick.
+    pub fn axes(self) -> (GamepadAxis, GamepadAxis) {
+        match self {
+            Self::Left => (GamepadAxis::LeftStickX, GamepadAxis::LeftStickY),
+            Self::Right => (GamepadAxis::RightStickX, GamepadAxis::RightStickY),
+        }
+    }
+
+    fn read(self, gamepad: &GamepadState) -> Vec2 {
+        let (x, y) = self.axes();
+        Vec2::new(gamepad.axis(x), gamepad.axis(y))
+    }
+}
+
+/// Shape applied to an analog input after its dead zone.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ResponseCurve {
+    /// Output equals input.
+    #[default]
+    Linear,
+    /// Output is the input squared, for finer control near the center.
+    Quadratic,
+    /// Output is the input cubed.
+    Cubic,
+    /// Output is the input raised to a custom exponent.
+    Power(f32),
+}
+
+impl ResponseCurve {
+    /// Applies the curve to a magnitude in 0 to 1.
+    pub fn apply(self, magnitude: f32) -> f32 {
+        match self {
+            Self::Linear => magnitude,
+            Self::Quadratic => magnitude * magnitude,
+            Self::Cubic => magnitude * magnitude * magnitude,
+            Self::Power(exponent) => magnitude.powf(exponent),
+        }
+    }
+}
+
+/// How a raw analog value is shaped before it reaches an action.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AxisResponse {
+    /// Magnitudes below this are treated as zero; the rest is rescaled to 0 to 1.
+    pub dead_zone: f32,
+    /// Curve applied after the dead zone.
+    pub curve: ResponseCurve,
+    /// Multiplier applied last.
+    pub scale: f32,
+}
+
+impl Default for AxisResponse {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.15,
+            curve: ResponseCurve::Linear,
+            scale: 1.0,
+        }
+    }
+}
+
+impl AxisResponse {
+    /// Shapes a single axis value.
+    pub fn apply(&self, value: f32) -> f32 {
+        value.signum() * self.shape(value.abs())
+    }
+
+    /// Shapes a stick vector, using a radial dead zone so diagonals are not
+    /// clipped.
+    pub fn apply_2d(&self, value: Vec2) -> Vec2 {
+        let length = value.length();
+        if length <= self.dead_zone {
+            return Vec2::ZERO;
+        }
+        value / length * self.shape(length)
+    }
+
+    fn shape(&self, magnitude: f32) -> f32 {
+        if magnitude <= self.dead_zone {
+            return 0.0;
+        }
+        let live = ((magnitude - self.dead_zone) / (1.0 - self.dead_zone)).min(1.0);
+        self.curve.apply(live) * self.scale
+    }
+}
+
+/// The kind of device a binding belongs to, for per-device profiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DeviceKind {
+    /// Keyboard and mouse.
+    KeyboardMouse,
+    /// Gamepad.
+    Gamepad,
+}
+
+/// A physical input that a binding reads, used to consume inputs across contexts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputSource {
+    /// A keyboard key.
+    Key(KeyCode),
+    /// Every keyboard key, such as for a text console.
+    AnyKey,
+    /// A mouse button.
+    Mouse(MouseButton),
+    /// Mouse motion.
+    MouseMotion,
+    /// Mouse scrolling.
+    MouseScroll,
+    /// A gamepad button.
+    Gamepad(GamepadButton),
+    /// A gamepad axis.
+    GamepadAxis(GamepadAxis),
+}
+
+/// A set of inputs hidden from bindings, filled by consuming input contexts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InputMask {
+    sources: HashSet<InputSource>,
+}
+
+impl InputMask {
+    pub(crate) fn insert(&mut self, source: InputSource) {
+        self.sources.insert(source);
+    }
+
+    pub(crate) fn blocks(&self, source: InputSource) -> bool {
+        if self.sources.is_empty() {
+            return false;
+        }
+        matches!(source, InputSource::Key(_)) && self.sources.contains(&InputSource::AnyKey)
+            || self.sources.contains(&source)
+    }
+}
+
+/// An input binding.
+///
+/// Every binding evaluates to a vector: buttons produce `(1, 0)` while held,
+/// axes produce `(value, 0)` and two-dimensional bindings their full vector. The
+/// owning action then reads it as a button, axis or vector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputBinding {
+    /// Keyboard key.
+    Key(KeyCode),
+    /// Mouse button.
+    Mouse(MouseButton),
+    /// Gamepad button.
+    Gamepad(GamepadButton),
+    /// Single gamepad axis.
+    GamepadAxis {
+        /// The axis.
+        axis: GamepadAxis,
+        /// Dead zone and response curve.
+        response: AxisResponse,
+    },
+    /// Gamepad stick as a vector.
+    GamepadStick {
+        /// The stick.
+        stick: Stick,
+        /// Radial dead zone and response curve.
+        response: AxisResponse,
+    },
+    /// Mouse motion since last frame, in pixels, as a vector.
+    MouseMotion,
+    /// Vertical scroll since last frame, in lines, as an axis.
+    MouseScroll,
+    /// Two bindings forming one axis: `positive` minus `negative`.
+    Axis {
+        /// Binding pushing towards -1.
+        negative: Box<InputBinding>,
+        /// Binding pushing towards +1.
+        positive: Box<InputBinding>,
+    },
+    /// Four bindings forming a vector, normalized so diagonals are not faster.
+    ///
+    /// Up is +y.
+    Vector {
+        /// Binding pushing towards +y.
+        up: Box<InputBinding>,
+        /// Binding pushing towards -y.
+        down: Box<InputBinding>,
+        /// Binding pushing towards -x.
+        left: Box<InputBinding>,
+        /// Binding pushing towards +x.
+        right: Box<InputBinding>,
+    },
+    /// A binding that only applies while every modifier is held, such as Ctrl+S.
+    Chord {
+        /// Modifiers that must be held.
+        modifiers: Vec<Modifier>,
+        /// The binding itself.
+        binding: Box<InputBinding>,
+    },
+}
+
+impl InputBinding {
+    /// Creates an [`InputBinding::Axis`] from two bindings.
+    pub fn axis(negative: InputBinding, positive: InputBinding) -> Self {
+        Self::Axis {
+            negative: Box::new(negative),
+            positive: Box::new(positive),
+        }
+    }
+
+    /// Creates an [`InputBinding::Vector`] from four bindings.
+    pub fn vector(
+        up: InputBinding,
+        down: InputBinding,
+        left: InputBinding,
+        right: InputBinding,
+    ) -> Self {
+        Self::Vector {
+            up: Box::new(up),
+            down: Box::new(down),
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    /// Creates a vector from four keys.
+    pub fn keys_2d(up: KeyCode, down: KeyCode, left: KeyCode, right: KeyCode) -> Self {
+        Self::vector(
+            Self::Key(up),
+            Self::Key(down),
+            Self::Key(left),
+            Self::Key(right),
+        )
+    }
+
+    /// W, A, S and D as a vector.
+    pub fn wasd() -> Self {
+        Self::keys_2d(KeyCode::W, KeyCode::S, KeyCode::A, KeyCode::D)
+    }
+
+    /// The arrow keys as a vector.
+    pub fn arrow_keys() -> Self {
+        Self::keys_2d(KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right)
+    }
+
+    /// The gamepad D-pad as a vector.
+    pub fn dpad() -> Self {
+        Self::vector(
+            Self::Gamepad(GamepadButton::DPadUp),
+            Self::Gamepad(GamepadButton::DPadDown),
+            Self::Gamepad(GamepadButton::DPadLeft),
+            Self::Gamepad(GamepadButton::DPadRight),
+        )
+    }
+
+    /// A gamepad stick with the default [`AxisResponse`].
+    pub fn stick(stick: Stick) -> Self {
+        Self::GamepadStick {
+            stick,
+            response: AxisResponse::default(),
+        }
+    }
+
+    /// A key combined with modifiers, such as Ctrl+S.
+    pub fn chord(modifiers: impl Into<Vec<Modifier>>, key: KeyCode) -> Self {
+        Self::Chord {
+            modifiers: modifiers.into(),
+            binding: Box::new(Self::Key(key)),
+        }
+    }
+
+    /// Evaluates the binding against the current input state.
+    pub fn evaluate(&self, input: &InputState) -> Vec2 {
+        self.evaluate_masked(input, &InputMask::default())
+    }
+
+    /// Returns the device the binding reads.
+    ///
+    /// Composites report the device of their first part.
+    pub fn device(&self) -> DeviceKind {
+        match self {
+            Self::Key(_) | Self::Mouse(_) | Self::MouseMotion | Self::MouseScroll => {
+                DeviceKind::KeyboardMouse
+            }
+            Self::Gamepad(_) | Self::GamepadAxis { .. } | Self::GamepadStick { .. } => {
+                DeviceKind::Gamepad
+            }
+            Self::Axis { positive, .. } => positive.device(),
+            Self::Vector { up, .. } => up.device(),
+            Self::Chord { binding, .. } => binding.device(),
+        }
+    }
+
+    /// Appends the inputs that trigger the binding to `out`, each with the
+    /// modifiers it requires, sorted.
+    pub(crate) fn triggers(&self, out: &mut Vec<(Vec<Modifier>, InputSource)>) {
+        let mut modifiers = Vec::new();
+        let binding = match self {
+            Self::Chord {
+                modifiers: required,
+                binding,
+            } => {
+                modifiers.extend_from_slice(required);
+                modifiers.sort_unstable();
+                modifiers.dedup();
+                binding
+            }
+            _ => self,
+        };
+
+        let mut sources = Vec::new();
+        binding.sources(&mut sources);
+        out.extend(
+            sources
+                .into_iter()
+                .map(|source| (modifiers.clone(), source)),
+        );
+    }
+
+    /// Appends the physical inputs the binding reads to `out`.
+    ///
+    /// Chord modifiers are not included: a consumed modifier still combines with
+    /// keys bound elsewhere.
+    pub fn sources(&self, out: &mut Vec<InputSource>) {
+        match self {
+            Self::Key(key) => out.push(InputSource::Key(*key)),
+            Self::Mouse(mouse) => out.push(InputSource::Mouse(*mouse)),
+            Self::Gamepad(pad) => out.push(InputSource::Gamepad(*pad)),
+            Self::GamepadAxis { axis, .. } => out.push(InputSource::GamepadAxis(*axis)),
+            Self::GamepadStick { stick, .. } => {
+                let (x, y) = stick.axes();
+                out.push(InputSource::GamepadAxis(x));
+                out.push(InputSource::GamepadAxis(y));
+            }
+            Self::MouseMotion => out.push(InputSource::MouseMotion),
+            Self::MouseScroll => out.push(InputSource::MouseScroll),
+            Self::Axis { negative, positive } => {
+                negative.sources(out);
+                positive.sources(out);
+            }
+            Self::Vector {
+                up,
+                down,
+                left,
+                right,
+            } => {
+                for binding in [up, down, left, right] {
+                    binding.sources(out);
+                }
+            }
+            Self::Chord { binding, .. } => binding.sources(out),
+        }
+    }
+
+    pub(crate) fn evaluate_masked(&self, input: &InputState, mask: &InputMask) -> Vec2 {
+        match self {
+            Self::Key(key) => {
+                button(!mask.blocks(InputSource::Key(*key)) && input.keyboard.pressed(*key))
+            }
+            Self::Mouse(mouse) => {
+                button(!mask.blocks(InputSource::Mouse(*mouse)) && input.mouse.pressed(*mouse))
+            }
+            Self::Gamepad(pad) => button(
+                !mask.blocks(InputSource::Gamepad(*pad))
+                    && input.gamepads.iter().any(|g| g.pressed(*pad)),
+            ),
+            Self::GamepadAxis { axis, response } => {
+                if mask.blocks(InputSource::GamepadAxis(*axis)) {
+                    return Vec2::ZERO;
+                }
+                let value = strongest(input, |g| Vec2::new(response.apply(g.axis(*axis)), 0.0));
+                Vec2::new(value.x, 0.0)
+            }
+            Self::GamepadStick { stick, response } => {
+                let (x, y) = stick.axes();
+                if mask.blocks(InputSource::GamepadAxis(x))
+                    || mask.blocks(InputSource::GamepadAxis(y))
+                {
+                    return Vec2::ZERO;
+                }
+                strongest(input, |g| response.apply_2d(stick.read(g)))
+            }
+            Self::MouseMotion if !mask.blocks(InputSource::MouseMotion) => {
+                Vec2::new(input.mouse.delta.0, input.mouse.delta.1)
+            }
+            Self::MouseScroll if !mask.blocks(InputSource::MouseScroll) => {
+                Vec2::new(input.mouse.scroll_delta, 0.0)
+            }
+            Self::MouseMotion | Self::MouseScroll => Vec2::ZERO,
+            Self::Axis { negative, positive } => Vec2::new(
+                positive.evaluate_masked(input, mask).length()
+                    - negative.evaluate_masked(input, mask).length(),
+                0.0,
+            ),
+            Self::Vector {
+                up,
+                down,
+                left,
+                right,
+            } => {
+                let value = Vec2::new(
+                    right.evaluate_masked(input, mask).length()
+                        - left.evaluate_masked(input, mask).length(),
+                    up.evaluate_masked(input, mask).length()
+                        - down.evaluate_masked(input, mask).length(),
+                );
+                value.clamp_length_max(1.0)
+            }
+            Self::Chord { modifiers, binding } => {
+                if modifiers.iter().all(|modifier| modifier.is_held(input)) {
+                    binding.evaluate_masked(input, mask)
+                } else {
+                    Vec2::ZERO
+                }
+            }
+        }
+    }
+}
+
+fn button(pressed: bool) -> Vec2 {
+    if pressed { Vec2::X } else { Vec2::ZERO }
+}
+
+/// Returns the largest value read from any connected gamepad.
+fn strongest(input: &InputState, read: impl Fn(&GamepadState) -> Vec2) -> Vec2 {
+    input
+        .gamepads
+        .iter()
+        .map(read)
+        .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        .unwrap_or(Vec2::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_zone_rescales_the_live_range() {
+        let response = AxisResponse {
+            dead_zone: 0.2,
+            ..AxisResponse::default()
+        };
+
+        assert!(response.apply(0.1).abs() < f32::EPSILON);
+        assert!((response.apply(0.6) - 0.5).abs() < 1e-6);
+        assert!((response.apply(-1.0) + 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn response_curves_shape_the_output() {
+        let response = AxisResponse {
+            dead_zone: 0.0,
+            curve: ResponseCurve::Quadratic,
+            scale: 2.0,
+        };
+
+        assert!((response.apply(-0.5) + 0.5).abs() < f32::EPSILON);
+        assert!((ResponseCurve::Power(3.0).apply(0.5) - 0.125).abs() < 1e-6);
+    }
+
+    #[test]
+    fn radial_dead_zone_keeps_direction() {
+        let response = AxisResponse::default();
+
+        assert_eq!(response.apply_2d(Vec2::new(0.1, 0.05)), Vec2::ZERO);
+        let value = response.apply_2d(Vec2::new(0.0, -1.0));
+        assert!((value - Vec2::new(0.0, -1.0)).length() < 1e-6);
+    }
+}
@@ -0,0 +1,337 @@
+//! Time-stamped input history.
+
+use std::collections::VecDeque;
+use std::f32::consts::FRAC_PI_4;
+use std::time::Duration;
+
+use glam::Vec2;
+
+use crate::action::ActionMap;
+use crate::state::InputState;
+
+/// Stick or key direction in eight sectors, relative to the way a character
+/// faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// No direction held.
+    Neutral,
+    /// Up.
+    Up,
+    /// Up and towards the facing direction.
+    UpForward,
+    /// Towards the facing direction.
+    Forward,
+    /// Down and towards the facing direction.
+    DownForward,
+    /// Down.
+    Down,
+    /// Down and away from the facing direction.
+    DownBack,
+    /// Away from the facing direction.
+    Back,
+    /// Up and away from the facing direction.
+    UpBack,
+}
+
+impl Direction {
+    /// Magnitude below which a vector reads as [`Direction::Neutral`].
+    pub const THRESHOLD: f32 = 0.5;
+
+    /// Quantizes a vector, with +y up, into a direction.
+    pub fn from_vector(value: Vec2, facing: Facing) -> Self {
+        if value.length() < Self::THRESHOLD {
+            return Self::Neutral;
+        }
+
+        let x = match facing {
+            Facing::Right => value.x,
+            Facing::Left => -value.x,
+        };
+        // RATIONALE: the rounded sector index is within -4..=4
+        #[allow(clippy::cast_possible_truncation)]
+        let sector = (value.y.atan2(x) / FRAC_PI_4).round() as i32;
+        match sector.rem_euclid(8) {
+            0 => Self::Forward,
+            1 => Self::UpForward,
+            2 => Self::Up,
+            3 => Self::UpBack,
+            4 => Self::Back,
+            5 => Self::DownBack,
+            6 => Self::Down,
+            _ => Self::DownForward,
+        }
+    }
+}
+
+/// The way a character faces, mapping left/right onto forward/back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Facing {
+    /// Forward is +x.
+    #[default]
+    Right,
+    /// Forward is -x.
+    Left,
+}
+
+/// An input recorded in an [`InputBuffer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InputToken {
+    /// A tracked button action was pressed.
+    Pressed(String),
+    /// A tracked button action was released.
+    Released(String),
+    /// The tracked direction changed.
+    Direction(Direction),
+}
+
+/// A time-stamped entry of an [`InputBuffer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferedInput {
+    /// The input.
+    pub input: InputToken,
+    /// When it happened, on the [`InputState::time`] clock.
+    pub time: Duration,
+    consumed: bool,
+}
+
+impl BufferedInput {
+    /// Returns `true` once a buffered press or combo has used this input.
+    pub fn is_consumed(&self) -> bool {
+        self.consumed
+    }
+}
+
+/// A history of action presses and direction changes, for buffered inputs and
+/// combos.
+///
+/// Inputs older than the retention window are dropped.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use syn_input::{Action, ActionMap, InputBinding, InputBuffer, InputState, KeyCode};
+///
+/// let mut actions = ActionMap::new();
+/// actions.register(Action::button("jump").with_binding(InputBinding::Key(KeyCode::Space)));
+/// let mut buffer = InputBuffer::new(Duration::from_secs(1)).with_button("jump");
+///
+/// // Jump pressed slightly before landing...
+/// let mut input = InputState::new();
+/// input.keyboard.keys_mut().press(KeyCode::Space);
+/// actions.update(&input);
+/// buffer.update(&actions, &input);
+///
+/// // ...is still honoured 100 ms later, once.
+/// input.begin_frame(Duration::from_millis(100));
+/// actions.update(&input);
+/// buffer.update(&actions, &input);
+/// assert!(buffer.consume_press("jump", Duration::from_millis(150)));
+/// assert!(!buffer.consume_press("jump", Duration::from_millis(150)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputBuffer {
+    retention: Duration,
+    history: VecDeque<BufferedInput>,
+    buttons: Vec<String>,
+    direction_action: Option<String>,
+    facing: Facing,
+    direction: Direction,
+    now: Duration,
+}
+
+impl InputBuffer {
+    /// Creates an empty buffer that keeps inputs for `retention`.
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            retention,
+            history: VecDeque::new(),
+            buttons: Vec::new(),
+            direction_action: None,
+            facing: Facing::default(),
+            direction: Direction::Neutral,
+            now: Duration::ZERO,
+        }
+    }
+
+    /// Records presses and releases of a button action.
+    #[must_use]
+    pub fn with_button(mut self, action: impl Into<String>) -> Self {
+        self.buttons.push(action.into());
+        self
+    }
+
+    /// Records direction changes of a vector action, such as "move".
+    #[must_use]
+    pub fn with_direction(mut self, action: impl Into<String>) -> Self {
+        self.direction_action = Some(action.into());
+        self
+    }
+
+    /// Sets the way the character faces, for later direction changes.
+    pub fn set_facing(&mut self, facing: Facing) {
+        self.facing = facing;
+    }
+
+    /// Returns the way the character faces.
+    pub fn facing(&self) -> Facing {
+        self.facing
+    }
+
+    /// Returns the current time of the buffer.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Returns the direction currently held.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Records this frame's changes of the tracked actions.
+    ///
+    /// Call after [`ActionMap::update`].
+    pub fn update(&mut self, actions: &ActionMap, input: &InputState) {
+        self.now = input.time();
+
+        if let Some(name) = &self.direction_action {
+            let direction = Direction::from_vector(actions.value::<Vec2>(name), self.facing);
+            if direction != self.direction {
+                self.direction = direction;
+                self.push_at(InputToken::Direction(direction), self.now);
+            }
+        }
+
+        for index in 0..self.buttons.len() {
+            let name = &self.buttons[index];
+            let token = if actions.just_pressed(name) {
+                InputToken::Pressed(name.clone())
+            } else if actions.just_released(name) {
+                InputToken::Released(name.clone())
+            } else {
+                continue;
+            };
+            self.push_at(token, self.now);
+        }
+
+        self.prune();
+    }
+
+    /// Records an input at the current time, such as one from network play.
+    pub fn push(&mut self, input: InputToken) {
+        self.push_at(input, self.now);
+    }
+
+    /// Advances the buffer clock without recording, dropping expired inputs.
+    pub fn advance(&mut self, delta: Duration) {
+        self.now += delta;
+        self.prune();
+    }
+
+    /// Returns the recorded inputs, oldest first.
+    pub fn history(&self) -> &VecDeque<BufferedInput> {
+        &self.history
+    }
+
+    /// Returns `true` if the action was pressed within `window` and that press
+    /// has not been consumed.
+    pub fn pressed_within(&self, action: &str, window: Duration) -> bool {
+        self.find_press(action, window).is_some()
+    }
+
+    /// Consumes the latest unconsumed press of the action within `window`.
+    ///
+    /// Returns `true` if there was one.
+    pub fn consume_press(&mut self, action: &str, window: Duration) -> bool {
+        match self.find_press(action, window) {
+            Some(index) => {
+                self.history[index].consumed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes every recorded input.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    pub(crate) fn consume(&mut self, indices: &[usize]) {
+        for &index in indices {
+            self.history[index].consumed = true;
+        }
+    }
+
+    fn find_press(&self, action: &str, window: Duration) -> Option<usize> {
+        self.history
+            .iter()
+            .enumerate()
+            .rev()
+            .take_while(|(_, entry)| self.now.saturating_sub(entry.time) <= window)
+            .find(|(_, entry)| {
+                !entry.consumed
+                    && matches!(&entry.input, InputToken::Pressed(name) if name == action)
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn push_at(&mut self, input: InputToken, time: Duration) {
+        self.history.push_back(BufferedInput {
+            input,
+            time,
+            consumed: false,
+        });
+    }
+
+    fn prune(&mut self) {
+        while self
+            .history
+            .front()
+            .is_some_and(|entry| self.now.saturating_sub(entry.time) > self.retention)
+        {
+            self.history.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors_quantize_relative_to_facing() {
+        let down_right = Vec2::new(0.7, -0.7);
+
+        assert_eq!(
+            Direction::from_vector(Vec2::new(0.2, 0.1), Facing::Right),
+            Direction::Neutral
+        );
+        assert_eq!(
+            Direction::from_vector(down_right, Facing::Right),
+            Direction::DownForward
+        );
+        assert_eq!(
+            Direction::from_vector(down_right, Facing::Left),
+            Direction::DownBack
+        );
+        assert_eq!(Direction::from_vector(Vec2::Y, Facing::Left), Direction::Up);
+        assert_eq!(
+            Direction::from_vector(-Vec2::X, Facing::Right),
+            Direction::Back
+        );
+    }
+
+    #[test]
+    fn old_inputs_expire() {
+        let mut buffer = InputBuffer::new(Duration::from_millis(500));
+        buffer.push(InputToken::Pressed("punch".into()));
+        buffer.advance(Duration::from_millis(300));
+        buffer.push(InputToken::Pressed("kick".into()));
+        buffer.advance(Duration::from_millis(300));
+
+        assert_eq!(buffer.history().len(), 1);
+        assert!(buffer.pressed_within("kick", Duration::from_millis(300)));
+        assert!(!buffer.pressed_within("kick", Duration::from_millis(200)));
+    }
+}
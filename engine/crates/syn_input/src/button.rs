@@ -0,0 +1,265 @@
+//! Edge-triggered button state tracking.
+
+use std::fmt;
+use std::hash::Hash;
+use std::time::Duration;
+
+use hashbrown::HashMap;
+
+use crate::keyboard::KeyState;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ButtonEntry {
+    down: bool,
+    just_pressed: bool,
+    just_released: bool,
+    held: Duration,
+}
+
+/// Per-frame state of a set of buttons, such as keys or mouse buttons.
+///
+/// Presses and releases are recorded as they arrive; [`begin_frame`] clears the
+/// edges at the start of each frame. A button pressed and released within one
+/// frame reports both [`just_pressed`] and [`just_released`], so quick taps are
+/// never lost.
+///
+/// [`begin_frame`]: ButtonStates::begin_frame
+/// [`just_pressed`]: ButtonStates::just_pressed
+/// [`just_released`]: ButtonStates::just_released
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use syn_input::{ButtonStates, KeyCode, KeyState};
+///
+/// let mut keys = ButtonStates::new();
+/// keys.press(KeyCode::Space);
+/// assert_eq!(keys.state(KeyCode::Space), KeyState::JustPressed);
+///
+/// keys.begin_frame(Duration::from_millis(16));
+/// assert_eq!(keys.state(KeyCode::Space), KeyState::Pressed);
+/// assert_eq!(keys.held_duration(KeyCode::Space), Duration::from_millis(16));
+///
+/// keys.release(KeyCode::Space);
+/// assert_eq!(keys.state(KeyCode::Space), KeyState::JustReleased);
+/// ```
+#[derive(Clone)]
+pub struct ButtonStates<T> {
+    buttons: HashMap<T, ButtonEntry>,
+}
+
+impl<T> Default for ButtonStates<T> {
+    fn default() -> Self {
+        Self {
+            buttons: HashMap::new(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ButtonStates<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.buttons
+                    .iter()
+                    .filter(|(_, entry)| entry.down)
+                    .map(|(button, _)| button),
+            )
+            .finish()
+    }
+}
+
+impl<T: Copy + Eq + Hash> ButtonStates<T> {
+    /// Creates a state with every button released.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a press. Presses of a button that is already down are ignored.
+    pub fn press(&mut self, button: T) {
+        let entry = self.buttons.entry(button).or_default();
+        if !entry.down {
+            entry.down = true;
+            entry.just_pressed = true;
+            entry.held = Duration::ZERO;
+        }
+    }
+
+    /// Records a release. Releases of a button that is already up are ignored.
+    pub fn release(&mut self, button: T) {
+        if let Some(entry) = self.buttons.get_mut(&button).filter(|entry| entry.down) {
+            entry.down = false;
+            entry.just_released = true;
+        }
+    }
+
+    /// Releases every button that is down, such as when the window loses focus.
+    pub fn release_all(&mut self) {
+        for entry in self.buttons.values_mut() {
+            if entry.down {
+                entry.down = false;
+                entry.just_released = true;
+            }
+        }
+    }
+
+    /// Starts a new frame that lasted `delta`.
+    ///
+    /// Clears the edges of the previous frame and adds `delta` to the held
+    /// duration of every button still down.
+    pub fn begin_frame(&mut self, delta: Duration) {
+        self.buttons.retain(|_, entry| entry.down);
+        for entry in self.buttons.values_mut() {
+            entry.just_pressed = false;
+            entry.just_released = false;
+            entry.held += delta;
+        }
+    }
+
+    /// Returns the state of a button this frame.
+    ///
+    /// A button tapped within one frame reports [`KeyState::JustPressed`].
+    pub fn state(&self, button: T) -> KeyState {
+        match self.buttons.get(&button) {
+            Some(entry) if entry.just_pressed => KeyState::JustPressed,
+            Some(entry) if entry.just_released => KeyState::JustReleased,
+            Some(entry) if entry.down => KeyState::Pressed,
+            _ => KeyState::Released,
+        }
+    }
+
+    /// Returns `true` while the button is held down.
+    pub fn pressed(&self, button: T) -> bool {
+        self.buttons.get(&button).is_some_and(|entry| entry.down)
+    }
+
+    /// Returns `true` if the button went down this frame.
+    pub fn just_pressed(&self, button: T) -> bool {
+        self.buttons
+            .get(&button)
+            .is_some_and(|entry| entry.just_pressed)
+    }
+
+    /// Returns `true` if the button went up this frame.
+    pub fn just_released(&self, button: T) -> bool {
+        self.buttons
+            .get(&button)
+            .is_some_and(|entry| entry.just_released)
+    }
+
+    /// Returns how long the button has been held, or zero if it is up.
+    ///
+    /// The duration counts whole frames since the press: it is zero on the frame
+    /// the button went down.
+    pub fn held_duration(&self, button: T) -> Duration {
+        self.buttons
+            .get(&button)
+            .filter(|entry| entry.down)
+            .map_or(Duration::ZERO, |entry| entry.held)
+    }
+
+    /// Returns the buttons currently held down.
+    pub fn iter_pressed(&self) -> impl Iterator<Item = T> + '_ {
+        self.buttons
+            .iter()
+            .filter(|(_, entry)| entry.down)
+            .map(|(button, _)| *button)
+    }
+
+    /// Returns the buttons that went down this frame.
+    pub fn iter_just_pressed(&self) -> impl Iterator<Item = T> + '_ {
+        self.buttons
+            .iter()
+            .filter(|(_, entry)| entry.just_pressed)
+            .map(|(button, _)| *button)
+    }
+
+    /// Returns the buttons that went up this frame.
+    pub fn iter_just_released(&self) -> impl Iterator<Item = T> + '_ {
+        self.buttons
+            .iter()
+            .filter(|(_, entry)| entry.just_released)
+            .map(|(button, _)| *button)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    #[test]
+    fn press_and_release_walk_through_all_states() {
+        let mut buttons = ButtonStates::new();
+        assert_eq!(buttons.state(1), KeyState::Released);
+
+        buttons.press(1);
+        assert_eq!(buttons.state(1), KeyState::JustPressed);
+        assert!(buttons.pressed(1) && buttons.just_pressed(1));
+
+        buttons.begin_frame(FRAME);
+        assert_eq!(buttons.state(1), KeyState::Pressed);
+        assert!(!buttons.just_pressed(1));
+
+        buttons.release(1);
+        assert_eq!(buttons.state(1), KeyState::JustReleased);
+        assert!(!buttons.pressed(1) && buttons.just_released(1));
+
+        buttons.begin_frame(FRAME);
+        assert_eq!(buttons.state(1), KeyState::Released);
+        assert_eq!(buttons.iter_pressed().count(), 0);
+    }
+
+    #[test]
+    fn tap_within_one_frame_keeps_both_edges() {
+        let mut buttons = ButtonStates::new();
+        buttons.press('x');
+        buttons.release('x');
+
+        assert!(buttons.just_pressed('x'));
+        assert!(buttons.just_released('x'));
+        assert!(!buttons.pressed('x'));
+        assert_eq!(buttons.state('x'), KeyState::JustPressed);
+    }
+
+    #[test]
+    fn repeats_do_not_restart_the_press() {
+        let mut buttons = ButtonStates::new();
+        buttons.press(7);
+        buttons.begin_frame(FRAME);
+        buttons.press(7);
+
+        assert!(!buttons.just_pressed(7));
+        assert_eq!(buttons.held_duration(7), FRAME);
+    }
+
+    #[test]
+    fn held_duration_accumulates_across_frames() {
+        let mut buttons = ButtonStates::new();
+        buttons.press(0);
+        assert_eq!(buttons.held_duration(0), Duration::ZERO);
+
+        for _ in 0..3 {
+            buttons.begin_frame(FRAME);
+        }
+        assert_eq!(buttons.held_duration(0), FRAME * 3);
+
+        buttons.release(0);
+        assert_eq!(buttons.held_duration(0), Duration::ZERO);
+    }
+
+    #[test]
+    fn release_all_releases_every_held_button() {
+        let mut buttons = ButtonStates::new();
+        buttons.press(1);
+        buttons.press(2);
+        buttons.begin_frame(FRAME);
+        buttons.release_all();
+
+        let mut released: Vec<_> = buttons.iter_just_released().collect();
+        released.sort_unstable();
+        assert_eq!(released, [1, 2]);
+    }
+}
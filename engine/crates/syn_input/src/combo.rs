@@ -0,0 +1,427 @@
+//! Sequence and combo matching over an [`InputBuffer`].
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::buffer::{BufferedInput, Direction, InputBuffer, InputToken};
+
+/// One step of a [`Sequence`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SequenceStep {
+    /// The tracked direction changed to this one.
+    Direction(Direction),
+    /// A button action was pressed.
+    Press(String),
+    /// Button actions pressed together, optionally while holding a direction,
+    /// such as forward + punch.
+    Together {
+        /// Direction held when the last button went down.
+        direction: Option<Direction>,
+        /// Buttons pressed within the sequence's together window.
+        buttons: Vec<String>,
+    },
+}
+
+impl SequenceStep {
+    /// A press of a button action.
+    pub fn press(action: impl Into<String>) -> Self {
+        Self::Press(action.into())
+    }
+
+    /// A button action pressed while holding a direction.
+    pub fn direction_press(direction: Direction, action: impl Into<String>) -> Self {
+        Self::Together {
+            direction: Some(direction),
+            buttons: vec![action.into()],
+        }
+    }
+}
+
+/// An ordered list of inputs with timing windows, such as a quarter-circle
+/// motion followed by a punch.
+///
+/// Matching is lenient: unrelated inputs between two steps are ignored, as long
+/// as each step follows the previous one within the step window.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use syn_input::{Direction, InputBuffer, InputToken, Sequence};
+///
+/// let fireball = Sequence::motion(
+///     [Direction::Down, Direction::DownForward, Direction::Forward],
+///     "punch",
+/// );
+///
+/// let mut buffer = InputBuffer::new(Duration::from_secs(1));
+/// for token in [
+///     InputToken::Direction(Direction::Down),
+///     InputToken::Direction(Direction::DownForward),
+///     InputToken::Direction(Direction::Forward),
+///     InputToken::Pressed("punch".into()),
+/// ] {
+///     buffer.push(token);
+///     buffer.advance(Duration::from_millis(16));
+/// }
+///
+/// assert!(fireball.matches(&buffer));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sequence {
+    /// The steps, in order.
+    pub steps: Vec<SequenceStep>,
+    /// Longest time between two consecutive steps.
+    pub step_window: Duration,
+    /// Longest time between the last step and now.
+    pub buffer_window: Duration,
+    /// Longest time between buttons of one [`SequenceStep::Together`].
+    pub together_window: Duration,
+}
+
+impl Sequence {
+    /// Creates a sequence with default windows: 250 ms between steps, 150 ms
+    /// of buffering and 50 ms for simultaneous presses.
+    pub fn new(steps: impl Into<Vec<SequenceStep>>) -> Self {
+        Self {
+            steps: steps.into(),
+            step_window: Duration::from_millis(250),
+            buffer_window: Duration::from_millis(150),
+            together_window: Duration::from_millis(50),
+        }
+    }
+
+    /// Creates a motion input: directions in order, then a button press.
+    pub fn motion(
+        directions: impl IntoIterator<Item = Direction>,
+        action: impl Into<String>,
+    ) -> Self {
+        let mut steps: Vec<_> = directions
+            .into_iter()
+            .map(SequenceStep::Direction)
+            .collect();
+        steps.push(SequenceStep::Press(action.into()));
+        Self::new(steps)
+    }
+
+    /// Creates a combo of button presses, in order.
+    pub fn presses<S: Into<String>>(actions: impl IntoIterator<Item = S>) -> Self {
+        Self::new(
+            actions
+                .into_iter()
+                .map(SequenceStep::press)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Sets the longest time between two consecutive steps.
+    #[must_use]
+    pub fn with_step_window(mut self, window: Duration) -> Self {
+        self.step_window = window;
+        self
+    }
+
+    /// Sets the longest time between the last step and now.
+    #[must_use]
+    pub fn with_buffer_window(mut self, window: Duration) -> Self {
+        self.buffer_window = window;
+        self
+    }
+
+    /// Sets the longest time between buttons pressed together.
+    #[must_use]
+    pub fn with_together_window(mut self, window: Duration) -> Self {
+        self.together_window = window;
+        self
+    }
+
+    /// Returns `true` if the buffer ends with the sequence.
+    pub fn matches(&self, buffer: &InputBuffer) -> bool {
+        self.find(buffer).is_some()
+    }
+
+    /// Returns the buffer indices of the inputs completing the sequence, using
+    /// only unconsumed presses.
+    pub fn find(&self, buffer: &InputBuffer) -> Option<Vec<usize>> {
+        let history = buffer.history();
+        let mut matched = Vec::new();
+        let mut end = history.len();
+        let mut anchor = buffer.now();
+
+        for (index, step) in self.steps.iter().enumerate().rev() {
+            let window = if index + 1 == self.steps.len() {
+                self.buffer_window
+            } else {
+                self.step_window
+            };
+            let start = matched.len();
+
+            match step {
+                SequenceStep::Direction(direction) => {
+                    let token = InputToken::Direction(*direction);
+                    matched.push(search(history, end, anchor, window, |e| e.input == token)?);
+                }
+                SequenceStep::Press(action) => {
+                    matched.push(search(history, end, anchor, window, |e| {
+                        is_press(e, action)
+                    })?);
+                }
+                SequenceStep::Together { direction, buttons } => {
+                    self.find_together(
+                        history,
+                        end,
+                        anchor,
+                        window,
+                        *direction,
+                        buttons,
+                        &mut matched,
+                    )?;
+                }
+            }
+
+            let earliest = matched[start..].iter().copied().min()?;
+            end = earliest;
+            anchor = history[earliest].time;
+        }
+
+        Some(matched)
+    }
+
+    // RATIONALE: the search state is threaded explicitly to keep `find` flat
+    #[allow(clippy::too_many_arguments)]
+    fn find_together(
+        &self,
+        history: &VecDeque<BufferedInput>,
+        end: usize,
+        anchor: Duration,
+        window: Duration,
+        direction: Option<Direction>,
+        buttons: &[String],
+        matched: &mut Vec<usize>,
+    ) -> Option<()> {
+        let Some((first, rest)) = buttons.split_first() else {
+            let held = direction_at(history, end);
+            return (direction == Some(held) || direction.is_none()).then_some(());
+        };
+
+        // The latest button anchors the group; the others may come slightly earlier.
+        let latest = search(history, end, anchor, window, |e| {
+            buttons.iter().any(|button| is_press(e, button))
+        })?;
+        let time = history[latest].time;
+        let mut group = vec![latest];
+        for button in std::iter::once(first).chain(rest) {
+            if group.iter().any(|&i| is_press(&history[i], button)) {
+                continue;
+            }
+            let index = search(history, latest, time, self.together_window, |e| {
+                is_press(e, button)
+            })?;
+            group.push(index);
+        }
+
+        if direction.is_some_and(|direction| direction_at(history, latest) != direction) {
+            return None;
+        }
+
+        matched.extend(group);
+        Some(())
+    }
+}
+
+/// Searches backwards from `end` for an unconsumed input within `window` of
+/// `anchor`.
+fn search(
+    history: &VecDeque<BufferedInput>,
+    end: usize,
+    anchor: Duration,
+    window: Duration,
+    predicate: impl Fn(&BufferedInput) -> bool,
+) -> Option<usize> {
+    (0..end)
+        .rev()
+        .take_while(|&index| anchor.saturating_sub(history[index].time) <= window)
+        .find(|&index| !history[index].is_consumed() && predicate(&history[index]))
+}
+
+fn is_press(entry: &BufferedInput, action: &str) -> bool {
+    matches!(&entry.input, InputToken::Pressed(name) if name == action)
+}
+
+/// Returns the direction held just before `index`.
+fn direction_at(history: &VecDeque<BufferedInput>, index: usize) -> Direction {
+    history
+        .range(..index)
+        .rev()
+        .find_map(|entry| match entry.input {
+            InputToken::Direction(direction) => Some(direction),
+            _ => None,
+        })
+        .unwrap_or(Direction::Neutral)
+}
+
+/// Named sequences checked together, longest first, so a special move wins over
+/// the plain attack it ends with.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use syn_input::{ComboMatcher, InputBuffer, InputToken, Sequence};
+///
+/// let mut combos = ComboMatcher::new();
+/// combos.add("jab", Sequence::presses(["punch"]));
+/// combos.add("one-two", Sequence::presses(["punch", "punch"]));
+///
+/// let mut buffer = InputBuffer::new(Duration::from_secs(1));
+/// buffer.push(InputToken::Pressed("punch".into()));
+/// assert_eq!(combos.check(&mut buffer), Some("jab"));
+///
+/// buffer.advance(Duration::from_millis(100));
+/// buffer.push(InputToken::Pressed("punch".into()));
+/// // The first punch was consumed by "jab", so this is another jab.
+/// assert_eq!(combos.check(&mut buffer), Some("jab"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ComboMatcher {
+    combos: Vec<(String, Sequence)>,
+}
+
+impl ComboMatcher {
+    /// Creates an empty matcher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a named sequence.
+    pub fn add(&mut self, name: impl Into<String>, sequence: Sequence) {
+        let index = self
+            .combos
+            .partition_point(|(_, existing)| existing.steps.len() >= sequence.steps.len());
+        self.combos.insert(index, (name.into(), sequence));
+    }
+
+    /// Returns the longest sequence the buffer ends with and consumes its
+    /// inputs, so it triggers once.
+    pub fn check(&self, buffer: &mut InputBuffer) -> Option<&str> {
+        self.combos.iter().find_map(|(name, sequence)| {
+            let indices = sequence.find(buffer)?;
+            buffer.consume(&indices);
+            Some(name.as_str())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(16);
+
+    fn buffer_with(tokens: &[InputToken]) -> InputBuffer {
+        let mut buffer = InputBuffer::new(Duration::from_secs(2));
+        for token in tokens {
+            buffer.push(token.clone());
+            buffer.advance(STEP);
+        }
+        buffer
+    }
+
+    fn dir(direction: Direction) -> InputToken {
+        InputToken::Direction(direction)
+    }
+
+    fn press(action: &str) -> InputToken {
+        InputToken::Pressed(action.into())
+    }
+
+    #[test]
+    fn motion_ignores_unrelated_inputs_but_respects_order() {
+        let fireball = Sequence::motion(
+            [Direction::Down, Direction::DownForward, Direction::Forward],
+            "punch",
+        );
+
+        let sloppy = buffer_with(&[
+            dir(Direction::Down),
+            dir(Direction::DownBack),
+            dir(Direction::DownForward),
+            press("kick"),
+            dir(Direction::Forward),
+            press("punch"),
+        ]);
+        assert!(fireball.matches(&sloppy));
+
+        let reversed = buffer_with(&[
+            dir(Direction::Forward),
+            dir(Direction::DownForward),
+            dir(Direction::Down),
+            press("punch"),
+        ]);
+        assert!(!fireball.matches(&reversed));
+    }
+
+    #[test]
+    fn windows_limit_step_gaps_and_buffering() {
+        let fireball = Sequence::motion([Direction::Down, Direction::Forward], "punch");
+
+        let mut slow = buffer_with(&[dir(Direction::Down)]);
+        slow.advance(Duration::from_millis(400));
+        slow.push(dir(Direction::Forward));
+        slow.push(press("punch"));
+        assert!(!fireball.matches(&slow));
+
+        let mut late = buffer_with(&[
+            dir(Direction::Down),
+            dir(Direction::Forward),
+            press("punch"),
+        ]);
+        assert!(fireball.matches(&late));
+        late.advance(Duration::from_millis(200));
+        assert!(!fireball.matches(&late));
+        assert!(
+            fireball
+                .clone()
+                .with_buffer_window(Duration::from_millis(300))
+                .matches(&late)
+        );
+    }
+
+    #[test]
+    fn together_checks_held_direction_and_simultaneous_buttons() {
+        let forward_punch =
+            Sequence::new([SequenceStep::direction_press(Direction::Forward, "punch")]);
+        let throw = Sequence::new([SequenceStep::Together {
+            direction: None,
+            buttons: vec!["punch".into(), "kick".into()],
+        }]);
+
+        let buffer = buffer_with(&[dir(Direction::Forward), press("kick"), press("punch")]);
+        assert!(forward_punch.matches(&buffer));
+        assert!(throw.matches(&buffer));
+
+        let mut apart = buffer_with(&[press("kick")]);
+        apart.advance(Duration::from_millis(100));
+        apart.push(press("punch"));
+        assert!(!throw.matches(&apart));
+        assert!(!forward_punch.matches(&apart));
+    }
+
+    #[test]
+    fn combos_prefer_longer_sequences_and_consume_inputs() {
+        let mut combos = ComboMatcher::new();
+        combos.add("punch", Sequence::presses(["punch"]));
+        combos.add(
+            "fireball",
+            Sequence::motion([Direction::Down, Direction::Forward], "punch"),
+        );
+
+        let mut buffer = buffer_with(&[
+            dir(Direction::Down),
+            dir(Direction::Forward),
+            press("punch"),
+        ]);
+        assert_eq!(combos.check(&mut buffer), Some("fireball"));
+        assert_eq!(combos.check(&mut buffer), None);
+    }
+}
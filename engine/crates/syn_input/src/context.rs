@@ -0,0 +1,467 @@
+//! Stackable, prioritised input contexts.
+
+use crate::action::{Action, ActionMap};
+use crate::binding::{InputBinding, InputMask, InputSource, Modifier};
+use crate::profile::InputProfile;
+use crate::state::InputState;
+use crate::value::{ActionKind, ActionValue, FromActionValue};
+
+/// A named set of actions that can be pushed onto an [`InputContextStack`].
+///
+/// Gameplay, vehicles, menus and the developer console each use their own
+/// context. A consuming context hides the inputs it binds from every context of
+/// lower priority.
+#[derive(Debug, Clone)]
+pub struct InputContext {
+    /// The context name, unique within a stack.
+    pub name: String,
+    /// Contexts with higher priority see input first.
+    pub priority: i32,
+    /// Whether inputs bound here are hidden from lower-priority contexts.
+    pub consume: bool,
+    /// Additional inputs hidden from lower-priority contexts when consuming, even
+    /// though no action here binds them.
+    pub blocked: Vec<InputSource>,
+    /// The actions of this context.
+    pub actions: ActionMap,
+}
+
+impl InputContext {
+    /// Creates a non-consuming context with no actions.
+    pub fn new(name: impl Into<String>, priority: i32) -> Self {
+        Self {
+            name: name.into(),
+            priority,
+            consume: false,
+            blocked: Vec::new(),
+            actions: ActionMap::new(),
+        }
+    }
+
+    /// Makes the context consume the inputs it binds.
+    #[must_use]
+    pub fn consuming(mut self) -> Self {
+        self.consume = true;
+        self
+    }
+
+    /// Makes the context consume an input it does not bind.
+    #[must_use]
+    pub fn blocking(mut self, source: InputSource) -> Self {
+        self.consume = true;
+        self.blocked.push(source);
+        self
+    }
+
+    /// Registers an action.
+    #[must_use]
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.actions.register(action);
+        self
+    }
+
+    fn consume_into(&self, mask: &mut InputMask) {
+        if !self.consume {
+            return;
+        }
+
+        let mut sources = self.blocked.clone();
+        for action in self.actions.actions() {
+            for binding in &action.bindings {
+                binding.sources(&mut sources);
+            }
+        }
+        for source in sources {
+            mask.insert(source);
+        }
+    }
+}
+
+/// An action within a context.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionRef {
+    /// The context name.
+    pub context: String,
+    /// The action name.
+    pub action: String,
+}
+
+/// Two actions triggered by the same input at the same time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingConflict {
+    /// The shared input.
+    pub source: InputSource,
+    /// Modifiers both bindings require, if they are chords.
+    pub modifiers: Vec<Modifier>,
+    /// The action in the higher-priority context.
+    pub first: ActionRef,
+    /// The action in the lower or same-priority context.
+    pub second: ActionRef,
+}
+
+/// Input contexts ordered by priority.
+///
+/// Contexts of equal priority are ordered by push order, the latest on top.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::{
+///     Action, InputBinding, InputContext, InputContextStack, InputState, KeyCode,
+/// };
+///
+/// let mut stack = InputContextStack::new();
+/// stack.push(
+///     InputContext::new("gameplay", 0)
+///         .with_action(Action::button("jump").with_binding(InputBinding::Key(KeyCode::Space))),
+/// );
+/// stack.push(
+///     InputContext::new("menu", 100)
+///         .consuming()
+///         .with_action(Action::button("confirm").with_binding(InputBinding::Key(KeyCode::Space))),
+/// );
+///
+/// let mut input = InputState::new();
+/// input.keyboard.keys_mut().press(KeyCode::Space);
+/// stack.update(&input);
+///
+/// assert!(stack.pressed("confirm"));
+/// assert!(!stack.pressed("jump"));
+///
+/// stack.remove("menu");
+/// stack.update(&input);
+/// assert!(stack.pressed("jump"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputContextStack {
+    /// Sorted by ascending priority; the last context is on top.
+    contexts: Vec<InputContext>,
+}
+
+impl InputContextStack {
+    /// Creates an empty stack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes a context above every context of lower or equal priority.
+    ///
+    /// A context with the same name is replaced.
+    pub fn push(&mut self, context: InputContext) {
+        self.remove(&context.name);
+        let index = self
+            .contexts
+            .partition_point(|existing| existing.priority <= context.priority);
+        self.contexts.insert(index, context);
+    }
+
+    /// Removes and returns the top-most context.
+    pub fn pop(&mut self) -> Option<InputContext> {
+        self.contexts.pop()
+    }
+
+    /// Removes and returns a context by name.
+    pub fn remove(&mut self, name: &str) -> Option<InputContext> {
+        let index = self.contexts.iter().position(|c| c.name == name)?;
+        Some(self.contexts.remove(index))
+    }
+
+    /// Returns `true` if a context with that name is on the stack.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns a context by name.
+    pub fn get(&self, name: &str) -> Option<&InputContext> {
+        self.contexts.iter().find(|c| c.name == name)
+    }
+
+    /// Returns a context by name, mutably.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut InputContext> {
+        self.contexts.iter_mut().find(|c| c.name == name)
+    }
+
+    /// Returns the top-most context.
+    pub fn top(&self) -> Option<&InputContext> {
+        self.contexts.last()
+    }
+
+    /// Returns the contexts from top to bottom.
+    pub fn iter(&self) -> impl Iterator<Item = &InputContext> {
+        self.contexts.iter().rev()
+    }
+
+    /// Returns the number of contexts.
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Returns `true` if the stack is empty.
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+
+    /// Evaluates every context for the current frame, from top to bottom.
+    pub fn update(&mut self, input: &InputState) {
+        let mut mask = InputMask::default();
+        for context in self.contexts.iter_mut().rev() {
+            context.actions.update_masked(input, &mask);
+            context.consume_into(&mut mask);
+        }
+    }
+
+    /// Returns the value of an action from the top-most context defining it.
+    pub fn value<T: FromActionValue>(&self, action: &str) -> T {
+        match self.find(action) {
+            Some(actions) => actions.value(action),
+            None => T::from_action_value(ActionValue::zero(ActionKind::default())),
+        }
+    }
+
+    /// Returns `true` while the action of the top-most context defining it is
+    /// actuated.
+    pub fn pressed(&self, action: &str) -> bool {
+        self.find(action)
+            .is_some_and(|actions| actions.pressed(action))
+    }
+
+    /// Returns `true` if the action of the top-most context defining it became
+    /// actuated this frame.
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.find(action)
+            .is_some_and(|actions| actions.just_pressed(action))
+    }
+
+    /// Returns `true` if the action of the top-most context defining it stopped
+    /// being actuated this frame.
+    pub fn just_released(&self, action: &str) -> bool {
+        self.find(action)
+            .is_some_and(|actions| actions.just_released(action))
+    }
+
+    /// Replaces the bindings of the contexts on the stack with those of a profile.
+    ///
+    /// Contexts the profile does not mention are left unchanged, and profile
+    /// contexts not on the stack are ignored.
+    pub fn apply_profile(&mut self, profile: &InputProfile) {
+        for context in &mut self.contexts {
+            let Some(actions) = profile.context(&context.name) else {
+                continue;
+            };
+            for action in actions.actions() {
+                context.actions.register(action.clone());
+            }
+        }
+    }
+
+    /// Returns every pair of actions that one input would trigger together.
+    ///
+    /// Two bindings conflict when they share an input and the same modifiers,
+    /// belong to different actions, and no consuming context above the lower
+    /// one hides that input.
+    pub fn conflicts(&self) -> Vec<BindingConflict> {
+        let mut conflicts = Vec::new();
+        let mut live: Vec<(Vec<Modifier>, InputSource, ActionRef)> = Vec::new();
+        let mut mask = InputMask::default();
+
+        for context in self.iter() {
+            let mut context_triggers = Vec::new();
+            for action in context.actions.actions() {
+                let action_ref = ActionRef {
+                    context: context.name.clone(),
+                    action: action.name.clone(),
+                };
+                let mut triggers = Vec::new();
+                for binding in &action.bindings {
+                    binding.triggers(&mut triggers);
+                }
+                for (modifiers, source) in triggers {
+                    if !mask.blocks(source) {
+                        context_triggers.push((modifiers, source, action_ref.clone()));
+                    }
+                }
+            }
+
+            context_triggers.sort_by(|a, b| a.2.action.cmp(&b.2.action));
+            context_triggers.dedup();
+            for (modifiers, source, action) in &context_triggers {
+                for (_, _, first) in live
+                    .iter()
+                    .filter(|(m, s, a)| m == modifiers && s == source && a != action)
+                {
+                    conflicts.push(BindingConflict {
+                        source: *source,
+                        modifiers: modifiers.clone(),
+                        first: first.clone(),
+                        second: action.clone(),
+                    });
+                }
+                live.push((modifiers.clone(), *source, action.clone()));
+            }
+
+            context.consume_into(&mut mask);
+        }
+
+        conflicts
+    }
+
+    /// Returns the conflicts that adding `binding` to an action would create,
+    /// such as to warn the player while rebinding.
+    pub fn conflicts_with(
+        &self,
+        context: &str,
+        action: &str,
+        binding: &InputBinding,
+    ) -> Vec<BindingConflict> {
+        let mut candidate = self.clone();
+        let Some(target) = candidate.get_mut(context) else {
+            return Vec::new();
+        };
+        let mut updated = target
+            .actions
+            .get(action)
+            .cloned()
+            .unwrap_or_else(|| Action::button(action));
+        updated.bindings = vec![binding.clone()];
+        target.actions.register(updated);
+
+        let is_target = |r: &ActionRef| r.context == context && r.action == action;
+        candidate
+            .conflicts()
+            .into_iter()
+            .filter(|conflict| is_target(&conflict.first) || is_target(&conflict.second))
+            .collect()
+    }
+
+    fn find(&self, action: &str) -> Option<&ActionMap> {
+        self.iter()
+            .map(|context| &context.actions)
+            .find(|actions| actions.get(action).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::KeyCode;
+    use crate::mouse::MouseButton;
+    use glam::Vec2;
+
+    fn gameplay() -> InputContext {
+        InputContext::new("gameplay", 0)
+            .with_action(Action::axis_2d("move").with_binding(InputBinding::wasd()))
+            .with_action(
+                Action::button("fire").with_binding(InputBinding::Mouse(MouseButton::Left)),
+            )
+    }
+
+    #[test]
+    fn push_orders_by_priority_then_recency() {
+        let mut stack = InputContextStack::new();
+        stack.push(InputContext::new("console", 1000));
+        stack.push(gameplay());
+        stack.push(InputContext::new("vehicle", 0));
+
+        let names: Vec<_> = stack.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["console", "vehicle", "gameplay"]);
+
+        assert_eq!(stack.pop().unwrap().name, "console");
+        assert_eq!(stack.top().unwrap().name, "vehicle");
+    }
+
+    #[test]
+    fn consuming_context_hides_only_its_bindings() {
+        let mut stack = InputContextStack::new();
+        stack.push(gameplay());
+        stack.push(
+            InputContext::new("menu", 10)
+                .consuming()
+                .with_action(Action::button("back").with_binding(InputBinding::Key(KeyCode::S))),
+        );
+
+        let mut input = InputState::new();
+        input.keyboard.keys_mut().press(KeyCode::S);
+        input.keyboard.keys_mut().press(KeyCode::D);
+        input.mouse.buttons.press(MouseButton::Left);
+        stack.update(&input);
+
+        assert!(stack.pressed("back"));
+        assert_eq!(stack.value::<Vec2>("move"), Vec2::X);
+        assert!(stack.pressed("fire"));
+    }
+
+    #[test]
+    fn non_consuming_context_shares_inputs() {
+        let mut stack = InputContextStack::new();
+        stack.push(gameplay());
+        stack.push(InputContext::new("hud", 5).with_action(
+            Action::button("aim").with_binding(InputBinding::Mouse(MouseButton::Left)),
+        ));
+
+        let mut input = InputState::new();
+        input.mouse.buttons.press(MouseButton::Left);
+        stack.update(&input);
+
+        assert!(stack.pressed("aim"));
+        assert!(stack.pressed("fire"));
+    }
+
+    #[test]
+    fn conflicts_respect_consumption_and_modifiers() {
+        let mut stack = InputContextStack::new();
+        stack.push(
+            gameplay()
+                .with_action(Action::button("crouch").with_binding(InputBinding::Key(KeyCode::S)))
+                .with_action(
+                    Action::button("save")
+                        .with_binding(InputBinding::chord([Modifier::Control], KeyCode::S)),
+                ),
+        );
+        stack.push(InputContext::new("hud", 5).with_action(
+            Action::button("shoot").with_binding(InputBinding::Mouse(MouseButton::Left)),
+        ));
+        stack.push(
+            InputContext::new("menu", 10)
+                .consuming()
+                .with_action(Action::button("down").with_binding(InputBinding::Key(KeyCode::S))),
+        );
+
+        let conflicts = stack.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].source, InputSource::Mouse(MouseButton::Left));
+        assert_eq!(conflicts[0].first.action, "shoot");
+        assert_eq!(conflicts[0].second.action, "fire");
+
+        stack.remove("menu");
+        let conflicts = stack.conflicts();
+        assert_eq!(conflicts.len(), 2);
+        assert!(
+            conflicts
+                .iter()
+                .any(|c| c.source == InputSource::Key(KeyCode::S) && c.modifiers.is_empty())
+        );
+
+        let candidate = stack.conflicts_with("hud", "shoot", &InputBinding::Key(KeyCode::W));
+        assert_eq!(candidate.len(), 1);
+        assert_eq!(candidate[0].second.action, "move");
+    }
+
+    #[test]
+    fn blocking_any_key_silences_the_keyboard() {
+        let mut stack = InputContextStack::new();
+        stack.push(gameplay());
+        stack.push(InputContext::new("console", 1000).blocking(InputSource::AnyKey));
+
+        let mut input = InputState::new();
+        input.keyboard.keys_mut().press(KeyCode::W);
+        input.mouse.buttons.press(MouseButton::Left);
+        stack.update(&input);
+
+        assert_eq!(stack.value::<Vec2>("move"), Vec2::ZERO);
+        assert!(stack.pressed("fire"));
+
+        stack.remove("console");
+        stack.update(&input);
+        assert!(stack.just_pressed("move"));
+    }
+}
@@ -0,0 +1,50 @@
+//! Input error types.
+
+use thiserror::Error;
+
+/// Errors raised while loading or saving input profiles.
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    /// Reading or writing the profile file failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The RON document is malformed.
+    #[error("Invalid RON profile: {0}")]
+    RonParse(#[from] ron::error::SpannedError),
+
+    /// The profile could not be written as RON.
+    #[error("RON serialization failed: {0}")]
+    RonWrite(#[from] ron::Error),
+
+    /// The TOML document is malformed.
+    #[error("Invalid TOML profile: {0}")]
+    TomlParse(#[from] toml::de::Error),
+
+    /// The profile could not be written as TOML.
+    #[error("TOML serialization failed: {0}")]
+    TomlWrite(#[from] toml::ser::Error),
+
+    /// The file extension is neither `.ron` nor `.toml`.
+    #[error("Unsupported profile format: {0}")]
+    UnsupportedFormat(String),
+}
+
+/// Result type for profile operations.
+pub type ProfileResult<T> = std::result::Result<T, ProfileError>;
+
+/// Errors raised while parsing an SDL game controller mapping.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MappingError {
+    /// The line has no GUID or no name.
+    #[error("Mapping is missing its GUID or name")]
+    Incomplete,
+
+    /// The GUID is not 32 hexadecimal digits.
+    #[error("Invalid controller GUID: {0}")]
+    InvalidGuid(String),
+
+    /// An element is not a valid `target:source` pair.
+    #[error("Invalid mapping element: {0}")]
+    InvalidElement(String),
+}
@@ -1,15 +1,22 @@
 //! Gamepad/controller input handling.
 
+use std::time::Duration;
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::button::ButtonStates;
+
 /// Gamepad buttons.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GamepadButton {
-    /// South face button (A on Xbox, X on PlayStation).
+    /// South face button (A on Xbox, X on `PlayStation`).
     South,
-    /// East face button (B on Xbox, Circle on PlayStation).
+    /// East face button (B on Xbox, Circle on `PlayStation`).
     East,
-    /// West face button (X on Xbox, Square on PlayStation).
+    /// West face button (X on Xbox, Square on `PlayStation`).
     West,
-    /// North face button (Y on Xbox, Triangle on PlayStation).
+    /// North face button (Y on Xbox, Triangle on `PlayStation`).
     North,
     /// Left bumper.
     LeftBumper,
@@ -23,12 +30,43 @@ pub enum GamepadButton {
     Start,
     /// Select/Back button.
     Select,
-    /// D-pad directions.
-    DPadUp, DPadDown, DPadLeft, DPadRight,
+    /// Guide button (Xbox, PS).
+    Guide,
+    /// D-pad up.
+    DPadUp,
+    /// D-pad down.
+    DPadDown,
+    /// D-pad left.
+    DPadLeft,
+    /// D-pad right.
+    DPadRight,
+}
+
+impl GamepadButton {
+    /// Every button, in declaration order.
+    pub const ALL: [GamepadButton; 15] = [
+        GamepadButton::South,
+        GamepadButton::East,
+        GamepadButton::West,
+        GamepadButton::North,
+        GamepadButton::LeftBumper,
+        GamepadButton::RightBumper,
+        GamepadButton::LeftStick,
+        GamepadButton::RightStick,
+        GamepadButton::Start,
+        GamepadButton::Select,
+        GamepadButton::Guide,
+        GamepadButton::DPadUp,
+        GamepadButton::DPadDown,
+        GamepadButton::DPadLeft,
+        GamepadButton::DPadRight,
+    ];
 }
 
 /// Gamepad axes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Sticks range from -1 to 1 with +y up; triggers range from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GamepadAxis {
     /// Left stick X axis.
     LeftStickX,
@@ -43,3 +81,171 @@ pub enum GamepadAxis {
     /// Right trigger.
     RightTrigger,
 }
+
+impl GamepadAxis {
+    /// Every axis, in declaration order.
+    pub const ALL: [GamepadAxis; 6] = [
+        GamepadAxis::LeftStickX,
+        GamepadAxis::LeftStickY,
+        GamepadAxis::RightStickX,
+        GamepadAxis::RightStickY,
+        GamepadAxis::LeftTrigger,
+        GamepadAxis::RightTrigger,
+    ];
+}
+
+/// Identifies a connected gamepad for as long as it stays connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GamepadId(pub u32);
+
+/// A normalized gamepad event, applied with [`InputState::handle_gamepad_event`].
+///
+/// [`InputState::handle_gamepad_event`]: crate::InputState::handle_gamepad_event
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEvent {
+    /// A gamepad was plugged in.
+    Connected {
+        /// The gamepad.
+        id: GamepadId,
+        /// Human-readable device name.
+        name: String,
+    },
+    /// A gamepad was unplugged.
+    Disconnected {
+        /// The gamepad.
+        id: GamepadId,
+    },
+    /// A button was pressed or released.
+    Button {
+        /// The gamepad.
+        id: GamepadId,
+        /// The button.
+        button: GamepadButton,
+        /// `true` if pressed.
+        pressed: bool,
+    },
+    /// An axis moved.
+    Axis {
+        /// The gamepad.
+        id: GamepadId,
+        /// The axis.
+        axis: GamepadAxis,
+        /// The new value.
+        value: f32,
+    },
+}
+
+/// Input state of one gamepad slot.
+///
+/// Slots are filled by [`GamepadEvent::Connected`] and keep their index when the
+/// pad is unplugged, so player two stays player two after a reconnect. Pads
+/// created with [`GamepadState::new`] are virtual: connected, with no id.
+#[derive(Debug, Clone)]
+pub struct GamepadState {
+    /// Button states.
+    pub buttons: ButtonStates<GamepadButton>,
+    axes: HashMap<GamepadAxis, f32>,
+    id: Option<GamepadId>,
+    name: String,
+    connected: bool,
+    just_connected: bool,
+    just_disconnected: bool,
+}
+
+impl Default for GamepadState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GamepadState {
+    /// Creates a virtual gamepad with every button released and every axis at rest.
+    pub fn new() -> Self {
+        Self {
+            buttons: ButtonStates::new(),
+            axes: HashMap::new(),
+            id: None,
+            name: String::new(),
+            connected: true,
+            just_connected: false,
+            just_disconnected: false,
+        }
+    }
+
+    /// Returns the id of the pad in this slot, or `None` for a virtual pad.
+    pub fn id(&self) -> Option<GamepadId> {
+        self.id
+    }
+
+    /// Returns the device name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` while the pad is plugged in.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Returns `true` if the pad was plugged in this frame.
+    pub fn just_connected(&self) -> bool {
+        self.just_connected
+    }
+
+    /// Returns `true` if the pad was unplugged this frame.
+    pub fn just_disconnected(&self) -> bool {
+        self.just_disconnected
+    }
+
+    /// Returns `true` while the button is held down.
+    pub fn pressed(&self, button: GamepadButton) -> bool {
+        self.buttons.pressed(button)
+    }
+
+    /// Returns `true` if the button went down this frame.
+    pub fn just_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons.just_pressed(button)
+    }
+
+    /// Returns `true` if the button went up this frame.
+    pub fn just_released(&self, button: GamepadButton) -> bool {
+        self.buttons.just_released(button)
+    }
+
+    /// Returns how long the button has been held, or zero if it is up.
+    pub fn held_duration(&self, button: GamepadButton) -> Duration {
+        self.buttons.held_duration(button)
+    }
+
+    /// Returns the value of an axis: -1 to 1 for sticks, 0 to 1 for triggers.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    /// Sets the value of an axis, clamped to -1 to 1.
+    pub fn set_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.axes.insert(axis, value.clamp(-1.0, 1.0));
+    }
+
+    /// Starts a new frame that lasted `delta`.
+    pub(crate) fn begin_frame(&mut self, delta: Duration) {
+        self.buttons.begin_frame(delta);
+        self.just_connected = false;
+        self.just_disconnected = false;
+    }
+
+    pub(crate) fn connect(&mut self, id: GamepadId, name: &str) {
+        self.id = Some(id);
+        self.name.clear();
+        self.name.push_str(name);
+        self.connected = true;
+        self.just_connected = true;
+    }
+
+    pub(crate) fn disconnect(&mut self) {
+        self.buttons.release_all();
+        self.axes.clear();
+        self.connected = false;
+        self.just_disconnected = true;
+    }
+}
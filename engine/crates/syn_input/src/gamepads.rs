@@ -0,0 +1,331 @@
+//! Gamepad hot-plug, mapping and force feedback.
+
+use std::time::Duration;
+
+use hashbrown::HashMap;
+
+use crate::backend::{GamepadBackend, RawGamepadEvent};
+use crate::gamepad::{GamepadEvent, GamepadId};
+use crate::mapping::{GamepadDb, GamepadMapping};
+use crate::state::InputState;
+
+/// A rumble request.
+///
+/// Overlapping effects on one pad combine by taking the strongest speed of each
+/// motor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumbleEffect {
+    /// Strong (low-frequency) motor speed, from 0 to 1.
+    pub strong: f32,
+    /// Weak (high-frequency) motor speed, from 0 to 1.
+    pub weak: f32,
+    /// How long the effect lasts.
+    pub duration: Duration,
+}
+
+impl RumbleEffect {
+    /// Creates an effect, clamping the motor speeds to 0 to 1.
+    pub fn new(strong: f32, weak: f32, duration: Duration) -> Self {
+        Self {
+            strong: strong.clamp(0.0, 1.0),
+            weak: weak.clamp(0.0, 1.0),
+            duration,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ActiveRumble {
+    id: GamepadId,
+    effect: RumbleEffect,
+    remaining: Duration,
+}
+
+#[derive(Debug)]
+struct ConnectedPad {
+    mapping: GamepadMapping,
+    known: bool,
+    rumble: (f32, f32),
+}
+
+/// Connects a [`GamepadBackend`] to an [`InputState`].
+///
+/// Each update polls the backend, normalizes raw input through the controller's
+/// [`GamepadMapping`] (falling back to [`GamepadMapping::standard`] for
+/// controllers missing from the database), feeds the resulting
+/// [`GamepadEvent`]s to the input state and plays rumble effects.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use syn_input::{
+///     GamepadButton, GamepadDb, Gamepads, InputState, MockGamepadBackend, RumbleEffect,
+/// };
+///
+/// let mut gamepads = Gamepads::new(MockGamepadBackend::new(), GamepadDb::new());
+/// let id = gamepads.backend_mut().connect("03000000de280000ff11000001000000", "Pad");
+/// gamepads.backend_mut().press(id, 0);
+///
+/// let mut input = InputState::new();
+/// input.begin_frame(Duration::from_millis(16));
+/// gamepads.update(&mut input);
+///
+/// assert!(input.gamepads[0].just_connected());
+/// assert!(input.gamepads[0].pressed(GamepadButton::South));
+///
+/// assert!(gamepads.rumble(id, RumbleEffect::new(1.0, 0.5, Duration::from_millis(100))));
+/// assert_eq!(gamepads.backend().rumble(id), (1.0, 0.5));
+/// ```
+#[derive(Debug)]
+pub struct Gamepads<B> {
+    backend: B,
+    db: GamepadDb,
+    fallback: GamepadMapping,
+    pads: HashMap<GamepadId, ConnectedPad>,
+    rumbles: Vec<ActiveRumble>,
+    raw: Vec<RawGamepadEvent>,
+    events: Vec<GamepadEvent>,
+}
+
+impl<B: GamepadBackend> Gamepads<B> {
+    /// Creates a manager for a backend and mapping database.
+    pub fn new(backend: B, db: GamepadDb) -> Self {
+        Self {
+            backend,
+            db,
+            fallback: GamepadMapping::standard(),
+            pads: HashMap::new(),
+            rumbles: Vec::new(),
+            raw: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns the backend.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns the backend mutably.
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Returns the mapping database.
+    pub fn db(&self) -> &GamepadDb {
+        &self.db
+    }
+
+    /// Returns the mapping database mutably.
+    ///
+    /// Mappings added here apply to controllers connected afterwards.
+    pub fn db_mut(&mut self) -> &mut GamepadDb {
+        &mut self.db
+    }
+
+    /// Polls the backend and applies its input to `input`, then advances rumble
+    /// effects by `input.delta()`.
+    ///
+    /// Call once per frame, after [`InputState::begin_frame`].
+    pub fn update(&mut self, input: &mut InputState) {
+        self.events.clear();
+        self.backend.poll(&mut self.raw);
+
+        for raw in self.raw.drain(..) {
+            let id = raw.id();
+            match raw {
+                RawGamepadEvent::Connected { guid, name, .. } => {
+                    let known = self.db.get(&guid);
+                    let mapping = known.unwrap_or(&self.fallback).clone();
+                    self.pads.insert(
+                        id,
+                        ConnectedPad {
+                            mapping,
+                            known: known.is_some(),
+                            rumble: (0.0, 0.0),
+                        },
+                    );
+                    self.events.push(GamepadEvent::Connected { id, name });
+                }
+                RawGamepadEvent::Disconnected { .. } => {
+                    self.pads.remove(&id);
+                    self.rumbles.retain(|rumble| rumble.id != id);
+                    self.events.push(GamepadEvent::Disconnected { id });
+                }
+                event => {
+                    if let Some(pad) = self.pads.get(&id) {
+                        pad.mapping.translate(&event, &mut self.events);
+                    }
+                }
+            }
+        }
+
+        for event in &self.events {
+            input.handle_gamepad_event(event);
+        }
+
+        let delta = input.delta();
+        for rumble in &mut self.rumbles {
+            rumble.remaining = rumble.remaining.saturating_sub(delta);
+        }
+        self.rumbles.retain(|rumble| !rumble.remaining.is_zero());
+        self.apply_rumble();
+    }
+
+    /// Returns the normalized events applied by the last update.
+    pub fn events(&self) -> &[GamepadEvent] {
+        &self.events
+    }
+
+    /// Returns `true` while the controller is connected.
+    pub fn is_connected(&self, id: GamepadId) -> bool {
+        self.pads.contains_key(&id)
+    }
+
+    /// Returns `true` if the controller was found in the mapping database rather
+    /// than using the standard fallback layout.
+    pub fn is_mapped(&self, id: GamepadId) -> bool {
+        self.pads.get(&id).is_some_and(|pad| pad.known)
+    }
+
+    /// Returns the mapping of a connected controller.
+    pub fn mapping(&self, id: GamepadId) -> Option<&GamepadMapping> {
+        self.pads.get(&id).map(|pad| &pad.mapping)
+    }
+
+    /// Starts a rumble effect.
+    ///
+    /// Returns `false`, doing nothing, if the controller is not connected or has
+    /// no rumble motors.
+    pub fn rumble(&mut self, id: GamepadId, effect: RumbleEffect) -> bool {
+        if !self.pads.contains_key(&id) || !self.backend.supports_rumble(id) {
+            return false;
+        }
+        if !effect.duration.is_zero() {
+            self.rumbles.push(ActiveRumble {
+                id,
+                effect,
+                remaining: effect.duration,
+            });
+            self.apply_rumble();
+        }
+        true
+    }
+
+    /// Stops every rumble effect of a controller.
+    pub fn stop_rumble(&mut self, id: GamepadId) {
+        self.rumbles.retain(|rumble| rumble.id != id);
+        self.apply_rumble();
+    }
+
+    /// Sends the combined motor speeds of every pad whose speeds changed.
+    fn apply_rumble(&mut self) {
+        for (&id, pad) in &mut self.pads {
+            let speeds = self.rumbles.iter().filter(|rumble| rumble.id == id).fold(
+                (0.0_f32, 0.0_f32),
+                |(strong, weak), rumble| {
+                    (
+                        strong.max(rumble.effect.strong),
+                        weak.max(rumble.effect.weak),
+                    )
+                },
+            );
+            if speeds != pad.rumble {
+                pad.rumble = speeds;
+                self.backend.set_rumble(id, speeds.0, speeds.1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MockGamepadBackend;
+    use crate::gamepad::{GamepadAxis, GamepadButton};
+
+    const FRAME: Duration = Duration::from_millis(10);
+    const PS4_GUID: &str = "030000004c050000c405000000010000";
+
+    fn frame(gamepads: &mut Gamepads<MockGamepadBackend>, input: &mut InputState) {
+        input.begin_frame(FRAME);
+        gamepads.update(input);
+    }
+
+    #[test]
+    fn hot_plug_keeps_slots_stable() {
+        let mut gamepads = Gamepads::new(MockGamepadBackend::new(), GamepadDb::new());
+        let mut input = InputState::new();
+
+        let first = gamepads.backend_mut().connect(PS4_GUID, "First");
+        let second = gamepads.backend_mut().connect(PS4_GUID, "Second");
+        frame(&mut gamepads, &mut input);
+        assert_eq!(input.gamepad_slot(second), Some(1));
+
+        gamepads.backend_mut().press(first, 0);
+        frame(&mut gamepads, &mut input);
+        gamepads.backend_mut().disconnect(first);
+        frame(&mut gamepads, &mut input);
+
+        let slot = &input.gamepads[0];
+        assert!(slot.just_disconnected());
+        assert!(slot.just_released(GamepadButton::South));
+        assert!(!gamepads.is_connected(first));
+
+        let third = gamepads.backend_mut().connect(PS4_GUID, "Third");
+        frame(&mut gamepads, &mut input);
+        assert_eq!(input.gamepad_slot(third), Some(0));
+        assert_eq!(input.gamepads[0].name(), "Third");
+        assert!(input.gamepads[0].just_connected());
+    }
+
+    #[test]
+    fn database_mappings_override_the_fallback() {
+        let mut db = GamepadDb::new();
+        db.load(&format!(
+            "{PS4_GUID},PS4 Controller,a:b1,b:b2,leftx:a0,lefty:a1,"
+        ));
+        let mut gamepads = Gamepads::new(MockGamepadBackend::new(), db);
+        let mut input = InputState::new();
+
+        let known = gamepads.backend_mut().connect(PS4_GUID, "PS4");
+        let unknown = gamepads
+            .backend_mut()
+            .connect("05000000000000000000000000000000", "Generic");
+        gamepads.backend_mut().press(known, 1);
+        gamepads.backend_mut().press(unknown, 1);
+        gamepads.backend_mut().move_axis(known, 1, -1.0);
+        frame(&mut gamepads, &mut input);
+
+        assert!(gamepads.is_mapped(known));
+        assert!(!gamepads.is_mapped(unknown));
+        assert!(input.gamepads[0].pressed(GamepadButton::South));
+        assert!(input.gamepads[1].pressed(GamepadButton::East));
+        assert!((input.gamepads[0].axis(GamepadAxis::LeftStickY) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn rumble_effects_combine_and_expire() {
+        let mut gamepads = Gamepads::new(MockGamepadBackend::new(), GamepadDb::new());
+        let mut input = InputState::new();
+        let id = gamepads.backend_mut().connect(PS4_GUID, "Pad");
+        frame(&mut gamepads, &mut input);
+
+        assert!(gamepads.rumble(id, RumbleEffect::new(0.8, 0.0, FRAME * 2)));
+        assert!(gamepads.rumble(id, RumbleEffect::new(0.2, 0.6, FRAME * 4)));
+        assert_eq!(gamepads.backend().rumble(id), (0.8, 0.6));
+
+        frame(&mut gamepads, &mut input);
+        frame(&mut gamepads, &mut input);
+        assert_eq!(gamepads.backend().rumble(id), (0.2, 0.6));
+
+        gamepads.stop_rumble(id);
+        assert_eq!(gamepads.backend().rumble(id), (0.0, 0.0));
+
+        gamepads.backend_mut().set_rumble_supported(id, false);
+        assert!(!gamepads.rumble(id, RumbleEffect::new(1.0, 1.0, FRAME)));
+        assert!(!gamepads.rumble(GamepadId(99), RumbleEffect::new(1.0, 1.0, FRAME)));
+    }
+}
@@ -0,0 +1,315 @@
+//! Interactions turning an action's press pattern into phased events.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// A press pattern an action reacts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Interaction {
+    /// Performs as soon as the action is pressed.
+    Press,
+    /// Performs on release if the press was short enough.
+    Tap {
+        /// Longest press that still counts as a tap.
+        max_duration: Duration,
+    },
+    /// Performs once the action has been held long enough.
+    Hold {
+        /// How long the action must be held.
+        duration: Duration,
+    },
+    /// Performs on the second press if it follows the first quickly enough.
+    DoubleTap {
+        /// Longest time between the two presses.
+        max_gap: Duration,
+    },
+    /// Performs on release if the action was held long enough.
+    ReleaseAfterHold {
+        /// Shortest hold that counts.
+        min_duration: Duration,
+    },
+    /// Performs on release with a charge level that grows while held.
+    Charge {
+        /// Shortest hold that counts; the charge starts at zero here.
+        min_duration: Duration,
+        /// Hold at which the charge is full.
+        full_duration: Duration,
+    },
+}
+
+/// The phase of an [`ActionEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionPhase {
+    /// The interaction began and may still perform or cancel.
+    Started,
+    /// The interaction completed.
+    Performed,
+    /// The interaction began but its pattern was not completed.
+    Cancelled,
+}
+
+/// An interaction phase change raised by an action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionEvent {
+    /// The action name.
+    pub action: String,
+    /// The interaction that raised the event.
+    pub interaction: Interaction,
+    /// The new phase.
+    pub phase: ActionPhase,
+    /// How long the action had been held.
+    pub held: Duration,
+    /// Charge level in 0 to 1 for [`Interaction::Charge`]; 1 for every other
+    /// performed event and 0 otherwise.
+    pub charge: f32,
+}
+
+/// Per-action state of one interaction.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InteractionTracker {
+    active: bool,
+    held: Duration,
+    since_first_tap: Duration,
+}
+
+impl InteractionTracker {
+    /// Advances the interaction by one frame and returns the phases it entered.
+    ///
+    /// `delta` is the time since the previous update; the action counts as held
+    /// for that time if it was pressed at the previous update.
+    pub(crate) fn update(
+        &mut self,
+        interaction: Interaction,
+        pressed: bool,
+        was_pressed: bool,
+        delta: Duration,
+        out: &mut Vec<(ActionPhase, Duration, f32)>,
+    ) {
+        let just_pressed = pressed && !was_pressed;
+        let just_released = !pressed && was_pressed;
+        if just_pressed {
+            self.held = Duration::ZERO;
+        } else if was_pressed {
+            self.held += delta;
+        }
+        let held = self.held;
+        let mut emit = |phase: ActionPhase, charge: f32| out.push((phase, held, charge));
+
+        match interaction {
+            Interaction::Press => {
+                if just_pressed {
+                    emit(ActionPhase::Started, 0.0);
+                    emit(ActionPhase::Performed, 1.0);
+                }
+            }
+            Interaction::Tap { max_duration } => {
+                if just_pressed {
+                    self.active = true;
+                    emit(ActionPhase::Started, 0.0);
+                } else if self.active && held > max_duration {
+                    self.active = false;
+                    emit(ActionPhase::Cancelled, 0.0);
+                } else if self.active && just_released {
+                    self.active = false;
+                    emit(ActionPhase::Performed, 1.0);
+                }
+            }
+            Interaction::Hold { duration } => {
+                if just_pressed {
+                    self.active = true;
+                    emit(ActionPhase::Started, 0.0);
+                }
+                if self.active && pressed && held >= duration {
+                    self.active = false;
+                    emit(ActionPhase::Performed, 1.0);
+                } else if self.active && just_released {
+                    self.active = false;
+                    emit(ActionPhase::Cancelled, 0.0);
+                }
+            }
+            Interaction::DoubleTap { max_gap } => {
+                if self.active {
+                    self.since_first_tap += delta;
+                    if self.since_first_tap > max_gap {
+                        self.active = false;
+                        emit(ActionPhase::Cancelled, 0.0);
+                    }
+                }
+                if just_pressed && self.active {
+                    self.active = false;
+                    emit(ActionPhase::Performed, 1.0);
+                } else if just_pressed {
+                    self.active = true;
+                    self.since_first_tap = Duration::ZERO;
+                    emit(ActionPhase::Started, 0.0);
+                }
+            }
+            Interaction::ReleaseAfterHold { min_duration } => {
+                if just_pressed {
+                    self.active = true;
+                    emit(ActionPhase::Started, 0.0);
+                } else if self.active && just_released {
+                    self.active = false;
+                    if held >= min_duration {
+                        emit(ActionPhase::Performed, 1.0);
+                    } else {
+                        emit(ActionPhase::Cancelled, 0.0);
+                    }
+                }
+            }
+            Interaction::Charge {
+                min_duration,
+                full_duration,
+            } => {
+                if just_pressed {
+                    self.active = true;
+                    emit(ActionPhase::Started, 0.0);
+                } else if self.active && just_released {
+                    self.active = false;
+                    if held >= min_duration {
+                        emit(
+                            ActionPhase::Performed,
+                            charge_level(held, min_duration, full_duration),
+                        );
+                    } else {
+                        emit(ActionPhase::Cancelled, 0.0);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the current charge of a [`Interaction::Charge`] in progress.
+    pub(crate) fn charge(&self, interaction: Interaction) -> f32 {
+        match interaction {
+            Interaction::Charge {
+                min_duration,
+                full_duration,
+            } if self.active => charge_level(self.held, min_duration, full_duration),
+            _ => 0.0,
+        }
+    }
+}
+
+fn charge_level(held: Duration, min: Duration, full: Duration) -> f32 {
+    if full <= min {
+        return 1.0;
+    }
+    let charged = held.saturating_sub(min).as_secs_f32() / full.saturating_sub(min).as_secs_f32();
+    charged.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    /// Runs an interaction over a press pattern, one entry per frame.
+    fn run(interaction: Interaction, pattern: &[bool]) -> Vec<Vec<ActionPhase>> {
+        let mut tracker = InteractionTracker::default();
+        let mut was_pressed = false;
+        pattern
+            .iter()
+            .map(|&pressed| {
+                let mut out = Vec::new();
+                tracker.update(interaction, pressed, was_pressed, FRAME, &mut out);
+                was_pressed = pressed;
+                out.into_iter().map(|(phase, _, _)| phase).collect()
+            })
+            .collect()
+    }
+
+    use ActionPhase::{Cancelled, Performed, Started};
+
+    #[test]
+    fn tap_performs_on_quick_release_and_cancels_when_held() {
+        let tap = Interaction::Tap {
+            max_duration: Duration::from_millis(200),
+        };
+
+        assert_eq!(
+            run(tap, &[true, true, false]),
+            [vec![Started], vec![], vec![Performed]]
+        );
+        assert_eq!(
+            run(tap, &[true, true, true, true, false]),
+            [vec![Started], vec![], vec![], vec![Cancelled], vec![]]
+        );
+    }
+
+    #[test]
+    fn hold_performs_once_held_long_enough() {
+        let hold = Interaction::Hold {
+            duration: Duration::from_millis(200),
+        };
+
+        assert_eq!(
+            run(hold, &[true, true, true, true, false]),
+            [vec![Started], vec![], vec![Performed], vec![], vec![]]
+        );
+        assert_eq!(run(hold, &[true, false]), [vec![Started], vec![Cancelled]]);
+    }
+
+    #[test]
+    fn double_tap_needs_a_second_press_in_time() {
+        let double_tap = Interaction::DoubleTap {
+            max_gap: Duration::from_millis(300),
+        };
+
+        assert_eq!(
+            run(double_tap, &[true, false, true, false]),
+            [vec![Started], vec![], vec![Performed], vec![]]
+        );
+        assert_eq!(
+            run(double_tap, &[true, false, false, false, false, true]),
+            [
+                vec![Started],
+                vec![],
+                vec![],
+                vec![],
+                vec![Cancelled],
+                vec![Started]
+            ]
+        );
+    }
+
+    #[test]
+    fn release_after_hold_checks_the_hold_time() {
+        let release = Interaction::ReleaseAfterHold {
+            min_duration: Duration::from_millis(200),
+        };
+
+        assert_eq!(
+            run(release, &[true, true, false]),
+            [vec![Started], vec![], vec![Performed]]
+        );
+        assert_eq!(
+            run(release, &[true, false]),
+            [vec![Started], vec![Cancelled]]
+        );
+    }
+
+    #[test]
+    fn charge_reports_its_level_on_release() {
+        let charge = Interaction::Charge {
+            min_duration: Duration::from_millis(100),
+            full_duration: Duration::from_millis(500),
+        };
+        let mut tracker = InteractionTracker::default();
+        let mut out = Vec::new();
+
+        tracker.update(charge, true, false, FRAME, &mut out);
+        for _ in 0..3 {
+            tracker.update(charge, true, true, FRAME, &mut out);
+        }
+        assert!((tracker.charge(charge) - 0.5).abs() < 1e-6);
+
+        tracker.update(charge, false, true, FRAME, &mut out);
+        let (phase, held, level) = out.last().copied().unwrap();
+        assert_eq!(phase, Performed);
+        assert_eq!(held, Duration::from_millis(400));
+        assert!((level - 0.75).abs() < 1e-6);
+    }
+}
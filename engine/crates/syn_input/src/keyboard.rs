@@ -1,22 +1,601 @@
 //! Keyboard input handling.
 
-/// Keyboard key codes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use std::borrow::Cow;
+use std::time::Duration;
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use syn_platform::{LogicalKey, Modifiers, ScanCode};
+
+use crate::button::ButtonStates;
+use crate::layout::KeyboardLayout;
+
+/// Physical keyboard keys, named after their position on a US QWERTY keyboard.
+///
+/// Each key corresponds to one USB HID usage, so a [`KeyCode`] stays the same
+/// whatever the keyboard layout: [`KeyCode::A`] is the key right of Caps Lock,
+/// which types `q` on AZERTY. Use [`KeyboardLayout`](crate::KeyboardLayout) or
+/// [`KeyboardState::key_name`] to show what the key types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum KeyCode {
     /// A key.
-    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    A,
+    /// B key.
+    B,
+    /// C key.
+    C,
+    /// D key.
+    D,
+    /// E key.
+    E,
+    /// F key.
+    F,
+    /// G key.
+    G,
+    /// H key.
+    H,
+    /// I key.
+    I,
+    /// J key.
+    J,
+    /// K key.
+    K,
+    /// L key.
+    L,
+    /// M key.
+    M,
     /// N key.
-    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
-    /// Number keys.
-    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
-    /// Function keys.
-    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
-    /// Special keys.
-    Escape, Space, Enter, Tab, Backspace,
-    /// Arrow keys.
-    Left, Right, Up, Down,
-    /// Modifier keys.
-    LShift, RShift, LCtrl, RCtrl, LAlt, RAlt,
+    N,
+    /// O key.
+    O,
+    /// P key.
+    P,
+    /// Q key.
+    Q,
+    /// R key.
+    R,
+    /// S key.
+    S,
+    /// T key.
+    T,
+    /// U key.
+    U,
+    /// V key.
+    V,
+    /// W key.
+    W,
+    /// X key.
+    X,
+    /// Y key.
+    Y,
+    /// Z key.
+    Z,
+    /// 0 key on the top row.
+    Key0,
+    /// 1 key on the top row.
+    Key1,
+    /// 2 key on the top row.
+    Key2,
+    /// 3 key on the top row.
+    Key3,
+    /// 4 key on the top row.
+    Key4,
+    /// 5 key on the top row.
+    Key5,
+    /// 6 key on the top row.
+    Key6,
+    /// 7 key on the top row.
+    Key7,
+    /// 8 key on the top row.
+    Key8,
+    /// 9 key on the top row.
+    Key9,
+    /// F1 function key.
+    F1,
+    /// F2 function key.
+    F2,
+    /// F3 function key.
+    F3,
+    /// F4 function key.
+    F4,
+    /// F5 function key.
+    F5,
+    /// F6 function key.
+    F6,
+    /// F7 function key.
+    F7,
+    /// F8 function key.
+    F8,
+    /// F9 function key.
+    F9,
+    /// F10 function key.
+    F10,
+    /// F11 function key.
+    F11,
+    /// F12 function key.
+    F12,
+    /// F13 function key.
+    F13,
+    /// F14 function key.
+    F14,
+    /// F15 function key.
+    F15,
+    /// F16 function key.
+    F16,
+    /// F17 function key.
+    F17,
+    /// F18 function key.
+    F18,
+    /// F19 function key.
+    F19,
+    /// F20 function key.
+    F20,
+    /// F21 function key.
+    F21,
+    /// F22 function key.
+    F22,
+    /// F23 function key.
+    F23,
+    /// F24 function key.
+    F24,
+    /// Escape key.
+    Escape,
+    /// Space bar.
+    Space,
+    /// Enter key.
+    Enter,
+    /// Tab key.
+    Tab,
+    /// Backspace key.
+    Backspace,
+    /// Left arrow.
+    Left,
+    /// Right arrow.
+    Right,
+    /// Up arrow.
+    Up,
+    /// Down arrow.
+    Down,
+    /// Left Shift.
+    LShift,
+    /// Right Shift.
+    RShift,
+    /// Left Control.
+    LCtrl,
+    /// Right Control.
+    RCtrl,
+    /// Left Alt.
+    LAlt,
+    /// Right Alt (`AltGr` on many layouts).
+    RAlt,
+    /// Left Super (Windows, Command).
+    LSuper,
+    /// Right Super (Windows, Command).
+    RSuper,
+    /// `-` key right of 0 on US layouts.
+    Minus,
+    /// `=` key left of Backspace on US layouts.
+    Equals,
+    /// `[` key on US layouts.
+    LBracket,
+    /// `]` key on US layouts.
+    RBracket,
+    /// `\` key above Enter on US layouts.
+    Backslash,
+    /// Key left of Enter on ISO keyboards (`#` on UK layouts).
+    NonUsHash,
+    /// `;` key on US layouts.
+    Semicolon,
+    /// `'` key on US layouts.
+    Apostrophe,
+    /// `` ` `` key left of 1 on US layouts.
+    Grave,
+    /// `,` key on US layouts.
+    Comma,
+    /// `.` key on US layouts.
+    Period,
+    /// `/` key on US layouts.
+    Slash,
+    /// Key right of Left Shift on ISO keyboards (`<` on European layouts).
+    NonUsBackslash,
+    /// Ro key on Japanese keyboards.
+    IntlRo,
+    /// Yen key on Japanese keyboards.
+    IntlYen,
+    /// Caps Lock.
+    CapsLock,
+    /// Print Screen.
+    PrintScreen,
+    /// Scroll Lock.
+    ScrollLock,
+    /// Pause / Break.
+    Pause,
+    /// Insert.
+    Insert,
+    /// Home.
+    Home,
+    /// Page Up.
+    PageUp,
+    /// Delete (forward delete).
+    Delete,
+    /// End.
+    End,
+    /// Page Down.
+    PageDown,
+    /// Context menu key.
+    Menu,
+    /// Num Lock.
+    NumLock,
+    /// Numpad `/`.
+    NumpadDivide,
+    /// Numpad `*`.
+    NumpadMultiply,
+    /// Numpad `-`.
+    NumpadSubtract,
+    /// Numpad `+`.
+    NumpadAdd,
+    /// Numpad Enter.
+    NumpadEnter,
+    /// Numpad 0.
+    Numpad0,
+    /// Numpad 1.
+    Numpad1,
+    /// Numpad 2.
+    Numpad2,
+    /// Numpad 3.
+    Numpad3,
+    /// Numpad 4.
+    Numpad4,
+    /// Numpad 5.
+    Numpad5,
+    /// Numpad 6.
+    Numpad6,
+    /// Numpad 7.
+    Numpad7,
+    /// Numpad 8.
+    Numpad8,
+    /// Numpad 9.
+    Numpad9,
+    /// Numpad `.`.
+    NumpadDecimal,
+    /// Numpad `=`.
+    NumpadEquals,
+    /// Numpad `,`.
+    NumpadComma,
+    /// Media play/pause.
+    MediaPlayPause,
+    /// Media stop.
+    MediaStop,
+    /// Media next track.
+    MediaNext,
+    /// Media previous track.
+    MediaPrevious,
+    /// Volume up.
+    VolumeUp,
+    /// Volume down.
+    VolumeDown,
+    /// Volume mute.
+    VolumeMute,
+}
+
+impl KeyCode {
+    /// Every key with its HID usage, in declaration order.
+    const HID_USAGES: [(KeyCode, ScanCode); 129] = [
+        (KeyCode::A, ScanCode::keyboard(0x04)),
+        (KeyCode::B, ScanCode::keyboard(0x05)),
+        (KeyCode::C, ScanCode::keyboard(0x06)),
+        (KeyCode::D, ScanCode::keyboard(0x07)),
+        (KeyCode::E, ScanCode::keyboard(0x08)),
+        (KeyCode::F, ScanCode::keyboard(0x09)),
+        (KeyCode::G, ScanCode::keyboard(0x0A)),
+        (KeyCode::H, ScanCode::keyboard(0x0B)),
+        (KeyCode::I, ScanCode::keyboard(0x0C)),
+        (KeyCode::J, ScanCode::keyboard(0x0D)),
+        (KeyCode::K, ScanCode::keyboard(0x0E)),
+        (KeyCode::L, ScanCode::keyboard(0x0F)),
+        (KeyCode::M, ScanCode::keyboard(0x10)),
+        (KeyCode::N, ScanCode::keyboard(0x11)),
+        (KeyCode::O, ScanCode::keyboard(0x12)),
+        (KeyCode::P, ScanCode::keyboard(0x13)),
+        (KeyCode::Q, ScanCode::keyboard(0x14)),
+        (KeyCode::R, ScanCode::keyboard(0x15)),
+        (KeyCode::S, ScanCode::keyboard(0x16)),
+        (KeyCode::T, ScanCode::keyboard(0x17)),
+        (KeyCode::U, ScanCode::keyboard(0x18)),
+        (KeyCode::V, ScanCode::keyboard(0x19)),
+        (KeyCode::W, ScanCode::keyboard(0x1A)),
+        (KeyCode::X, ScanCode::keyboard(0x1B)),
+        (KeyCode::Y, ScanCode::keyboard(0x1C)),
+        (KeyCode::Z, ScanCode::keyboard(0x1D)),
+        (KeyCode::Key0, ScanCode::keyboard(0x27)),
+        (KeyCode::Key1, ScanCode::keyboard(0x1E)),
+        (KeyCode::Key2, ScanCode::keyboard(0x1F)),
+        (KeyCode::Key3, ScanCode::keyboard(0x20)),
+        (KeyCode::Key4, ScanCode::keyboard(0x21)),
+        (KeyCode::Key5, ScanCode::keyboard(0x22)),
+        (KeyCode::Key6, ScanCode::keyboard(0x23)),
+        (KeyCode::Key7, ScanCode::keyboard(0x24)),
+        (KeyCode::Key8, ScanCode::keyboard(0x25)),
+        (KeyCode::Key9, ScanCode::keyboard(0x26)),
+        (KeyCode::F1, ScanCode::keyboard(0x3A)),
+        (KeyCode::F2, ScanCode::keyboard(0x3B)),
+        (KeyCode::F3, ScanCode::keyboard(0x3C)),
+        (KeyCode::F4, ScanCode::keyboard(0x3D)),
+        (KeyCode::F5, ScanCode::keyboard(0x3E)),
+        (KeyCode::F6, ScanCode::keyboard(0x3F)),
+        (KeyCode::F7, ScanCode::keyboard(0x40)),
+        (KeyCode::F8, ScanCode::keyboard(0x41)),
+        (KeyCode::F9, ScanCode::keyboard(0x42)),
+        (KeyCode::F10, ScanCode::keyboard(0x43)),
+        (KeyCode::F11, ScanCode::keyboard(0x44)),
+        (KeyCode::F12, ScanCode::keyboard(0x45)),
+        (KeyCode::F13, ScanCode::keyboard(0x68)),
+        (KeyCode::F14, ScanCode::keyboard(0x69)),
+        (KeyCode::F15, ScanCode::keyboard(0x6A)),
+        (KeyCode::F16, ScanCode::keyboard(0x6B)),
+        (KeyCode::F17, ScanCode::keyboard(0x6C)),
+        (KeyCode::F18, ScanCode::keyboard(0x6D)),
+        (KeyCode::F19, ScanCode::keyboard(0x6E)),
+        (KeyCode::F20, ScanCode::keyboard(0x6F)),
+        (KeyCode::F21, ScanCode::keyboard(0x70)),
+        (KeyCode::F22, ScanCode::keyboard(0x71)),
+        (KeyCode::F23, ScanCode::keyboard(0x72)),
+        (KeyCode::F24, ScanCode::keyboard(0x73)),
+        (KeyCode::Escape, ScanCode::keyboard(0x29)),
+        (KeyCode::Space, ScanCode::keyboard(0x2C)),
+        (KeyCode::Enter, ScanCode::keyboard(0x28)),
+        (KeyCode::Tab, ScanCode::keyboard(0x2B)),
+        (KeyCode::Backspace, ScanCode::keyboard(0x2A)),
+        (KeyCode::Left, ScanCode::keyboard(0x50)),
+        (KeyCode::Right, ScanCode::keyboard(0x4F)),
+        (KeyCode::Up, ScanCode::keyboard(0x52)),
+        (KeyCode::Down, ScanCode::keyboard(0x51)),
+        (KeyCode::LShift, ScanCode::keyboard(0xE1)),
+        (KeyCode::RShift, ScanCode::keyboard(0xE5)),
+        (KeyCode::LCtrl, ScanCode::keyboard(0xE0)),
+        (KeyCode::RCtrl, ScanCode::keyboard(0xE4)),
+        (KeyCode::LAlt, ScanCode::keyboard(0xE2)),
+        (KeyCode::RAlt, ScanCode::keyboard(0xE6)),
+        (KeyCode::LSuper, ScanCode::keyboard(0xE3)),
+        (KeyCode::RSuper, ScanCode::keyboard(0xE7)),
+        (KeyCode::Minus, ScanCode::keyboard(0x2D)),
+        (KeyCode::Equals, ScanCode::keyboard(0x2E)),
+        (KeyCode::LBracket, ScanCode::keyboard(0x2F)),
+        (KeyCode::RBracket, ScanCode::keyboard(0x30)),
+        (KeyCode::Backslash, ScanCode::keyboard(0x31)),
+        (KeyCode::NonUsHash, ScanCode::keyboard(0x32)),
+        (KeyCode::Semicolon, ScanCode::keyboard(0x33)),
+        (KeyCode::Apostrophe, ScanCode::keyboard(0x34)),
+        (KeyCode::Grave, ScanCode::keyboard(0x35)),
+        (KeyCode::Comma, ScanCode::keyboard(0x36)),
+        (KeyCode::Period, ScanCode::keyboard(0x37)),
+        (KeyCode::Slash, ScanCode::keyboard(0x38)),
+        (KeyCode::NonUsBackslash, ScanCode::keyboard(0x64)),
+        (KeyCode::IntlRo, ScanCode::keyboard(0x87)),
+        (KeyCode::IntlYen, ScanCode::keyboard(0x89)),
+        (KeyCode::CapsLock, ScanCode::keyboard(0x39)),
+        (KeyCode::PrintScreen, ScanCode::keyboard(0x46)),
+        (KeyCode::ScrollLock, ScanCode::keyboard(0x47)),
+        (KeyCode::Pause, ScanCode::keyboard(0x48)),
+        (KeyCode::Insert, ScanCode::keyboard(0x49)),
+        (KeyCode::Home, ScanCode::keyboard(0x4A)),
+        (KeyCode::PageUp, ScanCode::keyboard(0x4B)),
+        (KeyCode::Delete, ScanCode::keyboard(0x4C)),
+        (KeyCode::End, ScanCode::keyboard(0x4D)),
+        (KeyCode::PageDown, ScanCode::keyboard(0x4E)),
+        (KeyCode::Menu, ScanCode::keyboard(0x65)),
+        (KeyCode::NumLock, ScanCode::keyboard(0x53)),
+        (KeyCode::NumpadDivide, ScanCode::keyboard(0x54)),
+        (KeyCode::NumpadMultiply, ScanCode::keyboard(0x55)),
+        (KeyCode::NumpadSubtract, ScanCode::keyboard(0x56)),
+        (KeyCode::NumpadAdd, ScanCode::keyboard(0x57)),
+        (KeyCode::NumpadEnter, ScanCode::keyboard(0x58)),
+        (KeyCode::Numpad0, ScanCode::keyboard(0x62)),
+        (KeyCode::Numpad1, ScanCode::keyboard(0x59)),
+        (KeyCode::Numpad2, ScanCode::keyboard(0x5A)),
+        (KeyCode::Numpad3, ScanCode::keyboard(0x5B)),
+        (KeyCode::Numpad4, ScanCode::keyboard(0x5C)),
+        (KeyCode::Numpad5, ScanCode::keyboard(0x5D)),
+        (KeyCode::Numpad6, ScanCode::keyboard(0x5E)),
+        (KeyCode::Numpad7, ScanCode::keyboard(0x5F)),
+        (KeyCode::Numpad8, ScanCode::keyboard(0x60)),
+        (KeyCode::Numpad9, ScanCode::keyboard(0x61)),
+        (KeyCode::NumpadDecimal, ScanCode::keyboard(0x63)),
+        (KeyCode::NumpadEquals, ScanCode::keyboard(0x67)),
+        (KeyCode::NumpadComma, ScanCode::keyboard(0x85)),
+        (KeyCode::MediaPlayPause, ScanCode::consumer(0xCD)),
+        (KeyCode::MediaStop, ScanCode::consumer(0xB7)),
+        (KeyCode::MediaNext, ScanCode::consumer(0xB5)),
+        (KeyCode::MediaPrevious, ScanCode::consumer(0xB6)),
+        (KeyCode::VolumeUp, ScanCode::keyboard(0x80)),
+        (KeyCode::VolumeDown, ScanCode::keyboard(0x81)),
+        (KeyCode::VolumeMute, ScanCode::keyboard(0x7F)),
+    ];
+
+    /// Volume keys some platforms report on the consumer page instead.
+    const CONSUMER_ALIASES: [(KeyCode, ScanCode); 3] = [
+        (KeyCode::VolumeUp, ScanCode::consumer(0xE9)),
+        (KeyCode::VolumeDown, ScanCode::consumer(0xEA)),
+        (KeyCode::VolumeMute, ScanCode::consumer(0xE2)),
+    ];
+
+    /// Returns every key, in declaration order.
+    pub fn all() -> impl Iterator<Item = KeyCode> {
+        Self::HID_USAGES.iter().map(|(key, _)| *key)
+    }
+
+    /// Returns the key at a physical location, if it has a [`KeyCode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use syn_input::KeyCode;
+    /// use syn_platform::ScanCode;
+    ///
+    /// assert_eq!(KeyCode::from_scan_code(ScanCode::keyboard(0x1A)), Some(KeyCode::W));
+    /// assert_eq!(KeyCode::from_scan_code(ScanCode::consumer(0xCD)), Some(KeyCode::MediaPlayPause));
+    /// ```
+    pub fn from_scan_code(code: ScanCode) -> Option<Self> {
+        Self::HID_USAGES
+            .iter()
+            .chain(&Self::CONSUMER_ALIASES)
+            .find(|(_, usage)| *usage == code)
+            .map(|(key, _)| *key)
+    }
+
+    /// Returns the HID usage of the key.
+    pub fn scan_code(self) -> ScanCode {
+        Self::HID_USAGES
+            .iter()
+            .find(|(key, _)| *key == self)
+            .map_or(ScanCode::UNIDENTIFIED, |(_, usage)| *usage)
+    }
+
+    /// Returns the label of the key on a US QWERTY keyboard.
+    ///
+    /// For a label matching the player's layout, use
+    /// [`KeyboardLayout::key_name`](crate::KeyboardLayout::key_name).
+    // RATIONALE: one arm per key reads better than a table split across helpers
+    #[allow(clippy::too_many_lines)]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::B => "B",
+            Self::C => "C",
+            Self::D => "D",
+            Self::E => "E",
+            Self::F => "F",
+            Self::G => "G",
+            Self::H => "H",
+            Self::I => "I",
+            Self::J => "J",
+            Self::K => "K",
+            Self::L => "L",
+            Self::M => "M",
+            Self::N => "N",
+            Self::O => "O",
+            Self::P => "P",
+            Self::Q => "Q",
+            Self::R => "R",
+            Self::S => "S",
+            Self::T => "T",
+            Self::U => "U",
+            Self::V => "V",
+            Self::W => "W",
+            Self::X => "X",
+            Self::Y => "Y",
+            Self::Z => "Z",
+            Self::Key0 => "0",
+            Self::Key1 => "1",
+            Self::Key2 => "2",
+            Self::Key3 => "3",
+            Self::Key4 => "4",
+            Self::Key5 => "5",
+            Self::Key6 => "6",
+            Self::Key7 => "7",
+            Self::Key8 => "8",
+            Self::Key9 => "9",
+            Self::F1 => "F1",
+            Self::F2 => "F2",
+            Self::F3 => "F3",
+            Self::F4 => "F4",
+            Self::F5 => "F5",
+            Self::F6 => "F6",
+            Self::F7 => "F7",
+            Self::F8 => "F8",
+            Self::F9 => "F9",
+            Self::F10 => "F10",
+            Self::F11 => "F11",
+            Self::F12 => "F12",
+            Self::F13 => "F13",
+            Self::F14 => "F14",
+            Self::F15 => "F15",
+            Self::F16 => "F16",
+            Self::F17 => "F17",
+            Self::F18 => "F18",
+            Self::F19 => "F19",
+            Self::F20 => "F20",
+            Self::F21 => "F21",
+            Self::F22 => "F22",
+            Self::F23 => "F23",
+            Self::F24 => "F24",
+            Self::Escape => "Escape",
+            Self::Space => "Space",
+            Self::Enter => "Enter",
+            Self::Tab => "Tab",
+            Self::Backspace => "Backspace",
+            Self::Left => "Left",
+            Self::Right => "Right",
+            Self::Up => "Up",
+            Self::Down => "Down",
+            Self::LShift => "Left Shift",
+            Self::RShift => "Right Shift",
+            Self::LCtrl => "Left Ctrl",
+            Self::RCtrl => "Right Ctrl",
+            Self::LAlt => "Left Alt",
+            Self::RAlt => "Right Alt",
+            Self::LSuper => "Left Super",
+            Self::RSuper => "Right Super",
+            Self::Minus => "-",
+            Self::Equals => "=",
+            Self::LBracket => "[",
+            Self::RBracket => "]",
+            Self::Backslash => "\\",
+            Self::NonUsHash => "#",
+            Self::Semicolon => ";",
+            Self::Apostrophe => "'",
+            Self::Grave => "`",
+            Self::Comma => ",",
+            Self::Period => ".",
+            Self::Slash => "/",
+            Self::NonUsBackslash => "<",
+            Self::IntlRo => "Ro",
+            Self::IntlYen => "Yen",
+            Self::CapsLock => "Caps Lock",
+            Self::PrintScreen => "Print Screen",
+            Self::ScrollLock => "Scroll Lock",
+            Self::Pause => "Pause",
+            Self::Insert => "Insert",
+            Self::Home => "Home",
+            Self::PageUp => "Page Up",
+            Self::Delete => "Delete",
+            Self::End => "End",
+            Self::PageDown => "Page Down",
+            Self::Menu => "Menu",
+            Self::NumLock => "Num Lock",
+            Self::NumpadDivide => "Numpad /",
+            Self::NumpadMultiply => "Numpad *",
+            Self::NumpadSubtract => "Numpad -",
+            Self::NumpadAdd => "Numpad +",
+            Self::NumpadEnter => "Numpad Enter",
+            Self::Numpad0 => "Numpad 0",
+            Self::Numpad1 => "Numpad 1",
+            Self::Numpad2 => "Numpad 2",
+            Self::Numpad3 => "Numpad 3",
+            Self::Numpad4 => "Numpad 4",
+            Self::Numpad5 => "Numpad 5",
+            Self::Numpad6 => "Numpad 6",
+            Self::Numpad7 => "Numpad 7",
+            Self::Numpad8 => "Numpad 8",
+            Self::Numpad9 => "Numpad 9",
+            Self::NumpadDecimal => "Numpad .",
+            Self::NumpadEquals => "Numpad =",
+            Self::NumpadComma => "Numpad ,",
+            Self::MediaPlayPause => "Play/Pause",
+            Self::MediaStop => "Stop",
+            Self::MediaNext => "Next Track",
+            Self::MediaPrevious => "Previous Track",
+            Self::VolumeUp => "Volume Up",
+            Self::VolumeDown => "Volume Down",
+            Self::VolumeMute => "Mute",
+        }
+    }
+
+    /// Returns `true` for keys on the numeric keypad.
+    pub fn is_numpad(self) -> bool {
+        matches!(self.scan_code().usage(), 0x54..=0x63 | 0x67 | 0x85)
+            && self.scan_code().page() == ScanCode::KEYBOARD_PAGE
+    }
 }
 
 /// The state of a key.
@@ -31,3 +610,246 @@ pub enum KeyState {
     /// Key was just released this frame.
     JustReleased,
 }
+
+/// Keyboard input state for the current frame.
+///
+/// Keys are tracked physically, by [`KeyCode`]. The logical view —
+/// [`character`](Self::character), [`key_name`](Self::key_name) and
+/// [`character_pressed`](Self::character_pressed) — uses the characters the
+/// platform reported for each key, falling back to the configured
+/// [`KeyboardLayout`] for keys not pressed yet.
+#[derive(Debug, Clone, Default)]
+pub struct KeyboardState {
+    keys: ButtonStates<KeyCode>,
+    modifiers: Modifiers,
+    text: String,
+    layout: KeyboardLayout,
+    learned: HashMap<KeyCode, char>,
+}
+
+impl KeyboardState {
+    /// Returns the state of a key this frame.
+    pub fn state(&self, key: KeyCode) -> KeyState {
+        self.keys.state(key)
+    }
+
+    /// Returns `true` while the key is held down.
+    pub fn pressed(&self, key: KeyCode) -> bool {
+        self.keys.pressed(key)
+    }
+
+    /// Returns `true` if the key went down this frame.
+    pub fn just_pressed(&self, key: KeyCode) -> bool {
+        self.keys.just_pressed(key)
+    }
+
+    /// Returns `true` if the key went up this frame.
+    pub fn just_released(&self, key: KeyCode) -> bool {
+        self.keys.just_released(key)
+    }
+
+    /// Returns how long the key has been held, or zero if it is up.
+    pub fn held_duration(&self, key: KeyCode) -> Duration {
+        self.keys.held_duration(key)
+    }
+
+    /// Returns the keys currently held down.
+    pub fn pressed_keys(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.keys.iter_pressed()
+    }
+
+    /// Returns the current modifier state.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Returns the text typed this frame.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the layout used for keys the platform has not reported a
+    /// character for.
+    pub fn layout(&self) -> KeyboardLayout {
+        self.layout
+    }
+
+    /// Sets the layout used for keys the platform has not reported a character
+    /// for.
+    pub fn set_layout(&mut self, layout: KeyboardLayout) {
+        self.layout = layout;
+    }
+
+    /// Returns the character a key types without modifiers.
+    pub fn character(&self, key: KeyCode) -> Option<char> {
+        self.learned
+            .get(&key)
+            .copied()
+            .or_else(|| self.layout.character(key))
+    }
+
+    /// Returns the key that types a character without modifiers.
+    ///
+    /// Letters match regardless of case.
+    pub fn key_for_character(&self, character: char) -> Option<KeyCode> {
+        let wanted = character.to_lowercase().next().unwrap_or(character);
+        self.learned
+            .iter()
+            .find(|(key, learned)| **learned == wanted && !key.is_numpad())
+            .map(|(key, _)| *key)
+            .or_else(|| {
+                self.layout
+                    .key_for_character(wanted)
+                    .filter(|key| !self.learned.contains_key(key))
+            })
+    }
+
+    /// Returns the label to show for a key in the player's layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use syn_input::{KeyCode, KeyboardLayout, KeyboardState};
+    ///
+    /// let mut keyboard = KeyboardState::default();
+    /// keyboard.set_layout(KeyboardLayout::Qwertz);
+    ///
+    /// assert_eq!(keyboard.key_name(KeyCode::Z), "Y");
+    /// assert_eq!(keyboard.key_name(KeyCode::LShift), "Left Shift");
+    /// ```
+    pub fn key_name(&self, key: KeyCode) -> Cow<'static, str> {
+        match self.learned.get(&key) {
+            Some(character) if !key.is_numpad() => Cow::Owned(character.to_uppercase().collect()),
+            _ => self.layout.key_name(key),
+        }
+    }
+
+    /// Returns `true` while the key typing `character` is held down.
+    pub fn character_pressed(&self, character: char) -> bool {
+        self.key_for_character(character)
+            .is_some_and(|key| self.pressed(key))
+    }
+
+    /// Returns `true` if the key typing `character` went down this frame.
+    pub fn character_just_pressed(&self, character: char) -> bool {
+        self.key_for_character(character)
+            .is_some_and(|key| self.just_pressed(key))
+    }
+
+    /// Returns the underlying key states.
+    pub fn keys(&self) -> &ButtonStates<KeyCode> {
+        &self.keys
+    }
+
+    /// Returns the underlying key states mutably, to inject synthetic input.
+    pub fn keys_mut(&mut self) -> &mut ButtonStates<KeyCode> {
+        &mut self.keys
+    }
+
+    /// Advances to a new frame that lasted `delta`.
+    pub(crate) fn begin_frame(&mut self, delta: Duration) {
+        self.keys.begin_frame(delta);
+        self.text.clear();
+    }
+
+    pub(crate) fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    pub(crate) fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    /// Records the character the platform reported for a key pressed without
+    /// modifiers.
+    pub(crate) fn learn(&mut self, key: KeyCode, logical_key: &LogicalKey) {
+        if !self.modifiers.is_empty() {
+            return;
+        }
+        let character = match logical_key {
+            LogicalKey::Character(text) => {
+                let mut chars = text.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(character), None) => character,
+                    _ => return,
+                }
+            }
+            LogicalKey::Dead(Some(character)) => *character,
+            LogicalKey::Named(_) | LogicalKey::Dead(None) | LogicalKey::Unidentified => return,
+        };
+        let character = character.to_lowercase().next().unwrap_or(character);
+        self.learned.insert(key, character);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_codes_map_to_keys() {
+        assert_eq!(
+            KeyCode::from_scan_code(ScanCode::keyboard(0x04)),
+            Some(KeyCode::A)
+        );
+        assert_eq!(
+            KeyCode::from_scan_code(ScanCode::keyboard(0x1D)),
+            Some(KeyCode::Z)
+        );
+        assert_eq!(
+            KeyCode::from_scan_code(ScanCode::keyboard(0x27)),
+            Some(KeyCode::Key0)
+        );
+        assert_eq!(
+            KeyCode::from_scan_code(ScanCode::keyboard(0x45)),
+            Some(KeyCode::F12)
+        );
+        assert_eq!(
+            KeyCode::from_scan_code(ScanCode::keyboard(0xE5)),
+            Some(KeyCode::RShift)
+        );
+        assert_eq!(KeyCode::from_scan_code(ScanCode::consumer(0x04)), None);
+        assert_eq!(KeyCode::from_scan_code(ScanCode::UNIDENTIFIED), None);
+    }
+
+    #[test]
+    fn every_key_round_trips_through_its_scan_code() {
+        for key in KeyCode::all() {
+            assert!(key.scan_code().is_identified(), "{key:?}");
+            assert_eq!(KeyCode::from_scan_code(key.scan_code()), Some(key));
+        }
+        assert_eq!(
+            KeyCode::from_scan_code(ScanCode::keyboard(0x4B)),
+            Some(KeyCode::PageUp)
+        );
+        assert_eq!(
+            KeyCode::from_scan_code(ScanCode::consumer(0xE9)),
+            Some(KeyCode::VolumeUp)
+        );
+        assert!(KeyCode::Numpad0.is_numpad());
+        assert!(!KeyCode::Key0.is_numpad());
+    }
+
+    #[test]
+    fn reported_characters_override_the_layout() {
+        let mut keyboard = KeyboardState::default();
+        keyboard.learn(KeyCode::Q, &LogicalKey::Character("a".into()));
+        keyboard.learn(KeyCode::LBracket, &LogicalKey::Dead(Some('^')));
+
+        assert_eq!(keyboard.key_name(KeyCode::Q), "A");
+        assert_eq!(keyboard.key_for_character('A'), Some(KeyCode::Q));
+        assert_eq!(keyboard.key_for_character('^'), Some(KeyCode::LBracket));
+        assert_eq!(keyboard.key_for_character('q'), None);
+        assert_eq!(keyboard.key_name(KeyCode::W), "W");
+
+        keyboard.keys_mut().press(KeyCode::Q);
+        assert!(keyboard.character_just_pressed('a'));
+
+        keyboard.set_modifiers(Modifiers {
+            shift: true,
+            ..Modifiers::default()
+        });
+        keyboard.learn(KeyCode::Key1, &LogicalKey::Character("!".into()));
+        assert_eq!(keyboard.character(KeyCode::Key1), Some('1'));
+    }
+}
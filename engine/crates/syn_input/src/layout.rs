@@ -0,0 +1,215 @@
+//! Keyboard layouts.
+//!
+//! [`KeyCode`]s name physical keys. A [`KeyboardLayout`] tells which character
+//! each key types, so rebinding UIs can show `Z` for the key a French player
+//! presses to move forward with [`KeyCode::W`].
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard::KeyCode;
+
+/// A keyboard layout, mapping physical keys to the characters they type.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::{KeyCode, KeyboardLayout};
+///
+/// let layout = KeyboardLayout::Azerty;
+///
+/// assert_eq!(layout.character(KeyCode::Q), Some('a'));
+/// assert_eq!(layout.key_for_character('Z'), Some(KeyCode::W));
+/// assert_eq!(layout.key_name(KeyCode::W), "Z");
+/// assert_eq!(layout.key_name(KeyCode::PageUp), "Page Up");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyboardLayout {
+    /// US QWERTY.
+    #[default]
+    Qwerty,
+    /// French AZERTY.
+    Azerty,
+    /// German QWERTZ.
+    Qwertz,
+}
+
+/// Unshifted characters of the AZERTY keys that differ from US QWERTY.
+const AZERTY: [(KeyCode, char); 26] = [
+    (KeyCode::A, 'q'),
+    (KeyCode::Q, 'a'),
+    (KeyCode::W, 'z'),
+    (KeyCode::Z, 'w'),
+    (KeyCode::M, ','),
+    (KeyCode::Semicolon, 'm'),
+    (KeyCode::Comma, ';'),
+    (KeyCode::Period, ':'),
+    (KeyCode::Slash, '!'),
+    (KeyCode::Key1, '&'),
+    (KeyCode::Key2, 'é'),
+    (KeyCode::Key3, '"'),
+    (KeyCode::Key4, '\''),
+    (KeyCode::Key5, '('),
+    (KeyCode::Key6, '-'),
+    (KeyCode::Key7, 'è'),
+    (KeyCode::Key8, '_'),
+    (KeyCode::Key9, 'ç'),
+    (KeyCode::Key0, 'à'),
+    (KeyCode::Minus, ')'),
+    (KeyCode::LBracket, '^'),
+    (KeyCode::RBracket, '$'),
+    (KeyCode::Apostrophe, 'ù'),
+    (KeyCode::Grave, '²'),
+    (KeyCode::NonUsHash, '*'),
+    (KeyCode::NonUsBackslash, '<'),
+];
+
+/// Unshifted characters of the QWERTZ keys that differ from US QWERTY.
+const QWERTZ: [(KeyCode, char); 12] = [
+    (KeyCode::Y, 'z'),
+    (KeyCode::Z, 'y'),
+    (KeyCode::Minus, 'ß'),
+    (KeyCode::Equals, '´'),
+    (KeyCode::LBracket, 'ü'),
+    (KeyCode::RBracket, '+'),
+    (KeyCode::Semicolon, 'ö'),
+    (KeyCode::Apostrophe, 'ä'),
+    (KeyCode::Grave, '^'),
+    (KeyCode::NonUsHash, '#'),
+    (KeyCode::Slash, '-'),
+    (KeyCode::NonUsBackslash, '<'),
+];
+
+impl KeyboardLayout {
+    /// Returns the character a key types without modifiers, or `None` for keys
+    /// that do not type text on their own, such as Shift or the arrows.
+    ///
+    /// Letters are lowercase. Dead keys, such as `^` on AZERTY, return the
+    /// accent they add.
+    pub fn character(self, key: KeyCode) -> Option<char> {
+        let overrides: &[(KeyCode, char)] = match self {
+            Self::Qwerty => &[],
+            Self::Azerty => &AZERTY,
+            Self::Qwertz => &QWERTZ,
+        };
+        overrides
+            .iter()
+            .find(|(overridden, _)| *overridden == key)
+            .map(|(_, character)| *character)
+            .or_else(|| qwerty_character(key))
+    }
+
+    /// Returns the key that types a character without modifiers.
+    ///
+    /// Letters match regardless of case. Numpad keys are never returned.
+    pub fn key_for_character(self, character: char) -> Option<KeyCode> {
+        let wanted = character.to_lowercase().next().unwrap_or(character);
+        KeyCode::all()
+            .filter(|key| !key.is_numpad())
+            .find(|key| self.character(*key) == Some(wanted))
+    }
+
+    /// Returns the label to show for a key: the uppercase character it types, or
+    /// its name for keys that do not type text.
+    pub fn key_name(self, key: KeyCode) -> Cow<'static, str> {
+        if key.is_numpad() {
+            return Cow::Borrowed(key.name());
+        }
+        match self.character(key) {
+            Some(' ') | None => Cow::Borrowed(key.name()),
+            Some(character) => Cow::Owned(character.to_uppercase().collect()),
+        }
+    }
+}
+
+/// Returns the unshifted US QWERTY character of a key.
+fn qwerty_character(key: KeyCode) -> Option<char> {
+    let character = match key {
+        KeyCode::Space => ' ',
+        KeyCode::Minus | KeyCode::NumpadSubtract => '-',
+        KeyCode::Equals | KeyCode::NumpadEquals => '=',
+        KeyCode::LBracket => '[',
+        KeyCode::RBracket => ']',
+        KeyCode::Backslash | KeyCode::NonUsBackslash => '\\',
+        KeyCode::NonUsHash => '#',
+        KeyCode::Semicolon => ';',
+        KeyCode::Apostrophe => '\'',
+        KeyCode::Grave => '`',
+        KeyCode::Comma | KeyCode::NumpadComma => ',',
+        KeyCode::Period | KeyCode::NumpadDecimal => '.',
+        KeyCode::Slash | KeyCode::NumpadDivide => '/',
+        KeyCode::NumpadMultiply => '*',
+        KeyCode::NumpadAdd => '+',
+        _ => {
+            // Letters and digits are named after the character they type.
+            let name = key.name();
+            let name = name.strip_prefix("Numpad ").unwrap_or(name);
+            let mut chars = name.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(character), None) if character.is_ascii_alphanumeric() => {
+                    Some(character.to_ascii_lowercase())
+                }
+                _ => None,
+            };
+        }
+    };
+    Some(character)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts_move_letters_and_punctuation() {
+        assert_eq!(KeyboardLayout::Qwerty.character(KeyCode::Q), Some('q'));
+        assert_eq!(KeyboardLayout::Qwerty.character(KeyCode::Key1), Some('1'));
+        assert_eq!(KeyboardLayout::Qwerty.character(KeyCode::F1), None);
+        assert_eq!(KeyboardLayout::Qwerty.character(KeyCode::LShift), None);
+
+        assert_eq!(
+            KeyboardLayout::Azerty.key_for_character('a'),
+            Some(KeyCode::Q)
+        );
+        assert_eq!(
+            KeyboardLayout::Azerty.key_for_character('m'),
+            Some(KeyCode::Semicolon)
+        );
+        assert_eq!(KeyboardLayout::Azerty.key_for_character('1'), None);
+        assert_eq!(
+            KeyboardLayout::Qwertz.key_for_character('y'),
+            Some(KeyCode::Z)
+        );
+        assert_eq!(
+            KeyboardLayout::Qwertz.key_for_character('Ö'),
+            Some(KeyCode::Semicolon)
+        );
+    }
+
+    #[test]
+    fn key_names_follow_the_layout() {
+        assert_eq!(KeyboardLayout::Qwerty.key_name(KeyCode::W), "W");
+        assert_eq!(KeyboardLayout::Azerty.key_name(KeyCode::W), "Z");
+        assert_eq!(KeyboardLayout::Azerty.key_name(KeyCode::Key2), "É");
+        assert_eq!(KeyboardLayout::Qwertz.key_name(KeyCode::Z), "Y");
+        assert_eq!(KeyboardLayout::Qwertz.key_name(KeyCode::Space), "Space");
+        assert_eq!(
+            KeyboardLayout::Qwerty.key_name(KeyCode::NonUsBackslash),
+            "\\"
+        );
+        assert_eq!(
+            KeyboardLayout::Azerty.key_name(KeyCode::NonUsBackslash),
+            "<"
+        );
+        assert_eq!(
+            KeyboardLayout::Qwertz.key_name(KeyCode::NonUsBackslash),
+            "<"
+        );
+        assert_eq!(
+            KeyboardLayout::Qwertz.key_name(KeyCode::Numpad7),
+            "Numpad 7"
+        );
+        assert_eq!(KeyboardLayout::Azerty.key_name(KeyCode::F5), "F5");
+    }
+}
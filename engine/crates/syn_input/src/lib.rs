@@ -1 +1,71 @@
-//! `syn_input` - TODO: Implement
+//! `syn_input` - Keyboard, mouse and gamepad input for Synarion Engine.
+//!
+//! This crate turns platform events into per-frame input state:
+//! - [`InputState`] - Keyboard, mouse and gamepad state fed from
+//!   [`syn_platform::Event`]s
+//! - [`ButtonStates`] - Edge-triggered button tracking with held durations
+//! - [`KeyCode`] - Physical keyboard keys covering the USB HID keyboard page
+//! - [`KeyboardLayout`] - AZERTY/QWERTZ/QWERTY character view and layout-aware
+//!   key names for rebinding UIs
+//! - [`ActionMap`] - Named button, axis and vector actions evaluated each frame
+//! - [`InputContextStack`] - Prioritised input contexts that consume inputs from
+//!   lower ones
+//! - [`InputProfile`] / [`InputProfiles`] - RON/TOML binding profiles with per-device
+//!   defaults and player overrides
+//! - [`RebindListener`] - Captures the next input to rebind an action
+//! - [`Interaction`] - Tap, hold, double-tap, release-after-hold and charge
+//!   patterns raising phased [`ActionEvent`]s
+//! - [`InputBuffer`] / [`ComboMatcher`] - Time-stamped input history with
+//!   buffered presses and motion/combo [`Sequence`]s
+//! - [`Gamepads`] - Gamepad hot-plug, SDL `gamecontrollerdb.txt` mappings
+//!   ([`GamepadDb`]) and rumble over a [`GamepadBackend`]
+//! - [`InputBinding`] - Keys, buttons, gamepad axes, composites, chords and mouse
+//!   motion/scroll bound to actions
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+mod action;
+mod backend;
+mod binding;
+mod buffer;
+mod button;
+mod combo;
+mod context;
+mod error;
+mod gamepad;
+mod gamepads;
+mod interaction;
+mod keyboard;
+mod layout;
+mod mapping;
+mod mouse;
+mod profile;
+mod rebind;
+mod state;
+mod value;
+
+pub use action::{Action, ActionMap};
+pub use backend::{GamepadBackend, MockGamepadBackend, RawGamepadEvent};
+pub use binding::{
+    AxisResponse, DeviceKind, InputBinding, InputSource, Modifier, ResponseCurve, Stick,
+};
+pub use buffer::{BufferedInput, Direction, Facing, InputBuffer, InputToken};
+pub use button::ButtonStates;
+pub use combo::{ComboMatcher, Sequence, SequenceStep};
+pub use context::{ActionRef, BindingConflict, InputContext, InputContextStack};
+pub use error::{MappingError, ProfileError, ProfileResult};
+pub use gamepad::{GamepadAxis, GamepadButton, GamepadEvent, GamepadId, GamepadState};
+pub use gamepads::{Gamepads, RumbleEffect};
+pub use interaction::{ActionEvent, ActionPhase, Interaction};
+pub use keyboard::{KeyCode, KeyState, KeyboardState};
+pub use layout::KeyboardLayout;
+pub use mapping::{AxisRange, GamepadDb, GamepadMapping, MappingSource, MappingTarget};
+pub use mouse::{MouseButton, MouseState, PIXELS_PER_LINE};
+pub use profile::{InputProfile, InputProfiles};
+pub use rebind::{RebindListener, RebindOutcome};
+pub use state::InputState;
+pub use value::{ACTUATION_THRESHOLD, ActionKind, ActionValue, FromActionValue};
+
+pub use glam::Vec2;
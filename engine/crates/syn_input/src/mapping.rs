@@ -0,0 +1,609 @@
+//! SDL game controller mappings.
+//!
+//! Parses the community `gamecontrollerdb.txt` format, one controller per line:
+//!
+//! ```text
+//! 030000005e0400008e02000000007801,XInput Controller,a:b0,b:b1,leftx:a0,lefty:a1,dpup:h0.1,platform:Windows,
+//! ```
+//!
+//! A [`GamepadMapping`] normalizes raw device buttons, axes and hats onto
+//! [`GamepadButton`] and [`GamepadAxis`].
+
+use hashbrown::HashMap;
+
+use crate::backend::RawGamepadEvent;
+use crate::error::MappingError;
+use crate::gamepad::{GamepadAxis, GamepadButton, GamepadEvent, GamepadId};
+
+/// Raw axis value above which an axis mapped to a button counts as pressed.
+const BUTTON_AXIS_THRESHOLD: f32 = 0.5;
+
+/// The part of an axis range a mapping element uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AxisRange {
+    /// The whole -1 to 1 range.
+    Full,
+    /// Only 0 to 1 (`+` prefix).
+    Positive,
+    /// Only -1 to 0 (`-` prefix).
+    Negative,
+}
+
+/// A raw device input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MappingSource {
+    /// Raw button `bN`.
+    Button(u8),
+    /// Raw axis `aN`, optionally halved and inverted (`~` suffix).
+    Axis {
+        /// Raw axis index.
+        index: u8,
+        /// Part of the raw range used.
+        range: AxisRange,
+        /// Whether the raw axis is inverted first.
+        inverted: bool,
+    },
+    /// Bit `mask` of raw hat `hN.mask`.
+    Hat {
+        /// Raw hat index.
+        index: u8,
+        /// Direction bit: 1 up, 2 right, 4 down, 8 left.
+        mask: u8,
+    },
+}
+
+/// A normalized gamepad input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MappingTarget {
+    /// A button.
+    Button(GamepadButton),
+    /// An axis, or half of it.
+    Axis {
+        /// The axis.
+        axis: GamepadAxis,
+        /// Part of the axis driven.
+        range: AxisRange,
+    },
+}
+
+/// How one controller model maps onto the normalized layout.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::{GamepadButton, GamepadMapping, MappingSource, MappingTarget};
+///
+/// let mapping = GamepadMapping::parse(
+///     "03000000de280000ff11000001000000,Steam Virtual Gamepad,a:b0,b:b1,platform:Linux,",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(mapping.name, "Steam Virtual Gamepad");
+/// assert_eq!(mapping.platform.as_deref(), Some("Linux"));
+/// assert!(mapping
+///     .elements
+///     .contains(&(MappingTarget::Button(GamepadButton::East), MappingSource::Button(1))));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GamepadMapping {
+    /// Lowercase SDL GUID of the controller model.
+    pub guid: String,
+    /// Controller name.
+    pub name: String,
+    /// Platform the mapping applies to, if restricted.
+    pub platform: Option<String>,
+    /// Mapped elements.
+    pub elements: Vec<(MappingTarget, MappingSource)>,
+}
+
+impl GamepadMapping {
+    /// GUID of [`GamepadMapping::standard`].
+    pub const STANDARD_GUID: &'static str = "00000000000000000000000000000000";
+
+    /// Parses one line of `gamecontrollerdb.txt`.
+    ///
+    /// Elements for outputs the engine does not model, such as paddles or the
+    /// touchpad, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`MappingError`] if the GUID, name or an element is malformed.
+    pub fn parse(line: &str) -> Result<Self, MappingError> {
+        let mut fields = line.trim().split(',');
+        let guid = fields.next().unwrap_or_default().trim();
+        let name = fields.next().map(str::trim).unwrap_or_default();
+        if guid.is_empty() || name.is_empty() {
+            return Err(MappingError::Incomplete);
+        }
+        if guid.len() != 32 || !guid.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(MappingError::InvalidGuid(guid.to_owned()));
+        }
+
+        let mut mapping = Self {
+            guid: guid.to_ascii_lowercase(),
+            name: name.to_owned(),
+            platform: None,
+            elements: Vec::new(),
+        };
+
+        for field in fields.map(str::trim).filter(|field| !field.is_empty()) {
+            let invalid = || MappingError::InvalidElement(field.to_owned());
+            let (key, value) = field.split_once(':').ok_or_else(invalid)?;
+            if key == "platform" {
+                mapping.platform = Some(value.to_owned());
+                continue;
+            }
+            let Some(target) = parse_target(key) else {
+                continue;
+            };
+            let source = parse_source(value).ok_or_else(invalid)?;
+            mapping.elements.push((target, source));
+        }
+
+        Ok(mapping)
+    }
+
+    /// The `XInput` layout, used for controllers missing from the database.
+    // RATIONALE: the layout is a constant covered by the tests, parsing cannot fail
+    #[allow(clippy::missing_panics_doc)]
+    pub fn standard() -> Self {
+        Self::parse(
+            "00000000000000000000000000000000,Standard Gamepad,\
+             a:b0,b:b1,x:b2,y:b3,leftshoulder:b4,rightshoulder:b5,back:b6,start:b7,\
+             leftstick:b8,rightstick:b9,guide:b10,\
+             dpup:h0.1,dpright:h0.2,dpdown:h0.4,dpleft:h0.8,\
+             leftx:a0,lefty:a1,lefttrigger:a2,rightx:a3,righty:a4,righttrigger:a5,",
+        )
+        .expect("the standard mapping is valid")
+    }
+
+    /// Translates a raw input event into normalized events.
+    ///
+    /// Connection events are not translated: they are handled by
+    /// [`Gamepads`](crate::Gamepads).
+    pub fn translate(&self, event: &RawGamepadEvent, out: &mut Vec<GamepadEvent>) {
+        match *event {
+            RawGamepadEvent::Button { id, index, pressed } => {
+                let value = f32::from(u8::from(pressed));
+                for (target, _) in self
+                    .elements
+                    .iter()
+                    .filter(|(_, source)| *source == MappingSource::Button(index))
+                {
+                    out.push(target_event(id, *target, value, AxisRange::Positive));
+                }
+            }
+            RawGamepadEvent::Axis { id, index, value } => {
+                for (target, source) in &self.elements {
+                    let MappingSource::Axis {
+                        index: source_index,
+                        range,
+                        inverted,
+                    } = *source
+                    else {
+                        continue;
+                    };
+                    if source_index != index {
+                        continue;
+                    }
+                    let raw = if inverted { -value } else { value };
+                    let value = match range {
+                        AxisRange::Full => raw,
+                        AxisRange::Positive => raw.max(0.0),
+                        AxisRange::Negative => (-raw).max(0.0),
+                    };
+                    out.push(target_event(id, *target, value, range));
+                }
+            }
+            RawGamepadEvent::Hat { id, index, mask } => {
+                for (target, source) in &self.elements {
+                    let MappingSource::Hat {
+                        index: source_index,
+                        mask: bit,
+                    } = *source
+                    else {
+                        continue;
+                    };
+                    if source_index == index {
+                        let value = f32::from(u8::from(mask & bit != 0));
+                        out.push(target_event(id, *target, value, AxisRange::Positive));
+                    }
+                }
+            }
+            RawGamepadEvent::Connected { .. } | RawGamepadEvent::Disconnected { .. } => {}
+        }
+    }
+}
+
+/// Builds the event for a mapped target from a source value.
+///
+/// `source_range` tells whether `value` spans -1 to 1 ([`AxisRange::Full`]) or
+/// 0 to 1.
+fn target_event(
+    id: GamepadId,
+    target: MappingTarget,
+    value: f32,
+    source_range: AxisRange,
+) -> GamepadEvent {
+    match target {
+        MappingTarget::Button(button) => GamepadEvent::Button {
+            id,
+            button,
+            pressed: value > BUTTON_AXIS_THRESHOLD,
+        },
+        MappingTarget::Axis { axis, range } => {
+            let full_source = source_range == AxisRange::Full;
+            let is_trigger = matches!(axis, GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger);
+            let mut value = match range {
+                AxisRange::Full if is_trigger && full_source => f32::midpoint(value, 1.0),
+                AxisRange::Full if !is_trigger && !full_source => value * 2.0 - 1.0,
+                AxisRange::Full | AxisRange::Positive => value,
+                AxisRange::Negative => -value,
+            };
+            // SDL reports stick y with +y down; the engine uses +y up.
+            if matches!(axis, GamepadAxis::LeftStickY | GamepadAxis::RightStickY) {
+                value = -value;
+            }
+            GamepadEvent::Axis { id, axis, value }
+        }
+    }
+}
+
+fn parse_target(key: &str) -> Option<MappingTarget> {
+    let (range, name) = split_range(key);
+    let button = match name {
+        "a" => GamepadButton::South,
+        "b" => GamepadButton::East,
+        "x" => GamepadButton::West,
+        "y" => GamepadButton::North,
+        "back" => GamepadButton::Select,
+        "guide" => GamepadButton::Guide,
+        "start" => GamepadButton::Start,
+        "leftstick" => GamepadButton::LeftStick,
+        "rightstick" => GamepadButton::RightStick,
+        "leftshoulder" => GamepadButton::LeftBumper,
+        "rightshoulder" => GamepadButton::RightBumper,
+        "dpup" => GamepadButton::DPadUp,
+        "dpdown" => GamepadButton::DPadDown,
+        "dpleft" => GamepadButton::DPadLeft,
+        "dpright" => GamepadButton::DPadRight,
+        _ => {
+            let axis = match name {
+                "leftx" => GamepadAxis::LeftStickX,
+                "lefty" => GamepadAxis::LeftStickY,
+                "rightx" => GamepadAxis::RightStickX,
+                "righty" => GamepadAxis::RightStickY,
+                "lefttrigger" => GamepadAxis::LeftTrigger,
+                "righttrigger" => GamepadAxis::RightTrigger,
+                _ => return None,
+            };
+            return Some(MappingTarget::Axis { axis, range });
+        }
+    };
+    Some(MappingTarget::Button(button))
+}
+
+fn parse_source(value: &str) -> Option<MappingSource> {
+    let (range, rest) = split_range(value);
+    let (kind, rest) = rest.split_at_checked(1)?;
+    match kind {
+        "b" if range == AxisRange::Full => rest.parse().ok().map(MappingSource::Button),
+        "a" => {
+            let (digits, inverted) = match rest.strip_suffix('~') {
+                Some(digits) => (digits, true),
+                None => (rest, false),
+            };
+            Some(MappingSource::Axis {
+                index: digits.parse().ok()?,
+                range,
+                inverted,
+            })
+        }
+        "h" if range == AxisRange::Full => {
+            let (index, mask) = rest.split_once('.')?;
+            Some(MappingSource::Hat {
+                index: index.parse().ok()?,
+                mask: mask.parse().ok()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn split_range(text: &str) -> (AxisRange, &str) {
+    if let Some(rest) = text.strip_prefix('+') {
+        (AxisRange::Positive, rest)
+    } else if let Some(rest) = text.strip_prefix('-') {
+        (AxisRange::Negative, rest)
+    } else {
+        (AxisRange::Full, text)
+    }
+}
+
+/// A set of controller mappings keyed by GUID.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::GamepadDb;
+///
+/// let mut db = GamepadDb::for_platform("Linux");
+/// let errors = db.load(
+///     "# Game controller mappings\n\
+///      03000000de280000ff11000001000000,Steam Virtual Gamepad,a:b0,platform:Linux,\n\
+///      03000000de280000ff11000001000000,Steam Virtual Gamepad,a:b1,platform:Windows,\n\
+///      not-a-guid,Broken,a:b0,\n",
+/// );
+///
+/// assert_eq!(db.len(), 1);
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].0, 4);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GamepadDb {
+    mappings: HashMap<String, GamepadMapping>,
+    platform: Option<String>,
+}
+
+impl GamepadDb {
+    /// Creates an empty database accepting mappings for every platform.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty database accepting only mappings for `platform`, such as
+    /// `"Windows"`, `"Mac OS X"` or `"Linux"`, plus unrestricted ones.
+    pub fn for_platform(platform: impl Into<String>) -> Self {
+        Self {
+            mappings: HashMap::new(),
+            platform: Some(platform.into()),
+        }
+    }
+
+    /// Returns the SDL name of the platform the engine was built for.
+    pub fn current_platform() -> &'static str {
+        match std::env::consts::OS {
+            "windows" => "Windows",
+            "macos" => "Mac OS X",
+            "ios" => "iOS",
+            "android" => "Android",
+            _ => "Linux",
+        }
+    }
+
+    /// Adds every mapping of a `gamecontrollerdb.txt` document.
+    ///
+    /// Comments, blank lines and other platforms' mappings are skipped. Later
+    /// mappings replace earlier ones with the same GUID. Returns the malformed
+    /// lines, numbered from 1, which are skipped too.
+    pub fn load(&mut self, text: &str) -> Vec<(usize, MappingError)> {
+        let mut errors = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match GamepadMapping::parse(line) {
+                Ok(mapping) => {
+                    if self.accepts(&mapping) {
+                        self.insert(mapping);
+                    }
+                }
+                Err(error) => errors.push((number + 1, error)),
+            }
+        }
+        errors
+    }
+
+    /// Adds a mapping, replacing any with the same GUID.
+    pub fn insert(&mut self, mapping: GamepadMapping) {
+        self.mappings.insert(mapping.guid.clone(), mapping);
+    }
+
+    /// Returns the mapping of a controller GUID.
+    pub fn get(&self, guid: &str) -> Option<&GamepadMapping> {
+        self.mappings.get(guid.to_ascii_lowercase().as_str())
+    }
+
+    /// Returns the number of mappings.
+    pub fn len(&self) -> usize {
+        self.mappings.len()
+    }
+
+    /// Returns `true` if the database has no mappings.
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    fn accepts(&self, mapping: &GamepadMapping) -> bool {
+        match (&self.platform, &mapping.platform) {
+            (Some(wanted), Some(platform)) => wanted == platform,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: GamepadId = GamepadId(1);
+
+    fn translate(mapping: &GamepadMapping, event: &RawGamepadEvent) -> Vec<GamepadEvent> {
+        let mut out = Vec::new();
+        mapping.translate(event, &mut out);
+        out
+    }
+
+    #[test]
+    fn parses_every_element_kind() {
+        let mapping = GamepadMapping::parse(
+            "030000004c050000c405000000010000,PS4 Controller,a:b1,dpup:h0.1,\
+             -leftx:b14,lefttrigger:+a3~,misc1:b15,crc:1234,platform:Mac OS X,",
+        )
+        .unwrap();
+
+        assert_eq!(mapping.platform.as_deref(), Some("Mac OS X"));
+        assert_eq!(
+            mapping.elements,
+            [
+                (
+                    MappingTarget::Button(GamepadButton::South),
+                    MappingSource::Button(1)
+                ),
+                (
+                    MappingTarget::Button(GamepadButton::DPadUp),
+                    MappingSource::Hat { index: 0, mask: 1 }
+                ),
+                (
+                    MappingTarget::Axis {
+                        axis: GamepadAxis::LeftStickX,
+                        range: AxisRange::Negative
+                    },
+                    MappingSource::Button(14)
+                ),
+                (
+                    MappingTarget::Axis {
+                        axis: GamepadAxis::LeftTrigger,
+                        range: AxisRange::Full
+                    },
+                    MappingSource::Axis {
+                        index: 3,
+                        range: AxisRange::Positive,
+                        inverted: true
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert_eq!(GamepadMapping::parse(""), Err(MappingError::Incomplete));
+        assert!(matches!(
+            GamepadMapping::parse("1234,Pad,a:b0"),
+            Err(MappingError::InvalidGuid(_))
+        ));
+        assert!(matches!(
+            GamepadMapping::parse("030000004c050000c405000000010000,Pad,a:q7"),
+            Err(MappingError::InvalidElement(_))
+        ));
+    }
+
+    #[test]
+    fn translates_buttons_hats_and_axes() {
+        let mapping = GamepadMapping::standard();
+
+        assert_eq!(
+            translate(
+                &mapping,
+                &RawGamepadEvent::Button {
+                    id: ID,
+                    index: 1,
+                    pressed: true
+                }
+            ),
+            [GamepadEvent::Button {
+                id: ID,
+                button: GamepadButton::East,
+                pressed: true
+            }]
+        );
+
+        let hat = translate(
+            &mapping,
+            &RawGamepadEvent::Hat {
+                id: ID,
+                index: 0,
+                mask: 1 | 2,
+            },
+        );
+        assert!(hat.contains(&GamepadEvent::Button {
+            id: ID,
+            button: GamepadButton::DPadUp,
+            pressed: true
+        }));
+        assert!(hat.contains(&GamepadEvent::Button {
+            id: ID,
+            button: GamepadButton::DPadLeft,
+            pressed: false
+        }));
+
+        assert_eq!(
+            translate(
+                &mapping,
+                &RawGamepadEvent::Axis {
+                    id: ID,
+                    index: 1,
+                    value: 0.5
+                }
+            ),
+            [GamepadEvent::Axis {
+                id: ID,
+                axis: GamepadAxis::LeftStickY,
+                value: -0.5
+            }]
+        );
+        assert_eq!(
+            translate(
+                &mapping,
+                &RawGamepadEvent::Axis {
+                    id: ID,
+                    index: 2,
+                    value: -1.0
+                }
+            ),
+            [GamepadEvent::Axis {
+                id: ID,
+                axis: GamepadAxis::LeftTrigger,
+                value: 0.0
+            }]
+        );
+    }
+
+    #[test]
+    fn half_axes_and_buttons_drive_each_other() {
+        let mapping = GamepadMapping::parse(
+            "030000004c050000c405000000010000,Pad,-leftx:b4,+leftx:b5,dpleft:-a0,dpright:+a0,",
+        )
+        .unwrap();
+
+        assert_eq!(
+            translate(
+                &mapping,
+                &RawGamepadEvent::Button {
+                    id: ID,
+                    index: 4,
+                    pressed: true
+                }
+            ),
+            [GamepadEvent::Axis {
+                id: ID,
+                axis: GamepadAxis::LeftStickX,
+                value: -1.0
+            }]
+        );
+        assert_eq!(
+            translate(
+                &mapping,
+                &RawGamepadEvent::Axis {
+                    id: ID,
+                    index: 0,
+                    value: -0.9
+                }
+            ),
+            [
+                GamepadEvent::Button {
+                    id: ID,
+                    button: GamepadButton::DPadLeft,
+                    pressed: true
+                },
+                GamepadEvent::Button {
+                    id: ID,
+                    button: GamepadButton::DPadRight,
+                    pressed: false
+                },
+            ]
+        );
+    }
+}
@@ -1,7 +1,17 @@
 //! Mouse input handling.
 
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use syn_platform::ScrollDelta;
+
+use crate::button::ButtonStates;
+
+/// Pixels of smooth scrolling counted as one line in [`MouseState::scroll_delta`].
+pub const PIXELS_PER_LINE: f32 = 20.0;
+
 /// Mouse buttons.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MouseButton {
     /// Left mouse button.
     Left,
@@ -9,17 +19,89 @@ pub enum MouseButton {
     Right,
     /// Middle mouse button.
     Middle,
+    /// Back side button.
+    Back,
+    /// Forward side button.
+    Forward,
     /// Additional buttons.
     Other(u16),
 }
 
+impl From<syn_platform::MouseButton> for MouseButton {
+    fn from(button: syn_platform::MouseButton) -> Self {
+        match button {
+            syn_platform::MouseButton::Left => Self::Left,
+            syn_platform::MouseButton::Right => Self::Right,
+            syn_platform::MouseButton::Middle => Self::Middle,
+            syn_platform::MouseButton::Back => Self::Back,
+            syn_platform::MouseButton::Forward => Self::Forward,
+            syn_platform::MouseButton::Other(id) => Self::Other(id),
+        }
+    }
+}
+
 /// Mouse input state.
 #[derive(Debug, Clone, Default)]
 pub struct MouseState {
     /// Current position.
     pub position: (f32, f32),
     /// Delta movement since last frame.
+    ///
+    /// Accumulated from raw device motion, so it keeps working while the cursor
+    /// is locked or hidden.
     pub delta: (f32, f32),
-    /// Scroll wheel delta.
+    /// Scroll wheel delta since last frame, in lines. Positive scrolls up.
     pub scroll_delta: f32,
+    /// Button states.
+    pub buttons: ButtonStates<MouseButton>,
+}
+
+impl MouseState {
+    /// Returns `true` while the button is held down.
+    pub fn pressed(&self, button: MouseButton) -> bool {
+        self.buttons.pressed(button)
+    }
+
+    /// Returns `true` if the button went down this frame.
+    pub fn just_pressed(&self, button: MouseButton) -> bool {
+        self.buttons.just_pressed(button)
+    }
+
+    /// Returns `true` if the button went up this frame.
+    pub fn just_released(&self, button: MouseButton) -> bool {
+        self.buttons.just_released(button)
+    }
+
+    /// Returns how long the button has been held, or zero if it is up.
+    pub fn held_duration(&self, button: MouseButton) -> Duration {
+        self.buttons.held_duration(button)
+    }
+
+    /// Starts a new frame that lasted `delta`, resetting the accumulated motion.
+    pub(crate) fn begin_frame(&mut self, delta: Duration) {
+        self.delta = (0.0, 0.0);
+        self.scroll_delta = 0.0;
+        self.buttons.begin_frame(delta);
+    }
+
+    pub(crate) fn add_motion(&mut self, dx: f64, dy: f64) {
+        self.delta.0 += narrow(dx);
+        self.delta.1 += narrow(dy);
+    }
+
+    pub(crate) fn add_scroll(&mut self, delta: ScrollDelta) {
+        match delta {
+            ScrollDelta::Lines { y, .. } => self.scroll_delta += y,
+            ScrollDelta::Pixels { y, .. } => self.scroll_delta += narrow(y) / PIXELS_PER_LINE,
+        }
+    }
+}
+
+/// Converts a platform coordinate to the `f32` precision used by input state.
+pub(crate) fn narrow(value: f64) -> f32 {
+    // RATIONALE: sub-pixel precision beyond f32 is irrelevant for input
+    #[allow(clippy::cast_possible_truncation)]
+    {
+        value as f32
+    }
 }
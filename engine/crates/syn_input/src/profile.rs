@@ -0,0 +1,450 @@
+//! Persisted binding profiles.
+//!
+//! A profile maps context names to their [`ActionMap`]s. The game ships one
+//! default profile per [`DeviceKind`]; the player's rebinds are saved as a
+//! separate override profile that [`InputProfiles`] merges on top.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, ActionMap};
+use crate::binding::{DeviceKind, InputBinding};
+use crate::context::InputContextStack;
+use crate::error::{ProfileError, ProfileResult};
+
+/// Action maps keyed by context name, serializable to RON and TOML.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::{Action, InputBinding, InputProfile, KeyCode};
+///
+/// let mut profile = InputProfile::new();
+/// profile
+///     .context_mut("gameplay")
+///     .register(Action::button("jump").with_binding(InputBinding::Key(KeyCode::Space)));
+///
+/// let text = profile.to_toml().unwrap();
+/// let loaded = InputProfile::from_toml(&text).unwrap();
+/// assert_eq!(loaded, profile);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputProfile {
+    /// Action maps keyed by context name.
+    #[serde(default)]
+    pub contexts: BTreeMap<String, ActionMap>,
+}
+
+impl InputProfile {
+    /// Creates an empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the action map of a context.
+    pub fn context(&self, name: &str) -> Option<&ActionMap> {
+        self.contexts.get(name)
+    }
+
+    /// Returns the action map of a context, creating it if missing.
+    pub fn context_mut(&mut self, name: &str) -> &mut ActionMap {
+        self.contexts.entry(name.to_owned()).or_default()
+    }
+
+    /// Returns a copy keeping only the bindings of the given devices.
+    ///
+    /// Actions left without bindings are kept, so they stay defined.
+    #[must_use]
+    pub fn for_devices(&self, devices: &[DeviceKind]) -> Self {
+        let mut filtered = Self::new();
+        for (name, actions) in &self.contexts {
+            let map = filtered.context_mut(name);
+            for action in actions.actions() {
+                let mut action = action.clone();
+                action
+                    .bindings
+                    .retain(|binding| devices.contains(&binding.device()));
+                map.register(action);
+            }
+        }
+        filtered
+    }
+
+    /// Merges another profile on top of this one.
+    ///
+    /// For every action in `overrides`, the bindings of the devices it mentions
+    /// replace the existing ones, so rebinding a key keeps the gamepad binding.
+    /// An override action with no bindings unbinds the action entirely. Actions
+    /// and contexts missing here are added.
+    pub fn merge(&mut self, overrides: &InputProfile) {
+        for (name, actions) in &overrides.contexts {
+            let map = self.context_mut(name);
+            for action in actions.actions() {
+                let merged = match map.get(&action.name) {
+                    Some(existing) if !action.bindings.is_empty() => merge_action(existing, action),
+                    Some(existing) => Action {
+                        bindings: Vec::new(),
+                        ..existing.clone()
+                    },
+                    None => action.clone(),
+                };
+                map.register(merged);
+            }
+        }
+    }
+
+    /// Parses a profile from RON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProfileError::RonParse`] if the document is malformed.
+    pub fn from_ron(text: &str) -> ProfileResult<Self> {
+        Ok(ron::from_str(text)?)
+    }
+
+    /// Writes the profile as pretty-printed RON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProfileError::RonWrite`] if serialization fails.
+    pub fn to_ron(&self) -> ProfileResult<String> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    /// Parses a profile from TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProfileError::TomlParse`] if the document is malformed.
+    pub fn from_toml(text: &str) -> ProfileResult<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Writes the profile as TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProfileError::TomlWrite`] if serialization fails.
+    pub fn to_toml(&self) -> ProfileResult<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Loads a profile, choosing the format from the `.ron` or `.toml` extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or has another
+    /// extension.
+    pub fn load(path: impl AsRef<Path>) -> ProfileResult<Self> {
+        let path = path.as_ref();
+        let format = Format::of(path)?;
+        let text = fs::read_to_string(path)?;
+        match format {
+            Format::Ron => Self::from_ron(&text),
+            Format::Toml => Self::from_toml(&text),
+        }
+    }
+
+    /// Saves the profile, choosing the format from the `.ron` or `.toml` extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile cannot be serialized or written, or the
+    /// path has another extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> ProfileResult<()> {
+        let path = path.as_ref();
+        let text = match Format::of(path)? {
+            Format::Ron => self.to_ron()?,
+            Format::Toml => self.to_toml()?,
+        };
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+impl PartialEq for InputProfile {
+    fn eq(&self, other: &Self) -> bool {
+        self.contexts.len() == other.contexts.len()
+            && self.contexts.iter().all(|(name, actions)| {
+                other.contexts.get(name).is_some_and(|theirs| {
+                    actions.len() == theirs.len()
+                        && actions.actions().all(|action| {
+                            theirs.get(&action.name).is_some_and(|other| {
+                                other.kind == action.kind
+                                    && other.bindings == action.bindings
+                                    && other.interactions == action.interactions
+                            })
+                        })
+                })
+            })
+    }
+}
+
+fn merge_action(existing: &Action, overrides: &Action) -> Action {
+    let devices: Vec<_> = overrides
+        .bindings
+        .iter()
+        .map(InputBinding::device)
+        .collect();
+    let mut merged = existing.clone();
+    merged
+        .bindings
+        .retain(|binding| !devices.contains(&binding.device()));
+    merged.bindings.extend(overrides.bindings.iter().cloned());
+    merged
+}
+
+enum Format {
+    Ron,
+    Toml,
+}
+
+impl Format {
+    fn of(path: &Path) -> ProfileResult<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ron") => Ok(Self::Ron),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(ProfileError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+}
+
+/// Shipped per-device defaults plus the player's overrides.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::{
+///     Action, DeviceKind, GamepadButton, InputBinding, InputProfile, InputProfiles, KeyCode,
+/// };
+///
+/// let mut keyboard = InputProfile::new();
+/// keyboard
+///     .context_mut("gameplay")
+///     .register(Action::button("jump").with_binding(InputBinding::Key(KeyCode::Space)));
+/// let mut gamepad = InputProfile::new();
+/// gamepad
+///     .context_mut("gameplay")
+///     .register(Action::button("jump").with_binding(InputBinding::Gamepad(GamepadButton::South)));
+///
+/// let mut profiles = InputProfiles::new();
+/// profiles.set_default(DeviceKind::KeyboardMouse, keyboard);
+/// profiles.set_default(DeviceKind::Gamepad, gamepad);
+/// profiles.rebind("gameplay", "jump", InputBinding::Key(KeyCode::J));
+///
+/// let resolved = profiles.resolve();
+/// let jump = resolved.context("gameplay").unwrap().get("jump").unwrap();
+/// assert_eq!(
+///     jump.bindings,
+///     [InputBinding::Gamepad(GamepadButton::South), InputBinding::Key(KeyCode::J)]
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputProfiles {
+    defaults: BTreeMap<DeviceKind, InputProfile>,
+    overrides: InputProfile,
+}
+
+impl InputProfiles {
+    /// Creates an empty set of profiles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the shipped default profile of a device.
+    pub fn set_default(&mut self, device: DeviceKind, profile: InputProfile) {
+        self.defaults.insert(device, profile);
+    }
+
+    /// Returns the shipped default profile of a device.
+    pub fn default_for(&self, device: DeviceKind) -> Option<&InputProfile> {
+        self.defaults.get(&device)
+    }
+
+    /// Returns the player's overrides.
+    pub fn overrides(&self) -> &InputProfile {
+        &self.overrides
+    }
+
+    /// Replaces the player's overrides, such as with a loaded settings file.
+    pub fn set_overrides(&mut self, overrides: InputProfile) {
+        self.overrides = overrides;
+    }
+
+    /// Rebinds an action for the device of `binding`, replacing the action's
+    /// other bindings on that device.
+    pub fn rebind(&mut self, context: &str, action: &str, binding: InputBinding) {
+        let kind = self
+            .defaults
+            .values()
+            .find_map(|profile| profile.context(context)?.get(action))
+            .map(|action| action.kind)
+            .unwrap_or_default();
+
+        let map = self.overrides.context_mut(context);
+        let rebound = match map.get(action) {
+            Some(existing) => merge_action(
+                existing,
+                &Action {
+                    bindings: vec![binding],
+                    ..existing.clone()
+                },
+            ),
+            None => Action::new(action, kind).with_binding(binding),
+        };
+        map.register(rebound);
+    }
+
+    /// Removes the player's override of an action, restoring the defaults.
+    pub fn reset(&mut self, context: &str, action: &str) {
+        if let Some(map) = self.overrides.contexts.get_mut(context) {
+            map.remove(action);
+        }
+    }
+
+    /// Removes every override.
+    pub fn reset_all(&mut self) {
+        self.overrides = InputProfile::new();
+    }
+
+    /// Merges every device's defaults, then the overrides.
+    pub fn resolve(&self) -> InputProfile {
+        let mut resolved = InputProfile::new();
+        for profile in self.defaults.values() {
+            resolved.merge(profile);
+        }
+        resolved.merge(&self.overrides);
+        resolved
+    }
+
+    /// Resolves the profiles and applies them to the contexts on a stack.
+    pub fn apply(&self, stack: &mut InputContextStack) {
+        stack.apply_profile(&self.resolve());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding::{AxisResponse, Modifier, Stick};
+    use crate::gamepad::GamepadAxis;
+    use crate::keyboard::KeyCode;
+    use crate::mouse::MouseButton;
+
+    fn sample() -> InputProfile {
+        let mut profile = InputProfile::new();
+        let gameplay = profile.context_mut("gameplay");
+        gameplay.register(
+            Action::axis_2d("move")
+                .with_binding(InputBinding::wasd())
+                .with_binding(InputBinding::stick(Stick::Left)),
+        );
+        gameplay.register(
+            Action::button("fire")
+                .with_binding(InputBinding::Mouse(MouseButton::Left))
+                .with_binding(InputBinding::GamepadAxis {
+                    axis: GamepadAxis::RightTrigger,
+                    response: AxisResponse::default(),
+                }),
+        );
+        profile.context_mut("editor").register(
+            Action::button("save")
+                .with_binding(InputBinding::chord([Modifier::Control], KeyCode::S)),
+        );
+        profile
+    }
+
+    #[test]
+    fn profiles_round_trip_through_ron_and_toml() {
+        let profile = sample();
+
+        let ron = profile.to_ron().unwrap();
+        assert_eq!(InputProfile::from_ron(&ron).unwrap(), profile);
+
+        let toml = profile.to_toml().unwrap();
+        assert_eq!(InputProfile::from_toml(&toml).unwrap(), profile);
+    }
+
+    #[test]
+    fn hand_written_ron_uses_defaults() {
+        let profile = InputProfile::from_ron(
+            r#"(contexts: {"menu": (actions: [(name: "back", bindings: [Key(Escape)])])})"#,
+        )
+        .unwrap();
+
+        let back = profile.context("menu").unwrap().get("back").unwrap();
+        assert_eq!(back.bindings, [InputBinding::Key(KeyCode::Escape)]);
+        assert!(InputProfile::from_toml("contexts = 3").is_err());
+    }
+
+    #[test]
+    fn merge_replaces_only_the_overridden_device() {
+        let mut profile = sample();
+        let mut overrides = InputProfile::new();
+        overrides
+            .context_mut("gameplay")
+            .register(Action::button("fire").with_binding(InputBinding::Mouse(MouseButton::Right)));
+        overrides
+            .context_mut("gameplay")
+            .register(Action::axis_2d("move"));
+        profile.merge(&overrides);
+
+        let gameplay = profile.context("gameplay").unwrap();
+        assert_eq!(
+            gameplay.get("fire").unwrap().bindings[1],
+            InputBinding::Mouse(MouseButton::Right)
+        );
+        assert_eq!(
+            gameplay.get("fire").unwrap().bindings[0].device(),
+            DeviceKind::Gamepad
+        );
+        assert!(gameplay.get("move").unwrap().bindings.is_empty());
+    }
+
+    #[test]
+    fn for_devices_filters_bindings() {
+        let gamepad = sample().for_devices(&[DeviceKind::Gamepad]);
+        let gameplay = gamepad.context("gameplay").unwrap();
+
+        assert_eq!(
+            gameplay.get("move").unwrap().bindings,
+            [InputBinding::stick(Stick::Left)]
+        );
+        assert!(
+            gamepad
+                .context("editor")
+                .unwrap()
+                .get("save")
+                .unwrap()
+                .bindings
+                .is_empty()
+        );
+        assert_eq!(gameplay.get("fire").unwrap().bindings.len(), 1);
+    }
+
+    #[test]
+    fn reset_restores_defaults() {
+        let mut profiles = InputProfiles::new();
+        profiles.set_default(DeviceKind::KeyboardMouse, sample());
+        profiles.rebind("gameplay", "fire", InputBinding::Key(KeyCode::F));
+        assert_eq!(
+            profiles
+                .resolve()
+                .context("gameplay")
+                .unwrap()
+                .get("fire")
+                .unwrap()
+                .bindings[1],
+            InputBinding::Key(KeyCode::F)
+        );
+
+        profiles.reset("gameplay", "fire");
+        assert_eq!(profiles.resolve(), sample());
+    }
+}
@@ -0,0 +1,290 @@
+//! Interactive rebinding.
+
+use hashbrown::HashMap;
+
+use crate::binding::{AxisResponse, DeviceKind, InputBinding, Modifier};
+use crate::gamepad::{GamepadAxis, GamepadButton};
+use crate::keyboard::KeyCode;
+use crate::state::InputState;
+
+/// Axis magnitude a gamepad axis must cross to be captured.
+const AXIS_CAPTURE_THRESHOLD: f32 = 0.5;
+
+const MODIFIER_KEYS: [(KeyCode, Modifier); 8] = [
+    (KeyCode::LShift, Modifier::Shift),
+    (KeyCode::RShift, Modifier::Shift),
+    (KeyCode::LCtrl, Modifier::Control),
+    (KeyCode::RCtrl, Modifier::Control),
+    (KeyCode::LAlt, Modifier::Alt),
+    (KeyCode::RAlt, Modifier::Alt),
+    (KeyCode::LSuper, Modifier::Super),
+    (KeyCode::RSuper, Modifier::Super),
+];
+
+/// The result of listening for a new binding.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RebindOutcome {
+    /// The player pressed an input.
+    Bound(InputBinding),
+    /// The player pressed the cancel key.
+    Cancelled,
+}
+
+/// Listens for the next input the player presses, to rebind an action.
+///
+/// Poll it once per frame, after the [`InputState`] has received the frame's
+/// events. Keys pressed while holding modifiers become chords; a modifier pressed
+/// and released on its own binds the modifier key itself.
+///
+/// # Example
+///
+/// ```
+/// use syn_input::{InputBinding, InputState, KeyCode, RebindListener, RebindOutcome};
+///
+/// let mut listener = RebindListener::new();
+/// let mut input = InputState::new();
+/// assert_eq!(listener.poll(&input), None);
+///
+/// input.keyboard.keys_mut().press(KeyCode::J);
+/// assert_eq!(
+///     listener.poll(&input),
+///     Some(RebindOutcome::Bound(InputBinding::Key(KeyCode::J)))
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RebindListener {
+    devices: Vec<DeviceKind>,
+    cancel_key: Option<KeyCode>,
+    capture_axes: bool,
+    axis_baseline: HashMap<(usize, GamepadAxis), f32>,
+    pending_modifier: Option<KeyCode>,
+}
+
+impl Default for RebindListener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RebindListener {
+    /// Listens on every device, with Escape cancelling.
+    pub fn new() -> Self {
+        Self {
+            devices: vec![DeviceKind::KeyboardMouse, DeviceKind::Gamepad],
+            cancel_key: Some(KeyCode::Escape),
+            capture_axes: true,
+            axis_baseline: HashMap::new(),
+            pending_modifier: None,
+        }
+    }
+
+    /// Only captures inputs of the given device.
+    #[must_use]
+    pub fn device(mut self, device: DeviceKind) -> Self {
+        self.devices = vec![device];
+        self
+    }
+
+    /// Sets the key that cancels listening, or `None` to make every key bindable.
+    #[must_use]
+    pub fn cancel_key(mut self, key: Option<KeyCode>) -> Self {
+        self.cancel_key = key;
+        self
+    }
+
+    /// Sets whether gamepad axes can be captured, such as for button-only actions.
+    #[must_use]
+    pub fn capture_axes(mut self, capture: bool) -> Self {
+        self.capture_axes = capture;
+        self
+    }
+
+    /// Checks the current frame for a new input.
+    pub fn poll(&mut self, input: &InputState) -> Option<RebindOutcome> {
+        if self.accepts(DeviceKind::KeyboardMouse) {
+            if let Some(outcome) = self.poll_keyboard(input) {
+                return Some(outcome);
+            }
+            if let Some(button) = input.mouse.buttons.iter_just_pressed().next() {
+                return Some(RebindOutcome::Bound(InputBinding::Mouse(button)));
+            }
+        } else if self
+            .cancel_key
+            .is_some_and(|key| input.keyboard.just_pressed(key))
+        {
+            return Some(RebindOutcome::Cancelled);
+        }
+
+        if self.accepts(DeviceKind::Gamepad) {
+            return self.poll_gamepads(input).map(RebindOutcome::Bound);
+        }
+        None
+    }
+
+    fn accepts(&self, device: DeviceKind) -> bool {
+        self.devices.contains(&device)
+    }
+
+    fn poll_keyboard(&mut self, input: &InputState) -> Option<RebindOutcome> {
+        let keyboard = &input.keyboard;
+        if self
+            .cancel_key
+            .is_some_and(|key| keyboard.just_pressed(key))
+        {
+            return Some(RebindOutcome::Cancelled);
+        }
+
+        let pressed = keyboard
+            .keys()
+            .iter_just_pressed()
+            .find(|key| modifier_of(*key).is_none());
+        if let Some(key) = pressed {
+            let mut modifiers: Vec<_> = keyboard.pressed_keys().filter_map(modifier_of).collect();
+            modifiers.sort_unstable();
+            modifiers.dedup();
+            self.pending_modifier = None;
+            let binding = if modifiers.is_empty() {
+                InputBinding::Key(key)
+            } else {
+                InputBinding::chord(modifiers, key)
+            };
+            return Some(RebindOutcome::Bound(binding));
+        }
+
+        if let Some(key) = keyboard.keys().iter_just_pressed().next() {
+            self.pending_modifier = Some(key);
+        }
+        match self.pending_modifier {
+            Some(key) if keyboard.just_released(key) => {
+                self.pending_modifier = None;
+                Some(RebindOutcome::Bound(InputBinding::Key(key)))
+            }
+            _ => None,
+        }
+    }
+
+    fn poll_gamepads(&mut self, input: &InputState) -> Option<InputBinding> {
+        for (slot, gamepad) in input.gamepads.iter().enumerate() {
+            if let Some(button) = GamepadButton::ALL
+                .iter()
+                .find(|button| gamepad.just_pressed(**button))
+            {
+                return Some(InputBinding::Gamepad(*button));
+            }
+
+            if !self.capture_axes {
+                continue;
+            }
+            for axis in GamepadAxis::ALL {
+                let value = gamepad.axis(axis).abs();
+                // Axes already deflected when listening starts must return to
+                // rest first, so a resting trigger offset is never captured.
+                let baseline = self.axis_baseline.entry((slot, axis)).or_insert(value);
+                let crossed = *baseline <= AXIS_CAPTURE_THRESHOLD && value > AXIS_CAPTURE_THRESHOLD;
+                *baseline = value;
+                if crossed {
+                    return Some(InputBinding::GamepadAxis {
+                        axis,
+                        response: AxisResponse::default(),
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+fn modifier_of(key: KeyCode) -> Option<Modifier> {
+    MODIFIER_KEYS
+        .iter()
+        .find(|(modifier_key, _)| *modifier_key == key)
+        .map(|(_, modifier)| *modifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamepad::GamepadState;
+    use crate::mouse::MouseButton;
+    use std::time::Duration;
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    #[test]
+    fn keys_held_with_modifiers_become_chords() {
+        let mut listener = RebindListener::new();
+        let mut input = InputState::new();
+
+        input.keyboard.keys_mut().press(KeyCode::LCtrl);
+        assert_eq!(listener.poll(&input), None);
+        input.begin_frame(FRAME);
+        input.keyboard.keys_mut().press(KeyCode::S);
+
+        assert_eq!(
+            listener.poll(&input),
+            Some(RebindOutcome::Bound(InputBinding::chord(
+                [Modifier::Control],
+                KeyCode::S
+            )))
+        );
+    }
+
+    #[test]
+    fn lone_modifier_binds_on_release() {
+        let mut listener = RebindListener::new();
+        let mut input = InputState::new();
+
+        input.keyboard.keys_mut().press(KeyCode::LShift);
+        assert_eq!(listener.poll(&input), None);
+        input.begin_frame(FRAME);
+        input.keyboard.keys_mut().release(KeyCode::LShift);
+
+        assert_eq!(
+            listener.poll(&input),
+            Some(RebindOutcome::Bound(InputBinding::Key(KeyCode::LShift)))
+        );
+    }
+
+    #[test]
+    fn cancel_key_and_device_filter() {
+        let mut listener = RebindListener::new().device(DeviceKind::Gamepad);
+        let mut input = InputState::new();
+        input.gamepads.push(GamepadState::new());
+
+        input.keyboard.keys_mut().press(KeyCode::J);
+        input.mouse.buttons.press(MouseButton::Left);
+        assert_eq!(listener.poll(&input), None);
+
+        input.gamepads[0].buttons.press(GamepadButton::North);
+        assert_eq!(
+            listener.poll(&input),
+            Some(RebindOutcome::Bound(InputBinding::Gamepad(
+                GamepadButton::North
+            )))
+        );
+
+        input.keyboard.keys_mut().press(KeyCode::Escape);
+        assert_eq!(listener.poll(&input), Some(RebindOutcome::Cancelled));
+    }
+
+    #[test]
+    fn axes_must_cross_the_threshold() {
+        let mut listener = RebindListener::new();
+        let mut input = InputState::new();
+        let mut pad = GamepadState::new();
+        pad.set_axis(GamepadAxis::LeftTrigger, 0.9);
+        input.gamepads.push(pad);
+
+        assert_eq!(listener.poll(&input), None);
+
+        input.gamepads[0].set_axis(GamepadAxis::LeftTrigger, 0.0);
+        input.gamepads[0].set_axis(GamepadAxis::RightStickY, -0.8);
+        assert_eq!(
+            listener.poll(&input),
+            Some(RebindOutcome::Bound(InputBinding::GamepadAxis {
+                axis: GamepadAxis::RightStickY,
+                response: AxisResponse::default(),
+            }))
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! Per-frame input state fed from platform events.
+
+use std::time::Duration;
+
+use syn_platform::Event;
+
+use crate::gamepad::{GamepadEvent, GamepadId, GamepadState};
+use crate::keyboard::{KeyCode, KeyboardState};
+use crate::mouse::{MouseState, narrow};
+
+/// Keyboard, mouse and gamepad state for the current frame.
+///
+/// Each frame, call [`begin_frame`](InputState::begin_frame) with the frame time,
+/// then feed every pumped platform event to
+/// [`handle_event`](InputState::handle_event). Game code then reads edge-triggered
+/// states that stay stable for the rest of the frame.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use syn_input::{InputState, KeyCode};
+/// use syn_platform::{ButtonState, Event, KeyboardInput, LogicalKey, ScanCode};
+///
+/// let mut input = InputState::new();
+/// input.begin_frame(Duration::from_millis(16));
+/// input.handle_event(&Event::KeyboardInput(KeyboardInput {
+///     scan_code: ScanCode::keyboard(0x2C),
+///     logical_key: LogicalKey::Character(" ".into()),
+///     state: ButtonState::Pressed,
+///     repeat: false,
+/// }));
+///
+/// assert!(input.keyboard.just_pressed(KeyCode::Space));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    /// Keyboard state.
+    pub keyboard: KeyboardState,
+    /// Mouse state.
+    pub mouse: MouseState,
+    /// Gamepad states, indexed by gamepad slot.
+    pub gamepads: Vec<GamepadState>,
+    time: Duration,
+    delta: Duration,
+}
+
+impl InputState {
+    /// Creates an input state with nothing pressed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new frame that lasted `delta`.
+    ///
+    /// Clears the edges and accumulated motion of the previous frame and extends
+    /// the held duration of everything still pressed.
+    pub fn begin_frame(&mut self, delta: Duration) {
+        self.time += delta;
+        self.delta = delta;
+        self.keyboard.begin_frame(delta);
+        self.mouse.begin_frame(delta);
+        for gamepad in &mut self.gamepads {
+            gamepad.begin_frame(delta);
+        }
+    }
+
+    /// Returns the total time passed to [`begin_frame`](InputState::begin_frame).
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns the duration of the current frame.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Applies a platform event to the current frame.
+    ///
+    /// Losing window focus releases every key and mouse button, so nothing stays
+    /// stuck down while another application has the keyboard.
+    pub fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::KeyboardInput(input) => {
+                let Some(key) = KeyCode::from_scan_code(input.scan_code) else {
+                    return;
+                };
+                if input.state.is_pressed() {
+                    self.keyboard.learn(key, &input.logical_key);
+                    self.keyboard.keys_mut().press(key);
+                } else {
+                    self.keyboard.keys_mut().release(key);
+                }
+            }
+            Event::ModifiersChanged(modifiers) => self.keyboard.set_modifiers(*modifiers),
+            Event::Text(text) => self.keyboard.push_text(text),
+            Event::CursorMoved { x, y } => self.mouse.position = (narrow(*x), narrow(*y)),
+            Event::MouseMotion { dx, dy } => self.mouse.add_motion(*dx, *dy),
+            Event::MouseWheel(delta) => self.mouse.add_scroll(*delta),
+            Event::MouseInput { button, state } => {
+                if state.is_pressed() {
+                    self.mouse.buttons.press((*button).into());
+                } else {
+                    self.mouse.buttons.release((*button).into());
+                }
+            }
+            Event::Focused(false) => {
+                self.keyboard.keys_mut().release_all();
+                self.mouse.buttons.release_all();
+            }
+            _ => {}
+        }
+    }
+
+    /// Applies every event of a frame, in order.
+    pub fn handle_events<'a>(&mut self, events: impl IntoIterator<Item = &'a Event>) {
+        for event in events {
+            self.handle_event(event);
+        }
+    }
+
+    /// Applies a gamepad event to the current frame.
+    ///
+    /// A connecting pad takes the first slot left by a disconnected one, or a new
+    /// slot at the end.
+    pub fn handle_gamepad_event(&mut self, event: &GamepadEvent) {
+        match event {
+            GamepadEvent::Connected { id, name } => {
+                let slot = match self.gamepad_slot(*id) {
+                    Some(slot) => slot,
+                    None => self.free_gamepad_slot(),
+                };
+                self.gamepads[slot].connect(*id, name);
+            }
+            GamepadEvent::Disconnected { id } => {
+                if let Some(slot) = self.gamepad_slot(*id) {
+                    self.gamepads[slot].disconnect();
+                }
+            }
+            GamepadEvent::Button {
+                id,
+                button,
+                pressed,
+            } => {
+                if let Some(slot) = self.gamepad_slot(*id) {
+                    let buttons = &mut self.gamepads[slot].buttons;
+                    if *pressed {
+                        buttons.press(*button);
+                    } else {
+                        buttons.release(*button);
+                    }
+                }
+            }
+            GamepadEvent::Axis { id, axis, value } => {
+                if let Some(slot) = self.gamepad_slot(*id) {
+                    self.gamepads[slot].set_axis(*axis, *value);
+                }
+            }
+        }
+    }
+
+    /// Returns the state of a gamepad slot, if one is attached.
+    pub fn gamepad(&self, slot: usize) -> Option<&GamepadState> {
+        self.gamepads.get(slot)
+    }
+
+    /// Returns the slot of a connected gamepad.
+    pub fn gamepad_slot(&self, id: GamepadId) -> Option<usize> {
+        self.gamepads
+            .iter()
+            .position(|pad| pad.is_connected() && pad.id() == Some(id))
+    }
+
+    fn free_gamepad_slot(&mut self) -> usize {
+        if let Some(slot) = self.gamepads.iter().position(|pad| !pad.is_connected()) {
+            return slot;
+        }
+        self.gamepads.push(GamepadState::new());
+        self.gamepads.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mouse::MouseButton;
+    use syn_platform::{ButtonState, KeyboardInput, LogicalKey, ScanCode, ScrollDelta};
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    fn key(usage: u16, state: ButtonState) -> Event {
+        Event::KeyboardInput(KeyboardInput {
+            scan_code: ScanCode::keyboard(usage),
+            logical_key: LogicalKey::Unidentified,
+            state,
+            repeat: false,
+        })
+    }
+
+    #[test]
+    fn keyboard_events_drive_key_edges() {
+        let mut input = InputState::new();
+        input.begin_frame(FRAME);
+        input.handle_event(&key(0x1A, ButtonState::Pressed));
+        assert!(input.keyboard.just_pressed(KeyCode::W));
+
+        input.begin_frame(FRAME);
+        assert!(input.keyboard.pressed(KeyCode::W));
+        assert!(!input.keyboard.just_pressed(KeyCode::W));
+        assert_eq!(input.keyboard.held_duration(KeyCode::W), FRAME);
+
+        input.begin_frame(FRAME);
+        input.handle_event(&key(0x1A, ButtonState::Released));
+        assert!(input.keyboard.just_released(KeyCode::W));
+        assert!(!input.keyboard.pressed(KeyCode::W));
+    }
+
+    #[test]
+    fn mouse_motion_and_scroll_accumulate_per_frame() {
+        let mut input = InputState::new();
+        input.begin_frame(FRAME);
+        input.handle_events(&[
+            Event::CursorMoved { x: 10.0, y: 20.0 },
+            Event::MouseMotion { dx: 2.0, dy: -1.0 },
+            Event::MouseMotion { dx: 3.0, dy: -1.0 },
+            Event::MouseWheel(ScrollDelta::Lines { x: 0.0, y: 1.0 }),
+            Event::MouseWheel(ScrollDelta::Pixels { x: 0.0, y: 40.0 }),
+            Event::MouseInput {
+                button: syn_platform::MouseButton::Left,
+                state: ButtonState::Pressed,
+            },
+        ]);
+
+        assert_eq!(input.mouse.position, (10.0, 20.0));
+        assert_eq!(input.mouse.delta, (5.0, -2.0));
+        assert!((input.mouse.scroll_delta - 3.0).abs() < f32::EPSILON);
+        assert!(input.mouse.just_pressed(MouseButton::Left));
+
+        input.begin_frame(FRAME);
+        assert_eq!(input.mouse.position, (10.0, 20.0));
+        assert_eq!(input.mouse.delta, (0.0, 0.0));
+        assert!(input.mouse.scroll_delta.abs() < f32::EPSILON);
+        assert!(input.mouse.pressed(MouseButton::Left));
+    }
+
+    #[test]
+    fn losing_focus_releases_everything() {
+        let mut input = InputState::new();
+        input.handle_event(&key(0x2C, ButtonState::Pressed));
+        input.handle_event(&Event::MouseInput {
+            button: syn_platform::MouseButton::Right,
+            state: ButtonState::Pressed,
+        });
+        input.begin_frame(FRAME);
+        input.handle_event(&Event::Focused(false));
+
+        assert!(input.keyboard.just_released(KeyCode::Space));
+        assert!(input.mouse.just_released(MouseButton::Right));
+    }
+
+    #[test]
+    fn text_is_collected_per_frame() {
+        let mut input = InputState::new();
+        input.handle_event(&Event::Text("h".into()));
+        input.handle_event(&Event::Text("i".into()));
+        assert_eq!(input.keyboard.text(), "hi");
+
+        input.begin_frame(FRAME);
+        assert_eq!(input.keyboard.text(), "");
+    }
+}
@@ -0,0 +1,114 @@
+//! Action value types.
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Magnitude above which an action counts as pressed.
+pub const ACTUATION_THRESHOLD: f32 = 0.5;
+
+/// The kind of value an action produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ActionKind {
+    /// A pressed/released state, such as "jump".
+    #[default]
+    Button,
+    /// A single axis in -1 to 1, such as "throttle".
+    Axis1D,
+    /// A two-dimensional vector, such as "move" or "look".
+    Axis2D,
+}
+
+/// The value of an action for the current frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActionValue {
+    /// Value of a [`ActionKind::Button`] action.
+    Button(bool),
+    /// Value of a [`ActionKind::Axis1D`] action.
+    Axis1D(f32),
+    /// Value of a [`ActionKind::Axis2D`] action.
+    Axis2D(Vec2),
+}
+
+impl ActionValue {
+    /// Returns the resting value of an action kind.
+    pub fn zero(kind: ActionKind) -> Self {
+        match kind {
+            ActionKind::Button => Self::Button(false),
+            ActionKind::Axis1D => Self::Axis1D(0.0),
+            ActionKind::Axis2D => Self::Axis2D(Vec2::ZERO),
+        }
+    }
+
+    /// Returns the kind of the value.
+    pub fn kind(self) -> ActionKind {
+        match self {
+            Self::Button(_) => ActionKind::Button,
+            Self::Axis1D(_) => ActionKind::Axis1D,
+            Self::Axis2D(_) => ActionKind::Axis2D,
+        }
+    }
+
+    /// Returns the magnitude of the value: 0 or 1 for buttons, the absolute value
+    /// for axes and the length for vectors.
+    pub fn magnitude(self) -> f32 {
+        match self {
+            Self::Button(pressed) => f32::from(u8::from(pressed)),
+            Self::Axis1D(value) => value.abs(),
+            Self::Axis2D(value) => value.length(),
+        }
+    }
+
+    /// Returns `true` if the magnitude exceeds [`ACTUATION_THRESHOLD`].
+    pub fn is_actuated(self) -> bool {
+        self.magnitude() > ACTUATION_THRESHOLD
+    }
+
+    /// Converts a raw binding vector to a value of the given kind.
+    pub(crate) fn from_raw(kind: ActionKind, raw: Vec2) -> Self {
+        match kind {
+            ActionKind::Button => Self::Button(raw.length() > ACTUATION_THRESHOLD),
+            ActionKind::Axis1D => Self::Axis1D(raw.x),
+            ActionKind::Axis2D => Self::Axis2D(raw),
+        }
+    }
+}
+
+/// Types an [`ActionValue`] can be read as, via
+/// [`ActionMap::value`](crate::ActionMap::value).
+///
+/// Reading a value as a different kind converts it: a button reads as 0 or 1, an
+/// axis as the x component of a vector, and a vector as its length.
+pub trait FromActionValue: Sized {
+    /// Converts the value.
+    fn from_action_value(value: ActionValue) -> Self;
+}
+
+impl FromActionValue for ActionValue {
+    fn from_action_value(value: ActionValue) -> Self {
+        value
+    }
+}
+
+impl FromActionValue for bool {
+    fn from_action_value(value: ActionValue) -> Self {
+        value.is_actuated()
+    }
+}
+
+impl FromActionValue for f32 {
+    fn from_action_value(value: ActionValue) -> Self {
+        match value {
+            ActionValue::Axis1D(value) => value,
+            _ => value.magnitude(),
+        }
+    }
+}
+
+impl FromActionValue for Vec2 {
+    fn from_action_value(value: ActionValue) -> Self {
+        match value {
+            ActionValue::Axis2D(value) => value,
+            _ => Vec2::new(f32::from_action_value(value), 0.0),
+        }
+    }
+}
@@ -1,9 +1,12 @@
 //! Axis-Aligned Bounding Box implementation.
 
-use glam::Vec3;
+use glam::{Mat3, Vec3};
+use syn_reflect::Reflect;
+
+use crate::transform::Transform;
 
 /// An axis-aligned bounding box defined by its minimum and maximum corners.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
 pub struct Aabb {
     /// The minimum corner of the bounding box.
     pub min: Vec3,
@@ -75,12 +78,29 @@ impl Aabb {
     }
 
     /// Returns the union of this AABB with another.
+    #[must_use]
     pub fn union(&self, other: &Aabb) -> Aabb {
         Aabb {
             min: self.min.min(other.min),
             max: self.max.max(other.max),
         }
     }
+
+    /// Returns the smallest AABB containing this one transformed by
+    /// `transform`, such as the world-space bounds of a mesh. Empty boxes
+    /// stay empty.
+    #[must_use]
+    pub fn transformed(&self, transform: &Transform) -> Aabb {
+        if self.min.cmpgt(self.max).any() {
+            return *self;
+        }
+        let half_extents = (self.half_extents() * transform.scale).abs();
+        let rotation = Mat3::from_quat(transform.rotation);
+        let half_extents = rotation.x_axis.abs() * half_extents.x
+            + rotation.y_axis.abs() * half_extents.y
+            + rotation.z_axis.abs() * half_extents.z;
+        Self::from_center_half_extents(transform.transform_point(self.center()), half_extents)
+    }
 }
 
 impl Default for Aabb {
@@ -88,3 +108,23 @@ impl Default for Aabb {
         Self::empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Quat;
+
+    #[test]
+    fn transformed_boxes_contain_the_transformed_corners() {
+        let aabb = Aabb::new(Vec3::new(-1.0, -2.0, -3.0), Vec3::new(1.0, 2.0, 3.0));
+        let transform = Transform::from_translation(Vec3::new(10.0, 0.0, 0.0))
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2))
+            .with_scale(Vec3::new(2.0, 1.0, -1.0));
+        let moved = aabb.transformed(&transform);
+        assert!((moved.min - Vec3::new(8.0, -2.0, -3.0)).abs().max_element() < 1e-5);
+        assert!((moved.max - Vec3::new(12.0, 2.0, 3.0)).abs().max_element() < 1e-5);
+
+        let empty = Aabb::empty();
+        assert_eq!(empty.transformed(&transform), empty);
+    }
+}
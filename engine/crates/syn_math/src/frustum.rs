@@ -1,10 +1,11 @@
 //! View frustum for culling operations.
 
-use glam::{Mat4, Vec3, Vec4};
 use crate::aabb::Aabb;
+use glam::{Mat4, Vec3, Vec4};
+use syn_reflect::Reflect;
 
 /// A plane defined by its normal and distance from origin.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Reflect)]
 pub struct Plane {
     /// The plane normal (pointing inward for frustum planes).
     pub normal: Vec3,
@@ -60,9 +61,21 @@ impl Frustum {
         for plane in &self.planes {
             // Find the corner of the AABB most in the direction of the plane normal
             let p = Vec3::new(
-                if plane.normal.x >= 0.0 { aabb.max.x } else { aabb.min.x },
-                if plane.normal.y >= 0.0 { aabb.max.y } else { aabb.min.y },
-                if plane.normal.z >= 0.0 { aabb.max.z } else { aabb.min.z },
+                if plane.normal.x >= 0.0 {
+                    aabb.max.x
+                } else {
+                    aabb.min.x
+                },
+                if plane.normal.y >= 0.0 {
+                    aabb.max.y
+                } else {
+                    aabb.min.y
+                },
+                if plane.normal.z >= 0.0 {
+                    aabb.max.z
+                } else {
+                    aabb.min.z
+                },
             );
 
             if plane.signed_distance(p) < 0.0 {
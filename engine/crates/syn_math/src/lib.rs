@@ -1 +1,26 @@
-//! `syn_math` - TODO: Implement
+//! `syn_math` - Math types for Synarion Engine.
+//!
+//! - [`Transform`] - Translation, rotation and scale, composed parent to child
+//! - [`Aabb`] - Axis-aligned bounding boxes
+//! - [`Ray`] - Rays intersecting boxes and planes
+//! - [`Frustum`] / [`Plane`] - View frustum culling
+//!
+//! Vectors, matrices and quaternions come from [`glam`], re-exported here.
+//! [`Transform`], [`Aabb`], [`Ray`] and [`Plane`] are
+//! [`Reflect`](syn_reflect::Reflect), as are the `glam` vectors and
+//! quaternions.
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+mod aabb;
+mod frustum;
+mod ray;
+mod transform;
+
+pub use aabb::Aabb;
+pub use frustum::{Frustum, Plane};
+pub use glam::{self, Mat4, Quat, Vec2, Vec3, Vec4};
+pub use ray::Ray;
+pub use transform::Transform;
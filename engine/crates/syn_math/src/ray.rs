@@ -1,10 +1,11 @@
 //! Ray implementation for raycasting.
 
-use glam::Vec3;
 use crate::aabb::Aabb;
+use glam::Vec3;
+use syn_reflect::Reflect;
 
 /// A ray defined by an origin and a direction.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
 pub struct Ray {
     /// The origin of the ray.
     pub origin: Vec3,
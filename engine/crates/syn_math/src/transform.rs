@@ -1,11 +1,12 @@
 //! Hierarchical transform component.
 
 use glam::{Mat4, Quat, Vec3};
+use syn_reflect::Reflect;
 
 /// A transform representing position, rotation, and scale.
 ///
 /// This is the fundamental component for positioning objects in 3D space.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
 pub struct Transform {
     /// The position in local space.
     pub translation: Vec3,
@@ -65,18 +66,21 @@ impl Transform {
     }
 
     /// Returns a new transform with the given translation.
+    #[must_use]
     pub fn with_translation(mut self, translation: Vec3) -> Self {
         self.translation = translation;
         self
     }
 
     /// Returns a new transform with the given rotation.
+    #[must_use]
     pub fn with_rotation(mut self, rotation: Quat) -> Self {
         self.rotation = rotation;
         self
     }
 
     /// Returns a new transform with the given scale.
+    #[must_use]
     pub fn with_scale(mut self, scale: Vec3) -> Self {
         self.scale = scale;
         self
@@ -123,6 +127,7 @@ impl Transform {
     }
 
     /// Multiplies two transforms together (parent * child).
+    #[must_use]
     pub fn mul_transform(&self, child: &Transform) -> Transform {
         Transform {
             translation: self.transform_point(child.translation),
@@ -132,6 +137,7 @@ impl Transform {
     }
 
     /// Returns the inverse of this transform.
+    #[must_use]
     pub fn inverse(&self) -> Transform {
         let inv_rotation = self.rotation.inverse();
         let inv_scale = Vec3::ONE / self.scale;
@@ -143,6 +149,7 @@ impl Transform {
     }
 
     /// Linearly interpolates between two transforms.
+    #[must_use]
     pub fn lerp(&self, other: &Transform, t: f32) -> Transform {
         Transform {
             translation: self.translation.lerp(other.translation, t),
@@ -165,3 +172,24 @@ impl std::ops::Mul for Transform {
         self.mul_transform(&rhs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn_reflect::{GetPath, TypeInfo, TypeRegistry};
+
+    #[test]
+    fn transforms_are_reflected_by_path() {
+        let mut transform = Transform::from_translation(Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(transform.path::<f32>("translation.y"), Ok(&2.0));
+        transform.set_path("scale.x", 4.0f32).unwrap();
+        assert_eq!(transform.scale, Vec3::new(4.0, 1.0, 1.0));
+
+        let mut registry = TypeRegistry::new();
+        registry.register::<Transform>();
+        let Some(TypeInfo::Struct(info)) = registry.get(std::any::TypeId::of::<Quat>()) else {
+            panic!("`Quat` is registered as a struct");
+        };
+        assert_eq!(info.fields().len(), 4);
+    }
+}
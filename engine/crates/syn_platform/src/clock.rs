@@ -0,0 +1,501 @@
+//! Game clock with a fixed-timestep accumulator.
+//!
+//! [`GameClock`] is the single source of truth for time in a frame: variable
+//! render delta, number of fixed updates to run, interpolation alpha between
+//! fixed states, time scaling, pause and single-stepping, plus frame pacing
+//! statistics through [`FrameStats`].
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Largest accepted time scale, so that scaled frame times stay far from the
+/// range of [`Duration`].
+const MAX_TIME_SCALE: f64 = 1000.0;
+
+/// Configuration of a [`GameClock`].
+#[derive(Debug, Clone)]
+pub struct GameClockConfig {
+    /// Duration of one fixed update (physics, networking ticks).
+    pub fixed_timestep: Duration,
+    /// Longest frame time fed into the accumulator.
+    ///
+    /// Longer frames (debugger breaks, loading hitches) are clamped so the fixed
+    /// update loop never tries to catch up on an unbounded backlog.
+    pub max_frame_time: Duration,
+    /// Maximum number of fixed updates run in a single frame.
+    ///
+    /// Time left in the accumulator beyond this budget is dropped, which prevents
+    /// the "spiral of death" when fixed updates are slower than real time.
+    pub max_fixed_steps: u32,
+    /// Number of recent frames kept for pacing statistics.
+    pub stats_window: usize,
+    /// A frame counts as a hitch when it lasts longer than this factor times the
+    /// average frame time of the window.
+    pub hitch_factor: f64,
+}
+
+impl Default for GameClockConfig {
+    fn default() -> Self {
+        Self {
+            fixed_timestep: Duration::from_secs(1) / 60,
+            max_frame_time: Duration::from_millis(250),
+            max_fixed_steps: 8,
+            stats_window: 120,
+            hitch_factor: 2.0,
+        }
+    }
+}
+
+/// Frame pacing statistics over a rolling window of real frame times.
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    samples: VecDeque<Duration>,
+    capacity: usize,
+    hitch_factor: f64,
+    total_hitches: u64,
+}
+
+impl FrameStats {
+    /// Creates empty statistics over a window of `capacity` frames.
+    pub fn new(capacity: usize, hitch_factor: f64) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            hitch_factor,
+            total_hitches: 0,
+        }
+    }
+
+    /// Records the duration of a frame.
+    pub fn record(&mut self, frame_time: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.is_hitch(frame_time) {
+            self.total_hitches += 1;
+        }
+
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(frame_time);
+    }
+
+    /// Returns the number of frames in the window.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if no frame has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the average frame time over the window.
+    pub fn average(&self) -> Duration {
+        match u32::try_from(self.samples.len()) {
+            Ok(0) | Err(_) => Duration::ZERO,
+            Ok(count) => self.samples.iter().sum::<Duration>() / count,
+        }
+    }
+
+    /// Returns the longest frame time in the window.
+    pub fn max(&self) -> Duration {
+        self.samples.iter().copied().max().unwrap_or_default()
+    }
+
+    /// Returns the frame time below which `percentile` percent of the window falls.
+    ///
+    /// `percentile` is clamped to `0.0..=100.0`; `percentile(99.0)` gives the
+    /// classic "1% low" frame time.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+
+        // RATIONALE: the rank is within 0..len, which always fits in usize
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let rank =
+            ((percentile.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+        sorted[rank]
+    }
+
+    /// Returns the number of hitches currently in the window.
+    pub fn hitches(&self) -> usize {
+        let threshold = self.average().mul_f64(self.hitch_factor);
+        self.samples
+            .iter()
+            .filter(|&&time| time > threshold)
+            .count()
+    }
+
+    /// Returns the number of hitches recorded since the statistics were created.
+    ///
+    /// Each frame is judged against the average of the frames preceding it.
+    pub fn total_hitches(&self) -> u64 {
+        self.total_hitches
+    }
+
+    /// Returns `true` if a frame of this duration would be a hitch.
+    fn is_hitch(&self, frame_time: Duration) -> bool {
+        !self.samples.is_empty() && frame_time > self.average().mul_f64(self.hitch_factor)
+    }
+}
+
+/// Drives game time with a fixed-update accumulator.
+///
+/// Each frame, call [`tick`](Self::tick) (or [`advance`](Self::advance) with an
+/// explicit duration), run [`fixed_steps`](Self::fixed_steps) fixed updates, then
+/// render with [`alpha`](Self::alpha) to interpolate between the last two fixed
+/// states.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use syn_platform::{GameClock, GameClockConfig};
+///
+/// let mut clock = GameClock::new(GameClockConfig {
+///     fixed_timestep: Duration::from_millis(10),
+///     ..GameClockConfig::default()
+/// });
+///
+/// clock.advance(Duration::from_millis(25));
+///
+/// assert_eq!(clock.fixed_steps(), 2);
+/// assert!((clock.alpha() - 0.5).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameClock {
+    config: GameClockConfig,
+    last_tick: Option<Instant>,
+    accumulator: Duration,
+    real_delta: Duration,
+    delta: Duration,
+    elapsed: Duration,
+    real_elapsed: Duration,
+    frame: u64,
+    fixed_steps: u32,
+    fixed_frame: u64,
+    time_scale: f64,
+    paused: bool,
+    step_requested: bool,
+    stats: FrameStats,
+}
+
+impl GameClock {
+    /// Creates a clock with the given configuration.
+    pub fn new(config: GameClockConfig) -> Self {
+        Self {
+            stats: FrameStats::new(config.stats_window, config.hitch_factor),
+            config,
+            last_tick: None,
+            accumulator: Duration::ZERO,
+            real_delta: Duration::ZERO,
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            real_elapsed: Duration::ZERO,
+            frame: 0,
+            fixed_steps: 0,
+            fixed_frame: 0,
+            time_scale: 1.0,
+            paused: false,
+            step_requested: false,
+        }
+    }
+
+    /// Starts a new frame, measuring real time since the previous tick.
+    ///
+    /// The first tick measures nothing and advances by zero.
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let real_delta = self
+            .last_tick
+            .map_or(Duration::ZERO, |last| now.duration_since(last));
+        self.last_tick = Some(now);
+        self.advance(real_delta);
+    }
+
+    /// Starts a new frame that lasted `real_delta` of wall-clock time.
+    ///
+    /// Use this instead of [`tick`](Self::tick) when time comes from elsewhere,
+    /// such as a replay or a test.
+    pub fn advance(&mut self, real_delta: Duration) {
+        self.frame += 1;
+        self.real_delta = real_delta;
+        self.real_elapsed += real_delta;
+        self.stats.record(real_delta);
+
+        let clamped = real_delta.min(self.config.max_frame_time);
+
+        if self.paused {
+            self.delta = Duration::ZERO;
+            self.fixed_steps = 0;
+
+            if std::mem::take(&mut self.step_requested) {
+                self.delta = self.config.fixed_timestep;
+                self.fixed_steps = 1;
+                self.accumulator = Duration::ZERO;
+            }
+        } else {
+            self.delta = clamped.mul_f64(self.time_scale);
+            self.accumulator += self.delta;
+
+            let step = self.config.fixed_timestep;
+            let mut steps = 0;
+            while self.accumulator >= step && steps < self.config.max_fixed_steps {
+                self.accumulator -= step;
+                steps += 1;
+            }
+            if self.accumulator >= step {
+                // Out of budget: drop the backlog instead of trying to catch up next frame.
+                self.accumulator = Duration::ZERO;
+            }
+            self.fixed_steps = steps;
+        }
+
+        self.elapsed += self.delta;
+        self.fixed_frame += u64::from(self.fixed_steps);
+    }
+
+    /// Returns the number of fixed updates to run this frame.
+    pub fn fixed_steps(&self) -> u32 {
+        self.fixed_steps
+    }
+
+    /// Returns the duration of one fixed update.
+    pub fn fixed_timestep(&self) -> Duration {
+        self.config.fixed_timestep
+    }
+
+    /// Returns the duration of one fixed update in seconds.
+    pub fn fixed_timestep_secs(&self) -> f32 {
+        self.config.fixed_timestep.as_secs_f32()
+    }
+
+    /// Returns how far the current time is between the last fixed update and the
+    /// next one, in `0.0..1.0`.
+    pub fn alpha(&self) -> f32 {
+        // RATIONALE: the accumulator is always shorter than one fixed step
+        #[allow(clippy::cast_possible_truncation)]
+        let alpha =
+            (self.accumulator.as_secs_f64() / self.config.fixed_timestep.as_secs_f64()) as f32;
+        alpha.min(1.0)
+    }
+
+    /// Returns the scaled game time elapsed this frame.
+    ///
+    /// This is zero while paused, except on a single-stepped frame.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Returns the scaled game time elapsed this frame in seconds.
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Returns the unscaled wall-clock duration of this frame.
+    pub fn real_delta(&self) -> Duration {
+        self.real_delta
+    }
+
+    /// Returns the total scaled game time.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the total wall-clock time fed into the clock.
+    pub fn real_elapsed(&self) -> Duration {
+        self.real_elapsed
+    }
+
+    /// Returns the number of frames since the clock was created.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the number of fixed updates run since the clock was created.
+    pub fn fixed_frame(&self) -> u64 {
+        self.fixed_frame
+    }
+
+    /// Returns the time scale applied to game time.
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Sets the time scale applied to game time (`0.5` is half speed).
+    ///
+    /// Values are clamped between zero and 1000; NaN counts as zero.
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        self.time_scale = if time_scale.is_nan() {
+            0.0
+        } else {
+            time_scale.clamp(0.0, MAX_TIME_SCALE)
+        };
+    }
+
+    /// Stops game time; real time and statistics keep running.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes game time.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.step_requested = false;
+    }
+
+    /// Returns `true` if game time is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Runs exactly one fixed update on the next frame while paused.
+    pub fn step(&mut self) {
+        if self.paused {
+            self.step_requested = true;
+        }
+    }
+
+    /// Returns the frame pacing statistics.
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self::new(GameClockConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn clock() -> GameClock {
+        GameClock::new(GameClockConfig {
+            fixed_timestep: ms(10),
+            max_frame_time: ms(100),
+            max_fixed_steps: 5,
+            ..GameClockConfig::default()
+        })
+    }
+
+    #[test]
+    fn accumulator_carries_remainder() {
+        let mut clock = clock();
+
+        clock.advance(ms(15));
+        assert_eq!(clock.fixed_steps(), 1);
+
+        clock.advance(ms(15));
+        assert_eq!(clock.fixed_steps(), 2);
+        assert_eq!(clock.fixed_frame(), 3);
+        assert!(clock.alpha() < 1e-6);
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        let mut clock = clock();
+
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(clock.fixed_steps(), 5);
+        assert_eq!(clock.delta(), ms(100));
+        assert!(clock.alpha() <= 1.0);
+
+        // The dropped backlog does not leak into the next frame.
+        clock.advance(ms(10));
+        assert_eq!(clock.fixed_steps(), 1);
+    }
+
+    #[test]
+    fn time_scale_slows_game_time() {
+        let mut clock = clock();
+        clock.set_time_scale(0.5);
+
+        clock.advance(ms(40));
+        assert_eq!(clock.delta(), ms(20));
+        assert_eq!(clock.fixed_steps(), 2);
+        assert_eq!(clock.real_delta(), ms(40));
+    }
+
+    #[test]
+    fn non_finite_time_scales_are_clamped() {
+        let mut clock = clock();
+        clock.set_time_scale(f64::INFINITY);
+        clock.advance(Duration::ZERO);
+        clock.advance(ms(10));
+        assert_eq!(clock.delta(), Duration::from_secs(10));
+
+        clock.set_time_scale(f64::NAN);
+        clock.advance(ms(10));
+        assert_eq!(clock.delta(), Duration::ZERO);
+
+        clock.set_time_scale(-2.0);
+        clock.advance(ms(10));
+        assert_eq!(clock.delta(), Duration::ZERO);
+    }
+
+    #[test]
+    fn pause_and_single_step() {
+        let mut clock = clock();
+        clock.pause();
+
+        clock.advance(ms(50));
+        assert_eq!(clock.fixed_steps(), 0);
+        assert_eq!(clock.delta(), Duration::ZERO);
+
+        clock.step();
+        clock.advance(ms(50));
+        assert_eq!(clock.fixed_steps(), 1);
+        assert_eq!(clock.delta(), ms(10));
+
+        clock.advance(ms(50));
+        assert_eq!(clock.fixed_steps(), 0);
+        assert_eq!(clock.elapsed(), ms(10));
+        assert_eq!(clock.real_elapsed(), ms(150));
+    }
+
+    #[test]
+    fn stats_report_average_percentiles_and_hitches() {
+        let mut stats = FrameStats::new(10, 2.0);
+        for _ in 0..9 {
+            stats.record(ms(10));
+        }
+        stats.record(ms(50));
+
+        assert_eq!(stats.len(), 10);
+        assert_eq!(stats.average(), ms(14));
+        assert_eq!(stats.percentile(50.0), ms(10));
+        assert_eq!(stats.percentile(100.0), ms(50));
+        assert_eq!(stats.max(), ms(50));
+        assert_eq!(stats.hitches(), 1);
+        assert_eq!(stats.total_hitches(), 1);
+    }
+
+    #[test]
+    fn stats_window_is_bounded() {
+        let mut stats = FrameStats::new(3, 2.0);
+        for millis in [1, 2, 3, 4] {
+            stats.record(ms(millis));
+        }
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats.percentile(0.0), ms(2));
+    }
+}
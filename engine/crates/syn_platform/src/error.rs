@@ -0,0 +1,52 @@
+//! Platform error types.
+
+use thiserror::Error;
+
+/// Errors raised by platform backends.
+#[derive(Error, Debug)]
+pub enum PlatformError {
+    /// The windowing event loop could not be created or failed while running.
+    #[error("Event loop error: {0}")]
+    EventLoop(#[from] winit::error::EventLoopError),
+
+    /// The operating system refused to create a window.
+    #[error("Window creation failed: {0}")]
+    WindowCreation(#[from] winit::error::OsError),
+}
+
+/// Result type for platform operations.
+pub type Result<T> = std::result::Result<T, PlatformError>;
+
+/// Errors raised while recording, loading or replaying platform events.
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    /// Reading or writing the recording failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The data does not start with the recording magic.
+    #[error("Not a recording file")]
+    InvalidMagic,
+
+    /// The recording was written by a newer, unsupported format version.
+    #[error("Unsupported recording version: {0}")]
+    UnsupportedVersion(u16),
+
+    /// The recording is malformed.
+    #[error("Corrupted recording: {0}")]
+    Corrupted(&'static str),
+
+    /// A checkpoint hash differs from the one captured while recording.
+    #[error("Replay diverged at frame {frame}: expected {expected:#018x}, got {actual:#018x}")]
+    Diverged {
+        /// Index of the frame whose checkpoint differs.
+        frame: usize,
+        /// Hash captured while recording.
+        expected: u64,
+        /// Hash computed during replay.
+        actual: u64,
+    },
+}
+
+/// Result type for recording and replay operations.
+pub type ReplayResult<T> = std::result::Result<T, ReplayError>;
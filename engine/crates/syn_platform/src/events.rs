@@ -1,16 +1,176 @@
 //! Event handling.
 
+use std::path::PathBuf;
+
+use crate::keys::{KeyboardInput, Modifiers};
+
 /// Platform events.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Event {
     /// Window was resized.
-    Resized { width: u32, height: u32 },
+    Resized {
+        /// New surface width in physical pixels.
+        width: u32,
+        /// New surface height in physical pixels.
+        height: u32,
+    },
+    /// The ratio between physical and logical pixels changed, for example when
+    /// the window moved to another monitor.
+    ScaleFactorChanged {
+        /// The new scale factor.
+        scale_factor: f64,
+    },
     /// Window close was requested.
     CloseRequested,
     /// Window gained or lost focus.
     Focused(bool),
     /// A redraw was requested.
     RedrawRequested,
+    /// The application was suspended; rendering surfaces must be released.
+    Suspended,
+    /// The application was resumed, including on startup.
+    Resumed,
+    /// A key was pressed or released.
+    KeyboardInput(KeyboardInput),
+    /// The state of the modifier keys changed.
+    ModifiersChanged(Modifiers),
+    /// Text was typed, after layout and dead key processing.
+    Text(String),
+    /// Input method editor (IME) composition event.
+    Ime(ImeEvent),
+    /// The cursor moved over the window.
+    CursorMoved {
+        /// Horizontal position in physical pixels from the left edge.
+        x: f64,
+        /// Vertical position in physical pixels from the top edge.
+        y: f64,
+    },
+    /// The cursor entered the window.
+    CursorEntered,
+    /// The cursor left the window.
+    CursorLeft,
+    /// A mouse button was pressed or released.
+    MouseInput {
+        /// The button.
+        button: MouseButton,
+        /// Whether it was pressed or released.
+        state: ButtonState,
+    },
+    /// The mouse wheel or touchpad scrolled.
+    MouseWheel(ScrollDelta),
+    /// Raw, unaccelerated mouse motion, reported even when the cursor is locked.
+    MouseMotion {
+        /// Horizontal motion in device units.
+        dx: f64,
+        /// Vertical motion in device units.
+        dy: f64,
+    },
+    /// A touch point changed.
+    Touch(Touch),
+    /// A file is being dragged over the window.
+    FileHovered(PathBuf),
+    /// A file was dropped onto the window.
+    FileDropped(PathBuf),
+    /// A hovered file left the window without being dropped.
+    FileHoverCancelled,
+}
+
+/// Whether a key or button is down or up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ButtonState {
+    /// The key or button went down.
+    Pressed,
+    /// The key or button went up.
+    Released,
+}
+
+impl ButtonState {
+    /// Returns `true` for [`ButtonState::Pressed`].
+    pub fn is_pressed(self) -> bool {
+        self == Self::Pressed
+    }
+}
+
+/// Mouse buttons as reported by the platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    /// Left mouse button.
+    Left,
+    /// Right mouse button.
+    Right,
+    /// Middle mouse button.
+    Middle,
+    /// Back side button.
+    Back,
+    /// Forward side button.
+    Forward,
+    /// Additional buttons.
+    Other(u16),
+}
+
+/// Scroll amount of a mouse wheel event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollDelta {
+    /// Scroll in lines, as reported by notched mouse wheels.
+    Lines {
+        /// Horizontal lines, positive to the right.
+        x: f32,
+        /// Vertical lines, positive away from the user.
+        y: f32,
+    },
+    /// Scroll in physical pixels, as reported by touchpads.
+    Pixels {
+        /// Horizontal pixels, positive to the right.
+        x: f64,
+        /// Vertical pixels, positive away from the user.
+        y: f64,
+    },
+}
+
+/// Phase of a touch point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TouchPhase {
+    /// The finger touched the surface.
+    Started,
+    /// The finger moved.
+    Moved,
+    /// The finger was lifted.
+    Ended,
+    /// The system cancelled the touch.
+    Cancelled,
+}
+
+/// A touch point update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    /// Identifier of the finger, unique while it touches the surface.
+    pub id: u64,
+    /// Phase of the touch.
+    pub phase: TouchPhase,
+    /// Horizontal position in physical pixels.
+    pub x: f64,
+    /// Vertical position in physical pixels.
+    pub y: f64,
+    /// Pressure in `0.0..=1.0`, if the device reports it.
+    pub force: Option<f64>,
+}
+
+/// Input method editor (IME) composition events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// The IME was enabled; text now arrives through [`ImeEvent::Commit`].
+    Enabled,
+    /// The composition text changed.
+    Preedit {
+        /// The text being composed.
+        text: String,
+        /// Byte range of the cursor or selection within `text`, if any.
+        cursor: Option<(usize, usize)>,
+    },
+    /// The composition was committed.
+    Commit(String),
+    /// The IME was disabled.
+    Disabled,
 }
 
 /// The event loop.
@@ -20,6 +180,10 @@ pub struct EventLoop {
 
 impl EventLoop {
     /// Creates a new event loop.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no display is available or an event loop already exists.
     pub fn new() -> Result<Self, winit::error::EventLoopError> {
         Ok(Self {
             inner: winit::event_loop::EventLoop::new()?,
@@ -30,6 +194,11 @@ impl EventLoop {
     pub fn inner(&self) -> &winit::event_loop::EventLoop<()> {
         &self.inner
     }
+
+    /// Returns the inner winit event loop mutably.
+    pub(crate) fn inner_mut(&mut self) -> &mut winit::event_loop::EventLoop<()> {
+        &mut self.inner
+    }
 }
 
 impl Default for EventLoop {
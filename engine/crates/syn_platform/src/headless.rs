@@ -0,0 +1,293 @@
+//! Window-less platform backend.
+//!
+//! [`HeadlessPlatform`] exposes a fixed-size virtual surface and delivers
+//! synthetic events, either queued directly or scripted for a given frame.
+//! Dedicated servers and CI runners use it where no display is available.
+
+use std::collections::BTreeMap;
+
+use crate::error::Result;
+use crate::events::Event;
+use crate::platform::Platform;
+
+/// Configuration of the headless virtual surface.
+#[derive(Debug, Clone)]
+pub struct HeadlessConfig {
+    /// Virtual surface width in pixels.
+    pub width: u32,
+    /// Virtual surface height in pixels.
+    pub height: u32,
+    /// Initial scale factor.
+    pub scale_factor: f64,
+    /// Number of frames after which the platform exits on its own, if any.
+    pub max_frames: Option<u64>,
+    /// Whether the first pump synthesizes the resume, resize and focus events of a
+    /// freshly created window. Disabled when replaying a recording, which already
+    /// contains them.
+    pub startup_events: bool,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            scale_factor: 1.0,
+            max_frames: None,
+            startup_events: true,
+        }
+    }
+}
+
+/// A platform backend without a window.
+///
+/// Unless disabled in [`HeadlessConfig::startup_events`], the first pump reports a
+/// resume, the initial surface size and focus, like a freshly created window would. Each following pump counts as one frame and delivers the
+/// events scheduled for it. Delivered [`Event::Resized`] events update the virtual
+/// surface, so [`surface_size`](Platform::surface_size) stays consistent with what
+/// the application has seen.
+///
+/// # Example
+///
+/// ```
+/// use syn_platform::{Event, HeadlessConfig, HeadlessPlatform, Platform};
+///
+/// let mut platform = HeadlessPlatform::new(HeadlessConfig::default());
+/// platform.schedule(1, Event::Resized { width: 640, height: 480 });
+///
+/// let mut events = Vec::new();
+/// platform.pump_events(&mut events).unwrap(); // frame 0
+/// platform.pump_events(&mut events).unwrap(); // frame 1
+///
+/// assert_eq!(platform.surface_size(), (640, 480));
+/// ```
+#[derive(Debug)]
+pub struct HeadlessPlatform {
+    config: HeadlessConfig,
+    size: (u32, u32),
+    scale_factor: f64,
+    focused: bool,
+    frame: u64,
+    script: BTreeMap<u64, Vec<Event>>,
+    queued: Vec<Event>,
+    redraw_requested: bool,
+    exiting: bool,
+}
+
+impl HeadlessPlatform {
+    /// Creates a headless platform with the given virtual surface.
+    pub fn new(config: HeadlessConfig) -> Self {
+        Self {
+            size: (config.width, config.height),
+            scale_factor: config.scale_factor,
+            config,
+            focused: false,
+            frame: 0,
+            script: BTreeMap::new(),
+            queued: Vec::new(),
+            redraw_requested: false,
+            exiting: false,
+        }
+    }
+
+    /// Schedules an event to be delivered on the given frame.
+    ///
+    /// Events scheduled for a frame that has already been pumped are delivered on
+    /// the next pump.
+    pub fn schedule(&mut self, frame: u64, event: Event) {
+        self.script.entry(frame).or_default().push(event);
+    }
+
+    /// Queues an event to be delivered on the next pump.
+    pub fn push_event(&mut self, event: Event) {
+        self.queued.push(event);
+    }
+
+    /// Returns the number of frames pumped so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns `true` if the virtual window currently has focus.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Applies the side effects of a delivered event to the virtual surface.
+    fn apply(&mut self, event: &Event) {
+        match *event {
+            Event::Resized { width, height } => self.size = (width, height),
+            Event::ScaleFactorChanged { scale_factor } => self.scale_factor = scale_factor,
+            Event::Focused(focused) => self.focused = focused,
+            _ => {}
+        }
+    }
+}
+
+impl Default for HeadlessPlatform {
+    fn default() -> Self {
+        Self::new(HeadlessConfig::default())
+    }
+}
+
+impl Platform for HeadlessPlatform {
+    fn pump_events(&mut self, events: &mut Vec<Event>) -> Result<()> {
+        if self.exiting {
+            return Ok(());
+        }
+
+        let start = events.len();
+
+        if self.frame == 0 && self.config.startup_events {
+            let (width, height) = self.size;
+            events.push(Event::Resumed);
+            events.push(Event::Resized { width, height });
+            events.push(Event::Focused(true));
+        }
+
+        let remaining = self.script.split_off(&(self.frame + 1));
+        for (_, scripted) in std::mem::replace(&mut self.script, remaining) {
+            events.extend(scripted);
+        }
+        events.append(&mut self.queued);
+
+        if std::mem::take(&mut self.redraw_requested) {
+            events.push(Event::RedrawRequested);
+        }
+
+        for event in &events[start..] {
+            self.apply(event);
+        }
+
+        self.frame += 1;
+        if self.config.max_frames.is_some_and(|max| self.frame >= max) {
+            self.exiting = true;
+        }
+
+        Ok(())
+    }
+
+    fn surface_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    fn exit(&mut self) {
+        self.exiting = true;
+    }
+
+    fn is_exiting(&self) -> bool {
+        self.exiting
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pump(platform: &mut HeadlessPlatform) -> Vec<Event> {
+        let mut events = Vec::new();
+        platform.pump_events(&mut events).unwrap();
+        events
+    }
+
+    #[test]
+    fn first_frame_reports_resume_surface_and_focus() {
+        let mut platform = HeadlessPlatform::new(HeadlessConfig {
+            width: 320,
+            height: 200,
+            ..HeadlessConfig::default()
+        });
+
+        assert_eq!(
+            pump(&mut platform),
+            vec![
+                Event::Resumed,
+                Event::Resized {
+                    width: 320,
+                    height: 200
+                },
+                Event::Focused(true),
+            ]
+        );
+        assert!(platform.is_focused());
+        assert!(pump(&mut platform).is_empty());
+    }
+
+    #[test]
+    fn scripted_events_arrive_on_their_frame() {
+        let mut platform = HeadlessPlatform::default();
+        platform.schedule(2, Event::Focused(false));
+        platform.schedule(
+            3,
+            Event::Resized {
+                width: 800,
+                height: 600,
+            },
+        );
+
+        pump(&mut platform);
+        assert!(pump(&mut platform).is_empty());
+        assert_eq!(pump(&mut platform), vec![Event::Focused(false)]);
+        assert!(!platform.is_focused());
+
+        pump(&mut platform);
+        assert_eq!(platform.surface_size(), (800, 600));
+        assert_eq!(platform.frame(), 4);
+    }
+
+    #[test]
+    fn scale_factor_follows_delivered_events() {
+        let mut platform = HeadlessPlatform::default();
+        platform.push_event(Event::ScaleFactorChanged { scale_factor: 2.0 });
+
+        assert!((platform.scale_factor() - 1.0).abs() < f64::EPSILON);
+        pump(&mut platform);
+        assert!((platform.scale_factor() - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn late_scheduled_events_are_not_lost() {
+        let mut platform = HeadlessPlatform::default();
+        pump(&mut platform);
+        pump(&mut platform);
+
+        platform.schedule(0, Event::CloseRequested);
+        assert_eq!(pump(&mut platform), vec![Event::CloseRequested]);
+    }
+
+    #[test]
+    fn redraw_is_delivered_once() {
+        let mut platform = HeadlessPlatform::default();
+        pump(&mut platform);
+
+        platform.request_redraw();
+        platform.push_event(Event::Focused(false));
+        assert_eq!(
+            pump(&mut platform),
+            vec![Event::Focused(false), Event::RedrawRequested]
+        );
+        assert!(pump(&mut platform).is_empty());
+    }
+
+    #[test]
+    fn exits_after_max_frames() {
+        let mut platform = HeadlessPlatform::new(HeadlessConfig {
+            max_frames: Some(2),
+            ..HeadlessConfig::default()
+        });
+
+        pump(&mut platform);
+        assert!(!platform.is_exiting());
+        pump(&mut platform);
+        assert!(platform.is_exiting());
+        assert!(pump(&mut platform).is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+//! Keyboard event types.
+//!
+//! Physical keys are identified by their USB HID usage ([`ScanCode`]), which
+//! does not depend on the keyboard layout. The character or named key the layout
+//! produces is reported separately as a [`LogicalKey`].
+
+use std::fmt;
+
+use crate::events::ButtonState;
+
+/// USB HID usage identifying a physical key, independent of the keyboard layout.
+///
+/// The value packs the HID usage page in the high 16 bits and the usage id in the
+/// low 16 bits. Most keys live on the keyboard page ([`ScanCode::keyboard`]); some
+/// media keys only exist on the consumer page ([`ScanCode::consumer`]).
+///
+/// # Example
+///
+/// ```
+/// use syn_platform::ScanCode;
+///
+/// // The key right of Tab: Q on QWERTY, A on AZERTY.
+/// let code = ScanCode::keyboard(0x14);
+///
+/// assert_eq!(code.page(), ScanCode::KEYBOARD_PAGE);
+/// assert_eq!(code.usage(), 0x14);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScanCode(pub u32);
+
+impl ScanCode {
+    /// HID usage page of keyboard keys.
+    pub const KEYBOARD_PAGE: u16 = 0x07;
+    /// HID usage page of consumer controls (media and browser keys).
+    pub const CONSUMER_PAGE: u16 = 0x0C;
+    /// HID usage page of generic desktop controls (system sleep and wake).
+    pub const GENERIC_DESKTOP_PAGE: u16 = 0x01;
+    /// A key the platform could not identify.
+    pub const UNIDENTIFIED: Self = Self(0);
+
+    /// Creates a scan code from a usage page and usage id.
+    #[inline]
+    pub const fn new(page: u16, usage: u16) -> Self {
+        Self(((page as u32) << 16) | usage as u32)
+    }
+
+    /// Creates a scan code on the keyboard usage page.
+    #[inline]
+    pub const fn keyboard(usage: u16) -> Self {
+        Self::new(Self::KEYBOARD_PAGE, usage)
+    }
+
+    /// Creates a scan code on the consumer usage page.
+    #[inline]
+    pub const fn consumer(usage: u16) -> Self {
+        Self::new(Self::CONSUMER_PAGE, usage)
+    }
+
+    /// Returns the HID usage page.
+    #[inline]
+    pub const fn page(self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+
+    /// Returns the HID usage id within its page.
+    #[inline]
+    pub const fn usage(self) -> u16 {
+        (self.0 & 0xFFFF) as u16
+    }
+
+    /// Returns `true` if the platform identified the key.
+    #[inline]
+    pub const fn is_identified(self) -> bool {
+        self.0 != Self::UNIDENTIFIED.0
+    }
+}
+
+impl fmt::Debug for ScanCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ScanCode({:#04x}:{:#04x})", self.page(), self.usage())
+    }
+}
+
+/// A non-printable key, as interpreted by the keyboard layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamedKey {
+    /// Enter or Return.
+    Enter,
+    /// Tab.
+    Tab,
+    /// Space bar.
+    Space,
+    /// Backspace.
+    Backspace,
+    /// Delete (forward delete).
+    Delete,
+    /// Escape.
+    Escape,
+    /// Insert.
+    Insert,
+    /// Home.
+    Home,
+    /// End.
+    End,
+    /// Page Up.
+    PageUp,
+    /// Page Down.
+    PageDown,
+    /// Up arrow.
+    ArrowUp,
+    /// Down arrow.
+    ArrowDown,
+    /// Left arrow.
+    ArrowLeft,
+    /// Right arrow.
+    ArrowRight,
+    /// Either Shift key.
+    Shift,
+    /// Either Control key.
+    Control,
+    /// Either Alt key.
+    Alt,
+    /// `AltGr`, used by many layouts to reach a third character level.
+    AltGraph,
+    /// Either Super key (Windows, Command).
+    Super,
+    /// Caps Lock.
+    CapsLock,
+    /// Num Lock.
+    NumLock,
+    /// Scroll Lock.
+    ScrollLock,
+    /// Print Screen.
+    PrintScreen,
+    /// Pause / Break.
+    Pause,
+    /// Context menu key.
+    ContextMenu,
+    /// Function key `F<n>`.
+    Function(u8),
+    /// Media play/pause.
+    MediaPlayPause,
+    /// Media stop.
+    MediaStop,
+    /// Media next track.
+    MediaTrackNext,
+    /// Media previous track.
+    MediaTrackPrevious,
+    /// Volume up.
+    AudioVolumeUp,
+    /// Volume down.
+    AudioVolumeDown,
+    /// Volume mute.
+    AudioVolumeMute,
+    /// Any other named key.
+    Other,
+}
+
+/// The meaning of a key under the active keyboard layout.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LogicalKey {
+    /// A key producing text, such as `"a"` or `"é"`.
+    Character(String),
+    /// A named, non-printable key.
+    Named(NamedKey),
+    /// A dead key waiting to be composed with the next one, such as `^`.
+    Dead(Option<char>),
+    /// The layout could not interpret the key.
+    Unidentified,
+}
+
+/// State of the keyboard modifiers.
+// RATIONALE: one flag per modifier reads better at call sites than a bit set
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    /// Either Shift key is held.
+    pub shift: bool,
+    /// Either Control key is held.
+    pub control: bool,
+    /// Either Alt key is held.
+    pub alt: bool,
+    /// Either Super key (Windows, Command) is held.
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    /// Returns `true` if no modifier is held.
+    pub fn is_empty(&self) -> bool {
+        !(self.shift || self.control || self.alt || self.super_key)
+    }
+}
+
+/// A key press or release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyboardInput {
+    /// The physical key, independent of the layout.
+    pub scan_code: ScanCode,
+    /// The key as interpreted by the layout.
+    pub logical_key: LogicalKey,
+    /// Whether the key was pressed or released.
+    pub state: ButtonState,
+    /// `true` for presses generated by key auto-repeat.
+    pub repeat: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_code_packs_page_and_usage() {
+        let code = ScanCode::consumer(0xCD);
+
+        assert_eq!(code.page(), ScanCode::CONSUMER_PAGE);
+        assert_eq!(code.usage(), 0xCD);
+        assert!(code.is_identified());
+        assert!(!ScanCode::UNIDENTIFIED.is_identified());
+    }
+
+    #[test]
+    fn modifiers_default_is_empty() {
+        assert!(Modifiers::default().is_empty());
+        assert!(
+            !Modifiers {
+                control: true,
+                ..Modifiers::default()
+            }
+            .is_empty()
+        );
+    }
+}
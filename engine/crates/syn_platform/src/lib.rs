@@ -1 +1,46 @@
-//! `syn_platform` - TODO: Implement
+//! `syn_platform` - Platform abstraction layer for Synarion Engine.
+//!
+//! This crate isolates the engine from the operating system:
+//! - [`Platform`] - Trait driving the application loop one frame at a time
+//! - [`WinitPlatform`] - Windowed backend built on `winit`
+//! - [`HeadlessPlatform`] - Window-less backend for dedicated servers and CI
+//! - [`Event`] - Backend-agnostic platform events (window, keyboard, text, mouse,
+//!   touch, drag-and-drop, lifecycle)
+//! - [`ScanCode`] - Layout-independent physical key identifiers (USB HID usages)
+//! - [`GameClock`] - Fixed-timestep game clock with time scaling and frame statistics
+//! - [`Timer`] - Elapsed time measurement
+//! - [`Recorder`] / [`ReplayPlayer`] - Event recording and deterministic replay
+//!
+//! Game code should depend on [`Platform`] rather than on a concrete backend, so the
+//! same loop can run with or without a display.
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+mod clock;
+mod error;
+mod events;
+mod headless;
+mod keys;
+mod platform;
+mod recording;
+mod replay;
+mod time;
+mod translate;
+mod window;
+mod winit_backend;
+
+pub use clock::{FrameStats, GameClock, GameClockConfig};
+pub use error::{PlatformError, ReplayError, ReplayResult, Result};
+pub use events::{
+    ButtonState, Event, EventLoop, ImeEvent, MouseButton, ScrollDelta, Touch, TouchPhase,
+};
+pub use headless::{HeadlessConfig, HeadlessPlatform};
+pub use keys::{KeyboardInput, LogicalKey, Modifiers, NamedKey, ScanCode};
+pub use platform::Platform;
+pub use recording::{RecordedFrame, Recording};
+pub use replay::{Recorder, ReplayMode, ReplayPlayer};
+pub use time::{Duration, Instant, Timer};
+pub use window::{Window, WindowConfig};
+pub use winit_backend::WinitPlatform;
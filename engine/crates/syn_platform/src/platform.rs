@@ -0,0 +1,73 @@
+//! The platform abstraction driving the application loop.
+
+use crate::error::Result;
+use crate::events::Event;
+use crate::window::Window;
+
+/// A platform backend that produces [`Event`]s for the application loop.
+///
+/// The loop is pull-based: the application calls [`pump_events`](Self::pump_events)
+/// once per frame, handles the returned events, then simulates and renders. This
+/// keeps control flow identical between the windowed backend and the headless one,
+/// so everything above the platform layer can run without a display.
+///
+/// # Example
+///
+/// ```
+/// use syn_platform::{Event, HeadlessConfig, HeadlessPlatform, Platform};
+///
+/// fn run(platform: &mut impl Platform) -> syn_platform::Result<u32> {
+///     let mut events = Vec::new();
+///     let mut frames = 0;
+///
+///     while !platform.is_exiting() {
+///         events.clear();
+///         platform.pump_events(&mut events)?;
+///
+///         for event in &events {
+///             if matches!(event, Event::CloseRequested) {
+///                 platform.exit();
+///             }
+///         }
+///         frames += 1;
+///     }
+///
+///     Ok(frames)
+/// }
+///
+/// let mut platform = HeadlessPlatform::new(HeadlessConfig::default());
+/// platform.schedule(2, Event::CloseRequested);
+///
+/// assert_eq!(run(&mut platform).unwrap(), 3);
+/// ```
+pub trait Platform {
+    /// Collects the events of one frame, appending them to `events`.
+    ///
+    /// Never blocks waiting for input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend failed to create its surface or its event
+    /// loop stopped unexpectedly.
+    fn pump_events(&mut self, events: &mut Vec<Event>) -> Result<()>;
+
+    /// Returns the current surface size in physical pixels.
+    fn surface_size(&self) -> (u32, u32);
+
+    /// Returns the ratio between physical and logical pixels.
+    fn scale_factor(&self) -> f64;
+
+    /// Asks for an [`Event::RedrawRequested`] to be delivered on a following pump.
+    fn request_redraw(&mut self);
+
+    /// Asks the platform to shut down.
+    fn exit(&mut self);
+
+    /// Returns `true` once the platform is shutting down.
+    fn is_exiting(&self) -> bool;
+
+    /// Returns the native window, if this backend has one.
+    fn window(&self) -> Option<&Window> {
+        None
+    }
+}
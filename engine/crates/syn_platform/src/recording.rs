@@ -0,0 +1,804 @@
+//! Recorded platform event streams and their file format.
+//!
+//! A [`Recording`] stores, for every frame, the wall-clock frame time, the
+//! platform events delivered during that frame and an optional checkpoint hash.
+//!
+//! # File format
+//!
+//! All integers are little-endian; `varint` is unsigned LEB128.
+//!
+//! | Field         | Encoding                                   |
+//! |---------------|--------------------------------------------|
+//! | magic         | `b"SYNREC"`                                |
+//! | version       | `u16`, currently [`Recording::VERSION`]    |
+//! | surface       | `varint` width, `varint` height            |
+//! | scale factor  | `f64`                                      |
+//! | frame count   | `varint`                                   |
+//! | frames        | delta nanoseconds `varint`, checkpoint flag `u8` (+ `u64`), event count `varint`, events |
+//!
+//! Each event starts with a one-byte tag followed by its fields.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::{ReplayError, ReplayResult};
+use crate::events::{ButtonState, Event, ImeEvent, MouseButton, ScrollDelta, Touch, TouchPhase};
+use crate::keys::{KeyboardInput, LogicalKey, Modifiers, NamedKey, ScanCode};
+
+/// One recorded frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordedFrame {
+    /// Wall-clock duration of the frame.
+    pub delta: Duration,
+    /// Events delivered during the frame, in order.
+    pub events: Vec<Event>,
+    /// Checkpoint hash captured at the end of the frame, such as a world hash.
+    pub checkpoint: Option<u64>,
+}
+
+/// A recorded stream of platform events with frame timings.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use syn_platform::{Event, RecordedFrame, Recording};
+///
+/// let mut recording = Recording::new((1280, 720), 1.0);
+/// recording.frames.push(RecordedFrame {
+///     delta: Duration::from_millis(16),
+///     events: vec![Event::Focused(true)],
+///     checkpoint: Some(0xC0FFEE),
+/// });
+///
+/// let mut bytes = Vec::new();
+/// recording.write_to(&mut bytes).unwrap();
+///
+/// assert_eq!(Recording::read_from(bytes.as_slice()).unwrap(), recording);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    /// Surface size when recording started, in physical pixels.
+    pub surface_size: (u32, u32),
+    /// Scale factor when recording started.
+    pub scale_factor: f64,
+    /// Recorded frames, in order.
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    /// File magic identifying a recording.
+    pub const MAGIC: [u8; 6] = *b"SYNREC";
+    /// Current file format version.
+    pub const VERSION: u16 = 1;
+
+    /// Creates an empty recording of a surface with the given size and scale.
+    pub fn new(surface_size: (u32, u32), scale_factor: f64) -> Self {
+        Self {
+            surface_size,
+            scale_factor,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Returns the number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if no frame was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Saves the recording to a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written.
+    pub fn save(&self, path: impl AsRef<Path>) -> ReplayResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a recording from a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a recording, was
+    /// written by a newer version or is corrupted.
+    pub fn load(path: impl AsRef<Path>) -> ReplayResult<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Encodes the recording into a writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_to(&self, writer: impl Write) -> ReplayResult<()> {
+        let mut encoder = Encoder { writer };
+        encoder.bytes(&Self::MAGIC)?;
+        encoder.bytes(&Self::VERSION.to_le_bytes())?;
+        encoder.varint(u64::from(self.surface_size.0))?;
+        encoder.varint(u64::from(self.surface_size.1))?;
+        encoder.f64(self.scale_factor)?;
+        encoder.len(self.frames.len())?;
+
+        for frame in &self.frames {
+            // RATIONALE: frames lasting more than 584 years are not supported
+            #[allow(clippy::cast_possible_truncation)]
+            encoder.varint(frame.delta.as_nanos() as u64)?;
+            match frame.checkpoint {
+                Some(hash) => {
+                    encoder.u8(1)?;
+                    encoder.bytes(&hash.to_le_bytes())?;
+                }
+                None => encoder.u8(0)?,
+            }
+            encoder.len(frame.events.len())?;
+            for event in &frame.events {
+                encoder.event(event)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decodes a recording from a reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, the data is not a recording, was
+    /// written by a newer version or is corrupted.
+    pub fn read_from(reader: impl Read) -> ReplayResult<Self> {
+        let mut decoder = Decoder { reader };
+
+        let mut magic = [0; 6];
+        decoder.fill(&mut magic)?;
+        if magic != Self::MAGIC {
+            return Err(ReplayError::InvalidMagic);
+        }
+
+        let version = u16::from_le_bytes(decoder.array()?);
+        if version == 0 || version > Self::VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let surface_size = (decoder.u32()?, decoder.u32()?);
+        let scale_factor = decoder.f64()?;
+        let frame_count = decoder.len()?;
+
+        let mut frames = Vec::with_capacity(frame_count.min(1 << 16));
+        for _ in 0..frame_count {
+            let delta = Duration::from_nanos(decoder.varint()?);
+            let checkpoint = match decoder.u8()? {
+                0 => None,
+                1 => Some(u64::from_le_bytes(decoder.array()?)),
+                _ => return Err(ReplayError::Corrupted("invalid checkpoint flag")),
+            };
+            let event_count = decoder.len()?;
+            let mut events = Vec::with_capacity(event_count.min(1 << 10));
+            for _ in 0..event_count {
+                events.push(decoder.event()?);
+            }
+            frames.push(RecordedFrame {
+                delta,
+                events,
+                checkpoint,
+            });
+        }
+
+        Ok(Self {
+            surface_size,
+            scale_factor,
+            frames,
+        })
+    }
+}
+
+/// Named keys in encoding order; [`NamedKey::Function`] is encoded separately.
+const NAMED_KEYS: [NamedKey; 34] = [
+    NamedKey::Enter,
+    NamedKey::Tab,
+    NamedKey::Space,
+    NamedKey::Backspace,
+    NamedKey::Delete,
+    NamedKey::Escape,
+    NamedKey::Insert,
+    NamedKey::Home,
+    NamedKey::End,
+    NamedKey::PageUp,
+    NamedKey::PageDown,
+    NamedKey::ArrowUp,
+    NamedKey::ArrowDown,
+    NamedKey::ArrowLeft,
+    NamedKey::ArrowRight,
+    NamedKey::Shift,
+    NamedKey::Control,
+    NamedKey::Alt,
+    NamedKey::AltGraph,
+    NamedKey::Super,
+    NamedKey::CapsLock,
+    NamedKey::NumLock,
+    NamedKey::ScrollLock,
+    NamedKey::PrintScreen,
+    NamedKey::Pause,
+    NamedKey::ContextMenu,
+    NamedKey::MediaPlayPause,
+    NamedKey::MediaStop,
+    NamedKey::MediaTrackNext,
+    NamedKey::MediaTrackPrevious,
+    NamedKey::AudioVolumeUp,
+    NamedKey::AudioVolumeDown,
+    NamedKey::AudioVolumeMute,
+    NamedKey::Other,
+];
+
+/// Tag of [`NamedKey::Function`] in the named key encoding.
+const FUNCTION_KEY_TAG: u8 = 0xFF;
+
+struct Encoder<W> {
+    writer: W,
+}
+
+impl<W: Write> Encoder<W> {
+    fn bytes(&mut self, bytes: &[u8]) -> ReplayResult<()> {
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+
+    fn u8(&mut self, value: u8) -> ReplayResult<()> {
+        self.bytes(&[value])
+    }
+
+    fn bool(&mut self, value: bool) -> ReplayResult<()> {
+        self.u8(u8::from(value))
+    }
+
+    fn varint(&mut self, mut value: u64) -> ReplayResult<()> {
+        loop {
+            // RATIONALE: masked to 7 bits
+            #[allow(clippy::cast_possible_truncation)]
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                return self.u8(byte);
+            }
+            self.u8(byte | 0x80)?;
+        }
+    }
+
+    fn len(&mut self, len: usize) -> ReplayResult<()> {
+        self.varint(len as u64)
+    }
+
+    fn f32(&mut self, value: f32) -> ReplayResult<()> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn f64(&mut self, value: f64) -> ReplayResult<()> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn str(&mut self, value: &str) -> ReplayResult<()> {
+        self.len(value.len())?;
+        self.bytes(value.as_bytes())
+    }
+
+    fn state(&mut self, state: ButtonState) -> ReplayResult<()> {
+        self.bool(state.is_pressed())
+    }
+
+    fn event(&mut self, event: &Event) -> ReplayResult<()> {
+        match event {
+            Event::Resized { width, height } => {
+                self.u8(0)?;
+                self.varint(u64::from(*width))?;
+                self.varint(u64::from(*height))
+            }
+            Event::ScaleFactorChanged { scale_factor } => {
+                self.u8(1)?;
+                self.f64(*scale_factor)
+            }
+            Event::CloseRequested => self.u8(2),
+            Event::Focused(focused) => {
+                self.u8(3)?;
+                self.bool(*focused)
+            }
+            Event::RedrawRequested => self.u8(4),
+            Event::Suspended => self.u8(5),
+            Event::Resumed => self.u8(6),
+            Event::KeyboardInput(input) => {
+                self.u8(7)?;
+                self.varint(u64::from(input.scan_code.0))?;
+                self.logical_key(&input.logical_key)?;
+                self.state(input.state)?;
+                self.bool(input.repeat)
+            }
+            Event::ModifiersChanged(modifiers) => {
+                self.u8(8)?;
+                self.u8(u8::from(modifiers.shift)
+                    | u8::from(modifiers.control) << 1
+                    | u8::from(modifiers.alt) << 2
+                    | u8::from(modifiers.super_key) << 3)
+            }
+            Event::Text(text) => {
+                self.u8(9)?;
+                self.str(text)
+            }
+            Event::Ime(ime) => {
+                self.u8(10)?;
+                self.ime(ime)
+            }
+            Event::CursorMoved { x, y } => {
+                self.u8(11)?;
+                self.f64(*x)?;
+                self.f64(*y)
+            }
+            Event::CursorEntered => self.u8(12),
+            Event::CursorLeft => self.u8(13),
+            Event::MouseInput { button, state } => {
+                self.u8(14)?;
+                self.mouse_button(*button)?;
+                self.state(*state)
+            }
+            Event::MouseWheel(ScrollDelta::Lines { x, y }) => {
+                self.u8(15)?;
+                self.f32(*x)?;
+                self.f32(*y)
+            }
+            Event::MouseWheel(ScrollDelta::Pixels { x, y }) => {
+                self.u8(16)?;
+                self.f64(*x)?;
+                self.f64(*y)
+            }
+            Event::MouseMotion { dx, dy } => {
+                self.u8(17)?;
+                self.f64(*dx)?;
+                self.f64(*dy)
+            }
+            Event::Touch(touch) => {
+                self.u8(18)?;
+                self.touch(touch)
+            }
+            Event::FileHovered(path) => {
+                self.u8(19)?;
+                self.str(&path.to_string_lossy())
+            }
+            Event::FileDropped(path) => {
+                self.u8(20)?;
+                self.str(&path.to_string_lossy())
+            }
+            Event::FileHoverCancelled => self.u8(21),
+        }
+    }
+
+    fn logical_key(&mut self, key: &LogicalKey) -> ReplayResult<()> {
+        match key {
+            LogicalKey::Character(text) => {
+                self.u8(0)?;
+                self.str(text)
+            }
+            LogicalKey::Named(NamedKey::Function(number)) => {
+                self.u8(1)?;
+                self.u8(FUNCTION_KEY_TAG)?;
+                self.u8(*number)
+            }
+            LogicalKey::Named(named) => {
+                let index = NAMED_KEYS
+                    .iter()
+                    .position(|candidate| candidate == named)
+                    .unwrap_or(NAMED_KEYS.len() - 1);
+                self.u8(1)?;
+                // RATIONALE: NAMED_KEYS has fewer than 256 entries
+                #[allow(clippy::cast_possible_truncation)]
+                self.u8(index as u8)
+            }
+            LogicalKey::Dead(accent) => {
+                self.u8(2)?;
+                self.varint(accent.map_or(0, |c| u64::from(c) + 1))
+            }
+            LogicalKey::Unidentified => self.u8(3),
+        }
+    }
+
+    fn ime(&mut self, ime: &ImeEvent) -> ReplayResult<()> {
+        match ime {
+            ImeEvent::Enabled => self.u8(0),
+            ImeEvent::Preedit { text, cursor } => {
+                self.u8(1)?;
+                self.str(text)?;
+                match cursor {
+                    Some((start, end)) => {
+                        self.u8(1)?;
+                        self.len(*start)?;
+                        self.len(*end)
+                    }
+                    None => self.u8(0),
+                }
+            }
+            ImeEvent::Commit(text) => {
+                self.u8(2)?;
+                self.str(text)
+            }
+            ImeEvent::Disabled => self.u8(3),
+        }
+    }
+
+    fn mouse_button(&mut self, button: MouseButton) -> ReplayResult<()> {
+        match button {
+            MouseButton::Left => self.u8(0),
+            MouseButton::Right => self.u8(1),
+            MouseButton::Middle => self.u8(2),
+            MouseButton::Back => self.u8(3),
+            MouseButton::Forward => self.u8(4),
+            MouseButton::Other(id) => {
+                self.u8(5)?;
+                self.varint(u64::from(id))
+            }
+        }
+    }
+
+    fn touch(&mut self, touch: &Touch) -> ReplayResult<()> {
+        self.varint(touch.id)?;
+        self.u8(match touch.phase {
+            TouchPhase::Started => 0,
+            TouchPhase::Moved => 1,
+            TouchPhase::Ended => 2,
+            TouchPhase::Cancelled => 3,
+        })?;
+        self.f64(touch.x)?;
+        self.f64(touch.y)?;
+        match touch.force {
+            Some(force) => {
+                self.u8(1)?;
+                self.f64(force)
+            }
+            None => self.u8(0),
+        }
+    }
+}
+
+struct Decoder<R> {
+    reader: R,
+}
+
+impl<R: Read> Decoder<R> {
+    fn fill(&mut self, buffer: &mut [u8]) -> ReplayResult<()> {
+        self.reader.read_exact(buffer)?;
+        Ok(())
+    }
+
+    fn array<const N: usize>(&mut self) -> ReplayResult<[u8; N]> {
+        let mut buffer = [0; N];
+        self.fill(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn u8(&mut self) -> ReplayResult<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn bool(&mut self) -> ReplayResult<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ReplayError::Corrupted("invalid boolean")),
+        }
+    }
+
+    fn varint(&mut self) -> ReplayResult<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ReplayError::Corrupted("varint overflow"))
+    }
+
+    fn u32(&mut self) -> ReplayResult<u32> {
+        u32::try_from(self.varint()?).map_err(|_| ReplayError::Corrupted("value out of range"))
+    }
+
+    fn len(&mut self) -> ReplayResult<usize> {
+        usize::try_from(self.varint()?).map_err(|_| ReplayError::Corrupted("length out of range"))
+    }
+
+    fn f32(&mut self) -> ReplayResult<f32> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn f64(&mut self) -> ReplayResult<f64> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    fn string(&mut self) -> ReplayResult<String> {
+        let len = self.len()?;
+        let mut bytes = Vec::new();
+        (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(ReplayError::Corrupted("truncated string"));
+        }
+        String::from_utf8(bytes).map_err(|_| ReplayError::Corrupted("invalid UTF-8"))
+    }
+
+    fn state(&mut self) -> ReplayResult<ButtonState> {
+        Ok(if self.bool()? {
+            ButtonState::Pressed
+        } else {
+            ButtonState::Released
+        })
+    }
+
+    fn event(&mut self) -> ReplayResult<Event> {
+        Ok(match self.u8()? {
+            0 => Event::Resized {
+                width: self.u32()?,
+                height: self.u32()?,
+            },
+            1 => Event::ScaleFactorChanged {
+                scale_factor: self.f64()?,
+            },
+            2 => Event::CloseRequested,
+            3 => Event::Focused(self.bool()?),
+            4 => Event::RedrawRequested,
+            5 => Event::Suspended,
+            6 => Event::Resumed,
+            7 => Event::KeyboardInput(KeyboardInput {
+                scan_code: ScanCode(self.u32()?),
+                logical_key: self.logical_key()?,
+                state: self.state()?,
+                repeat: self.bool()?,
+            }),
+            8 => {
+                let bits = self.u8()?;
+                Event::ModifiersChanged(Modifiers {
+                    shift: bits & 1 != 0,
+                    control: bits & 2 != 0,
+                    alt: bits & 4 != 0,
+                    super_key: bits & 8 != 0,
+                })
+            }
+            9 => Event::Text(self.string()?),
+            10 => Event::Ime(self.ime()?),
+            11 => Event::CursorMoved {
+                x: self.f64()?,
+                y: self.f64()?,
+            },
+            12 => Event::CursorEntered,
+            13 => Event::CursorLeft,
+            14 => Event::MouseInput {
+                button: self.mouse_button()?,
+                state: self.state()?,
+            },
+            15 => Event::MouseWheel(ScrollDelta::Lines {
+                x: self.f32()?,
+                y: self.f32()?,
+            }),
+            16 => Event::MouseWheel(ScrollDelta::Pixels {
+                x: self.f64()?,
+                y: self.f64()?,
+            }),
+            17 => Event::MouseMotion {
+                dx: self.f64()?,
+                dy: self.f64()?,
+            },
+            18 => Event::Touch(self.touch()?),
+            19 => Event::FileHovered(PathBuf::from(self.string()?)),
+            20 => Event::FileDropped(PathBuf::from(self.string()?)),
+            21 => Event::FileHoverCancelled,
+            _ => return Err(ReplayError::Corrupted("unknown event tag")),
+        })
+    }
+
+    fn logical_key(&mut self) -> ReplayResult<LogicalKey> {
+        Ok(match self.u8()? {
+            0 => LogicalKey::Character(self.string()?),
+            1 => match self.u8()? {
+                FUNCTION_KEY_TAG => LogicalKey::Named(NamedKey::Function(self.u8()?)),
+                index => LogicalKey::Named(
+                    *NAMED_KEYS
+                        .get(usize::from(index))
+                        .ok_or(ReplayError::Corrupted("unknown named key"))?,
+                ),
+            },
+            2 => match self.varint()? {
+                0 => LogicalKey::Dead(None),
+                code => LogicalKey::Dead(Some(
+                    u32::try_from(code - 1)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or(ReplayError::Corrupted("invalid dead key"))?,
+                )),
+            },
+            3 => LogicalKey::Unidentified,
+            _ => return Err(ReplayError::Corrupted("unknown logical key tag")),
+        })
+    }
+
+    fn ime(&mut self) -> ReplayResult<ImeEvent> {
+        Ok(match self.u8()? {
+            0 => ImeEvent::Enabled,
+            1 => {
+                let text = self.string()?;
+                let cursor = if self.bool()? {
+                    Some((self.len()?, self.len()?))
+                } else {
+                    None
+                };
+                ImeEvent::Preedit { text, cursor }
+            }
+            2 => ImeEvent::Commit(self.string()?),
+            3 => ImeEvent::Disabled,
+            _ => return Err(ReplayError::Corrupted("unknown IME tag")),
+        })
+    }
+
+    fn mouse_button(&mut self) -> ReplayResult<MouseButton> {
+        Ok(match self.u8()? {
+            0 => MouseButton::Left,
+            1 => MouseButton::Right,
+            2 => MouseButton::Middle,
+            3 => MouseButton::Back,
+            4 => MouseButton::Forward,
+            5 => MouseButton::Other(
+                u16::try_from(self.varint()?)
+                    .map_err(|_| ReplayError::Corrupted("invalid mouse button"))?,
+            ),
+            _ => return Err(ReplayError::Corrupted("unknown mouse button tag")),
+        })
+    }
+
+    fn touch(&mut self) -> ReplayResult<Touch> {
+        let id = self.varint()?;
+        let phase = match self.u8()? {
+            0 => TouchPhase::Started,
+            1 => TouchPhase::Moved,
+            2 => TouchPhase::Ended,
+            3 => TouchPhase::Cancelled,
+            _ => return Err(ReplayError::Corrupted("unknown touch phase")),
+        };
+        let x = self.f64()?;
+        let y = self.f64()?;
+        let force = if self.bool()? {
+            Some(self.f64()?)
+        } else {
+            None
+        };
+        Ok(Touch {
+            id,
+            phase,
+            x,
+            y,
+            force,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_event() -> Vec<Event> {
+        vec![
+            Event::Resized {
+                width: 1920,
+                height: 1080,
+            },
+            Event::ScaleFactorChanged { scale_factor: 1.5 },
+            Event::CloseRequested,
+            Event::Focused(true),
+            Event::RedrawRequested,
+            Event::Suspended,
+            Event::Resumed,
+            Event::KeyboardInput(KeyboardInput {
+                scan_code: ScanCode::keyboard(0x14),
+                logical_key: LogicalKey::Character("a".into()),
+                state: ButtonState::Pressed,
+                repeat: true,
+            }),
+            Event::KeyboardInput(KeyboardInput {
+                scan_code: ScanCode::keyboard(0x3A),
+                logical_key: LogicalKey::Named(NamedKey::Function(1)),
+                state: ButtonState::Released,
+                repeat: false,
+            }),
+            Event::KeyboardInput(KeyboardInput {
+                scan_code: ScanCode::keyboard(0x2F),
+                logical_key: LogicalKey::Dead(Some('^')),
+                state: ButtonState::Pressed,
+                repeat: false,
+            }),
+            Event::ModifiersChanged(Modifiers {
+                control: true,
+                super_key: true,
+                ..Modifiers::default()
+            }),
+            Event::Text("é".into()),
+            Event::Ime(ImeEvent::Preedit {
+                text: "にほ".into(),
+                cursor: Some((0, 3)),
+            }),
+            Event::CursorMoved { x: 1.5, y: -2.0 },
+            Event::CursorEntered,
+            Event::CursorLeft,
+            Event::MouseInput {
+                button: MouseButton::Other(9),
+                state: ButtonState::Pressed,
+            },
+            Event::MouseWheel(ScrollDelta::Lines { x: 0.0, y: 1.0 }),
+            Event::MouseWheel(ScrollDelta::Pixels { x: 3.0, y: 4.0 }),
+            Event::MouseMotion { dx: 0.25, dy: 8.0 },
+            Event::Touch(Touch {
+                id: 3,
+                phase: TouchPhase::Moved,
+                x: 10.0,
+                y: 20.0,
+                force: Some(0.5),
+            }),
+            Event::FileHovered(PathBuf::from("a.png")),
+            Event::FileDropped(PathBuf::from("b.png")),
+            Event::FileHoverCancelled,
+        ]
+    }
+
+    #[test]
+    fn round_trips_every_event() {
+        let mut recording = Recording::new((800, 600), 2.0);
+        recording.frames.push(RecordedFrame {
+            delta: Duration::from_micros(16_667),
+            events: every_event(),
+            checkpoint: None,
+        });
+        recording.frames.push(RecordedFrame {
+            delta: Duration::from_millis(40),
+            events: Vec::new(),
+            checkpoint: Some(u64::MAX),
+        });
+
+        let mut bytes = Vec::new();
+        recording.write_to(&mut bytes).unwrap();
+
+        assert_eq!(Recording::read_from(bytes.as_slice()).unwrap(), recording);
+    }
+
+    #[test]
+    fn rejects_foreign_and_future_files() {
+        assert!(matches!(
+            Recording::read_from(&b"NOTREC\x01\x00"[..]),
+            Err(ReplayError::InvalidMagic)
+        ));
+
+        let mut bytes = Recording::MAGIC.to_vec();
+        bytes.extend_from_slice(&(Recording::VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            Recording::read_from(bytes.as_slice()),
+            Err(ReplayError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let mut recording = Recording::new((1, 1), 1.0);
+        recording.frames.push(RecordedFrame {
+            events: vec![Event::Text("hello".into())],
+            ..RecordedFrame::default()
+        });
+
+        let mut bytes = Vec::new();
+        recording.write_to(&mut bytes).unwrap();
+        bytes.truncate(bytes.len() - 2);
+
+        assert!(Recording::read_from(bytes.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,392 @@
+//! Recording and deterministic replay of platform events.
+//!
+//! [`Recorder`] wraps any [`Platform`] and captures every pumped frame into a
+//! [`Recording`]. [`ReplayPlayer`] feeds a recording back, frame by frame, through
+//! a [`HeadlessPlatform`], so bug reports from QA reproduce without a window.
+//!
+//! For a replay to be frame-exact, the game must drive its
+//! [`GameClock`](crate::GameClock) from the recorded frame times: call
+//! [`GameClock::advance`](crate::GameClock::advance) with `frame_delta()` both
+//! while recording and while replaying, instead of
+//! [`GameClock::tick`](crate::GameClock::tick).
+
+use std::time::{Duration, Instant};
+
+use crate::error::{ReplayError, ReplayResult, Result};
+use crate::events::Event;
+use crate::headless::{HeadlessConfig, HeadlessPlatform};
+use crate::platform::Platform;
+use crate::recording::{RecordedFrame, Recording};
+use crate::window::Window;
+
+/// A platform wrapper that records every pumped frame.
+///
+/// # Example
+///
+/// ```
+/// use syn_platform::{HeadlessPlatform, Platform, Recorder};
+///
+/// let mut recorder = Recorder::new(HeadlessPlatform::default());
+/// let mut events = Vec::new();
+///
+/// recorder.pump_events(&mut events).unwrap();
+/// recorder.checkpoint(42);
+///
+/// let recording = recorder.into_recording();
+/// assert_eq!(recording.len(), 1);
+/// assert_eq!(recording.frames[0].checkpoint, Some(42));
+/// ```
+pub struct Recorder<P> {
+    platform: P,
+    recording: Recording,
+    last_pump: Option<Instant>,
+    frame_delta: Duration,
+}
+
+impl<P: Platform> Recorder<P> {
+    /// Starts recording the frames pumped from `platform`.
+    pub fn new(platform: P) -> Self {
+        let recording = Recording::new(platform.surface_size(), platform.scale_factor());
+        Self {
+            platform,
+            recording,
+            last_pump: None,
+            frame_delta: Duration::ZERO,
+        }
+    }
+
+    /// Returns the wall-clock duration of the last pumped frame.
+    ///
+    /// The first frame lasts zero.
+    pub fn frame_delta(&self) -> Duration {
+        self.frame_delta
+    }
+
+    /// Attaches a checkpoint hash, such as a world hash, to the last pumped frame.
+    ///
+    /// Does nothing before the first pump.
+    pub fn checkpoint(&mut self, hash: u64) {
+        if let Some(frame) = self.recording.frames.last_mut() {
+            frame.checkpoint = Some(hash);
+        }
+    }
+
+    /// Returns the frames recorded so far.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Stops recording and returns the recording.
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+
+    /// Returns the wrapped platform.
+    pub fn inner(&self) -> &P {
+        &self.platform
+    }
+
+    /// Returns the wrapped platform mutably.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.platform
+    }
+}
+
+impl<P: Platform> Platform for Recorder<P> {
+    fn pump_events(&mut self, events: &mut Vec<Event>) -> Result<()> {
+        let now = Instant::now();
+        self.frame_delta = self
+            .last_pump
+            .map_or(Duration::ZERO, |last| now.duration_since(last));
+        self.last_pump = Some(now);
+
+        let start = events.len();
+        self.platform.pump_events(events)?;
+
+        self.recording.frames.push(RecordedFrame {
+            delta: self.frame_delta,
+            events: events[start..].to_vec(),
+            checkpoint: None,
+        });
+
+        Ok(())
+    }
+
+    fn surface_size(&self) -> (u32, u32) {
+        self.platform.surface_size()
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.platform.scale_factor()
+    }
+
+    fn request_redraw(&mut self) {
+        self.platform.request_redraw();
+    }
+
+    fn exit(&mut self) {
+        self.platform.exit();
+    }
+
+    fn is_exiting(&self) -> bool {
+        self.platform.is_exiting()
+    }
+
+    fn window(&self) -> Option<&Window> {
+        self.platform.window()
+    }
+}
+
+/// How a [`ReplayPlayer`] treats checkpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Replays events and ignores checkpoints.
+    Playback,
+    /// Replays events and fails on the first checkpoint that differs from the
+    /// recording.
+    Verify,
+}
+
+/// A platform that replays a [`Recording`] through the headless backend.
+///
+/// Each pump delivers exactly the events of the next recorded frame. The player
+/// exits once the last frame has been pumped. Redraw requests are ignored: the
+/// recorded redraws are replayed instead.
+///
+/// # Example
+///
+/// ```
+/// use syn_platform::{
+///     Event, HeadlessPlatform, Platform, Recorder, ReplayError, ReplayMode, ReplayPlayer,
+/// };
+///
+/// let mut recorder = Recorder::new(HeadlessPlatform::default());
+/// let mut events = Vec::new();
+/// recorder.pump_events(&mut events).unwrap();
+/// recorder.checkpoint(7);
+///
+/// let mut player = ReplayPlayer::new(recorder.into_recording(), ReplayMode::Verify);
+/// let mut replayed = Vec::new();
+/// player.pump_events(&mut replayed).unwrap();
+///
+/// assert_eq!(replayed, events);
+/// assert!(matches!(
+///     player.checkpoint(8),
+///     Err(ReplayError::Diverged { frame: 0, expected: 7, actual: 8 })
+/// ));
+/// assert!(player.is_exiting());
+/// ```
+pub struct ReplayPlayer {
+    platform: HeadlessPlatform,
+    recording: Recording,
+    mode: ReplayMode,
+    next_frame: usize,
+    frame_delta: Duration,
+    exit_requested: bool,
+}
+
+impl ReplayPlayer {
+    /// Creates a player for a recording.
+    pub fn new(recording: Recording, mode: ReplayMode) -> Self {
+        let platform = HeadlessPlatform::new(HeadlessConfig {
+            width: recording.surface_size.0,
+            height: recording.surface_size.1,
+            scale_factor: recording.scale_factor,
+            max_frames: None,
+            startup_events: false,
+        });
+
+        Self {
+            platform,
+            recording,
+            mode,
+            next_frame: 0,
+            frame_delta: Duration::ZERO,
+            exit_requested: false,
+        }
+    }
+
+    /// Returns the replay mode.
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// Returns the number of frames replayed so far.
+    pub fn frame(&self) -> usize {
+        self.next_frame
+    }
+
+    /// Returns the recorded wall-clock duration of the last replayed frame.
+    pub fn frame_delta(&self) -> Duration {
+        self.frame_delta
+    }
+
+    /// Returns `true` once every recorded frame has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.recording.len()
+    }
+
+    /// Returns the recording being replayed.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Compares a checkpoint hash with the one recorded for the last replayed frame.
+    ///
+    /// Frames recorded without a checkpoint accept any hash.
+    ///
+    /// # Errors
+    ///
+    /// In [`ReplayMode::Verify`], returns [`ReplayError::Diverged`] if the hashes
+    /// differ.
+    pub fn checkpoint(&self, hash: u64) -> ReplayResult<()> {
+        if self.mode == ReplayMode::Playback || self.next_frame == 0 {
+            return Ok(());
+        }
+
+        let frame = self.next_frame - 1;
+        match self.recording.frames[frame].checkpoint {
+            Some(expected) if expected != hash => Err(ReplayError::Diverged {
+                frame,
+                expected,
+                actual: hash,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Platform for ReplayPlayer {
+    fn pump_events(&mut self, events: &mut Vec<Event>) -> Result<()> {
+        let Some(frame) = self.recording.frames.get(self.next_frame) else {
+            return Ok(());
+        };
+
+        for event in &frame.events {
+            self.platform.push_event(event.clone());
+        }
+        self.frame_delta = frame.delta;
+        self.next_frame += 1;
+
+        self.platform.pump_events(events)
+    }
+
+    fn surface_size(&self) -> (u32, u32) {
+        self.platform.surface_size()
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.platform.scale_factor()
+    }
+
+    fn request_redraw(&mut self) {}
+
+    fn exit(&mut self) {
+        self.exit_requested = true;
+    }
+
+    fn is_exiting(&self) -> bool {
+        self.exit_requested || self.is_finished()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::GameClock;
+
+    #[test]
+    fn replay_reproduces_events_and_timings() {
+        let mut source = HeadlessPlatform::default();
+        source.schedule(
+            1,
+            Event::Resized {
+                width: 640,
+                height: 480,
+            },
+        );
+        source.schedule(2, Event::CloseRequested);
+
+        let mut recorder = Recorder::new(source);
+        let mut live_frames = Vec::new();
+        for _ in 0..3 {
+            let mut events = Vec::new();
+            recorder.pump_events(&mut events).unwrap();
+            live_frames.push(events);
+        }
+        let recording = recorder.into_recording();
+        assert_eq!(recording.surface_size, (1280, 720));
+
+        let mut player = ReplayPlayer::new(recording.clone(), ReplayMode::Playback);
+        for (index, expected) in live_frames.iter().enumerate() {
+            assert!(!player.is_exiting());
+            let mut events = Vec::new();
+            player.pump_events(&mut events).unwrap();
+            assert_eq!(&events, expected);
+            assert_eq!(player.frame_delta(), recording.frames[index].delta);
+        }
+
+        assert!(player.is_exiting());
+        assert_eq!(player.surface_size(), (640, 480));
+    }
+
+    #[test]
+    fn verify_mode_detects_divergence() {
+        let mut recording = Recording::new((100, 100), 1.0);
+        for hash in [Some(1), None, Some(3)] {
+            recording.frames.push(RecordedFrame {
+                delta: Duration::from_millis(16),
+                events: Vec::new(),
+                checkpoint: hash,
+            });
+        }
+
+        let mut player = ReplayPlayer::new(recording.clone(), ReplayMode::Verify);
+        let mut events = Vec::new();
+
+        player.pump_events(&mut events).unwrap();
+        assert!(player.checkpoint(1).is_ok());
+        player.pump_events(&mut events).unwrap();
+        assert!(player.checkpoint(99).is_ok());
+        player.pump_events(&mut events).unwrap();
+        assert!(matches!(
+            player.checkpoint(4),
+            Err(ReplayError::Diverged {
+                frame: 2,
+                expected: 3,
+                actual: 4
+            })
+        ));
+
+        let mut playback = ReplayPlayer::new(recording, ReplayMode::Playback);
+        playback.pump_events(&mut events).unwrap();
+        assert!(playback.checkpoint(0).is_ok());
+    }
+
+    #[test]
+    fn recorded_deltas_drive_the_clock_identically() {
+        let mut recording = Recording::new((1, 1), 1.0);
+        for millis in [0, 16, 35, 9] {
+            recording.frames.push(RecordedFrame {
+                delta: Duration::from_millis(millis),
+                ..RecordedFrame::default()
+            });
+        }
+
+        let run = || {
+            let mut player = ReplayPlayer::new(recording.clone(), ReplayMode::Playback);
+            let mut clock = GameClock::default();
+            let mut steps = Vec::new();
+            let mut events = Vec::new();
+            while !player.is_exiting() {
+                player.pump_events(&mut events).unwrap();
+                clock.advance(player.frame_delta());
+                steps.push(clock.fixed_steps());
+            }
+            steps
+        };
+
+        assert_eq!(run(), run());
+        assert_eq!(run().len(), 4);
+    }
+}
@@ -0,0 +1,628 @@
+//! Translation of `winit` events into platform [`Event`]s.
+//!
+//! Everything here is a pure function of winit values, so the mapping is tested
+//! with constructed inputs and without opening a window.
+
+use winit::event::{
+    DeviceEvent, ElementState, Ime, MouseScrollDelta, TouchPhase as WinitTouchPhase, WindowEvent,
+};
+use winit::keyboard::{Key, KeyCode, ModifiersState, NamedKey as WinitNamedKey, PhysicalKey};
+
+use crate::events::{ButtonState, Event, ImeEvent, MouseButton, ScrollDelta, Touch, TouchPhase};
+use crate::keys::{KeyboardInput, LogicalKey, Modifiers, NamedKey, ScanCode};
+
+/// Translates a window event, appending its platform equivalents to `out`.
+///
+/// Events without an equivalent are dropped. Scale factor changes only report the
+/// new factor; the resulting resize arrives as a separate event.
+pub(crate) fn translate_window_event(event: &WindowEvent, out: &mut Vec<Event>) {
+    match event {
+        WindowEvent::Resized(size) => out.push(Event::Resized {
+            width: size.width,
+            height: size.height,
+        }),
+        WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+            out.push(Event::ScaleFactorChanged {
+                scale_factor: *scale_factor,
+            });
+        }
+        WindowEvent::CloseRequested => out.push(Event::CloseRequested),
+        WindowEvent::Focused(focused) => out.push(Event::Focused(*focused)),
+        WindowEvent::RedrawRequested => out.push(Event::RedrawRequested),
+        WindowEvent::KeyboardInput { event, .. } => translate_key(
+            event.physical_key,
+            &event.logical_key,
+            event.text.as_deref(),
+            event.state,
+            event.repeat,
+            out,
+        ),
+        WindowEvent::ModifiersChanged(modifiers) => {
+            out.push(Event::ModifiersChanged(translate_modifiers(
+                modifiers.state(),
+            )));
+        }
+        WindowEvent::Ime(ime) => out.push(Event::Ime(translate_ime(ime))),
+        WindowEvent::CursorMoved { position, .. } => out.push(Event::CursorMoved {
+            x: position.x,
+            y: position.y,
+        }),
+        WindowEvent::CursorEntered { .. } => out.push(Event::CursorEntered),
+        WindowEvent::CursorLeft { .. } => out.push(Event::CursorLeft),
+        WindowEvent::MouseInput { state, button, .. } => out.push(Event::MouseInput {
+            button: translate_mouse_button(*button),
+            state: translate_state(*state),
+        }),
+        WindowEvent::MouseWheel { delta, .. } => {
+            out.push(Event::MouseWheel(translate_scroll(*delta)));
+        }
+        WindowEvent::Touch(touch) => out.push(Event::Touch(Touch {
+            id: touch.id,
+            phase: translate_touch_phase(touch.phase),
+            x: touch.location.x,
+            y: touch.location.y,
+            force: touch.force.map(|force| force.normalized()),
+        })),
+        WindowEvent::HoveredFile(path) => out.push(Event::FileHovered(path.clone())),
+        WindowEvent::DroppedFile(path) => out.push(Event::FileDropped(path.clone())),
+        WindowEvent::HoveredFileCancelled => out.push(Event::FileHoverCancelled),
+        _ => {}
+    }
+}
+
+/// Translates a device event, if it has a platform equivalent.
+pub(crate) fn translate_device_event(event: &DeviceEvent) -> Option<Event> {
+    match *event {
+        DeviceEvent::MouseMotion { delta: (dx, dy) } => Some(Event::MouseMotion { dx, dy }),
+        _ => None,
+    }
+}
+
+/// Translates the parts of a winit key event.
+///
+/// Pressed keys that produce printable text also emit an [`Event::Text`].
+pub(crate) fn translate_key(
+    physical_key: PhysicalKey,
+    logical_key: &Key,
+    text: Option<&str>,
+    state: ElementState,
+    repeat: bool,
+    out: &mut Vec<Event>,
+) {
+    let state = translate_state(state);
+
+    out.push(Event::KeyboardInput(KeyboardInput {
+        scan_code: translate_physical_key(physical_key),
+        logical_key: translate_logical_key(logical_key),
+        state,
+        repeat,
+    }));
+
+    let printable = text.filter(|text| !text.is_empty() && !text.chars().any(char::is_control));
+    if let (Some(text), true) = (printable, state.is_pressed()) {
+        out.push(Event::Text(text.to_owned()));
+    }
+}
+
+fn translate_state(state: ElementState) -> ButtonState {
+    match state {
+        ElementState::Pressed => ButtonState::Pressed,
+        ElementState::Released => ButtonState::Released,
+    }
+}
+
+fn translate_mouse_button(button: winit::event::MouseButton) -> MouseButton {
+    match button {
+        winit::event::MouseButton::Left => MouseButton::Left,
+        winit::event::MouseButton::Right => MouseButton::Right,
+        winit::event::MouseButton::Middle => MouseButton::Middle,
+        winit::event::MouseButton::Back => MouseButton::Back,
+        winit::event::MouseButton::Forward => MouseButton::Forward,
+        winit::event::MouseButton::Other(id) => MouseButton::Other(id),
+    }
+}
+
+fn translate_scroll(delta: MouseScrollDelta) -> ScrollDelta {
+    match delta {
+        MouseScrollDelta::LineDelta(x, y) => ScrollDelta::Lines { x, y },
+        MouseScrollDelta::PixelDelta(position) => ScrollDelta::Pixels {
+            x: position.x,
+            y: position.y,
+        },
+    }
+}
+
+fn translate_touch_phase(phase: WinitTouchPhase) -> TouchPhase {
+    match phase {
+        WinitTouchPhase::Started => TouchPhase::Started,
+        WinitTouchPhase::Moved => TouchPhase::Moved,
+        WinitTouchPhase::Ended => TouchPhase::Ended,
+        WinitTouchPhase::Cancelled => TouchPhase::Cancelled,
+    }
+}
+
+fn translate_ime(ime: &Ime) -> ImeEvent {
+    match ime {
+        Ime::Enabled => ImeEvent::Enabled,
+        Ime::Preedit(text, cursor) => ImeEvent::Preedit {
+            text: text.clone(),
+            cursor: *cursor,
+        },
+        Ime::Commit(text) => ImeEvent::Commit(text.clone()),
+        Ime::Disabled => ImeEvent::Disabled,
+    }
+}
+
+fn translate_modifiers(state: ModifiersState) -> Modifiers {
+    Modifiers {
+        shift: state.shift_key(),
+        control: state.control_key(),
+        alt: state.alt_key(),
+        super_key: state.super_key(),
+    }
+}
+
+fn translate_logical_key(key: &Key) -> LogicalKey {
+    match key {
+        Key::Character(text) => LogicalKey::Character(text.to_string()),
+        Key::Named(named) => LogicalKey::Named(translate_named_key(*named)),
+        Key::Dead(accent) => LogicalKey::Dead(*accent),
+        Key::Unidentified(_) => LogicalKey::Unidentified,
+    }
+}
+
+fn translate_named_key(key: WinitNamedKey) -> NamedKey {
+    match key {
+        WinitNamedKey::Enter => NamedKey::Enter,
+        WinitNamedKey::Tab => NamedKey::Tab,
+        WinitNamedKey::Space => NamedKey::Space,
+        WinitNamedKey::Backspace => NamedKey::Backspace,
+        WinitNamedKey::Delete => NamedKey::Delete,
+        WinitNamedKey::Escape => NamedKey::Escape,
+        WinitNamedKey::Insert => NamedKey::Insert,
+        WinitNamedKey::Home => NamedKey::Home,
+        WinitNamedKey::End => NamedKey::End,
+        WinitNamedKey::PageUp => NamedKey::PageUp,
+        WinitNamedKey::PageDown => NamedKey::PageDown,
+        WinitNamedKey::ArrowUp => NamedKey::ArrowUp,
+        WinitNamedKey::ArrowDown => NamedKey::ArrowDown,
+        WinitNamedKey::ArrowLeft => NamedKey::ArrowLeft,
+        WinitNamedKey::ArrowRight => NamedKey::ArrowRight,
+        WinitNamedKey::Shift => NamedKey::Shift,
+        WinitNamedKey::Control => NamedKey::Control,
+        WinitNamedKey::Alt => NamedKey::Alt,
+        WinitNamedKey::AltGraph => NamedKey::AltGraph,
+        WinitNamedKey::Super | WinitNamedKey::Meta => NamedKey::Super,
+        WinitNamedKey::CapsLock => NamedKey::CapsLock,
+        WinitNamedKey::NumLock => NamedKey::NumLock,
+        WinitNamedKey::ScrollLock => NamedKey::ScrollLock,
+        WinitNamedKey::PrintScreen => NamedKey::PrintScreen,
+        WinitNamedKey::Pause => NamedKey::Pause,
+        WinitNamedKey::ContextMenu => NamedKey::ContextMenu,
+        WinitNamedKey::MediaPlayPause => NamedKey::MediaPlayPause,
+        WinitNamedKey::MediaStop => NamedKey::MediaStop,
+        WinitNamedKey::MediaTrackNext => NamedKey::MediaTrackNext,
+        WinitNamedKey::MediaTrackPrevious => NamedKey::MediaTrackPrevious,
+        WinitNamedKey::AudioVolumeUp => NamedKey::AudioVolumeUp,
+        WinitNamedKey::AudioVolumeDown => NamedKey::AudioVolumeDown,
+        WinitNamedKey::AudioVolumeMute => NamedKey::AudioVolumeMute,
+        WinitNamedKey::F1 => NamedKey::Function(1),
+        WinitNamedKey::F2 => NamedKey::Function(2),
+        WinitNamedKey::F3 => NamedKey::Function(3),
+        WinitNamedKey::F4 => NamedKey::Function(4),
+        WinitNamedKey::F5 => NamedKey::Function(5),
+        WinitNamedKey::F6 => NamedKey::Function(6),
+        WinitNamedKey::F7 => NamedKey::Function(7),
+        WinitNamedKey::F8 => NamedKey::Function(8),
+        WinitNamedKey::F9 => NamedKey::Function(9),
+        WinitNamedKey::F10 => NamedKey::Function(10),
+        WinitNamedKey::F11 => NamedKey::Function(11),
+        WinitNamedKey::F12 => NamedKey::Function(12),
+        WinitNamedKey::F13 => NamedKey::Function(13),
+        WinitNamedKey::F14 => NamedKey::Function(14),
+        WinitNamedKey::F15 => NamedKey::Function(15),
+        WinitNamedKey::F16 => NamedKey::Function(16),
+        WinitNamedKey::F17 => NamedKey::Function(17),
+        WinitNamedKey::F18 => NamedKey::Function(18),
+        WinitNamedKey::F19 => NamedKey::Function(19),
+        WinitNamedKey::F20 => NamedKey::Function(20),
+        WinitNamedKey::F21 => NamedKey::Function(21),
+        WinitNamedKey::F22 => NamedKey::Function(22),
+        WinitNamedKey::F23 => NamedKey::Function(23),
+        WinitNamedKey::F24 => NamedKey::Function(24),
+        _ => NamedKey::Other,
+    }
+}
+
+/// Maps a winit physical key to its USB HID usage.
+fn translate_physical_key(key: PhysicalKey) -> ScanCode {
+    match key {
+        PhysicalKey::Code(code) => key_code_usage(code),
+        PhysicalKey::Unidentified(_) => ScanCode::UNIDENTIFIED,
+    }
+}
+
+/// Returns the USB HID usage of a winit key code.
+///
+/// Codes of the W3C specification without a HID usage map to
+/// [`ScanCode::UNIDENTIFIED`].
+#[allow(clippy::too_many_lines)]
+fn key_code_usage(code: KeyCode) -> ScanCode {
+    let usage = match code {
+        KeyCode::KeyA => 0x04,
+        KeyCode::KeyB => 0x05,
+        KeyCode::KeyC => 0x06,
+        KeyCode::KeyD => 0x07,
+        KeyCode::KeyE => 0x08,
+        KeyCode::KeyF => 0x09,
+        KeyCode::KeyG => 0x0A,
+        KeyCode::KeyH => 0x0B,
+        KeyCode::KeyI => 0x0C,
+        KeyCode::KeyJ => 0x0D,
+        KeyCode::KeyK => 0x0E,
+        KeyCode::KeyL => 0x0F,
+        KeyCode::KeyM => 0x10,
+        KeyCode::KeyN => 0x11,
+        KeyCode::KeyO => 0x12,
+        KeyCode::KeyP => 0x13,
+        KeyCode::KeyQ => 0x14,
+        KeyCode::KeyR => 0x15,
+        KeyCode::KeyS => 0x16,
+        KeyCode::KeyT => 0x17,
+        KeyCode::KeyU => 0x18,
+        KeyCode::KeyV => 0x19,
+        KeyCode::KeyW => 0x1A,
+        KeyCode::KeyX => 0x1B,
+        KeyCode::KeyY => 0x1C,
+        KeyCode::KeyZ => 0x1D,
+        KeyCode::Digit1 => 0x1E,
+        KeyCode::Digit2 => 0x1F,
+        KeyCode::Digit3 => 0x20,
+        KeyCode::Digit4 => 0x21,
+        KeyCode::Digit5 => 0x22,
+        KeyCode::Digit6 => 0x23,
+        KeyCode::Digit7 => 0x24,
+        KeyCode::Digit8 => 0x25,
+        KeyCode::Digit9 => 0x26,
+        KeyCode::Digit0 => 0x27,
+        KeyCode::Enter => 0x28,
+        KeyCode::Escape => 0x29,
+        KeyCode::Backspace => 0x2A,
+        KeyCode::Tab => 0x2B,
+        KeyCode::Space => 0x2C,
+        KeyCode::Minus => 0x2D,
+        KeyCode::Equal => 0x2E,
+        KeyCode::BracketLeft => 0x2F,
+        KeyCode::BracketRight => 0x30,
+        KeyCode::Backslash => 0x31,
+        KeyCode::Semicolon => 0x33,
+        KeyCode::Quote => 0x34,
+        KeyCode::Backquote => 0x35,
+        KeyCode::Comma => 0x36,
+        KeyCode::Period => 0x37,
+        KeyCode::Slash => 0x38,
+        KeyCode::CapsLock => 0x39,
+        KeyCode::F1 => 0x3A,
+        KeyCode::F2 => 0x3B,
+        KeyCode::F3 => 0x3C,
+        KeyCode::F4 => 0x3D,
+        KeyCode::F5 => 0x3E,
+        KeyCode::F6 => 0x3F,
+        KeyCode::F7 => 0x40,
+        KeyCode::F8 => 0x41,
+        KeyCode::F9 => 0x42,
+        KeyCode::F10 => 0x43,
+        KeyCode::F11 => 0x44,
+        KeyCode::F12 => 0x45,
+        KeyCode::PrintScreen => 0x46,
+        KeyCode::ScrollLock => 0x47,
+        KeyCode::Pause => 0x48,
+        KeyCode::Insert => 0x49,
+        KeyCode::Home => 0x4A,
+        KeyCode::PageUp => 0x4B,
+        KeyCode::Delete => 0x4C,
+        KeyCode::End => 0x4D,
+        KeyCode::PageDown => 0x4E,
+        KeyCode::ArrowRight => 0x4F,
+        KeyCode::ArrowLeft => 0x50,
+        KeyCode::ArrowDown => 0x51,
+        KeyCode::ArrowUp => 0x52,
+        KeyCode::NumLock => 0x53,
+        KeyCode::NumpadDivide => 0x54,
+        KeyCode::NumpadMultiply => 0x55,
+        KeyCode::NumpadSubtract => 0x56,
+        KeyCode::NumpadAdd => 0x57,
+        KeyCode::NumpadEnter => 0x58,
+        KeyCode::Numpad1 => 0x59,
+        KeyCode::Numpad2 => 0x5A,
+        KeyCode::Numpad3 => 0x5B,
+        KeyCode::Numpad4 => 0x5C,
+        KeyCode::Numpad5 => 0x5D,
+        KeyCode::Numpad6 => 0x5E,
+        KeyCode::Numpad7 => 0x5F,
+        KeyCode::Numpad8 => 0x60,
+        KeyCode::Numpad9 => 0x61,
+        KeyCode::Numpad0 => 0x62,
+        KeyCode::NumpadDecimal => 0x63,
+        KeyCode::IntlBackslash => 0x64,
+        KeyCode::ContextMenu => 0x65,
+        KeyCode::Power => 0x66,
+        KeyCode::NumpadEqual => 0x67,
+        KeyCode::F13 => 0x68,
+        KeyCode::F14 => 0x69,
+        KeyCode::F15 => 0x6A,
+        KeyCode::F16 => 0x6B,
+        KeyCode::F17 => 0x6C,
+        KeyCode::F18 => 0x6D,
+        KeyCode::F19 => 0x6E,
+        KeyCode::F20 => 0x6F,
+        KeyCode::F21 => 0x70,
+        KeyCode::F22 => 0x71,
+        KeyCode::F23 => 0x72,
+        KeyCode::F24 => 0x73,
+        KeyCode::Open => 0x74,
+        KeyCode::Help => 0x75,
+        KeyCode::Props => 0x76,
+        KeyCode::Select => 0x77,
+        KeyCode::Again => 0x79,
+        KeyCode::Undo => 0x7A,
+        KeyCode::Cut => 0x7B,
+        KeyCode::Copy => 0x7C,
+        KeyCode::Paste => 0x7D,
+        KeyCode::Find => 0x7E,
+        KeyCode::AudioVolumeMute => 0x7F,
+        KeyCode::AudioVolumeUp => 0x80,
+        KeyCode::AudioVolumeDown => 0x81,
+        KeyCode::NumpadComma => 0x85,
+        KeyCode::IntlRo => 0x87,
+        KeyCode::KanaMode => 0x88,
+        KeyCode::IntlYen => 0x89,
+        KeyCode::Convert => 0x8A,
+        KeyCode::NonConvert => 0x8B,
+        KeyCode::Lang1 => 0x90,
+        KeyCode::Lang2 => 0x91,
+        KeyCode::Lang3 | KeyCode::Katakana => 0x92,
+        KeyCode::Lang4 | KeyCode::Hiragana => 0x93,
+        KeyCode::Lang5 => 0x94,
+        KeyCode::NumpadParenLeft => 0xB6,
+        KeyCode::NumpadParenRight => 0xB7,
+        KeyCode::NumpadBackspace => 0xBB,
+        KeyCode::NumpadHash => 0xCC,
+        KeyCode::NumpadMemoryStore => 0xD0,
+        KeyCode::NumpadMemoryRecall => 0xD1,
+        KeyCode::NumpadMemoryClear => 0xD2,
+        KeyCode::NumpadMemoryAdd => 0xD3,
+        KeyCode::NumpadMemorySubtract => 0xD4,
+        KeyCode::NumpadClear => 0xD8,
+        KeyCode::NumpadClearEntry => 0xD9,
+        KeyCode::ControlLeft => 0xE0,
+        KeyCode::ShiftLeft => 0xE1,
+        KeyCode::AltLeft => 0xE2,
+        KeyCode::SuperLeft => 0xE3,
+        KeyCode::ControlRight => 0xE4,
+        KeyCode::ShiftRight => 0xE5,
+        KeyCode::AltRight => 0xE6,
+        KeyCode::SuperRight => 0xE7,
+        _ => return consumer_usage(code),
+    };
+
+    ScanCode::keyboard(usage)
+}
+
+/// Returns the HID usage of keys that only exist outside the keyboard page.
+fn consumer_usage(code: KeyCode) -> ScanCode {
+    match code {
+        KeyCode::MediaTrackNext => ScanCode::consumer(0xB5),
+        KeyCode::MediaTrackPrevious => ScanCode::consumer(0xB6),
+        KeyCode::MediaStop => ScanCode::consumer(0xB7),
+        KeyCode::Eject => ScanCode::consumer(0xB8),
+        KeyCode::MediaPlayPause => ScanCode::consumer(0xCD),
+        KeyCode::MediaSelect => ScanCode::consumer(0x183),
+        KeyCode::LaunchMail => ScanCode::consumer(0x18A),
+        KeyCode::LaunchApp2 => ScanCode::consumer(0x192),
+        KeyCode::LaunchApp1 => ScanCode::consumer(0x194),
+        KeyCode::BrowserSearch => ScanCode::consumer(0x221),
+        KeyCode::BrowserHome => ScanCode::consumer(0x223),
+        KeyCode::BrowserBack => ScanCode::consumer(0x224),
+        KeyCode::BrowserForward => ScanCode::consumer(0x225),
+        KeyCode::BrowserStop => ScanCode::consumer(0x226),
+        KeyCode::BrowserRefresh => ScanCode::consumer(0x227),
+        KeyCode::BrowserFavorites => ScanCode::consumer(0x22A),
+        KeyCode::Sleep => ScanCode::new(ScanCode::GENERIC_DESKTOP_PAGE, 0x82),
+        KeyCode::WakeUp => ScanCode::new(ScanCode::GENERIC_DESKTOP_PAGE, 0x83),
+        _ => ScanCode::UNIDENTIFIED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use winit::dpi::{PhysicalPosition, PhysicalSize};
+    use winit::event::{DeviceId, Force};
+    use winit::keyboard::NativeKeyCode;
+
+    use super::*;
+
+    fn translate(event: &WindowEvent) -> Vec<Event> {
+        let mut out = Vec::new();
+        translate_window_event(event, &mut out);
+        out
+    }
+
+    #[test]
+    fn window_lifecycle_events() {
+        assert_eq!(
+            translate(&WindowEvent::Resized(PhysicalSize::new(800, 600))),
+            vec![Event::Resized {
+                width: 800,
+                height: 600
+            }]
+        );
+        assert_eq!(
+            translate(&WindowEvent::Focused(false)),
+            vec![Event::Focused(false)]
+        );
+        assert_eq!(
+            translate(&WindowEvent::CloseRequested),
+            vec![Event::CloseRequested]
+        );
+    }
+
+    #[test]
+    fn mouse_events() {
+        let device_id = DeviceId::dummy();
+
+        assert_eq!(
+            translate(&WindowEvent::CursorMoved {
+                device_id,
+                position: PhysicalPosition::new(10.0, 20.0),
+            }),
+            vec![Event::CursorMoved { x: 10.0, y: 20.0 }]
+        );
+        assert_eq!(
+            translate(&WindowEvent::MouseInput {
+                device_id,
+                state: ElementState::Pressed,
+                button: winit::event::MouseButton::Back,
+            }),
+            vec![Event::MouseInput {
+                button: MouseButton::Back,
+                state: ButtonState::Pressed,
+            }]
+        );
+        assert_eq!(
+            translate(&WindowEvent::MouseWheel {
+                device_id,
+                delta: MouseScrollDelta::LineDelta(0.0, -1.0),
+                phase: WinitTouchPhase::Moved,
+            }),
+            vec![Event::MouseWheel(ScrollDelta::Lines { x: 0.0, y: -1.0 })]
+        );
+        assert_eq!(
+            translate_device_event(&DeviceEvent::MouseMotion { delta: (3.0, -2.0) }),
+            Some(Event::MouseMotion { dx: 3.0, dy: -2.0 })
+        );
+    }
+
+    #[test]
+    fn touch_reports_normalized_force() {
+        let event = WindowEvent::Touch(winit::event::Touch {
+            device_id: DeviceId::dummy(),
+            phase: WinitTouchPhase::Started,
+            location: PhysicalPosition::new(5.0, 6.0),
+            force: Some(Force::Normalized(0.5)),
+            id: 7,
+        });
+
+        assert_eq!(
+            translate(&event),
+            vec![Event::Touch(Touch {
+                id: 7,
+                phase: TouchPhase::Started,
+                x: 5.0,
+                y: 6.0,
+                force: Some(0.5),
+            })]
+        );
+    }
+
+    #[test]
+    fn text_ime_and_files() {
+        assert_eq!(
+            translate(&WindowEvent::Ime(Ime::Commit("日本".into()))),
+            vec![Event::Ime(ImeEvent::Commit("日本".into()))]
+        );
+        assert_eq!(
+            translate(&WindowEvent::DroppedFile(PathBuf::from("level.map"))),
+            vec![Event::FileDropped(PathBuf::from("level.map"))]
+        );
+        assert_eq!(
+            translate(&WindowEvent::ModifiersChanged(
+                (ModifiersState::SHIFT | ModifiersState::CONTROL).into()
+            )),
+            vec![Event::ModifiersChanged(Modifiers {
+                shift: true,
+                control: true,
+                ..Modifiers::default()
+            })]
+        );
+    }
+
+    #[test]
+    fn key_press_reports_physical_logical_and_text() {
+        let mut out = Vec::new();
+        // Physical Q on an AZERTY layout types "a".
+        translate_key(
+            PhysicalKey::Code(KeyCode::KeyQ),
+            &Key::Character("a".into()),
+            Some("a"),
+            ElementState::Pressed,
+            false,
+            &mut out,
+        );
+
+        assert_eq!(
+            out,
+            vec![
+                Event::KeyboardInput(KeyboardInput {
+                    scan_code: ScanCode::keyboard(0x14),
+                    logical_key: LogicalKey::Character("a".into()),
+                    state: ButtonState::Pressed,
+                    repeat: false,
+                }),
+                Event::Text("a".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn control_keys_and_releases_produce_no_text() {
+        let mut out = Vec::new();
+        translate_key(
+            PhysicalKey::Code(KeyCode::Enter),
+            &Key::Named(WinitNamedKey::Enter),
+            Some("\r"),
+            ElementState::Pressed,
+            false,
+            &mut out,
+        );
+        translate_key(
+            PhysicalKey::Code(KeyCode::KeyA),
+            &Key::Character("a".into()),
+            Some("a"),
+            ElementState::Released,
+            false,
+            &mut out,
+        );
+
+        assert_eq!(out.len(), 2);
+        assert!(
+            out.iter()
+                .all(|event| matches!(event, Event::KeyboardInput(_)))
+        );
+    }
+
+    #[test]
+    fn key_codes_map_to_hid_usages() {
+        assert_eq!(key_code_usage(KeyCode::KeyA), ScanCode::keyboard(0x04));
+        assert_eq!(key_code_usage(KeyCode::Digit0), ScanCode::keyboard(0x27));
+        assert_eq!(
+            key_code_usage(KeyCode::NumpadEnter),
+            ScanCode::keyboard(0x58)
+        );
+        assert_eq!(
+            key_code_usage(KeyCode::SuperRight),
+            ScanCode::keyboard(0xE7)
+        );
+        assert_eq!(
+            key_code_usage(KeyCode::MediaPlayPause),
+            ScanCode::consumer(0xCD)
+        );
+        assert_eq!(key_code_usage(KeyCode::F35), ScanCode::UNIDENTIFIED);
+        assert_eq!(
+            translate_physical_key(PhysicalKey::Unidentified(NativeKeyCode::Xkb(42))),
+            ScanCode::UNIDENTIFIED
+        );
+    }
+}
@@ -13,7 +13,7 @@ pub struct WindowConfig {
     pub height: u32,
     /// Whether the window is resizable.
     pub resizable: bool,
-    /// Whether to enable VSync.
+    /// Whether to enable vertical sync.
     pub vsync: bool,
 }
 
@@ -35,12 +35,22 @@ pub struct Window {
 }
 
 impl Window {
+    /// Wraps a window created by the winit backend.
+    pub(crate) fn from_winit(inner: winit::window::Window) -> Self {
+        Self { inner }
+    }
+
     /// Returns the window's current size.
     pub fn size(&self) -> (u32, u32) {
         let size = self.inner.inner_size();
         (size.width, size.height)
     }
 
+    /// Returns the ratio between physical and logical pixels.
+    pub fn scale_factor(&self) -> f64 {
+        self.inner.scale_factor()
+    }
+
     /// Returns the inner winit window.
     pub fn inner(&self) -> &winit::window::Window {
         &self.inner
@@ -53,13 +63,17 @@ impl Window {
 }
 
 impl HasWindowHandle for Window {
-    fn window_handle(&self) -> Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
+    fn window_handle(
+        &self,
+    ) -> Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
         self.inner.window_handle()
     }
 }
 
 impl HasDisplayHandle for Window {
-    fn display_handle(&self) -> Result<raw_window_handle::DisplayHandle<'_>, raw_window_handle::HandleError> {
+    fn display_handle(
+        &self,
+    ) -> Result<raw_window_handle::DisplayHandle<'_>, raw_window_handle::HandleError> {
         self.inner.display_handle()
     }
 }
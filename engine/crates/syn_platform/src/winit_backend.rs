@@ -0,0 +1,155 @@
+//! Windowed platform backend built on `winit`.
+
+use std::time::Duration;
+
+use winit::application::ApplicationHandler;
+use winit::dpi::PhysicalSize;
+use winit::event::{DeviceEvent, DeviceId, WindowEvent};
+use winit::event_loop::ActiveEventLoop;
+use winit::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+use winit::window::{WindowAttributes, WindowId};
+
+use crate::error::{PlatformError, Result};
+use crate::events::{Event, EventLoop};
+use crate::platform::Platform;
+use crate::translate::{translate_device_event, translate_window_event};
+use crate::window::{Window, WindowConfig};
+
+/// A platform backend that owns a native window.
+///
+/// The window is created lazily on the first pump, once the operating system
+/// reports the application as resumed.
+pub struct WinitPlatform {
+    event_loop: EventLoop,
+    state: WinitState,
+}
+
+/// State shared with winit while events are being pumped.
+struct WinitState {
+    config: WindowConfig,
+    window: Option<Window>,
+    events: Vec<Event>,
+    error: Option<PlatformError>,
+    exit_requested: bool,
+    exiting: bool,
+}
+
+impl WinitPlatform {
+    /// Creates the event loop; the window itself is opened on the first pump.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no display is available or an event loop already exists.
+    pub fn new(config: WindowConfig) -> Result<Self> {
+        Ok(Self::with_event_loop(EventLoop::new()?, config))
+    }
+
+    /// Creates a platform from an existing event loop.
+    pub fn with_event_loop(event_loop: EventLoop, config: WindowConfig) -> Self {
+        Self {
+            event_loop,
+            state: WinitState {
+                config,
+                window: None,
+                events: Vec::new(),
+                error: None,
+                exit_requested: false,
+                exiting: false,
+            },
+        }
+    }
+}
+
+impl Platform for WinitPlatform {
+    fn pump_events(&mut self, events: &mut Vec<Event>) -> Result<()> {
+        if self.state.exiting {
+            return Ok(());
+        }
+
+        let status = self
+            .event_loop
+            .inner_mut()
+            .pump_app_events(Some(Duration::ZERO), &mut self.state);
+
+        events.append(&mut self.state.events);
+        if let PumpStatus::Exit(_) = status {
+            self.state.exiting = true;
+        }
+
+        match self.state.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn surface_size(&self) -> (u32, u32) {
+        self.state.window.as_ref().map_or(
+            (self.state.config.width, self.state.config.height),
+            Window::size,
+        )
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.state.window.as_ref().map_or(1.0, Window::scale_factor)
+    }
+
+    fn request_redraw(&mut self) {
+        if let Some(window) = &self.state.window {
+            window.request_redraw();
+        }
+    }
+
+    fn exit(&mut self) {
+        self.state.exit_requested = true;
+    }
+
+    fn is_exiting(&self) -> bool {
+        self.state.exiting || self.state.exit_requested
+    }
+
+    fn window(&self) -> Option<&Window> {
+        self.state.window.as_ref()
+    }
+}
+
+impl ApplicationHandler for WinitState {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        self.events.push(Event::Resumed);
+        if self.window.is_some() {
+            return;
+        }
+
+        let attributes = WindowAttributes::default()
+            .with_title(self.config.title.clone())
+            .with_inner_size(PhysicalSize::new(self.config.width, self.config.height))
+            .with_resizable(self.config.resizable);
+
+        match event_loop.create_window(attributes) {
+            Ok(window) => self.window = Some(Window::from_winit(window)),
+            Err(error) => {
+                self.error = Some(error.into());
+                event_loop.exit();
+            }
+        }
+    }
+
+    fn window_event(&mut self, _event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+        translate_window_event(&event, &mut self.events);
+    }
+
+    fn device_event(&mut self, _event_loop: &ActiveEventLoop, _id: DeviceId, event: DeviceEvent) {
+        if let Some(event) = translate_device_event(&event) {
+            self.events.push(event);
+        }
+    }
+
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        self.events.push(Event::Suspended);
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if self.exit_requested {
+            event_loop.exit();
+        }
+    }
+}
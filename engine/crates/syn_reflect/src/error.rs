@@ -0,0 +1,51 @@
+//! Reflection error types.
+
+use thiserror::Error;
+
+/// Errors returned when accessing reflected values.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ReflectError {
+    /// A path names a field, item or key the value does not have.
+    #[error("`{type_name}` has no field `{field}`")]
+    NoSuchField {
+        /// The type of the value.
+        type_name: &'static str,
+        /// The missing field, item or key.
+        field: String,
+    },
+
+    /// An enum has no variant of the given name.
+    #[error("`{type_name}` has no variant `{variant}`")]
+    NoSuchVariant {
+        /// The enum's type name.
+        type_name: &'static str,
+        /// The missing variant.
+        variant: String,
+    },
+
+    /// A variant cannot be switched to, as reflection cannot create its
+    /// fields.
+    #[error("variant `{variant}` of `{type_name}` has fields")]
+    VariantHasFields {
+        /// The enum's type name.
+        type_name: &'static str,
+        /// The variant.
+        variant: &'static str,
+    },
+
+    /// A value has another type than the one expected.
+    #[error("expected `{expected}`, found `{found}`")]
+    TypeMismatch {
+        /// The type expected.
+        expected: &'static str,
+        /// The type found.
+        found: &'static str,
+    },
+
+    /// A path has an empty segment.
+    #[error("invalid path `{0}`")]
+    InvalidPath(String),
+}
+
+/// Result type for reflection operations.
+pub type ReflectResult<T> = Result<T, ReflectError>;
@@ -0,0 +1,411 @@
+//! Reflection of standard library and `glam` types.
+
+use std::any::{Any, type_name};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use glam::{Quat, Vec2, Vec3, Vec4};
+
+use crate::error::{ReflectError, ReflectResult};
+use crate::info::{
+    EnumInfo, FieldInfo, ListInfo, MapInfo, StructInfo, TypeInfo, Typed, ValueInfo, VariantInfo,
+};
+use crate::reflect::{Enum, List, Map, Reflect, ReflectMut, ReflectRef, Struct};
+use crate::registry::TypeRegistry;
+
+/// Implements the methods of [`Reflect`] common to every type, given the
+/// variant of [`ReflectRef`] and [`ReflectMut`] describing it.
+macro_rules! reflect_methods {
+    ($kind:ident) => {
+        fn type_name(&self) -> &'static str {
+            type_name::<Self>()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn into_any(self: Box<Self>) -> Box<dyn Any> {
+            self
+        }
+
+        fn as_reflect(&self) -> &dyn Reflect {
+            self
+        }
+
+        fn as_reflect_mut(&mut self) -> &mut dyn Reflect {
+            self
+        }
+
+        fn reflect_ref(&self) -> ReflectRef<'_> {
+            ReflectRef::$kind(self)
+        }
+
+        fn reflect_mut(&mut self) -> ReflectMut<'_> {
+            ReflectMut::$kind(self)
+        }
+
+        fn apply(&mut self, value: Box<dyn Reflect>) -> ReflectResult<()> {
+            *self = *downcast(value)?;
+            Ok(())
+        }
+    };
+}
+
+/// Unboxes a reflected value of type `T`.
+fn downcast<T: Reflect>(value: Box<dyn Reflect>) -> ReflectResult<Box<T>> {
+    let found = value.type_name();
+    value.downcast().map_err(|_| ReflectError::TypeMismatch {
+        expected: type_name::<T>(),
+        found,
+    })
+}
+
+/// Reflects types without reflected parts.
+macro_rules! impl_value {
+    ($($ty:ty),*) => {$(
+        impl Reflect for $ty {
+            reflect_methods!(Value);
+        }
+
+        impl Typed for $ty {
+            fn type_info() -> TypeInfo {
+                TypeInfo::Value(ValueInfo::new::<Self>())
+            }
+        }
+    )*};
+}
+
+impl_value!(
+    bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, String
+);
+
+/// Reflects `glam` types as structs of `f32` components.
+macro_rules! impl_glam {
+    ($($ty:ty { $($index:literal => $field:ident),* }),*) => {$(
+        impl Reflect for $ty {
+            reflect_methods!(Struct);
+        }
+
+        impl Struct for $ty {
+            fn field_len(&self) -> usize {
+                [$(stringify!($field)),*].len()
+            }
+
+            fn name_at(&self, index: usize) -> Option<&'static str> {
+                [$(stringify!($field)),*].get(index).copied()
+            }
+
+            fn field_at(&self, index: usize) -> Option<&dyn Reflect> {
+                match index {
+                    $($index => Some(&self.$field),)*
+                    _ => None,
+                }
+            }
+
+            // The SIMD types reach their components through `DerefMut`, so
+            // only one can be borrowed at a time.
+            fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+                match index {
+                    $($index => Some(&mut self.$field),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl Typed for $ty {
+            fn type_info() -> TypeInfo {
+                TypeInfo::Struct(StructInfo::new::<Self>(vec![
+                    $(FieldInfo::new::<f32>(stringify!($field))),*
+                ]))
+            }
+
+            fn register_dependencies(registry: &mut TypeRegistry) {
+                registry.register::<f32>();
+            }
+        }
+    )*};
+}
+
+impl_glam!(
+    Vec2 { 0 => x, 1 => y },
+    Vec3 { 0 => x, 1 => y, 2 => z },
+    Vec4 { 0 => x, 1 => y, 2 => z, 3 => w },
+    Quat { 0 => x, 1 => y, 2 => z, 3 => w }
+);
+
+impl<T: Reflect + Typed> Reflect for Option<T> {
+    reflect_methods!(Enum);
+}
+
+impl<T: Reflect + Typed> Enum for Option<T> {
+    fn variant_name(&self) -> &'static str {
+        match self {
+            None => "None",
+            Some(_) => "Some",
+        }
+    }
+
+    fn variant_index(&self) -> usize {
+        usize::from(self.is_some())
+    }
+
+    fn field_len(&self) -> usize {
+        usize::from(self.is_some())
+    }
+
+    fn name_at(&self, index: usize) -> Option<&'static str> {
+        (self.is_some() && index == 0).then_some("0")
+    }
+
+    fn field_at(&self, index: usize) -> Option<&dyn Reflect> {
+        match self {
+            Some(value) if index == 0 => Some(value),
+            _ => None,
+        }
+    }
+
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+        match self {
+            Some(value) if index == 0 => Some(value),
+            _ => None,
+        }
+    }
+
+    fn set_variant(&mut self, name: &str) -> ReflectResult<()> {
+        match name {
+            "None" => {
+                *self = None;
+                Ok(())
+            }
+            "Some" => Err(ReflectError::VariantHasFields {
+                type_name: type_name::<Self>(),
+                variant: "Some",
+            }),
+            _ => Err(ReflectError::NoSuchVariant {
+                type_name: type_name::<Self>(),
+                variant: name.to_owned(),
+            }),
+        }
+    }
+}
+
+impl<T: Reflect + Typed> Typed for Option<T> {
+    fn type_info() -> TypeInfo {
+        TypeInfo::Enum(EnumInfo::new::<Self>(vec![
+            VariantInfo::new("None", Vec::new()),
+            VariantInfo::new("Some", vec![FieldInfo::new::<T>("0")]),
+        ]))
+    }
+
+    fn register_dependencies(registry: &mut TypeRegistry) {
+        registry.register::<T>();
+    }
+}
+
+impl<T: Reflect + Typed> Reflect for Vec<T> {
+    reflect_methods!(List);
+}
+
+impl<T: Reflect + Typed> List for Vec<T> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&dyn Reflect> {
+        self.as_slice().get(index).map(|item| item as &dyn Reflect)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+        self.as_mut_slice()
+            .get_mut(index)
+            .map(|item| item as &mut dyn Reflect)
+    }
+
+    fn push(&mut self, item: Box<dyn Reflect>) -> ReflectResult<()> {
+        Vec::push(self, *downcast(item)?);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<Box<dyn Reflect>> {
+        Vec::pop(self).map(|item| Box::new(item) as Box<dyn Reflect>)
+    }
+}
+
+impl<T: Reflect + Typed> Typed for Vec<T> {
+    fn type_info() -> TypeInfo {
+        TypeInfo::List(ListInfo::new::<Self, T>())
+    }
+
+    fn register_dependencies(registry: &mut TypeRegistry) {
+        registry.register::<T>();
+    }
+}
+
+/// Reflects maps whose keys parse from their text.
+macro_rules! impl_map {
+    ($map:ident, $($key_bound:path),*) => {
+        // RATIONALE: maps are reflected with their default hasher only.
+        #[allow(clippy::implicit_hasher)]
+        impl<K, V> Reflect for $map<K, V>
+        where
+            K: Reflect + Typed + FromStr $(+ $key_bound)*,
+            V: Reflect + Typed,
+        {
+            reflect_methods!(Map);
+        }
+
+        // RATIONALE: maps are reflected with their default hasher only.
+        #[allow(clippy::implicit_hasher)]
+        impl<K, V> Map for $map<K, V>
+        where
+            K: Reflect + Typed + FromStr $(+ $key_bound)*,
+            V: Reflect + Typed,
+        {
+            fn len(&self) -> usize {
+                self.len()
+            }
+
+            fn get(&self, key: &str) -> Option<&dyn Reflect> {
+                let key: K = key.parse().ok()?;
+                $map::get(self, &key).map(|value| value as &dyn Reflect)
+            }
+
+            fn get_mut(&mut self, key: &str) -> Option<&mut dyn Reflect> {
+                let key: K = key.parse().ok()?;
+                $map::get_mut(self, &key).map(|value| value as &mut dyn Reflect)
+            }
+
+            fn entries(&self) -> Vec<(&dyn Reflect, &dyn Reflect)> {
+                self.iter()
+                    .map(|(key, value)| (key as &dyn Reflect, value as &dyn Reflect))
+                    .collect()
+            }
+
+            fn insert(
+                &mut self,
+                key: &str,
+                value: Box<dyn Reflect>,
+            ) -> ReflectResult<Option<Box<dyn Reflect>>> {
+                let key: K = key.parse().map_err(|_| ReflectError::TypeMismatch {
+                    expected: type_name::<K>(),
+                    found: type_name::<str>(),
+                })?;
+                let value = downcast::<V>(value)?;
+                Ok($map::insert(self, key, *value)
+                    .map(|replaced| Box::new(replaced) as Box<dyn Reflect>))
+            }
+
+            fn remove(&mut self, key: &str) -> Option<Box<dyn Reflect>> {
+                let key: K = key.parse().ok()?;
+                $map::remove(self, &key).map(|value| Box::new(value) as Box<dyn Reflect>)
+            }
+        }
+
+        // RATIONALE: maps are reflected with their default hasher only.
+        #[allow(clippy::implicit_hasher)]
+        impl<K, V> Typed for $map<K, V>
+        where
+            K: Reflect + Typed + FromStr $(+ $key_bound)*,
+            V: Reflect + Typed,
+        {
+            fn type_info() -> TypeInfo {
+                TypeInfo::Map(MapInfo::new::<Self, K, V>())
+            }
+
+            fn register_dependencies(registry: &mut TypeRegistry) {
+                registry.register::<K>();
+                registry.register::<V>();
+            }
+        }
+    };
+}
+
+impl_map!(HashMap, Eq, Hash);
+impl_map!(BTreeMap, Ord);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GetPath;
+
+    #[test]
+    fn containers_reach_their_items() {
+        let mut scores: HashMap<String, Vec<Option<u32>>> = HashMap::new();
+        scores.insert("ayla".to_owned(), vec![Some(3), None]);
+
+        assert_eq!(scores.path::<u32>("ayla.0.0"), Ok(&3));
+        assert!(scores.reflect_path("ayla.1.0").is_err());
+        scores.set_path("ayla.0.0", 5u32).unwrap();
+        assert_eq!(scores["ayla"][0], Some(5));
+
+        let ReflectMut::List(list) = scores.reflect_path_mut("ayla").unwrap().reflect_mut() else {
+            panic!("`Vec` is a list");
+        };
+        list.push(Box::new(Some(7u32))).unwrap();
+        assert!(list.push(Box::new(7u32)).is_err());
+        assert_eq!(list.len(), 3);
+
+        let ReflectMut::Enum(option) = scores.reflect_path_mut("ayla.0").unwrap().reflect_mut()
+        else {
+            panic!("`Option` is an enum");
+        };
+        option.set_variant("None").unwrap();
+        assert_eq!(
+            option.set_variant("Some"),
+            Err(ReflectError::VariantHasFields {
+                type_name: type_name::<Option<u32>>(),
+                variant: "Some",
+            })
+        );
+        assert_eq!(scores["ayla"], [None, None, Some(7)]);
+
+        scores
+            .set_path_boxed("ayla.2", Box::new(Some(8u32)))
+            .unwrap();
+        assert_eq!(
+            scores.set_path_boxed("ayla.2", Box::new(8u32)),
+            Err(ReflectError::TypeMismatch {
+                expected: type_name::<Option<u32>>(),
+                found: type_name::<u32>(),
+            })
+        );
+        assert_eq!(scores["ayla"][2], Some(8));
+
+        let ReflectMut::Map(map) = scores.reflect_mut() else {
+            panic!("`HashMap` is a map");
+        };
+        let inserted = map.insert("bren", Box::new(vec![Some(1u32)])).unwrap();
+        assert!(inserted.is_none());
+        assert!(map.insert("bren", Box::new(1u32)).is_err());
+        let replaced = map.insert("bren", Box::new(Vec::<Option<u32>>::new()));
+        assert!(replaced.unwrap().unwrap().is::<Vec<Option<u32>>>());
+        let removed = map.remove("ayla").unwrap();
+        assert_eq!(
+            removed.downcast_ref::<Vec<Option<u32>>>().unwrap(),
+            &[None, None, Some(8)]
+        );
+        assert!(map.remove("ayla").is_none());
+        assert_eq!(map.len(), 1);
+        assert_eq!(scores["bren"], []);
+    }
+
+    #[test]
+    fn glam_types_are_structs_of_components() {
+        let mut rotation = Quat::IDENTITY;
+        assert_eq!(rotation.path::<f32>("w"), Ok(&1.0));
+        rotation.set_path("x", 0.5f32).unwrap();
+        assert!((rotation.x - 0.5).abs() < f32::EPSILON);
+
+        let TypeInfo::Struct(info) = Vec3::type_info() else {
+            panic!("`Vec3` is a struct");
+        };
+        let names: Vec<_> = info.fields().iter().map(FieldInfo::name).collect();
+        assert_eq!(names, ["x", "y", "z"]);
+    }
+}
@@ -0,0 +1,327 @@
+//! Static type information: fields, variants and their attributes.
+
+use std::any::{TypeId, type_name};
+use std::ops::RangeInclusive;
+
+use crate::reflect::Reflect;
+use crate::registry::TypeRegistry;
+
+/// A reflected type whose structure is known without a value, for
+/// inspectors and serializers.
+pub trait Typed: Reflect {
+    /// Returns the type's structure.
+    fn type_info() -> TypeInfo;
+
+    /// Registers the types of the fields, items or entries in `registry`.
+    fn register_dependencies(registry: &mut TypeRegistry) {
+        let _ = registry;
+    }
+}
+
+/// Editor hints attached to a type, variant or field with
+/// `#[reflect(...)]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Attributes {
+    range: Option<RangeInclusive<f64>>,
+    tooltip: Option<&'static str>,
+}
+
+impl Attributes {
+    /// Returns the range of valid values, from `#[reflect(range = a..=b)]`.
+    pub fn range(&self) -> Option<&RangeInclusive<f64>> {
+        self.range.as_ref()
+    }
+
+    /// Returns the help text, from `#[reflect(tooltip = "...")]`.
+    pub fn tooltip(&self) -> Option<&'static str> {
+        self.tooltip
+    }
+}
+
+/// Implements the attribute builders of an info type.
+macro_rules! impl_attributes {
+    ($($info:ty),*) => {$(
+        impl $info {
+            /// Returns the attributes.
+            pub fn attributes(&self) -> &Attributes {
+                &self.attributes
+            }
+
+            /// Sets the range of valid values.
+            #[must_use]
+            pub fn with_range(mut self, range: RangeInclusive<f64>) -> Self {
+                self.attributes.range = Some(range);
+                self
+            }
+
+            /// Sets the help text.
+            #[must_use]
+            pub fn with_tooltip(mut self, tooltip: &'static str) -> Self {
+                self.attributes.tooltip = Some(tooltip);
+                self
+            }
+        }
+    )*};
+}
+
+impl_attributes!(StructInfo, EnumInfo, VariantInfo, FieldInfo);
+
+/// The structure of a reflected type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeInfo {
+    /// A struct, with named or positional fields.
+    Struct(StructInfo),
+    /// An enum and its variants.
+    Enum(EnumInfo),
+    /// A sequence of items of one type.
+    List(ListInfo),
+    /// A collection of keys and values of one type each.
+    Map(MapInfo),
+    /// A type without reflected parts.
+    Value(ValueInfo),
+}
+
+impl TypeInfo {
+    /// Returns the type's name.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Struct(info) => info.type_name,
+            Self::Enum(info) => info.type_name,
+            Self::List(info) => info.type_name,
+            Self::Map(info) => info.type_name,
+            Self::Value(info) => info.type_name,
+        }
+    }
+
+    /// Returns the type's id.
+    pub fn type_id(&self) -> TypeId {
+        match self {
+            Self::Struct(info) => info.type_id,
+            Self::Enum(info) => info.type_id,
+            Self::List(info) => info.type_id,
+            Self::Map(info) => info.type_id,
+            Self::Value(info) => info.type_id,
+        }
+    }
+}
+
+/// The fields of a struct.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    fields: Vec<FieldInfo>,
+    attributes: Attributes,
+}
+
+impl StructInfo {
+    /// Describes `T`, a struct with `fields`.
+    pub fn new<T: Reflect>(fields: Vec<FieldInfo>) -> Self {
+        Self {
+            type_name: type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            fields,
+            attributes: Attributes::default(),
+        }
+    }
+
+    /// Returns the reflected fields, in declaration order.
+    pub fn fields(&self) -> &[FieldInfo] {
+        &self.fields
+    }
+
+    /// Returns the field named `name`.
+    pub fn field(&self, name: &str) -> Option<&FieldInfo> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+/// The variants of an enum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    variants: Vec<VariantInfo>,
+    attributes: Attributes,
+}
+
+impl EnumInfo {
+    /// Describes `T`, an enum with `variants`.
+    pub fn new<T: Reflect>(variants: Vec<VariantInfo>) -> Self {
+        Self {
+            type_name: type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            variants,
+            attributes: Attributes::default(),
+        }
+    }
+
+    /// Returns the variants, in declaration order.
+    pub fn variants(&self) -> &[VariantInfo] {
+        &self.variants
+    }
+
+    /// Returns the variant named `name`.
+    pub fn variant(&self, name: &str) -> Option<&VariantInfo> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+}
+
+/// A variant of an enum and its fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantInfo {
+    name: &'static str,
+    fields: Vec<FieldInfo>,
+    attributes: Attributes,
+}
+
+impl VariantInfo {
+    /// Describes the variant `name` with `fields`.
+    pub fn new(name: &'static str, fields: Vec<FieldInfo>) -> Self {
+        Self {
+            name,
+            fields,
+            attributes: Attributes::default(),
+        }
+    }
+
+    /// Returns the variant's name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the reflected fields, in declaration order.
+    pub fn fields(&self) -> &[FieldInfo] {
+        &self.fields
+    }
+}
+
+/// A field of a struct or variant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldInfo {
+    name: &'static str,
+    type_name: &'static str,
+    type_id: TypeId,
+    attributes: Attributes,
+}
+
+impl FieldInfo {
+    /// Describes the field `name`, of type `T`.
+    pub fn new<T: Reflect>(name: &'static str) -> Self {
+        Self {
+            name,
+            type_name: type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    /// Returns the field's name, or its position in a tuple.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the name of the field's type.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the id of the field's type, to look it up in a
+    /// [`TypeRegistry`].
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+}
+
+/// The item type of a list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    item_type_name: &'static str,
+    item_type_id: TypeId,
+}
+
+impl ListInfo {
+    /// Describes `T`, a list of `I`.
+    pub fn new<T: Reflect, I: Reflect>() -> Self {
+        Self {
+            type_name: type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            item_type_name: type_name::<I>(),
+            item_type_id: TypeId::of::<I>(),
+        }
+    }
+
+    /// Returns the name of the item type.
+    pub fn item_type_name(&self) -> &'static str {
+        self.item_type_name
+    }
+
+    /// Returns the id of the item type.
+    pub fn item_type_id(&self) -> TypeId {
+        self.item_type_id
+    }
+}
+
+/// The key and value types of a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    key_type_name: &'static str,
+    key_type_id: TypeId,
+    value_type_name: &'static str,
+    value_type_id: TypeId,
+}
+
+impl MapInfo {
+    /// Describes `T`, a map from `K` to `V`.
+    pub fn new<T: Reflect, K: Reflect, V: Reflect>() -> Self {
+        Self {
+            type_name: type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            key_type_name: type_name::<K>(),
+            key_type_id: TypeId::of::<K>(),
+            value_type_name: type_name::<V>(),
+            value_type_id: TypeId::of::<V>(),
+        }
+    }
+
+    /// Returns the name of the key type.
+    pub fn key_type_name(&self) -> &'static str {
+        self.key_type_name
+    }
+
+    /// Returns the id of the key type.
+    pub fn key_type_id(&self) -> TypeId {
+        self.key_type_id
+    }
+
+    /// Returns the name of the value type.
+    pub fn value_type_name(&self) -> &'static str {
+        self.value_type_name
+    }
+
+    /// Returns the id of the value type.
+    pub fn value_type_id(&self) -> TypeId {
+        self.value_type_id
+    }
+}
+
+/// A type without reflected parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+}
+
+impl ValueInfo {
+    /// Describes `T`.
+    pub fn new<T: Reflect>() -> Self {
+        Self {
+            type_name: type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+        }
+    }
+}
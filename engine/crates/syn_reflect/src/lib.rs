@@ -0,0 +1,131 @@
+//! `syn_reflect` - Runtime reflection for Synarion Engine.
+//!
+//! - [`Reflect`] - Values inspected and modified by field name, derived with
+//!   `#[derive(Reflect)]`
+//! - [`Struct`] / [`Enum`] / [`List`] / [`Map`] - The reflected kinds of
+//!   types, told apart with [`ReflectRef`]
+//! - [`GetPath`] - Nested access by path such as `"transform.translation.x"`
+//! - [`Typed`] / [`TypeInfo`] - Fields, variants and their range and tooltip
+//!   [`Attributes`], known without a value
+//! - [`TypeRegistry`] - Reflected types looked up by id or name
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+// Lets the derive macro's `::syn_reflect` paths resolve inside this crate.
+extern crate self as syn_reflect;
+
+mod error;
+mod impls;
+mod info;
+mod path;
+mod reflect;
+mod registry;
+
+pub use error::{ReflectError, ReflectResult};
+pub use info::{
+    Attributes, EnumInfo, FieldInfo, ListInfo, MapInfo, StructInfo, TypeInfo, Typed, ValueInfo,
+    VariantInfo,
+};
+pub use path::GetPath;
+pub use reflect::{Enum, List, Map, Reflect, ReflectMut, ReflectRef, Struct};
+pub use registry::TypeRegistry;
+pub use syn_reflect_derive::Reflect;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The shape of a collider.
+    #[derive(Debug, PartialEq, Reflect)]
+    enum Shape {
+        Sphere {
+            #[reflect(range = 0.0..=100.0)]
+            radius: f32,
+        },
+        Capsule(f32, #[reflect(skip)] u8),
+        #[reflect(tooltip = "Uses the mesh")]
+        Mesh,
+    }
+
+    #[derive(Reflect)]
+    #[reflect(tooltip = "A physics collider")]
+    struct Collider<T> {
+        shape: Shape,
+        layers: T,
+        #[reflect(skip)]
+        cache: Vec<u8>,
+    }
+
+    #[test]
+    fn derived_enums_expose_their_current_variant() {
+        let mut collider = Collider {
+            shape: Shape::Sphere { radius: 2.0 },
+            layers: 3u32,
+            cache: Vec::new(),
+        };
+        assert_eq!(collider.path::<f32>("shape.radius"), Ok(&2.0));
+        collider.set_path("layers", 5u32).unwrap();
+        assert_eq!(collider.layers, 5);
+
+        let ReflectMut::Enum(shape) = collider.reflect_path_mut("shape").unwrap().reflect_mut()
+        else {
+            panic!("`Shape` is an enum");
+        };
+        shape.set_variant("Mesh").unwrap();
+        assert_eq!(shape.variant_index(), 2);
+        assert!(shape.set_variant("Capsule").is_err());
+        assert_eq!(collider.shape, Shape::Mesh);
+        assert_eq!(
+            collider.reflect_path("shape.radius").map(|_| ()),
+            Err(ReflectError::NoSuchField {
+                type_name: std::any::type_name::<Shape>(),
+                field: "radius".to_owned(),
+            })
+        );
+
+        collider.shape = Shape::Capsule(1.5, 0);
+        assert_eq!(collider.path::<f32>("shape.0"), Ok(&1.5));
+        assert!(collider.reflect_path("shape.1").is_err());
+        assert!(collider.cache.is_empty());
+
+        // Derived types can be replaced whole from a value of unknown type.
+        collider
+            .set_path_boxed("shape", Box::new(Shape::Mesh))
+            .unwrap();
+        assert_eq!(collider.shape, Shape::Mesh);
+        assert!(collider.set_path_boxed("shape", Box::new(1.5f32)).is_err());
+    }
+
+    #[test]
+    fn type_info_describes_fields_and_attributes() {
+        let mut registry = TypeRegistry::new();
+        registry.register::<Collider<u32>>();
+        // The collider, the shape, f32 and u32.
+        assert_eq!(registry.len(), 4);
+
+        let Some(TypeInfo::Struct(collider)) =
+            registry.get(std::any::TypeId::of::<Collider<u32>>())
+        else {
+            panic!("`Collider` is a struct");
+        };
+        assert_eq!(collider.attributes().tooltip(), Some("A physics collider"));
+        let names: Vec<_> = collider.fields().iter().map(FieldInfo::name).collect();
+        assert_eq!(names, ["shape", "layers"]);
+
+        let Some(TypeInfo::Enum(shape)) = registry.get(collider.fields()[0].type_id()) else {
+            panic!("`Shape` is an enum");
+        };
+        let sphere = shape.variant("Sphere").unwrap();
+        assert_eq!(
+            sphere.fields()[0].attributes().range(),
+            Some(&(0.0..=100.0))
+        );
+        assert_eq!(shape.variant("Capsule").unwrap().fields().len(), 1);
+        assert_eq!(
+            shape.variant("Mesh").unwrap().attributes().tooltip(),
+            Some("Uses the mesh")
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Access to nested fields by path, such as `"transform.translation.x"`.
+
+use std::any::type_name;
+
+use crate::error::{ReflectError, ReflectResult};
+use crate::reflect::{Reflect, ReflectMut, ReflectRef};
+
+/// Reaches nested fields of reflected values by path.
+///
+/// A path is a list of segments separated by dots: field names of structs
+/// and of the current variant of enums, positions in lists, and keys of
+/// maps. The empty path is the value itself.
+///
+/// # Example
+///
+/// ```
+/// use syn_reflect::{GetPath, Reflect};
+///
+/// #[derive(Reflect)]
+/// struct Stats {
+///     levels: Vec<u32>,
+/// }
+///
+/// #[derive(Reflect)]
+/// struct Player {
+///     name: String,
+///     stats: Stats,
+/// }
+///
+/// let mut player = Player {
+///     name: "Ayla".to_owned(),
+///     stats: Stats { levels: vec![1, 4] },
+/// };
+/// assert_eq!(player.path::<u32>("stats.levels.1"), Ok(&4));
+///
+/// player.set_path("stats.levels.0", 2u32).unwrap();
+/// assert_eq!(player.stats.levels, [2, 4]);
+/// assert!(player.set_path("name", 3u32).is_err());
+/// ```
+pub trait GetPath {
+    /// Returns the value at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::NoSuchField`] if a segment names nothing, and
+    /// [`ReflectError::InvalidPath`] if a segment is empty.
+    fn reflect_path(&self, path: &str) -> ReflectResult<&dyn Reflect>;
+
+    /// Returns the value at `path` mutably.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`reflect_path`](Self::reflect_path).
+    fn reflect_path_mut(&mut self, path: &str) -> ReflectResult<&mut dyn Reflect>;
+
+    /// Returns the `T` at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`reflect_path`](Self::reflect_path), and
+    /// [`ReflectError::TypeMismatch`] if the value is not a `T`.
+    fn path<T: Reflect>(&self, path: &str) -> ReflectResult<&T> {
+        let value = self.reflect_path(path)?;
+        let found = value.type_name();
+        value.downcast_ref().ok_or(ReflectError::TypeMismatch {
+            expected: type_name::<T>(),
+            found,
+        })
+    }
+
+    /// Returns the `T` at `path` mutably.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`path`](Self::path).
+    fn path_mut<T: Reflect>(&mut self, path: &str) -> ReflectResult<&mut T> {
+        let value = self.reflect_path_mut(path)?;
+        let found = value.type_name();
+        value.downcast_mut().ok_or(ReflectError::TypeMismatch {
+            expected: type_name::<T>(),
+            found,
+        })
+    }
+
+    /// Replaces the `T` at `path` with `value`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`path`](Self::path).
+    fn set_path<T: Reflect>(&mut self, path: &str, value: T) -> ReflectResult<()> {
+        *self.path_mut(path)? = value;
+        Ok(())
+    }
+
+    /// Replaces the value at `path` with `value`, whose type is only known at
+    /// runtime, such as a value decoded by a script or from the network.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`reflect_path`](Self::reflect_path), and
+    /// [`ReflectError::TypeMismatch`] if `value` has another type than the
+    /// value at `path`.
+    fn set_path_boxed(&mut self, path: &str, value: Box<dyn Reflect>) -> ReflectResult<()> {
+        self.reflect_path_mut(path)?.apply(value)
+    }
+}
+
+impl<R: Reflect + ?Sized> GetPath for R {
+    fn reflect_path(&self, path: &str) -> ReflectResult<&dyn Reflect> {
+        let mut value = self.as_reflect();
+        for segment in segments(path)? {
+            value = child(value, segment)?;
+        }
+        Ok(value)
+    }
+
+    fn reflect_path_mut(&mut self, path: &str) -> ReflectResult<&mut dyn Reflect> {
+        let mut value = self.as_reflect_mut();
+        for segment in segments(path)? {
+            value = child_mut(value, segment)?;
+        }
+        Ok(value)
+    }
+}
+
+fn segments(path: &str) -> ReflectResult<Vec<&str>> {
+    if path.is_empty() {
+        return Ok(Vec::new());
+    }
+    let segments: Vec<&str> = path.split('.').collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return Err(ReflectError::InvalidPath(path.to_owned()));
+    }
+    Ok(segments)
+}
+
+fn child<'a>(value: &'a dyn Reflect, segment: &str) -> ReflectResult<&'a dyn Reflect> {
+    let found = match value.reflect_ref() {
+        ReflectRef::Struct(value) => value.field(segment),
+        ReflectRef::Enum(value) => value.field(segment),
+        ReflectRef::List(value) => segment.parse().ok().and_then(|index| value.get(index)),
+        ReflectRef::Map(value) => value.get(segment),
+        ReflectRef::Value(_) => None,
+    };
+    found.ok_or_else(|| no_such_field(value.type_name(), segment))
+}
+
+fn child_mut<'a>(value: &'a mut dyn Reflect, segment: &str) -> ReflectResult<&'a mut dyn Reflect> {
+    let type_name = value.type_name();
+    let found = match value.reflect_mut() {
+        ReflectMut::Struct(value) => value.field_mut(segment),
+        ReflectMut::Enum(value) => value.field_mut(segment),
+        ReflectMut::List(value) => segment.parse().ok().and_then(|index| value.get_mut(index)),
+        ReflectMut::Map(value) => value.get_mut(segment),
+        ReflectMut::Value(_) => None,
+    };
+    found.ok_or_else(|| no_such_field(type_name, segment))
+}
+
+fn no_such_field(type_name: &'static str, segment: &str) -> ReflectError {
+    ReflectError::NoSuchField {
+        type_name,
+        field: segment.to_owned(),
+    }
+}
@@ -0,0 +1,246 @@
+//! The reflection traits, one per kind of type.
+
+use std::any::Any;
+
+use crate::error::ReflectResult;
+
+/// A value whose structure can be inspected and modified at runtime, by
+/// field name rather than through its Rust type.
+///
+/// Derive it with `#[derive(Reflect)]` for structs and enums; it is
+/// implemented for primitives, `String`, `Option`, `Vec`, `HashMap`,
+/// `BTreeMap` and the `glam` vector and quaternion types.
+///
+/// Use [`reflect_ref`](Self::reflect_ref) to tell structs, enums, lists,
+/// maps and plain values apart, and [`GetPath`](crate::GetPath) to reach
+/// nested fields by path.
+pub trait Reflect: Any + Send + Sync {
+    /// Returns the name of the value's type.
+    fn type_name(&self) -> &'static str;
+
+    /// Returns the value as [`Any`], to downcast it.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns the value as mutable [`Any`], to downcast it.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Converts the boxed value into a boxed [`Any`], to downcast it.
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+
+    /// Returns the value as a reflected value.
+    fn as_reflect(&self) -> &dyn Reflect;
+
+    /// Returns the value as a mutable reflected value.
+    fn as_reflect_mut(&mut self) -> &mut dyn Reflect;
+
+    /// Returns the value's structure.
+    fn reflect_ref(&self) -> ReflectRef<'_>;
+
+    /// Returns the value's structure mutably.
+    fn reflect_mut(&mut self) -> ReflectMut<'_>;
+
+    /// Replaces the value with `value`, which must have the same type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::TypeMismatch`](crate::ReflectError::TypeMismatch)
+    /// if `value` has another type.
+    fn apply(&mut self, value: Box<dyn Reflect>) -> ReflectResult<()>;
+}
+
+impl dyn Reflect {
+    /// Returns `true` if the value is a `T`.
+    pub fn is<T: Reflect>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// Returns the value as a `T`, or `None` if it is not one.
+    pub fn downcast_ref<T: Reflect>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Returns the value mutably as a `T`, or `None` if it is not one.
+    pub fn downcast_mut<T: Reflect>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+
+    /// Returns the boxed value as a `T`, or the box back if it is not one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the box unchanged if the value is not a `T`.
+    pub fn downcast<T: Reflect>(self: Box<Self>) -> Result<Box<T>, Box<dyn Reflect>> {
+        if self.is::<T>() {
+            Ok(self
+                .into_any()
+                .downcast()
+                .unwrap_or_else(|_| unreachable!("the type was just checked")))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// The structure of a reflected value.
+pub enum ReflectRef<'a> {
+    /// A struct, with named or positional fields.
+    Struct(&'a dyn Struct),
+    /// An enum, exposing the fields of its current variant.
+    Enum(&'a dyn Enum),
+    /// A sequence indexed by position.
+    List(&'a dyn List),
+    /// A collection indexed by key.
+    Map(&'a dyn Map),
+    /// A value without reflected parts, such as a number or a string.
+    Value(&'a dyn Reflect),
+}
+
+/// The structure of a mutably borrowed reflected value.
+pub enum ReflectMut<'a> {
+    /// A struct, with named or positional fields.
+    Struct(&'a mut dyn Struct),
+    /// An enum, exposing the fields of its current variant.
+    Enum(&'a mut dyn Enum),
+    /// A sequence indexed by position.
+    List(&'a mut dyn List),
+    /// A collection indexed by key.
+    Map(&'a mut dyn Map),
+    /// A value without reflected parts, such as a number or a string.
+    Value(&'a mut dyn Reflect),
+}
+
+/// A reflected struct. Tuple structs name their fields `0`, `1`, and so on.
+pub trait Struct: Reflect {
+    /// Returns the number of reflected fields.
+    fn field_len(&self) -> usize;
+
+    /// Returns the name of the field at `index`.
+    fn name_at(&self, index: usize) -> Option<&'static str>;
+
+    /// Returns the field at `index`.
+    fn field_at(&self, index: usize) -> Option<&dyn Reflect>;
+
+    /// Returns the field at `index` mutably.
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect>;
+
+    /// Returns the field named `name`.
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        let index = (0..self.field_len()).find(|index| self.name_at(*index) == Some(name))?;
+        self.field_at(index)
+    }
+
+    /// Returns the field named `name` mutably.
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        let index = (0..self.field_len()).find(|index| self.name_at(*index) == Some(name))?;
+        self.field_at_mut(index)
+    }
+}
+
+/// A reflected enum, whose fields are those of its current variant.
+pub trait Enum: Reflect {
+    /// Returns the name of the current variant.
+    fn variant_name(&self) -> &'static str;
+
+    /// Returns the position of the current variant in the declaration.
+    fn variant_index(&self) -> usize;
+
+    /// Returns the number of reflected fields of the current variant.
+    fn field_len(&self) -> usize;
+
+    /// Returns the name of the field at `index` of the current variant.
+    fn name_at(&self, index: usize) -> Option<&'static str>;
+
+    /// Returns the field at `index` of the current variant.
+    fn field_at(&self, index: usize) -> Option<&dyn Reflect>;
+
+    /// Returns the field at `index` of the current variant mutably.
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect>;
+
+    /// Switches to the variant named `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::NoSuchVariant`](crate::ReflectError::NoSuchVariant)
+    /// if there is no such variant, and
+    /// [`ReflectError::VariantHasFields`](crate::ReflectError::VariantHasFields)
+    /// if the variant has fields, which reflection cannot create.
+    fn set_variant(&mut self, name: &str) -> ReflectResult<()>;
+
+    /// Returns the field named `name` of the current variant.
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        let index = (0..self.field_len()).find(|index| self.name_at(*index) == Some(name))?;
+        self.field_at(index)
+    }
+
+    /// Returns the field named `name` of the current variant mutably.
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        let index = (0..self.field_len()).find(|index| self.name_at(*index) == Some(name))?;
+        self.field_at_mut(index)
+    }
+}
+
+/// A reflected sequence, such as a `Vec`.
+pub trait List: Reflect {
+    /// Returns the number of items.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the list has no item.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the item at `index`.
+    fn get(&self, index: usize) -> Option<&dyn Reflect>;
+
+    /// Returns the item at `index` mutably.
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Reflect>;
+
+    /// Appends an item.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::TypeMismatch`](crate::ReflectError::TypeMismatch)
+    /// if the item has the wrong type.
+    fn push(&mut self, item: Box<dyn Reflect>) -> ReflectResult<()>;
+
+    /// Removes the last item and returns it.
+    fn pop(&mut self) -> Option<Box<dyn Reflect>>;
+}
+
+/// A reflected collection indexed by key, such as a `HashMap`.
+///
+/// Keys are looked up from their text, as they appear in paths.
+pub trait Map: Reflect {
+    /// Returns the number of entries.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the map has no entry.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value of the key parsed from `key`.
+    fn get(&self, key: &str) -> Option<&dyn Reflect>;
+
+    /// Returns the value of the key parsed from `key` mutably.
+    fn get_mut(&mut self, key: &str) -> Option<&mut dyn Reflect>;
+
+    /// Returns the entries, in the map's iteration order.
+    fn entries(&self) -> Vec<(&dyn Reflect, &dyn Reflect)>;
+
+    /// Inserts `value` under the key parsed from `key`, and returns the value
+    /// it replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::TypeMismatch`](crate::ReflectError::TypeMismatch)
+    /// if `key` does not parse as the key type or `value` has the wrong type.
+    fn insert(
+        &mut self,
+        key: &str,
+        value: Box<dyn Reflect>,
+    ) -> ReflectResult<Option<Box<dyn Reflect>>>;
+
+    /// Removes the entry of the key parsed from `key` and returns its value.
+    fn remove(&mut self, key: &str) -> Option<Box<dyn Reflect>>;
+}
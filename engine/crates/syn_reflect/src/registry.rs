@@ -0,0 +1,91 @@
+//! The type registry: reflected types looked up by id or name.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+
+use crate::info::{TypeInfo, Typed};
+
+/// The [`TypeInfo`] of reflected types, looked up by type id or name.
+///
+/// Registering a type registers the types of its fields too, so an
+/// inspector can describe a whole component from its id.
+///
+/// # Example
+///
+/// ```
+/// use syn_reflect::{Reflect, TypeInfo, TypeRegistry};
+///
+/// #[derive(Reflect)]
+/// struct Light {
+///     #[reflect(range = 0.0..=10.0, tooltip = "Brightness in lux")]
+///     intensity: f32,
+///     enabled: bool,
+/// }
+///
+/// let mut registry = TypeRegistry::new();
+/// registry.register::<Light>();
+///
+/// let TypeInfo::Struct(info) = registry.get_by_name(std::any::type_name::<Light>()).unwrap()
+/// else {
+///     unreachable!()
+/// };
+/// let intensity = info.field("intensity").unwrap();
+/// assert_eq!(intensity.attributes().tooltip(), Some("Brightness in lux"));
+/// assert!(registry.get(intensity.type_id()).is_some());
+/// ```
+#[derive(Debug, Default)]
+pub struct TypeRegistry {
+    types: HashMap<TypeId, TypeInfo>,
+    names: HashMap<&'static str, TypeId>,
+}
+
+impl TypeRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `T` and, recursively, the types it is made of. Registering
+    /// a type twice does nothing.
+    pub fn register<T: Typed>(&mut self) {
+        let type_id = TypeId::of::<T>();
+        if self.types.contains_key(&type_id) {
+            return;
+        }
+        let info = T::type_info();
+        self.names.insert(info.type_name(), type_id);
+        self.types.insert(type_id, info);
+        T::register_dependencies(self);
+    }
+
+    /// Returns `true` if `T` is registered.
+    pub fn contains<T: Typed>(&self) -> bool {
+        self.types.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns the structure of the type of id `type_id`.
+    pub fn get(&self, type_id: TypeId) -> Option<&TypeInfo> {
+        self.types.get(&type_id)
+    }
+
+    /// Returns the structure of the type named `name`, as given by
+    /// [`std::any::type_name`].
+    pub fn get_by_name(&self, name: &str) -> Option<&TypeInfo> {
+        self.get(*self.names.get(name)?)
+    }
+
+    /// Returns the number of types registered.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Returns `true` if no type is registered.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Iterates over the registered types, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &TypeInfo> {
+        self.types.values()
+    }
+}
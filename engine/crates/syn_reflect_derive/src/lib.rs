@@ -0,0 +1,464 @@
+//! `syn_reflect_derive` - `#[derive(Reflect)]` for `syn_reflect`.
+//!
+//! Use it through the `syn_reflect::Reflect` re-export.
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Expr, ExprRange, Fields, Generics, Ident, LitStr, RangeLimits,
+    Type, parse_macro_input, parse_quote,
+};
+
+/// Implements `Reflect` and `Typed` for a struct or enum, and `Struct` or
+/// `Enum` for its fields.
+///
+/// Every field must be reflectable, unless marked `#[reflect(skip)]`.
+/// Fields accept `#[reflect(range = 0.0..=1.0)]` and
+/// `#[reflect(tooltip = "...")]`; types and variants accept tooltips.
+#[proc_macro_derive(Reflect, attributes(reflect))]
+pub fn derive_reflect(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let attributes = Attributes::parse(&input.attrs)?;
+    if attributes.skip || attributes.range.is_some() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "only fields can be skipped or given a range",
+        ));
+    }
+    let generics = with_bounds(&input.generics);
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let name = &input.ident;
+    let type_attributes = attributes.tokens();
+
+    let (kind, body, info, dependencies) = match &input.data {
+        Data::Struct(data) => {
+            let fields = reflected_fields(&data.fields)?;
+            let info = field_infos(&fields);
+            (
+                format_ident!("Struct"),
+                expand_struct(&fields),
+                quote! {
+                    ::syn_reflect::TypeInfo::Struct(
+                        ::syn_reflect::StructInfo::new::<Self>(#info)#type_attributes
+                    )
+                },
+                fields.iter().map(|field| field.ty).collect::<Vec<_>>(),
+            )
+        }
+        Data::Enum(data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    Ok(Variant {
+                        ident: &variant.ident,
+                        attributes: Attributes::parse(&variant.attrs)?,
+                        fields: &variant.fields,
+                        reflected: reflected_fields(&variant.fields)?,
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            let variant_infos = variants.iter().map(|variant| {
+                let name = variant.ident.to_string();
+                let info = field_infos(&variant.reflected);
+                let attributes = variant.attributes.tokens();
+                quote!(::syn_reflect::VariantInfo::new(#name, #info)#attributes)
+            });
+            (
+                format_ident!("Enum"),
+                expand_enum(&variants),
+                quote! {
+                    ::syn_reflect::TypeInfo::Enum(
+                        ::syn_reflect::EnumInfo::new::<Self>(
+                            ::std::vec![#(#variant_infos),*]
+                        )#type_attributes
+                    )
+                },
+                variants
+                    .iter()
+                    .flat_map(|variant| variant.reflected.iter().map(|field| field.ty))
+                    .collect(),
+            )
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "unions cannot derive `Reflect`",
+            ));
+        }
+    };
+
+    let reflect = reflect_impl(&generics, name, &kind);
+    Ok(quote! {
+        #reflect
+
+        impl #impl_generics ::syn_reflect::#kind for #name #type_generics #where_clause {
+            #body
+        }
+
+        impl #impl_generics ::syn_reflect::Typed for #name #type_generics #where_clause {
+            fn type_info() -> ::syn_reflect::TypeInfo {
+                #info
+            }
+
+            fn register_dependencies(registry: &mut ::syn_reflect::TypeRegistry) {
+                #(registry.register::<#dependencies>();)*
+            }
+        }
+    })
+}
+
+/// Implements `Reflect`, whose methods only depend on the kind of type.
+fn reflect_impl(generics: &Generics, name: &Ident, kind: &Ident) -> TokenStream2 {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::syn_reflect::Reflect for #name #type_generics #where_clause {
+            fn type_name(&self) -> &'static str {
+                ::std::any::type_name::<Self>()
+            }
+
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any {
+                self
+            }
+
+            fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn ::std::any::Any> {
+                self
+            }
+
+            fn as_reflect(&self) -> &dyn ::syn_reflect::Reflect {
+                self
+            }
+
+            fn as_reflect_mut(&mut self) -> &mut dyn ::syn_reflect::Reflect {
+                self
+            }
+
+            fn reflect_ref(&self) -> ::syn_reflect::ReflectRef<'_> {
+                ::syn_reflect::ReflectRef::#kind(self)
+            }
+
+            fn reflect_mut(&mut self) -> ::syn_reflect::ReflectMut<'_> {
+                ::syn_reflect::ReflectMut::#kind(self)
+            }
+
+            fn apply(
+                &mut self,
+                value: ::std::boxed::Box<dyn ::syn_reflect::Reflect>,
+            ) -> ::syn_reflect::ReflectResult<()> {
+                let found = value.type_name();
+                let value = value.downcast::<Self>().map_err(|_| {
+                    ::syn_reflect::ReflectError::TypeMismatch {
+                        expected: ::std::any::type_name::<Self>(),
+                        found,
+                    }
+                })?;
+                *self = *value;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Adds `Reflect + Typed` bounds to the type parameters.
+fn with_bounds(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    let parameters: Vec<Ident> = generics
+        .type_params()
+        .map(|parameter| parameter.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+    for parameter in parameters {
+        where_clause
+            .predicates
+            .push(parse_quote!(#parameter: ::syn_reflect::Reflect + ::syn_reflect::Typed));
+    }
+    generics
+}
+
+/// A field exposed by reflection.
+struct Field<'a> {
+    /// The field's name, or its position for tuple fields.
+    name: String,
+    /// How the field is accessed: its name, or its position.
+    member: TokenStream2,
+    /// The binding of the field in enum patterns.
+    binding: Ident,
+    ty: &'a Type,
+    attributes: Attributes,
+}
+
+fn reflected_fields(fields: &Fields) -> syn::Result<Vec<Field<'_>>> {
+    let mut reflected = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let attributes = Attributes::parse(&field.attrs)?;
+        if attributes.skip {
+            continue;
+        }
+        let (name, member) = if let Some(ident) = &field.ident {
+            (ident.to_string(), quote!(#ident))
+        } else {
+            let index = syn::Index::from(index);
+            (index.index.to_string(), quote!(#index))
+        };
+        reflected.push(Field {
+            binding: format_ident!("field_{}", index),
+            name,
+            member,
+            ty: &field.ty,
+            attributes,
+        });
+    }
+    Ok(reflected)
+}
+
+fn field_infos(fields: &[Field<'_>]) -> TokenStream2 {
+    let infos = fields.iter().map(|field| {
+        let name = &field.name;
+        let ty = field.ty;
+        let attributes = field.attributes.tokens();
+        quote!(::syn_reflect::FieldInfo::new::<#ty>(#name)#attributes)
+    });
+    quote!(::std::vec![#(#infos),*])
+}
+
+fn expand_struct(fields: &[Field<'_>]) -> TokenStream2 {
+    let count = fields.len();
+    let indices = 0..count;
+    let names = fields.iter().map(|field| &field.name);
+    let members: Vec<_> = fields.iter().map(|field| &field.member).collect();
+    let indices_again = 0..count;
+    let indices_mut = 0..count;
+    quote! {
+        fn field_len(&self) -> usize {
+            #count
+        }
+
+        fn name_at(&self, index: usize) -> ::std::option::Option<&'static str> {
+            match index {
+                #(#indices => ::std::option::Option::Some(#names),)*
+                _ => ::std::option::Option::None,
+            }
+        }
+
+        fn field_at(&self, index: usize) -> ::std::option::Option<&dyn ::syn_reflect::Reflect> {
+            match index {
+                #(#indices_again => ::std::option::Option::Some(&self.#members),)*
+                _ => ::std::option::Option::None,
+            }
+        }
+
+        fn field_at_mut(
+            &mut self,
+            index: usize,
+        ) -> ::std::option::Option<&mut dyn ::syn_reflect::Reflect> {
+            match index {
+                #(#indices_mut => ::std::option::Option::Some(&mut self.#members),)*
+                _ => ::std::option::Option::None,
+            }
+        }
+    }
+}
+
+struct Variant<'a> {
+    ident: &'a Ident,
+    attributes: Attributes,
+    fields: &'a Fields,
+    reflected: Vec<Field<'a>>,
+}
+
+impl Variant<'_> {
+    /// Returns a pattern binding the reflected fields of the variant.
+    fn pattern(&self) -> TokenStream2 {
+        let ident = self.ident;
+        let bindings = self.reflected.iter().map(|field| {
+            let member = &field.member;
+            let binding = &field.binding;
+            quote!(#member: #binding)
+        });
+        match self.fields {
+            Fields::Unit => quote!(Self::#ident),
+            Fields::Named(_) | Fields::Unnamed(_) => quote!(Self::#ident { #(#bindings,)* .. }),
+        }
+    }
+}
+
+fn expand_enum(variants: &[Variant<'_>]) -> TokenStream2 {
+    let patterns: Vec<_> = variants.iter().map(Variant::pattern).collect();
+    let names: Vec<_> = variants
+        .iter()
+        .map(|variant| variant.ident.to_string())
+        .collect();
+    let indices = 0..variants.len();
+    let lens = variants.iter().map(|variant| variant.reflected.len());
+    let field_names = variants.iter().map(|variant| {
+        let indices = 0..variant.reflected.len();
+        let names = variant.reflected.iter().map(|field| &field.name);
+        quote! {
+            match index {
+                #(#indices => ::std::option::Option::Some(#names),)*
+                _ => ::std::option::Option::None,
+            }
+        }
+    });
+    let field_values: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let indices = 0..variant.reflected.len();
+            let bindings = variant.reflected.iter().map(|field| &field.binding);
+            quote! {
+                match index {
+                    #(#indices => ::std::option::Option::Some(#bindings),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        })
+        .collect();
+    let set_variants = variants.iter().map(|variant| {
+        let ident = variant.ident;
+        let name = ident.to_string();
+        if matches!(variant.fields, Fields::Unit) {
+            quote! {
+                #name => {
+                    *self = Self::#ident;
+                    ::std::result::Result::Ok(())
+                }
+            }
+        } else {
+            quote! {
+                #name => ::std::result::Result::Err(::syn_reflect::ReflectError::VariantHasFields {
+                    type_name: ::std::any::type_name::<Self>(),
+                    variant: #name,
+                }),
+            }
+        }
+    });
+    // RATIONALE: `#[allow]`s below keep user lints such as
+    // `clippy::match_same_arms` quiet in generated code.
+    quote! {
+        fn variant_name(&self) -> &'static str {
+            match self {
+                #(#patterns => #names,)*
+            }
+        }
+
+        #[allow(unused_variables)]
+        fn variant_index(&self) -> usize {
+            match self {
+                #(#patterns => #indices,)*
+            }
+        }
+
+        #[allow(unused_variables, clippy::match_same_arms)]
+        fn field_len(&self) -> usize {
+            match self {
+                #(#patterns => #lens,)*
+            }
+        }
+
+        #[allow(unused_variables)]
+        fn name_at(&self, index: usize) -> ::std::option::Option<&'static str> {
+            match self {
+                #(#patterns => #field_names,)*
+            }
+        }
+
+        #[allow(unused_variables)]
+        fn field_at(&self, index: usize) -> ::std::option::Option<&dyn ::syn_reflect::Reflect> {
+            match self {
+                #(#patterns => #field_values,)*
+            }
+        }
+
+        #[allow(unused_variables)]
+        fn field_at_mut(
+            &mut self,
+            index: usize,
+        ) -> ::std::option::Option<&mut dyn ::syn_reflect::Reflect> {
+            match self {
+                #(#patterns => #field_values,)*
+            }
+        }
+
+        fn set_variant(&mut self, name: &str) -> ::syn_reflect::ReflectResult<()> {
+            match name {
+                #(#set_variants)*
+                _ => ::std::result::Result::Err(::syn_reflect::ReflectError::NoSuchVariant {
+                    type_name: ::std::any::type_name::<Self>(),
+                    variant: name.to_owned(),
+                }),
+            }
+        }
+    }
+}
+
+/// The `#[reflect(...)]` attributes of a type, variant or field.
+#[derive(Default)]
+struct Attributes {
+    skip: bool,
+    range: Option<(Expr, Expr)>,
+    tooltip: Option<LitStr>,
+}
+
+impl Attributes {
+    fn parse(attributes: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for attribute in attributes {
+            if !attribute.path().is_ident("reflect") {
+                continue;
+            }
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    parsed.skip = true;
+                } else if meta.path.is_ident("range") {
+                    let range: ExprRange = meta.value()?.parse()?;
+                    let (Some(start), Some(end), RangeLimits::Closed(_)) =
+                        (range.start, range.end, range.limits)
+                    else {
+                        return Err(meta.error("expected an inclusive range such as `0.0..=1.0`"));
+                    };
+                    parsed.range = Some((*start, *end));
+                } else if meta.path.is_ident("tooltip") {
+                    parsed.tooltip = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("expected `skip`, `range` or `tooltip`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+
+    /// Returns the builder calls adding the attributes to an info.
+    fn tokens(&self) -> TokenStream2 {
+        let range = self.range.as_ref().map(|(start, end)| {
+            // RATIONALE: ranges are stored as `f64` whatever the field type.
+            quote! {
+                .with_range({
+                    #[allow(clippy::cast_lossless, clippy::cast_precision_loss)]
+                    let range = ((#start) as f64, (#end) as f64);
+                    range.0..=range.1
+                })
+            }
+        });
+        let tooltip = self
+            .tooltip
+            .as_ref()
+            .map(|tooltip| quote!(.with_tooltip(#tooltip)));
+        quote!(#range #tooltip)
+    }
+}
@@ -0,0 +1,42 @@
+//! Scene components: world-space transforms and bounds.
+
+use std::ops::Deref;
+
+use syn_math::{Aabb, Transform};
+
+/// The world-space transform of an entity: its [`Transform`] composed with
+/// those of its ancestors.
+///
+/// Maintained by [`TransformPropagator`](crate::TransformPropagator) for
+/// every entity with a `Transform`; move entities through their `Transform`
+/// instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GlobalTransform(pub(crate) Transform);
+
+impl Deref for GlobalTransform {
+    type Target = Transform;
+
+    fn deref(&self) -> &Transform {
+        &self.0
+    }
+}
+
+/// The bounds of an entity in its own space, such as those of its mesh.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalBounds(pub Aabb);
+
+/// The world-space bounds of an entity's [`LocalBounds`], cached for culling
+/// and picking.
+///
+/// Maintained by [`TransformPropagator`](crate::TransformPropagator) along
+/// with the [`GlobalTransform`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldBounds(pub(crate) Aabb);
+
+impl Deref for WorldBounds {
+    type Target = Aabb;
+
+    fn deref(&self) -> &Aabb {
+        &self.0
+    }
+}
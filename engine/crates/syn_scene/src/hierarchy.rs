@@ -0,0 +1,97 @@
+//! Reparenting that keeps entities where they are in the world.
+
+use std::any::type_name;
+
+use syn_ecs::{ChildOf, EcsError, EcsResult, Entity, World};
+use syn_math::Transform;
+
+/// Returns the world-space transform of `entity`, composed from the
+/// [`Transform`]s of the entity and its ancestors rather than read from a
+/// [`GlobalTransform`](crate::GlobalTransform) that may be out of date.
+///
+/// Entities without a `Transform` count as the identity.
+pub fn world_transform(world: &World, entity: Entity) -> Transform {
+    let mut transform = local_transform(world, entity);
+    for ancestor in world.ancestors::<ChildOf>(entity) {
+        transform = local_transform(world, ancestor).mul_transform(&transform);
+    }
+    transform
+}
+
+fn local_transform(world: &World, entity: Entity) -> Transform {
+    world
+        .get::<Transform>(entity)
+        .map_or(Transform::IDENTITY, |transform| *transform)
+}
+
+/// Makes `child` a child of `parent`, or a root if `parent` is `None`, and
+/// sets its [`Transform`] so that it stays where it is in the world.
+///
+/// Transforms cannot shear, so the world transform is only kept exactly
+/// when rotated ancestors scale uniformly.
+///
+/// # Errors
+///
+/// Returns [`EcsError::NoSuchEntity`] if `child` or `parent` does not exist,
+/// and [`EcsError::RelationshipCycle`] if `parent` is `child` or one of its
+/// descendants.
+pub fn set_parent(world: &mut World, child: Entity, parent: Option<Entity>) -> EcsResult<()> {
+    if !world.contains(child) {
+        return Err(EcsError::NoSuchEntity(child));
+    }
+    let global = world_transform(world, child);
+    let Some(parent) = parent else {
+        world.remove::<ChildOf>(child);
+        return world.insert(child, (global,));
+    };
+
+    if !world.contains(parent) {
+        return Err(EcsError::NoSuchEntity(parent));
+    }
+    if parent == child || world.ancestors::<ChildOf>(parent).contains(&child) {
+        return Err(EcsError::RelationshipCycle {
+            relationship: type_name::<ChildOf>(),
+            entity: child,
+        });
+    }
+    let local = world_transform(world, parent)
+        .inverse()
+        .mul_transform(&global);
+    world.insert(child, (ChildOf(parent), local))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn_math::{Quat, Vec3};
+
+    #[test]
+    fn reparenting_keeps_the_world_transform() {
+        let mut world = World::new();
+        world.add_relationship::<ChildOf>();
+        let crane = world.spawn((Transform::from_translation(Vec3::new(0.0, 5.0, 0.0))
+            .with_rotation(Quat::from_rotation_y(1.0)),));
+        let hook = world.spawn((Transform::from_translation(Vec3::X), ChildOf(crane)));
+        let crate_ = world.spawn((Transform::from_translation(Vec3::new(3.0, 0.0, 1.0)),));
+
+        let before = world_transform(&world, crate_);
+        set_parent(&mut world, crate_, Some(hook)).unwrap();
+        assert_eq!(world.target::<ChildOf>(crate_), Some(hook));
+        let after = world_transform(&world, crate_);
+        assert!((after.translation - before.translation).length() < 1e-5);
+        assert!(after.rotation.angle_between(before.rotation) < 1e-5);
+
+        assert_eq!(
+            set_parent(&mut world, crane, Some(crate_)),
+            Err(EcsError::RelationshipCycle {
+                relationship: type_name::<ChildOf>(),
+                entity: crane,
+            })
+        );
+
+        set_parent(&mut world, crate_, None).unwrap();
+        assert_eq!(world.target::<ChildOf>(crate_), None);
+        let detached = *world.get::<Transform>(crate_).unwrap();
+        assert!((detached.translation - before.translation).length() < 1e-5);
+    }
+}
@@ -1 +1,26 @@
-//! `syn_scene` - TODO: Implement
+//! `syn_scene` - Scene graph for Synarion Engine.
+//!
+//! Entities form a hierarchy through [`ChildOf`] and [`Children`], and are
+//! placed relative to their parent by a [`Transform`].
+//!
+//! - [`GlobalTransform`] - World-space transforms composed down the hierarchy
+//! - [`LocalBounds`] / [`WorldBounds`] - Bounds in entity space, cached in
+//!   world space
+//! - [`TransformPropagator`] - Updates of the dirty subtrees, in parallel
+//! - [`set_parent`] - Reparenting that keeps the world transform
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+mod components;
+mod hierarchy;
+mod propagate;
+
+pub use components::{GlobalTransform, LocalBounds, WorldBounds};
+pub use hierarchy::{set_parent, world_transform};
+pub use propagate::TransformPropagator;
+
+// Re-export the hierarchy and transform types for convenience
+pub use syn_ecs::{ChildOf, Children};
+pub use syn_math::{Aabb, Transform};
@@ -0,0 +1,317 @@
+//! Transform propagation: world-space transforms and bounds updated down the
+//! hierarchy.
+
+use std::collections::HashSet;
+
+use syn_ecs::{Changed, ChildOf, Children, Entity, Tick, World};
+use syn_math::{Aabb, Transform};
+use syn_tasks::TaskPool;
+
+use crate::components::{GlobalTransform, LocalBounds, WorldBounds};
+use crate::hierarchy::world_transform;
+
+/// The entities whose parent changed since the last propagation, recorded by
+/// hooks.
+#[derive(Default)]
+struct Reparented(Vec<Entity>);
+
+fn mark_reparented(world: &mut World, entity: Entity) {
+    if let Some(reparented) = world.get_resource_mut::<Reparented>() {
+        reparented.0.push(entity);
+    }
+}
+
+/// Keeps the [`GlobalTransform`] and [`WorldBounds`] of entities up to date
+/// with their [`Transform`], [`LocalBounds`] and [`ChildOf`] hierarchy.
+///
+/// Each run only recomputes the subtrees under entities whose `Transform` or
+/// `LocalBounds` changed or was removed, or whose parent changed, since the
+/// previous run: the component change ticks, the world's removal tracking and
+/// the reparenting hooks are the dirty flags. Removals count until
+/// [`World::clear_trackers`], which is usually called once per frame.
+/// Independent subtrees are computed in parallel on the workers of a
+/// [`TaskPool`].
+///
+/// # Example
+///
+/// ```
+/// use syn_ecs::{ChildOf, World};
+/// use syn_math::{Transform, Vec3};
+/// use syn_scene::{GlobalTransform, TransformPropagator};
+/// use syn_tasks::TaskPool;
+///
+/// let pool = TaskPool::new(2);
+/// let mut world = World::new();
+/// let mut propagator = TransformPropagator::new(&mut world);
+///
+/// let ship = world.spawn((Transform::from_translation(Vec3::X),));
+/// let turret = world.spawn((Transform::from_translation(Vec3::Y), ChildOf(ship)));
+/// assert_eq!(propagator.run(&mut world, &pool), 2);
+/// assert_eq!(
+///     world.get::<GlobalTransform>(turret).unwrap().translation,
+///     Vec3::new(1.0, 1.0, 0.0)
+/// );
+///
+/// // Nothing moved, so nothing is recomputed.
+/// assert_eq!(propagator.run(&mut world, &pool), 0);
+/// ```
+#[derive(Debug)]
+pub struct TransformPropagator {
+    last_run: Tick,
+}
+
+impl TransformPropagator {
+    /// Creates a propagator whose first run updates every entity, and
+    /// registers the [`ChildOf`] relationship and the hooks tracking
+    /// reparenting in `world`.
+    pub fn new(world: &mut World) -> Self {
+        if !world.contains_resource::<Reparented>() {
+            world.insert_resource(Reparented::default());
+            world.add_relationship::<ChildOf>();
+            world.on_insert::<ChildOf>(mark_reparented);
+            world.on_remove::<ChildOf>(mark_reparented);
+        }
+        Self {
+            last_run: Tick::new(0),
+        }
+    }
+
+    /// Updates the world-space transforms and bounds of the dirty subtrees,
+    /// and returns the number of entities updated.
+    ///
+    /// Entities with a [`Transform`] get a [`GlobalTransform`], and those
+    /// with [`LocalBounds`] too get [`WorldBounds`]. Entities without a
+    /// `Transform` have neither, and pass their parent's transform on to their
+    /// children.
+    pub fn run(&mut self, world: &mut World, pool: &TaskPool) -> usize {
+        let since = std::mem::replace(&mut self.last_run, world.increment_change_tick());
+        let mut subtrees: Vec<Subtree> = dirty_roots(world, since)
+            .into_iter()
+            .map(|root| Subtree {
+                root,
+                parent: parent_transform(world, root),
+                updates: Vec::new(),
+            })
+            .collect();
+
+        let shared = &*world;
+        pool.parallel_for_mut(&mut subtrees, |_, subtree| subtree.compute(shared));
+
+        let mut updated = 0;
+        for update in subtrees.into_iter().flat_map(|subtree| subtree.updates) {
+            update.apply(world);
+            updated += 1;
+        }
+        updated
+    }
+}
+
+/// Returns the dirty entities without a dirty ancestor, sorted.
+fn dirty_roots(world: &mut World, since: Tick) -> Vec<Entity> {
+    let reparented = world
+        .get_resource_mut::<Reparented>()
+        .map(|reparented| std::mem::take(&mut reparented.0))
+        .unwrap_or_default();
+    let mut dirty: HashSet<Entity> = reparented
+        .into_iter()
+        .chain(world.removed::<Transform>().iter().copied())
+        .chain(world.removed::<LocalBounds>().iter().copied())
+        .filter(|entity| world.contains(*entity))
+        .collect();
+    dirty.extend(
+        world
+            .query_filtered::<Entity, Changed<Transform>>()
+            .since(since)
+            .iter(),
+    );
+    dirty.extend(
+        world
+            .query_filtered::<Entity, Changed<LocalBounds>>()
+            .since(since)
+            .iter(),
+    );
+
+    let mut roots: Vec<Entity> = dirty
+        .iter()
+        .copied()
+        .filter(|entity| {
+            !world
+                .ancestors::<ChildOf>(*entity)
+                .iter()
+                .any(|ancestor| dirty.contains(ancestor))
+        })
+        .collect();
+    roots.sort_unstable();
+    roots
+}
+
+/// Returns the world-space transform of the parent of `root`, which is up to
+/// date since `root` has no dirty ancestor.
+fn parent_transform(world: &World, root: Entity) -> Transform {
+    world
+        .target::<ChildOf>(root)
+        .map_or(Transform::IDENTITY, |parent| {
+            world
+                .get::<GlobalTransform>(parent)
+                .map_or_else(|| world_transform(world, parent), |global| global.0)
+        })
+}
+
+/// A dirty subtree and the updates computed for it.
+struct Subtree {
+    root: Entity,
+    /// The world-space transform of the root's parent.
+    parent: Transform,
+    updates: Vec<Update>,
+}
+
+impl Subtree {
+    fn compute(&mut self, world: &World) {
+        let mut stack = vec![(self.root, self.parent)];
+        while let Some((entity, parent)) = stack.pop() {
+            let global = world
+                .get::<Transform>(entity)
+                .map(|local| parent.mul_transform(&local));
+            if global.is_some() || world.has::<GlobalTransform>(entity) {
+                let bounds = global.zip(world.get::<LocalBounds>(entity));
+                self.updates.push(Update {
+                    entity,
+                    global,
+                    bounds: bounds.map(|(global, bounds)| bounds.0.transformed(&global)),
+                });
+            }
+            let global = global.unwrap_or(parent);
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.extend(children.iter().rev().map(|child| (*child, global)));
+            }
+        }
+    }
+}
+
+/// The new world-space transform and bounds of an entity, or `None` for an
+/// entity that lost its `Transform`.
+struct Update {
+    entity: Entity,
+    global: Option<Transform>,
+    bounds: Option<Aabb>,
+}
+
+impl Update {
+    fn apply(self, world: &mut World) {
+        let Some(global) = self.global else {
+            world.remove::<GlobalTransform>(self.entity);
+            world.remove::<WorldBounds>(self.entity);
+            return;
+        };
+        if let Some(cached) = world.get_mut::<GlobalTransform>(self.entity) {
+            cached.0 = global;
+        } else {
+            world
+                .insert(self.entity, (GlobalTransform(global),))
+                .expect("updated entities exist");
+        }
+        match self.bounds {
+            Some(bounds) => {
+                if let Some(cached) = world.get_mut::<WorldBounds>(self.entity) {
+                    cached.0 = bounds;
+                } else {
+                    world
+                        .insert(self.entity, (WorldBounds(bounds),))
+                        .expect("updated entities exist");
+                }
+            }
+            None => {
+                world.remove::<WorldBounds>(self.entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hierarchy::set_parent;
+    use syn_math::Vec3;
+
+    fn translation(world: &World, entity: Entity) -> Vec3 {
+        world.get::<GlobalTransform>(entity).unwrap().translation
+    }
+
+    #[test]
+    fn only_dirty_subtrees_are_recomputed() {
+        let pool = TaskPool::new(2);
+        let mut world = World::new();
+        let mut propagator = TransformPropagator::new(&mut world);
+        let ship = world.spawn((Transform::from_translation(Vec3::X),));
+        let turret = world.spawn((
+            Transform::from_translation(Vec3::Y),
+            ChildOf(ship),
+            LocalBounds(Aabb::new(-Vec3::ONE, Vec3::ONE)),
+        ));
+        let barrel = world.spawn((Transform::from_uniform_scale(2.0), ChildOf(turret)));
+        let buoy = world.spawn((Transform::from_translation(Vec3::Z),));
+
+        assert_eq!(propagator.run(&mut world, &pool), 4);
+        assert_eq!(translation(&world, barrel), Vec3::new(1.0, 1.0, 0.0));
+        assert_eq!(
+            **world.get::<WorldBounds>(turret).unwrap(),
+            Aabb::new(Vec3::new(0.0, 0.0, -1.0), Vec3::new(2.0, 2.0, 1.0))
+        );
+        assert_eq!(propagator.run(&mut world, &pool), 0);
+
+        world.get_mut::<Transform>(turret).unwrap().translation = Vec3::ZERO;
+        assert_eq!(propagator.run(&mut world, &pool), 2);
+        assert_eq!(translation(&world, barrel), Vec3::X);
+
+        // Reparenting keeps the barrel in place and only updates it.
+        set_parent(&mut world, barrel, Some(buoy)).unwrap();
+        assert_eq!(propagator.run(&mut world, &pool), 1);
+        assert_eq!(translation(&world, barrel), Vec3::X);
+        assert_eq!(
+            world.get::<Transform>(barrel).unwrap().translation,
+            Vec3::new(1.0, 0.0, -1.0)
+        );
+
+        world.remove::<ChildOf>(barrel);
+        assert_eq!(propagator.run(&mut world, &pool), 1);
+        assert_eq!(translation(&world, barrel), Vec3::new(1.0, 0.0, -1.0));
+
+        world.remove::<LocalBounds>(turret);
+        assert_eq!(propagator.run(&mut world, &pool), 1);
+        assert!(!world.has::<WorldBounds>(turret));
+
+        // Losing its transform drops the ship's cached one and moves its
+        // children back to the origin.
+        world.clear_trackers();
+        world.remove::<Transform>(ship);
+        assert_eq!(propagator.run(&mut world, &pool), 2);
+        assert!(!world.has::<GlobalTransform>(ship));
+        assert_eq!(translation(&world, turret), Vec3::ZERO);
+        world.clear_trackers();
+        assert_eq!(propagator.run(&mut world, &pool), 0);
+    }
+
+    #[test]
+    fn independent_subtrees_update_in_parallel() {
+        let pool = TaskPool::new(3);
+        let mut world = World::new();
+        let mut propagator = TransformPropagator::new(&mut world);
+        let parent = world.spawn(());
+        let leaves: Vec<Entity> = (0..64u8)
+            .map(|index| {
+                let root = world.spawn((
+                    Transform::from_translation(Vec3::splat(f32::from(index))),
+                    ChildOf(parent),
+                ));
+                world.spawn((Transform::from_translation(Vec3::X), ChildOf(root)))
+            })
+            .collect();
+
+        assert_eq!(propagator.run(&mut world, &pool), 128);
+        for (index, leaf) in (0..64u8).zip(&leaves) {
+            let expected = Vec3::splat(f32::from(index)) + Vec3::X;
+            assert_eq!(translation(&world, *leaf), expected);
+        }
+        assert!(!world.has::<GlobalTransform>(parent));
+    }
+}
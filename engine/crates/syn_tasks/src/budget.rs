@@ -0,0 +1,435 @@
+//! Time-sliced background work within a per-frame budget.
+
+use std::time::{Duration, Instant};
+
+/// How important a background task is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TaskPriority {
+    /// Work that can wait, such as AI planning far from the player.
+    Low,
+    /// Regular background work.
+    #[default]
+    Normal,
+    /// Work the player will notice soon, such as chunks near the camera.
+    High,
+}
+
+impl TaskPriority {
+    /// Every priority, from the lowest to the highest.
+    pub const ALL: [TaskPriority; 3] = [Self::Low, Self::Normal, Self::High];
+
+    /// Returns the index of the priority in [`ALL`](Self::ALL).
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Whether a task has more work to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    /// The task yielded and resumes in a later slice.
+    Pending,
+    /// The task has finished.
+    Done,
+}
+
+/// The time a task may use before yielding.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeSlice {
+    deadline: Instant,
+}
+
+impl TimeSlice {
+    /// Returns the time left in the frame's budget.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns `true` once the budget is spent and the task should yield.
+    pub fn is_over(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+/// A resumable task run a slice at a time by a [`BudgetScheduler`].
+///
+/// Each call to [`resume`](Self::resume) does a bounded amount of work, such
+/// as a loop checking [`TimeSlice::is_over`], and keeps its progress in the
+/// task itself. Closures taking a [`TimeSlice`] are tasks.
+pub trait SlicedTask: Send {
+    /// Does some work and reports whether more remains.
+    fn resume(&mut self, slice: &TimeSlice) -> TaskStatus;
+}
+
+impl<F: FnMut(&TimeSlice) -> TaskStatus + Send> SlicedTask for F {
+    fn resume(&mut self, slice: &TimeSlice) -> TaskStatus {
+        self(slice)
+    }
+}
+
+/// Identifies a task of a [`BudgetScheduler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskId(u64);
+
+/// Configuration of a [`BudgetScheduler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetConfig {
+    /// Time background tasks may use per frame.
+    pub budget: Duration,
+    /// Frames a task waits without running before it competes as one priority
+    /// higher, so low priority work never starves.
+    pub aging_frames: u32,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            budget: Duration::from_millis(2),
+            aging_frames: 30,
+        }
+    }
+}
+
+/// What a [`BudgetScheduler`] did during one frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// The frame's budget.
+    pub budget: Duration,
+    /// Time spent in tasks.
+    pub used: Duration,
+    /// Time spent beyond the budget by the last slice.
+    pub overrun: Duration,
+    /// Slices run.
+    pub slices: u32,
+    /// Tasks that finished.
+    pub completed: u32,
+}
+
+/// Totals of one [`TaskPriority`], for the profiler.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriorityStats {
+    /// Tasks waiting or in progress.
+    pub pending: usize,
+    /// Tasks that finished.
+    pub completed: u64,
+    /// Slices run.
+    pub slices: u64,
+    /// Time spent in the tasks.
+    pub time: Duration,
+    /// Longest wait, in frames, of a task before it ran.
+    pub max_wait_frames: u32,
+}
+
+/// Totals of a [`BudgetScheduler`] since it was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchedulerStats {
+    /// Frames run.
+    pub frames: u64,
+    /// The last frame.
+    pub last_frame: FrameStats,
+    /// Totals per priority, indexed by [`TaskPriority::index`].
+    pub priorities: [PriorityStats; 3],
+}
+
+struct Entry {
+    id: TaskId,
+    priority: TaskPriority,
+    task: Box<dyn SlicedTask>,
+    /// Frames since the task last ran.
+    waited: u32,
+    /// When the task last ran, to rotate between equally ranked tasks.
+    last_run: u64,
+}
+
+/// Runs resumable background tasks in the time left over each frame.
+///
+/// Each [`run_frame`](Self::run_frame) gives tasks slices until the frame's
+/// budget is spent, always resuming the highest priority task, and among
+/// equals the one that ran least recently. A task waiting for
+/// [`aging_frames`](BudgetConfig::aging_frames) competes as one priority
+/// higher, then two, so a steady stream of high priority work cannot starve
+/// the rest.
+///
+/// Tasks are cooperative: a slice that does not yield in time overruns the
+/// budget, which [`FrameStats::overrun`] reports.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use syn_tasks::{BudgetConfig, BudgetScheduler, TaskPriority, TaskStatus, TimeSlice};
+///
+/// let mut scheduler = BudgetScheduler::new(BudgetConfig {
+///     budget: Duration::from_millis(1),
+///     ..BudgetConfig::default()
+/// });
+///
+/// let mut generated = 0;
+/// let chunks = scheduler.spawn(TaskPriority::High, move |slice: &TimeSlice| {
+///     while generated < 64 {
+///         generated += 1; // Generate one chunk.
+///         if slice.is_over() {
+///             break;
+///         }
+///     }
+///     if generated == 64 { TaskStatus::Done } else { TaskStatus::Pending }
+/// });
+///
+/// while scheduler.is_pending(chunks) {
+///     let frame = scheduler.run_frame();
+///     assert!(frame.slices > 0);
+/// }
+/// assert_eq!(scheduler.stats().priorities[TaskPriority::High.index()].completed, 1);
+/// ```
+pub struct BudgetScheduler {
+    config: BudgetConfig,
+    tasks: Vec<Entry>,
+    next_id: u64,
+    /// Counts slices, ordering the tasks by when they last ran.
+    clock: u64,
+    stats: SchedulerStats,
+}
+
+impl BudgetScheduler {
+    /// Creates a scheduler with no tasks.
+    pub fn new(config: BudgetConfig) -> Self {
+        Self {
+            config,
+            tasks: Vec::new(),
+            next_id: 0,
+            clock: 0,
+            stats: SchedulerStats::default(),
+        }
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &BudgetConfig {
+        &self.config
+    }
+
+    /// Changes the per-frame budget, for instance when the frame rate target
+    /// changes.
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.config.budget = budget;
+    }
+
+    /// Adds a task.
+    pub fn spawn(&mut self, priority: TaskPriority, task: impl SlicedTask + 'static) -> TaskId {
+        let id = TaskId(self.next_id);
+        self.next_id += 1;
+        self.tasks.push(Entry {
+            id,
+            priority,
+            task: Box::new(task),
+            waited: 0,
+            last_run: 0,
+        });
+        self.stats.priorities[priority.index()].pending += 1;
+        id
+    }
+
+    /// Removes a task before it finishes, returning `true` if it was pending.
+    pub fn cancel(&mut self, id: TaskId) -> bool {
+        let Some(index) = self.tasks.iter().position(|entry| entry.id == id) else {
+            return false;
+        };
+        let entry = self.tasks.swap_remove(index);
+        self.stats.priorities[entry.priority.index()].pending -= 1;
+        true
+    }
+
+    /// Returns `true` if the task has neither finished nor been cancelled.
+    pub fn is_pending(&self, id: TaskId) -> bool {
+        self.tasks.iter().any(|entry| entry.id == id)
+    }
+
+    /// Returns the number of pending tasks.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if no task is pending.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Returns the totals so far.
+    pub fn stats(&self) -> &SchedulerStats {
+        &self.stats
+    }
+
+    /// Runs tasks until the budget is spent or none is left.
+    ///
+    /// A zero budget runs nothing; otherwise at least one slice runs if a task
+    /// is pending.
+    pub fn run_frame(&mut self) -> FrameStats {
+        let start = Instant::now();
+        let slice = TimeSlice {
+            deadline: start + self.config.budget,
+        };
+        let mut frame = FrameStats {
+            budget: self.config.budget,
+            ..FrameStats::default()
+        };
+        let mut ran = Vec::new();
+
+        while !self.config.budget.is_zero() && (frame.slices == 0 || !slice.is_over()) {
+            let Some(index) = self.next_task() else {
+                break;
+            };
+            self.clock += 1;
+            let entry = &mut self.tasks[index];
+            let slice_start = Instant::now();
+            let status = entry.task.resume(&slice);
+            let elapsed = slice_start.elapsed();
+
+            entry.last_run = self.clock;
+            let priority = &mut self.stats.priorities[entry.priority.index()];
+            priority.slices += 1;
+            priority.time += elapsed;
+            priority.max_wait_frames = priority.max_wait_frames.max(entry.waited);
+            entry.waited = 0;
+            frame.slices += 1;
+            ran.push(entry.id);
+
+            if status == TaskStatus::Done {
+                priority.pending -= 1;
+                priority.completed += 1;
+                frame.completed += 1;
+                self.tasks.swap_remove(index);
+            }
+        }
+
+        for entry in &mut self.tasks {
+            if !ran.contains(&entry.id) {
+                entry.waited = entry.waited.saturating_add(1);
+            }
+        }
+        frame.used = start.elapsed();
+        frame.overrun = frame.used.saturating_sub(frame.budget);
+        self.stats.frames += 1;
+        self.stats.last_frame = frame;
+        frame
+    }
+
+    /// Returns the index of the task to resume next.
+    fn next_task(&self) -> Option<usize> {
+        let aging = self.config.aging_frames.max(1);
+        self.tasks
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, entry)| {
+                let rank = entry.priority.index() as u64 + u64::from(entry.waited / aging);
+                (rank, std::cmp::Reverse((entry.last_run, entry.id)))
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+impl Default for BudgetScheduler {
+    fn default() -> Self {
+        Self::new(BudgetConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    use parking_lot::Mutex;
+
+    /// A task that logs its name and finishes after `slices` slices.
+    fn logged(
+        log: &Arc<Mutex<Vec<&'static str>>>,
+        name: &'static str,
+        slices: u32,
+    ) -> impl SlicedTask + use<> {
+        let log = Arc::clone(log);
+        let mut left = slices;
+        move |_: &TimeSlice| {
+            log.lock().push(name);
+            left -= 1;
+            if left == 0 {
+                TaskStatus::Done
+            } else {
+                TaskStatus::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn priorities_order_slices_and_aging_prevents_starvation() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut scheduler = BudgetScheduler::new(BudgetConfig {
+            budget: Duration::from_secs(1),
+            aging_frames: 2,
+        });
+        scheduler.spawn(TaskPriority::Low, logged(&log, "low", 1));
+        scheduler.spawn(TaskPriority::High, logged(&log, "high", 2));
+        let cancelled = scheduler.spawn(TaskPriority::Normal, logged(&log, "cancelled", 1));
+        scheduler.spawn(TaskPriority::Normal, logged(&log, "normal", 1));
+        assert!(scheduler.cancel(cancelled));
+        assert!(!scheduler.cancel(cancelled));
+
+        let frame = scheduler.run_frame();
+        assert_eq!(*log.lock(), ["high", "high", "normal", "low"]);
+        assert_eq!(frame.slices, 4);
+        assert_eq!(frame.completed, 3);
+        assert!(scheduler.is_empty());
+
+        // One slice per frame: after four frames of waiting, the low task ranks
+        // as high and has waited longer.
+        log.lock().clear();
+        scheduler.set_budget(Duration::from_nanos(1));
+        scheduler.spawn(TaskPriority::High, logged(&log, "high", u32::MAX));
+        scheduler.spawn(TaskPriority::Low, logged(&log, "low", 1));
+        for _ in 0..6 {
+            scheduler.run_frame();
+        }
+        assert_eq!(*log.lock(), ["high", "high", "high", "high", "low", "high"]);
+
+        let stats = scheduler.stats();
+        assert_eq!(stats.frames, 7);
+        assert_eq!(
+            stats.priorities[TaskPriority::Low.index()].max_wait_frames,
+            4
+        );
+        assert_eq!(stats.priorities[TaskPriority::Low.index()].completed, 2);
+        assert_eq!(stats.priorities[TaskPriority::High.index()].pending, 1);
+    }
+
+    #[test]
+    fn slices_stop_at_the_budget_and_resume_next_frame() {
+        let mut scheduler = BudgetScheduler::new(BudgetConfig {
+            budget: Duration::from_millis(5),
+            ..BudgetConfig::default()
+        });
+        let mut steps = 0;
+        let task = scheduler.spawn(TaskPriority::Normal, move |slice: &TimeSlice| {
+            while !slice.is_over() {
+                thread::sleep(Duration::from_millis(1));
+                steps += 1;
+                if steps == 12 {
+                    return TaskStatus::Done;
+                }
+            }
+            TaskStatus::Pending
+        });
+
+        let first = scheduler.run_frame();
+        assert!(scheduler.is_pending(task));
+        assert!(first.used >= first.budget);
+        assert_eq!(first.overrun, first.used.saturating_sub(first.budget));
+        while scheduler.is_pending(task) {
+            scheduler.run_frame();
+        }
+        assert!(scheduler.stats().frames >= 3);
+
+        scheduler.set_budget(Duration::ZERO);
+        scheduler.spawn(TaskPriority::High, |_: &TimeSlice| TaskStatus::Done);
+        assert_eq!(scheduler.run_frame().slices, 0);
+    }
+}
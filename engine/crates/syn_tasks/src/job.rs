@@ -0,0 +1,194 @@
+//! Job handles and dependencies.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::pool::Shared;
+
+/// A type-erased job, ready to run.
+pub(crate) type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// How long a thread waiting for a job sleeps before looking for work again.
+pub(crate) const WAIT_POLL: Duration = Duration::from_millis(1);
+
+/// Completion state shared by a job and the jobs depending on it.
+#[derive(Default)]
+pub(crate) struct JobState {
+    inner: Mutex<StateInner>,
+    finished: Condvar,
+}
+
+#[derive(Default)]
+struct StateInner {
+    done: bool,
+    dependents: Vec<Arc<PendingJob>>,
+}
+
+impl JobState {
+    fn is_done(&self) -> bool {
+        self.inner.lock().done
+    }
+
+    /// Marks the job done and releases the jobs waiting for it.
+    fn finish(&self) {
+        let dependents = {
+            let mut inner = self.inner.lock();
+            inner.done = true;
+            std::mem::take(&mut inner.dependents)
+        };
+        self.finished.notify_all();
+        for dependent in dependents {
+            dependent.release();
+        }
+    }
+
+    /// Makes `dependent` wait for this job, returning `false` if it is done.
+    fn add_dependent(&self, dependent: &Arc<PendingJob>) -> bool {
+        let mut inner = self.inner.lock();
+        if !inner.done {
+            inner.dependents.push(Arc::clone(dependent));
+        }
+        !inner.done
+    }
+}
+
+/// A job waiting for its dependencies.
+pub(crate) struct PendingJob {
+    /// Unfinished dependencies, plus one while the job is being set up.
+    remaining: AtomicUsize,
+    job: Mutex<Option<Job>>,
+    pool: Weak<Shared>,
+}
+
+impl PendingJob {
+    /// Queues `job` on the pool once every dependency has finished.
+    pub(crate) fn schedule(job: Job, dependencies: &[Dependency], pool: &Arc<Shared>) {
+        let pending = Arc::new(Self {
+            remaining: AtomicUsize::new(dependencies.len() + 1),
+            job: Mutex::new(Some(job)),
+            pool: Arc::downgrade(pool),
+        });
+        for dependency in dependencies {
+            if !dependency.0.add_dependent(&pending) {
+                pending.release();
+            }
+        }
+        pending.release();
+    }
+
+    /// Counts down one dependency, queueing the job after the last one.
+    fn release(&self) {
+        if self.remaining.fetch_sub(1, Ordering::AcqRel) != 1 {
+            return;
+        }
+        let job = self.job.lock().take();
+        // A pool dropped in the meantime has no workers left to run the job.
+        if let (Some(job), Some(pool)) = (job, self.pool.upgrade()) {
+            pool.push(job);
+        }
+    }
+}
+
+/// The result slot of a job.
+struct JobCell<T> {
+    state: Arc<JobState>,
+    result: Mutex<Option<thread::Result<T>>>,
+}
+
+/// A handle to a job's completion and result.
+///
+/// Dropping the handle detaches the job: it still runs, and its result is
+/// dropped. A panic inside the job is caught so the worker survives, and
+/// resumed by [`join`](Self::join).
+pub struct JobHandle<T> {
+    cell: Arc<JobCell<T>>,
+    pool: Weak<Shared>,
+}
+
+impl<T: Send + 'static> JobHandle<T> {
+    /// Wraps a closure into a job completing a new handle.
+    pub(crate) fn new(pool: Weak<Shared>, job: impl FnOnce() -> T + Send + 'static) -> (Self, Job) {
+        let cell = Arc::new(JobCell {
+            state: Arc::new(JobState::default()),
+            result: Mutex::new(None),
+        });
+        let job_cell = Arc::clone(&cell);
+        let job: Job = Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(job));
+            *job_cell.result.lock() = Some(result);
+            job_cell.state.finish();
+        });
+        (Self { cell, pool }, job)
+    }
+}
+
+impl<T> JobHandle<T> {
+    /// Returns `true` once the job has finished.
+    pub fn is_finished(&self) -> bool {
+        self.cell.state.is_done()
+    }
+
+    /// Returns a dependency on this job, for
+    /// [`TaskPool::spawn_after`](crate::TaskPool::spawn_after).
+    pub fn dependency(&self) -> Dependency {
+        Dependency(Arc::clone(&self.cell.state))
+    }
+
+    /// Blocks until the job has finished.
+    ///
+    /// While waiting, the calling thread runs other jobs of the pool, so
+    /// waiting from inside a job cannot starve the pool of workers.
+    pub fn wait(&self) {
+        let state = &self.cell.state;
+        loop {
+            if state.is_done() {
+                return;
+            }
+            if let Some(job) = self.pool.upgrade().and_then(|pool| pool.find_job()) {
+                job();
+                continue;
+            }
+            let mut inner = state.inner.lock();
+            if !inner.done {
+                state.finished.wait_for(&mut inner, WAIT_POLL);
+            }
+        }
+    }
+
+    /// Waits for the job and returns its result.
+    ///
+    /// # Panics
+    ///
+    /// Resumes the job's panic if it panicked.
+    pub fn join(self) -> T {
+        self.wait();
+        match self.cell.result.lock().take() {
+            Some(Ok(value)) => value,
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => unreachable!("a finished job stores its result"),
+        }
+    }
+}
+
+/// A job that other jobs can wait for, obtained from
+/// [`JobHandle::dependency`].
+#[derive(Clone)]
+pub struct Dependency(Arc<JobState>);
+
+impl Dependency {
+    /// Returns `true` once the job has finished.
+    pub fn is_finished(&self) -> bool {
+        self.0.is_done()
+    }
+}
+
+impl<T> From<&JobHandle<T>> for Dependency {
+    fn from(handle: &JobHandle<T>) -> Self {
+        handle.dependency()
+    }
+}
@@ -1 +1,29 @@
-//! `syn_tasks` - TODO: Implement
+//! `syn_tasks` - Job system for Synarion Engine.
+//!
+//! - [`TaskPool`] - Work-stealing worker threads, with job dependencies and
+//!   parallel loops
+//! - [`JobHandle`] / [`Dependency`] - Results of jobs and ordering between them
+//! - [`Scope`] - Jobs borrowing data from the stack
+//! - [`MainThreadQueue`] - Jobs that must run on the main thread
+//! - [`BudgetScheduler`] - Resumable background tasks within a per-frame time
+//!   budget
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+// Only the lifetime erasure of scoped jobs in `scope.rs` is allowed.
+#![deny(unsafe_code)]
+
+mod budget;
+mod job;
+mod main_thread;
+mod pool;
+mod scope;
+
+pub use budget::{
+    BudgetConfig, BudgetScheduler, FrameStats, PriorityStats, SchedulerStats, SlicedTask, TaskId,
+    TaskPriority, TaskStatus, TimeSlice,
+};
+pub use job::{Dependency, JobHandle};
+pub use main_thread::{MainThreadQueue, MainThreadSender};
+pub use pool::TaskPool;
+pub use scope::Scope;
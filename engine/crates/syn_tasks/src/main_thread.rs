@@ -0,0 +1,107 @@
+//! Jobs that must run on the main thread.
+
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Weak;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use crate::job::{Job, JobHandle};
+
+/// A queue of jobs run by the main thread, for work that only the main thread
+/// may do, such as window calls or GPU submissions from some backends.
+///
+/// The queue cannot leave the thread that created it; workers post jobs with
+/// a [`MainThreadSender`], and the main loop runs them with
+/// [`run_pending`](Self::run_pending) or [`run_for`](Self::run_for).
+///
+/// # Example
+///
+/// ```
+/// use syn_tasks::{MainThreadQueue, TaskPool};
+///
+/// let pool = TaskPool::new(2);
+/// let queue = MainThreadQueue::new();
+/// let sender = queue.sender();
+///
+/// let upload = pool.spawn(move || {
+///     let mesh = vec![0.0_f32; 9];
+///     sender.post(move || mesh.len())
+/// });
+/// let uploaded = upload.join();
+///
+/// while !uploaded.is_finished() {
+///     queue.run_pending();
+/// }
+/// assert_eq!(uploaded.join(), 9);
+/// ```
+pub struct MainThreadQueue {
+    sender: Sender<Job>,
+    receiver: Receiver<Job>,
+    /// Keeps the queue on the thread that created it.
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl MainThreadQueue {
+    /// Creates a queue owned by the calling thread.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns a sender for posting jobs from any thread.
+    pub fn sender(&self) -> MainThreadSender {
+        MainThreadSender(self.sender.clone())
+    }
+
+    /// Runs every job posted so far and returns how many ran.
+    pub fn run_pending(&self) -> usize {
+        self.receiver.try_iter().map(|job| job()).count()
+    }
+
+    /// Runs posted jobs until none is left or `budget` has elapsed, and
+    /// returns how many ran. At least one job runs if any is pending.
+    pub fn run_for(&self, budget: Duration) -> usize {
+        let start = Instant::now();
+        let mut ran = 0;
+        while let Ok(job) = self.receiver.try_recv() {
+            job();
+            ran += 1;
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+        ran
+    }
+}
+
+impl Default for MainThreadQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Posts jobs to a [`MainThreadQueue`] from any thread.
+#[derive(Clone)]
+pub struct MainThreadSender(Sender<Job>);
+
+impl MainThreadSender {
+    /// Posts a job and returns a handle to its result.
+    ///
+    /// Waiting for the handle blocks until the main thread runs the job, so
+    /// the main thread itself must not wait for it. If the queue was dropped,
+    /// the job never runs.
+    pub fn post<T: Send + 'static>(
+        &self,
+        job: impl FnOnce() -> T + Send + 'static,
+    ) -> JobHandle<T> {
+        let (handle, job) = JobHandle::new(Weak::new(), job);
+        // Sending only fails once the queue is dropped, as documented above.
+        let _ = self.0.send(job);
+        handle
+    }
+}
@@ -0,0 +1,332 @@
+//! Work-stealing worker thread pool.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+
+use crossbeam_deque::{Injector, Steal, Stealer, Worker};
+use parking_lot::{Condvar, Mutex};
+
+use crate::job::{Dependency, Job, JobHandle, PendingJob};
+
+/// Distinguishes pools, so a worker only pushes to its own pool's deque.
+static NEXT_POOL_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The deque of the pool worker running on this thread, if any.
+    static LOCAL: RefCell<Option<(usize, Worker<Job>)>> = const { RefCell::new(None) };
+}
+
+/// State shared by a pool, its workers and its job handles.
+pub(crate) struct Shared {
+    id: usize,
+    /// Jobs spawned from outside the workers.
+    injector: Injector<Job>,
+    stealers: Vec<Stealer<Job>>,
+    /// Jobs queued and not yet taken, so sleeping workers know to wake up.
+    queued: AtomicUsize,
+    sleep: Mutex<()>,
+    wake: Condvar,
+    shutdown: AtomicBool,
+}
+
+impl Shared {
+    /// Queues a job: on the current worker's own deque when spawned from one
+    /// of this pool's jobs, so related work stays on one core, or on the
+    /// shared injector otherwise.
+    pub(crate) fn push(&self, job: Job) {
+        let job = LOCAL.with_borrow(|local| match local {
+            Some((pool, worker)) if *pool == self.id => {
+                worker.push(job);
+                None
+            }
+            _ => Some(job),
+        });
+        if let Some(job) = job {
+            self.injector.push(job);
+        }
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        // Taking the lock orders the wake-up after a sleeping worker's check
+        // of `queued`.
+        drop(self.sleep.lock());
+        self.wake.notify_one();
+    }
+
+    /// Takes a job: from the current worker's deque first, then from the
+    /// injector, then stolen from another worker.
+    pub(crate) fn find_job(&self) -> Option<Job> {
+        let job = LOCAL.with_borrow(|local| match local {
+            Some((pool, worker)) if *pool == self.id => worker.pop().or_else(|| {
+                steal(|| {
+                    self.injector
+                        .steal_batch_and_pop(worker)
+                        .or_else(|| self.steal_from_workers())
+                })
+            }),
+            _ => steal(|| self.injector.steal().or_else(|| self.steal_from_workers())),
+        });
+        if job.is_some() {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+        }
+        job
+    }
+
+    fn steal_from_workers(&self) -> Steal<Job> {
+        self.stealers.iter().map(Stealer::steal).collect()
+    }
+
+    /// Puts a worker to sleep until a job is queued or the pool shuts down.
+    fn sleep(&self) {
+        let mut guard = self.sleep.lock();
+        if self.queued.load(Ordering::SeqCst) == 0 && !self.shutdown.load(Ordering::SeqCst) {
+            self.wake.wait(&mut guard);
+        }
+    }
+}
+
+/// Retries a steal until it succeeds or finds nothing.
+fn steal(mut attempt: impl FnMut() -> Steal<Job>) -> Option<Job> {
+    loop {
+        match attempt() {
+            Steal::Success(job) => return Some(job),
+            Steal::Empty => return None,
+            Steal::Retry => {}
+        }
+    }
+}
+
+/// A fixed set of worker threads that steal jobs from each other.
+///
+/// Each worker has its own deque: jobs spawned by a job go to the deque of the
+/// worker running it, and idle workers steal from the others. Jobs return
+/// their result through a [`JobHandle`], and can wait for other jobs with
+/// [`spawn_after`](Self::spawn_after), forming a dependency graph.
+///
+/// Work borrowing data from the stack goes through [`scope`](Self::scope) and
+/// [`parallel_for`](Self::parallel_for).
+///
+/// Dropping the pool waits for every queued job to finish.
+///
+/// # Example
+///
+/// ```
+/// use syn_tasks::TaskPool;
+///
+/// let pool = TaskPool::new(4);
+///
+/// let load = pool.spawn(|| vec![1, 2, 3]);
+/// let double = pool.spawn(|| 2);
+/// let factor = double.join();
+/// let scaled: Vec<i32> = load.join().into_iter().map(|value| value * factor).collect();
+///
+/// assert_eq!(scaled, [2, 4, 6]);
+/// ```
+pub struct TaskPool {
+    pub(crate) shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl TaskPool {
+    /// Starts a pool with `threads` workers, at least one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot spawn a thread.
+    pub fn new(threads: usize) -> Self {
+        let workers: Vec<Worker<Job>> = (0..threads.max(1)).map(|_| Worker::new_lifo()).collect();
+        let shared = Arc::new(Shared {
+            id: NEXT_POOL_ID.fetch_add(1, Ordering::Relaxed),
+            injector: Injector::new(),
+            stealers: workers.iter().map(Worker::stealer).collect(),
+            queued: AtomicUsize::new(0),
+            sleep: Mutex::new(()),
+            wake: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+        });
+        let threads = workers
+            .into_iter()
+            .enumerate()
+            .map(|(index, deque)| {
+                let shared = Arc::clone(&shared);
+                thread::Builder::new()
+                    .name(format!("syn-task-{index}"))
+                    .spawn(move || worker(&shared, deque))
+                    .expect("failed to spawn a worker thread")
+            })
+            .collect();
+        Self { shared, threads }
+    }
+
+    /// Starts a pool with one worker per available CPU core, minus one for the
+    /// main thread.
+    pub fn with_available_parallelism() -> Self {
+        let cores = thread::available_parallelism().map_or(1, usize::from);
+        Self::new(cores.saturating_sub(1))
+    }
+
+    /// Returns the number of worker threads.
+    pub fn thread_count(&self) -> usize {
+        self.threads.len()
+    }
+
+    /// Returns `true` if the calling thread is one of this pool's workers.
+    pub fn is_worker_thread(&self) -> bool {
+        LOCAL.with_borrow(|local| {
+            local
+                .as_ref()
+                .is_some_and(|(pool, _)| *pool == self.shared.id)
+        })
+    }
+
+    /// Queues a job to run on a worker thread.
+    pub fn spawn<T: Send + 'static>(
+        &self,
+        job: impl FnOnce() -> T + Send + 'static,
+    ) -> JobHandle<T> {
+        let (handle, job) = JobHandle::new(Arc::downgrade(&self.shared), job);
+        self.shared.push(job);
+        handle
+    }
+
+    /// Queues a job to run once every dependency has finished.
+    ///
+    /// Dependencies can only name jobs spawned before, so the jobs always form
+    /// an acyclic graph. A dependency that panicked still counts as finished.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use parking_lot::Mutex;
+    /// use syn_tasks::TaskPool;
+    ///
+    /// let pool = TaskPool::new(2);
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let step = |name: &'static str| {
+    ///     let log = Arc::clone(&log);
+    ///     move || log.lock().push(name)
+    /// };
+    ///
+    /// let terrain = pool.spawn(step("terrain"));
+    /// let props = pool.spawn(step("props"));
+    /// let navmesh = pool.spawn_after(&[terrain.dependency(), props.dependency()], step("navmesh"));
+    /// navmesh.join();
+    ///
+    /// assert_eq!(log.lock().last(), Some(&"navmesh"));
+    /// ```
+    pub fn spawn_after<T: Send + 'static>(
+        &self,
+        dependencies: &[Dependency],
+        job: impl FnOnce() -> T + Send + 'static,
+    ) -> JobHandle<T> {
+        let (handle, job) = JobHandle::new(Arc::downgrade(&self.shared), job);
+        PendingJob::schedule(job, dependencies, &self.shared);
+        handle
+    }
+}
+
+impl Drop for TaskPool {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        drop(self.shared.sleep.lock());
+        self.shared.wake.notify_all();
+        for thread in self.threads.drain(..) {
+            // Jobs catch their own panics, so workers only end on shutdown.
+            let _ = thread.join();
+        }
+    }
+}
+
+fn worker(shared: &Shared, deque: Worker<Job>) {
+    LOCAL.set(Some((shared.id, deque)));
+    loop {
+        if let Some(job) = shared.find_job() {
+            job();
+            continue;
+        }
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        shared.sleep();
+    }
+    LOCAL.set(None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn jobs_run_on_worker_threads() {
+        let pool = TaskPool::new(2);
+        let (sender, receiver) = mpsc::channel();
+        for index in 0..8 {
+            let sender = sender.clone();
+            pool.spawn(move || {
+                let name = thread::current().name().unwrap_or_default().to_owned();
+                sender.send((index, name)).unwrap();
+            });
+        }
+        drop(sender);
+        drop(pool);
+
+        let mut results: Vec<_> = receiver.iter().collect();
+        results.sort();
+        assert_eq!(results.len(), 8);
+        assert!(
+            results
+                .iter()
+                .all(|(_, name)| name.starts_with("syn-task-"))
+        );
+    }
+
+    #[test]
+    fn nested_jobs_are_stolen_and_joined_from_workers() {
+        let pool = Arc::new(TaskPool::new(3));
+        let inner_pool = Arc::clone(&pool);
+        let total = pool.spawn(move || {
+            let parts: Vec<_> = (0..64u64)
+                .map(|part| inner_pool.spawn(move || (part * 1000..(part + 1) * 1000).sum::<u64>()))
+                .collect();
+            parts.into_iter().map(JobHandle::join).sum::<u64>()
+        });
+        assert_eq!(total.join(), (0..64_000u64).sum::<u64>());
+
+        // Receiving, unlike joining, does not run jobs on the calling thread.
+        let (sender, receiver) = mpsc::channel();
+        let inner_pool = Arc::clone(&pool);
+        pool.spawn(move || sender.send(inner_pool.is_worker_thread()).unwrap());
+        assert!(receiver.recv().unwrap());
+        assert!(!pool.is_worker_thread());
+    }
+
+    #[test]
+    fn dependencies_order_a_diamond_and_panics_reach_join() {
+        let pool = TaskPool::new(4);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let step = |name: &'static str| {
+            let log = Arc::clone(&log);
+            move || log.lock().push(name)
+        };
+
+        let top = pool.spawn(step("top"));
+        let left = pool.spawn_after(&[top.dependency()], step("left"));
+        let right = pool.spawn_after(&[(&top).into()], step("right"));
+        let bottom = pool.spawn_after(&[left.dependency(), right.dependency()], step("bottom"));
+        bottom.join();
+        let log = log.lock().clone();
+        assert_eq!(log.first(), Some(&"top"));
+        assert_eq!(log.last(), Some(&"bottom"));
+        assert_eq!(log.len(), 4);
+
+        let failing = pool.spawn(|| panic!("job failed"));
+        let after = pool.spawn_after(&[failing.dependency()], || 5);
+        assert_eq!(after.join(), 5);
+        assert!(failing.is_finished());
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| failing.join()));
+        assert!(panic.is_err());
+    }
+}
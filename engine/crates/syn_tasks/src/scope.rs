@@ -0,0 +1,334 @@
+//! Scoped jobs and parallel loops over borrowed data.
+//!
+//! Scoped jobs are queued on the pool's workers like any other job. They may
+//! borrow from the stack because [`TaskPool::scope`] only returns once every
+//! job spawned in the scope has finished, even when one of them panics.
+
+use std::any::Any;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::job::{Job, WAIT_POLL};
+use crate::pool::{Shared, TaskPool};
+
+/// Batches per thread targeted by the automatic batch size, so threads that
+/// finish early can take over batches of slower ones.
+const BATCHES_PER_THREAD: usize = 4;
+
+/// Jobs that may borrow data living for `'env`, created by
+/// [`TaskPool::scope`].
+pub struct Scope<'env> {
+    pool: Arc<Shared>,
+    state: Arc<ScopeState>,
+    /// Invariant in `'env`, so jobs cannot borrow data living for less.
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+/// Completion state of a scope, shared with its jobs so that they can report
+/// finishing without borrowing the scope.
+#[derive(Default)]
+struct ScopeState {
+    /// Jobs spawned and not finished yet.
+    running: Mutex<usize>,
+    finished: Condvar,
+    panic: Mutex<Option<Box<dyn Any + Send>>>,
+}
+
+impl ScopeState {
+    fn finish_job(&self) {
+        let mut running = self.running.lock();
+        *running -= 1;
+        if *running == 0 {
+            self.finished.notify_all();
+        }
+    }
+}
+
+impl<'env> Scope<'env> {
+    fn new(pool: Arc<Shared>) -> Self {
+        Self {
+            pool,
+            state: Arc::default(),
+            env: PhantomData,
+        }
+    }
+
+    /// Queues a job on the pool. The job receives the scope, so it can spawn
+    /// more jobs.
+    pub fn spawn(&self, job: impl FnOnce(&Scope<'env>) + Send + 'env) {
+        *self.state.running.lock() += 1;
+        let scope = Self {
+            pool: Arc::clone(&self.pool),
+            state: Arc::clone(&self.state),
+            env: PhantomData,
+        };
+        let job: Box<dyn FnOnce() + Send + 'env> = Box::new(move || {
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| job(&scope))) {
+                scope.state.panic.lock().get_or_insert(payload);
+            }
+            // The job and its borrows are gone: the scope may return from here.
+            scope.state.finish_job();
+        });
+        self.pool.push(erase_lifetime(job));
+    }
+
+    /// Runs jobs of the pool until every job of the scope has finished.
+    ///
+    /// Jobs catch their own panics, so this cannot unwind while borrowing jobs
+    /// are still queued.
+    fn wait(&self) {
+        loop {
+            if *self.state.running.lock() == 0 {
+                return;
+            }
+            if let Some(job) = self.pool.find_job() {
+                job();
+                continue;
+            }
+            let mut running = self.state.running.lock();
+            if *running > 0 {
+                self.state.finished.wait_for(&mut running, WAIT_POLL);
+            }
+        }
+    }
+}
+
+/// Extends the lifetime of a scoped job to `'static`, so that it can be
+/// queued with the other jobs of the pool.
+// RATIONALE: the pool's queues only hold `'static` jobs. `TaskPool::scope`
+// waits for every job it spawned before returning, and each job reports
+// finishing only after its closure and borrows are gone, so no job outlives
+// the data it borrows.
+#[allow(unsafe_code)]
+fn erase_lifetime<'env>(job: Box<dyn FnOnce() + Send + 'env>) -> Job {
+    // SAFETY: only the lifetime changes, and the scope outlives the job as
+    // explained above.
+    unsafe { std::mem::transmute::<Box<dyn FnOnce() + Send + 'env>, Job>(job) }
+}
+
+impl TaskPool {
+    /// Runs `f` with a [`Scope`] whose jobs may borrow local data, and returns
+    /// once every job spawned in the scope has finished.
+    ///
+    /// The jobs run on the pool's workers. Once `f` returns, the calling
+    /// thread runs jobs of the pool too while it waits for them.
+    ///
+    /// # Panics
+    ///
+    /// Resumes the first panic of `f` or of a job, after every job finished.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    ///
+    /// use syn_tasks::TaskPool;
+    ///
+    /// let pool = TaskPool::new(2);
+    /// let chunks = [[1, 2], [3, 4], [5, 6]];
+    /// let total = AtomicU32::new(0);
+    ///
+    /// pool.scope(|scope| {
+    ///     let total = &total;
+    ///     for chunk in &chunks {
+    ///         scope.spawn(move |scope| {
+    ///             let sum: u32 = chunk.iter().sum();
+    ///             scope.spawn(move |_| {
+    ///                 total.fetch_add(sum, Ordering::Relaxed);
+    ///             });
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(total.into_inner(), 21);
+    /// ```
+    pub fn scope<'env, R>(&self, f: impl FnOnce(&Scope<'env>) -> R) -> R {
+        let scope = Scope::new(Arc::clone(&self.shared));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+        scope.wait();
+        if let Some(payload) = scope.state.panic.lock().take() {
+            panic::resume_unwind(payload);
+        }
+        result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+
+    /// Calls `f` with the index and a reference of every item, in parallel.
+    ///
+    /// Items are split into batches sized so that each thread gets several;
+    /// threads take the next batch as they finish, balancing uneven work.
+    ///
+    /// # Panics
+    ///
+    /// Resumes a panic of `f` after every batch finished.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    ///
+    /// use syn_tasks::TaskPool;
+    ///
+    /// let pool = TaskPool::new(3);
+    /// let values: Vec<u64> = (0..1000).collect();
+    /// let total = AtomicU64::new(0);
+    ///
+    /// pool.parallel_for(&values, |_, value| {
+    ///     total.fetch_add(*value, Ordering::Relaxed);
+    /// });
+    ///
+    /// assert_eq!(total.into_inner(), 499_500);
+    /// ```
+    pub fn parallel_for<T: Sync>(&self, items: &[T], f: impl Fn(usize, &T) + Sync) {
+        let batch = self.batch_size(items.len());
+        self.run_batches(items.chunks(batch).enumerate(), |(index, chunk)| {
+            for (offset, item) in chunk.iter().enumerate() {
+                f(index * batch + offset, item);
+            }
+        });
+    }
+
+    /// Calls `f` with the index and a mutable reference of every item, in
+    /// parallel, batching like [`parallel_for`](Self::parallel_for).
+    ///
+    /// # Panics
+    ///
+    /// Resumes a panic of `f` after every batch finished.
+    pub fn parallel_for_mut<T: Send>(&self, items: &mut [T], f: impl Fn(usize, &mut T) + Sync) {
+        let batch = self.batch_size(items.len());
+        self.run_batches(items.chunks_mut(batch).enumerate(), |(index, chunk)| {
+            for (offset, item) in chunk.iter_mut().enumerate() {
+                f(index * batch + offset, item);
+            }
+        });
+    }
+
+    /// Returns the number of items per batch for a loop over `len` items.
+    pub fn batch_size(&self, len: usize) -> usize {
+        len.div_ceil(self.thread_count() * BATCHES_PER_THREAD)
+            .max(1)
+    }
+
+    /// Runs `body` on every batch, on the calling thread and jobs on the
+    /// workers.
+    fn run_batches<I>(&self, batches: I, body: impl Fn(I::Item) + Sync)
+    where
+        I: ExactSizeIterator + Send,
+    {
+        let helpers = batches.len().min(self.thread_count() + 1).saturating_sub(1);
+        let batches = Mutex::new(batches);
+        let work = || {
+            loop {
+                let batch = batches.lock().next();
+                let Some(batch) = batch else {
+                    break;
+                };
+                body(batch);
+            }
+        };
+        if helpers == 0 {
+            work();
+            return;
+        }
+        self.scope(|scope| {
+            for _ in 0..helpers {
+                scope.spawn(|_| work());
+            }
+            work();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn parallel_loops_visit_every_item_once() {
+        let pool = TaskPool::new(3);
+        assert_eq!(pool.batch_size(0), 1);
+        assert_eq!(pool.batch_size(1000), 84);
+
+        let mut values: Vec<usize> = vec![0; 1000];
+        pool.parallel_for_mut(&mut values, |index, value| *value = index * 2);
+        assert!(
+            values
+                .iter()
+                .enumerate()
+                .all(|(index, value)| *value == index * 2)
+        );
+
+        let visits: Vec<AtomicUsize> = (0..values.len()).map(|_| AtomicUsize::new(0)).collect();
+        pool.parallel_for(&values, |index, value| {
+            assert_eq!(*value, index * 2);
+            visits[index].fetch_add(1, Ordering::Relaxed);
+        });
+        assert!(
+            visits
+                .iter()
+                .all(|count| count.load(Ordering::Relaxed) == 1)
+        );
+
+        pool.parallel_for(&[] as &[u8], |_, _| unreachable!());
+    }
+
+    #[test]
+    fn scopes_borrow_and_propagate_panics() {
+        let pool = TaskPool::new(2);
+        let mut results = vec![0; 8];
+        let total = pool.scope(|scope| {
+            for (index, slot) in results.iter_mut().enumerate() {
+                scope.spawn(move |_| *slot = index * index);
+            }
+            "done"
+        });
+        assert_eq!(total, "done");
+        assert_eq!(results, [0, 1, 4, 9, 16, 25, 36, 49]);
+
+        let finished = AtomicUsize::new(0);
+        let panic = panic::catch_unwind(AssertUnwindSafe(|| {
+            pool.scope(|scope| {
+                scope.spawn(|_| panic!("job failed"));
+                for _ in 0..4 {
+                    scope.spawn(|_| {
+                        finished.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+            });
+        }));
+        assert!(panic.is_err());
+        assert_eq!(finished.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn scoped_jobs_run_on_the_pool_workers() {
+        let pool = TaskPool::new(2);
+        let caller = thread::current().id();
+        let helpers = Mutex::new(Vec::new());
+        pool.scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|_| {
+                    thread::sleep(Duration::from_millis(5));
+                    let current = thread::current();
+                    if current.id() != caller {
+                        let name = current.name().unwrap_or_default().to_owned();
+                        helpers.lock().push((pool.is_worker_thread(), name));
+                    }
+                });
+            }
+        });
+
+        let helpers = helpers.into_inner();
+        assert!(!helpers.is_empty());
+        assert!(
+            helpers
+                .iter()
+                .all(|(worker, name)| *worker && name.starts_with("syn-task-"))
+        );
+    }
+}
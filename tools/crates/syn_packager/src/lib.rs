@@ -1 +1,85 @@
-//! `syn_packager` - TODO: Implement
+//! `syn_packager` - Packs game content into archives for Synarion Engine.
+//!
+//! - [`pack_directory`] - Collects a directory tree into a [`PackWriter`],
+//!   ready to be saved as an archive the engine mounts with
+//!   [`PackArchive`](syn_filesystem::PackArchive)
+
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+use std::fs;
+use std::path::Path;
+
+use syn_filesystem::{Compression, PackWriter, VfsError, VfsResult};
+
+/// Collects every file under `root` into an archive, keyed by its path relative
+/// to `root`.
+///
+/// # Errors
+///
+/// Returns an error if a directory or file cannot be read, or a file name is
+/// not a valid VFS path component.
+///
+/// # Example
+///
+/// ```
+/// use syn_filesystem::{Compression, PackArchive, Vfs};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// std::fs::create_dir(dir.path().join("textures")).unwrap();
+/// std::fs::write(dir.path().join("textures/rock.ktx"), b"rock").unwrap();
+///
+/// let writer = syn_packager::pack_directory(dir.path(), Compression::Lz4).unwrap();
+/// let mut bytes = Vec::new();
+/// writer.write_to(&mut bytes).unwrap();
+///
+/// let vfs = Vfs::new();
+/// vfs.mount("vfs://", 0, PackArchive::from_bytes(bytes).unwrap()).unwrap();
+/// assert_eq!(vfs.read("vfs://textures/rock.ktx").unwrap(), b"rock");
+/// ```
+pub fn pack_directory(root: &Path, compression: Compression) -> VfsResult<PackWriter> {
+    let mut writer = PackWriter::new();
+    add_directory(&mut writer, root, "", compression)?;
+    Ok(writer)
+}
+
+fn add_directory(
+    writer: &mut PackWriter,
+    dir: &Path,
+    prefix: &str,
+    compression: Compression,
+) -> VfsResult<()> {
+    let io_error = |path: &Path, error| VfsError::Io {
+        path: path.display().to_string(),
+        source: error,
+    };
+
+    let mut entries = fs::read_dir(dir)
+        .map_err(|error| io_error(dir, error))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| io_error(dir, error))?;
+    entries.sort_by_key(fs::DirEntry::file_name);
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_str().ok_or_else(|| VfsError::InvalidPath {
+            path: path.display().to_string(),
+            reason: "file name is not UTF-8",
+        })?;
+        let vfs_path = if prefix.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{prefix}/{name}")
+        };
+
+        if path.is_dir() {
+            add_directory(writer, &path, &vfs_path, compression)?;
+        } else {
+            let contents = fs::read(&path).map_err(|error| io_error(&path, error))?;
+            writer.add(&vfs_path, contents, compression)?;
+        }
+    }
+    Ok(())
+}
@@ -1,5 +1,38 @@
 //! Game Packager - Main entry point
+//!
+//! Usage: `syn-packager <input-dir> <output-archive> [--store]`
 
-fn main() {
-    println!("Game Packager - Coming soon!");
+use std::path::Path;
+use std::process::ExitCode;
+
+use syn_filesystem::Compression;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let store = args.iter().any(|arg| arg == "--store");
+    let paths: Vec<&String> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+
+    let [input, output] = paths.as_slice() else {
+        eprintln!("Usage: syn-packager <input-dir> <output-archive> [--store]");
+        return ExitCode::FAILURE;
+    };
+
+    let compression = if store {
+        Compression::None
+    } else {
+        Compression::Lz4
+    };
+
+    let result = syn_packager::pack_directory(Path::new(input), compression)
+        .and_then(|writer| writer.save(output).map(|()| writer.len()));
+    match result {
+        Ok(count) => {
+            println!("Packed {count} files into {output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("syn-packager: {error}");
+            ExitCode::FAILURE
+        }
+    }
 }